@@ -0,0 +1,22 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gomoku::ai::heuristic::Heuristic;
+use gomoku::core::positions;
+
+fn bench_evaluate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("evaluate");
+    for (name, state) in [
+        ("empty", positions::empty()),
+        ("midgame", positions::midgame()),
+        ("crowded", positions::crowded()),
+    ] {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &state, |b, state| {
+            b.iter(|| Heuristic::evaluate(black_box(state), 0))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_evaluate);
+criterion_main!(benches);
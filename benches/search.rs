@@ -0,0 +1,30 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gomoku::ai::lazy_smp::{lazy_smp_search_with_config, SearchConfig};
+use gomoku::core::positions;
+
+const SEARCH_DEPTH: i32 = 3;
+const SEARCH_SEED: u64 = 0x5EED;
+
+/// Fixed-depth search on one thread with a fixed seed, so every run searches the same tree
+fn search_config() -> SearchConfig {
+    SearchConfig {
+        num_threads: Some(1),
+        seed: Some(SEARCH_SEED),
+        ..SearchConfig::new(SEARCH_DEPTH, None)
+    }
+}
+
+fn bench_search(c: &mut Criterion) {
+    let config = search_config();
+    let mut group = c.benchmark_group("search");
+    group.sample_size(10);
+    for (name, state) in positions::canonical() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &state, |b, state| {
+            b.iter(|| lazy_smp_search_with_config(&mut state.clone(), &config))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_search);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gomoku::core::board::Player;
+use gomoku::core::positions;
+use gomoku::core::state::GameState;
+
+/// A 19x19 board filled without five in a row, but for one hole in the far corner, so
+/// the legal move scan has to go through the whole board before it finds a cell
+fn near_full() -> GameState {
+    let mut state = positions::empty();
+    for row in 0..19 {
+        for col in 0..19 {
+            let player = if (col + 2 * (row % 2) + row / 2) % 4 < 2 { Player::Max } else { Player::Min };
+            state.board.place_stone(row, col, player);
+        }
+    }
+    state.board.remove_stone(18, 18);
+    state
+}
+
+/// `is_terminal` against the full move generation it replaced
+fn bench_is_terminal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("is_terminal");
+    for (name, state) in [("midgame", positions::midgame()), ("near_full", near_full())] {
+        group.bench_with_input(BenchmarkId::new("short_circuit", name), &state, |b, state| {
+            b.iter(|| black_box(state).is_terminal())
+        });
+        group.bench_with_input(BenchmarkId::new("move_generation", name), &state, |b, state| {
+            b.iter(|| black_box(state).get_possible_moves().is_empty())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_is_terminal);
+criterion_main!(benches);
@@ -0,0 +1,114 @@
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+
+use crate::core::state::GameState;
+
+use super::lazy_smp::{lazy_smp_search_with_progress, lazy_smp_search_with_stop, DepthProgress, SearchConfig, SearchResult};
+
+/// Score at which the normalized evaluation reaches about three quarters of the bar
+const EVAL_SCALE: f32 = 10_000.0;
+
+/// What the analysis search found for one position
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    /// Search score, positive when Max (black) is ahead
+    pub score: i32,
+    /// `score` squashed into `-1.0..=1.0`
+    pub normalized: f32,
+    /// Suggested move for the side to move
+    pub best_move: Option<(usize, usize)>,
+    pub depth: i32,
+    /// Line the search expects, starting with `best_move`
+    pub pv: Vec<(usize, usize)>,
+}
+
+impl Analysis {
+    /// What a finished search found
+    pub fn from_result(result: SearchResult) -> Self {
+        Self {
+            score: result.score,
+            normalized: normalize_eval(result.score),
+            best_move: result.best_move,
+            depth: result.depth_reached,
+            pv: result.pv,
+        }
+    }
+
+    /// What a search found once it completed `progress.depth`
+    pub fn from_progress(progress: DepthProgress) -> Self {
+        Self {
+            score: progress.score,
+            normalized: normalize_eval(progress.score),
+            best_move: progress.pv.first().copied(),
+            depth: progress.depth,
+            pv: progress.pv,
+        }
+    }
+}
+
+/// Maps a search score to `-1.0..=1.0`, positive when Max is ahead. Small advantages
+/// stay readable while won positions pin the bar to the end.
+pub fn normalize_eval(score: i32) -> f32 {
+    (score as f32 / EVAL_SCALE).tanh()
+}
+
+/// Searches `state` and reports the result from Max's side
+pub fn analyze(state: &GameState, config: &SearchConfig, stop: Option<&AtomicBool>) -> Analysis {
+    Analysis::from_result(lazy_smp_search_with_stop(&mut state.clone(), config, stop))
+}
+
+/// Analysis search running on its own thread, so the UI keeps drawing meanwhile
+pub struct Analyzer {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Analysis>>,
+    /// Behind a mutex only so the analyzer can be shared between threads, as a resource is
+    progress: Mutex<Receiver<DepthProgress>>,
+}
+
+impl Analyzer {
+    /// Starts analysing `state`, or returns `None` when the game is over
+    pub fn start(state: &GameState, config: &SearchConfig) -> Option<Self> {
+        if state.is_terminal() {
+            return None;
+        }
+        let state = state.clone();
+        let config = *config;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let (sender, progress) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            let mut state = state;
+            Analysis::from_result(lazy_smp_search_with_progress(&mut state, &config, Some(&thread_stop), sender))
+        });
+
+        Some(Self {
+            stop,
+            handle: Some(handle),
+            progress: Mutex::new(progress),
+        })
+    }
+
+    /// The deepest depth completed since the last call, while the search goes on
+    pub fn try_progress(&mut self) -> Option<Analysis> {
+        self.progress.get_mut().ok()?.try_iter().last().map(Analysis::from_progress)
+    }
+
+    /// The result once the search is done, without waiting for it
+    pub fn try_finish(&mut self) -> Option<Analysis> {
+        if !self.handle.as_ref()?.is_finished() {
+            return None;
+        }
+        self.handle.take()?.join().ok()
+    }
+}
+
+impl Drop for Analyzer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
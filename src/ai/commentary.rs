@@ -0,0 +1,53 @@
+use crate::core::board::Player;
+use crate::core::moves::{MoveHandler, ThreatLevel};
+use crate::core::state::GameState;
+
+/// Describes in plain words what playing `mv` as `player` accomplishes, judged from the
+/// position before the move (`state`) and the position right after it.
+pub fn describe_move(state: &GameState, mv: (usize, usize), player: Player) -> String {
+    let (row, col) = mv;
+    let board = &state.board;
+    let opponent = player.opponent();
+
+    let mut after = state.clone();
+    after.current_player = player;
+    after.make_move(mv);
+    let pairs = after.capture_history.last().map_or(0, |captures| captures.len() / 2);
+
+    if after.winner == Some(player) {
+        return if after.check_capture_win() == Some(player) {
+            "Winning by captures".to_string()
+        } else {
+            "Completing five in a row".to_string()
+        };
+    }
+
+    if pairs > 0 {
+        return if pairs == 1 {
+            "Capturing a pair".to_string()
+        } else {
+            format!("Capturing {} pairs", pairs)
+        };
+    }
+
+    let own_threats: Vec<ThreatLevel> = MoveHandler::line_lengths_through(board, row, col, player)
+        .into_iter()
+        .filter_map(|length| MoveHandler::level_for_length(length, state.win_condition))
+        .collect();
+    if own_threats.len() >= 2 {
+        return "Creating a double threat".to_string();
+    }
+
+    match MoveHandler::threat_level(board, row, col, opponent, state.win_condition) {
+        Some(ThreatLevel::Five) => return "Blocking your five".to_string(),
+        Some(ThreatLevel::Four) => return "Blocking your four".to_string(),
+        Some(ThreatLevel::Three) => return "Blocking your open three".to_string(),
+        None => {}
+    }
+
+    match own_threats.first() {
+        Some(ThreatLevel::Four) | Some(ThreatLevel::Five) => "Extending to a four".to_string(),
+        Some(ThreatLevel::Three) => "Extending to a three".to_string(),
+        None => "Developing the position".to_string(),
+    }
+}
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::heuristic::Heuristic;
+use crate::ai::lazy_smp::SearchConfig;
+use crate::ai::minimax::quiescence;
+use crate::core::board::Player;
+use crate::core::moves::ThreatLevel;
+use crate::core::state::GameState;
+
+/// Levels offered in the settings menu, easiest first
+pub const DIFFICULTY_LEVELS: [&str; 3] = ["easy", "medium", "hard"];
+
+/// Moves scoring within this much of the best one are treated as equal on Easy
+pub const EASY_SCORE_MARGIN: i32 = 300;
+
+/// Root moves scoring further than this below the best are never sampled, whatever the temperature
+pub const TEMPERATURE_MARGIN: i32 = 500;
+/// Score gap that makes a move `e` times less likely at temperature 1.0
+pub const TEMPERATURE_SCALE: f32 = 100.0;
+
+/// Search the AI plays with, picked in the settings menu to compare strength and speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EngineKind {
+    /// Every candidate move searched to the full depth
+    #[default]
+    Advanced,
+    /// The same search with two prunings switched on to reach deeper in the same time:
+    /// `late_move_reductions`, and `threat_space_above` at `ThreatLevel::Three`, so once a
+    /// three is on the board only moves that make or block fours and fives are searched.
+    /// Settings saved under the engine's first name still load.
+    #[serde(alias = "LegacyPruning")]
+    Pruning,
+}
+
+/// Engines offered in the settings menu, in cycling order
+pub const ENGINE_KINDS: [EngineKind; 2] = [EngineKind::Advanced, EngineKind::Pruning];
+
+impl EngineKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            EngineKind::Advanced => "Advanced",
+            EngineKind::Pruning => "Pruning",
+        }
+    }
+
+    /// `config` with this engine's pruning switched on
+    pub fn configure(self, config: SearchConfig) -> SearchConfig {
+        match self {
+            EngineKind::Advanced => config,
+            EngineKind::Pruning => SearchConfig {
+                late_move_reductions: true,
+                threat_space_above: Some(ThreatLevel::Three),
+                ..config
+            },
+        }
+    }
+}
+
+/// Search depth, time budget and whether the move-history pattern bonus is used for a
+/// difficulty level. Unknown levels play as medium.
+pub fn difficulty_params(level: &str) -> (i32, Duration, bool) {
+    match level.to_ascii_lowercase().as_str() {
+        "easy" => (2, Duration::from_millis(200), false),
+        "hard" => (16, Duration::from_millis(1500), true),
+        _ => (10, Duration::from_millis(500), true),
+    }
+}
+
+/// Picks at random among the legal moves whose one-ply evaluation is within `margin` of
+/// the best, from the point of view of the side to move. A winning move always stands
+/// alone, so it is never passed over.
+pub fn pick_near_equal_move<R: Rng>(state: &mut GameState, margin: i32, rng: &mut R) -> Option<(usize, usize)> {
+    let player = state.current_player;
+    let scored: Vec<((usize, usize), i32)> = state
+        .get_possible_moves()
+        .into_iter()
+        .map(|mv| {
+            state.make_move(mv);
+            let eval = Heuristic::evaluate(state, 0);
+            state.undo_move(mv);
+            let score = if player == Player::Max { eval } else { -eval };
+            (mv, score)
+        })
+        .collect();
+
+    let best = scored.iter().map(|&(_, score)| score).max()?;
+    let candidates: Vec<(usize, usize)> = scored
+        .into_iter()
+        .filter(|&(_, score)| score >= best.saturating_sub(margin))
+        .map(|(mv, _)| mv)
+        .collect();
+
+    Some(candidates[rng.random_range(0..candidates.len())])
+}
+
+/// Samples a root move instead of always playing `best`: every legal move is scored by
+/// playing it and resolving the forcing replies, and those within `TEMPERATURE_MARGIN` of
+/// the top score are drawn with softmax odds `exp(gap / (temperature * TEMPERATURE_SCALE))`.
+/// At temperature 0 this is `best`, and a move that wins on the spot is always kept.
+pub fn pick_by_temperature<R: Rng>(
+    state: &mut GameState,
+    best: (usize, usize),
+    temperature: f32,
+    rng: &mut R,
+) -> (usize, usize) {
+    if temperature <= 0.0 {
+        return best;
+    }
+    let player = state.current_player;
+    let mut scored = Vec::new();
+    for mv in state.get_possible_moves() {
+        state.make_move(mv);
+        let wins = state.winner == Some(player);
+        let eval = quiescence(state, i32::MIN + 1, i32::MAX - 1, state.current_player == Player::Max);
+        state.undo_move(mv);
+        if wins {
+            return best;
+        }
+        scored.push((mv, if player == Player::Max { eval } else { -eval }));
+    }
+
+    let Some(top) = scored.iter().map(|&(_, score)| score).max() else {
+        return best;
+    };
+    let candidates: Vec<((usize, usize), f32)> = scored
+        .into_iter()
+        .filter(|&(mv, score)| mv == best || score >= top.saturating_sub(TEMPERATURE_MARGIN))
+        .map(|(mv, score)| {
+            let gap = score.saturating_sub(top) as f32;
+            (mv, (gap / (temperature * TEMPERATURE_SCALE)).exp())
+        })
+        .collect();
+
+    let total: f32 = candidates.iter().map(|&(_, weight)| weight).sum();
+    let mut roll = rng.random::<f32>() * total;
+    for &(mv, weight) in &candidates {
+        if roll < weight {
+            return mv;
+        }
+        roll -= weight;
+    }
+    best
+}
@@ -1,13 +1,29 @@
-use crate::core::board::{Board, Player};
+use crate::ai::minimax::MAX_QUIESCENCE_DEPTH;
+use crate::core::board::{Board, Player, LINE_DIRECTIONS};
+use crate::core::captures::CaptureHandler;
+use crate::core::moves::{MoveHandler, RuleValidator};
+use crate::core::rules::RuleFlags;
 use crate::core::state::GameState;
 
 pub struct Heuristic;
 
 const WINNING_SCORE: i32 = 1_000_000;
+/// Scores at least this far from zero are forced wins or losses. Terminal scores carry
+/// the depth left, down to minus the last quiescence ply.
+pub const WIN_SCORE_THRESHOLD: i32 = WINNING_SCORE - MAX_QUIESCENCE_DEPTH;
 const FIVE_IN_ROW_SCORE: i32 = 100_000;
 const LIVE_FOUR_SINGLE_SCORE: i32 = 15_000;
 const LIVE_FOUR_MULTIPLE_SCORE: i32 = 20_000;
 const HALF_FREE_FOUR_SCORE: i32 = 5_000;
+const DOUBLE_FOUR_SCORE: i32 = 500_000;
+// One move from a double four, so just below a win. Below a made one too, the opponent
+// may be the one to move and take the point, and with room left for the rest of the
+// pattern score so that only a real win reaches `WINNING_SCORE`.
+const DOUBLE_FOUR_FORK_SCORE: i32 = WINNING_SCORE - DOUBLE_FOUR_SCORE - FOUR_THREE_SCORE;
+// A fork point needs this many own stones in two directions around it, or one more in
+// a single direction for two fours on the same line
+const FORK_MIN_STONES: usize = 3;
+const FOUR_THREE_SCORE: i32 = 100_000;
 const WINNING_THREAT_SCORE: i32 = 10_000;
 const DEAD_FOUR_SCORE: i32 = 1_000;
 const LIVE_THREE_SCORE: i32 = 500;
@@ -16,8 +32,49 @@ const DEAD_THREE_SCORE: i32 = 100;
 const LIVE_TWO_SCORE: i32 = 50;
 const HALF_FREE_TWO_SCORE: i32 = 20;
 const CAPTURE_BONUS_MULTIPLIER: i32 = 1_000;
+const CAPTURE_THREAT_SCORE: i32 = 300;
 
-const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+const CLEARLY_WINNING_SCORE: i32 = FOUR_THREE_SCORE;
+const CLEARLY_BETTER_SCORE: i32 = WINNING_THREAT_SCORE;
+const BETTER_SCORE: i32 = DEAD_FOUR_SCORE;
+const SLIGHTLY_BETTER_SCORE: i32 = HALF_FREE_THREE_SCORE;
+
+const DIRECTIONS: [(isize, isize); 4] = LINE_DIRECTIONS;
+
+/// Multi-line shapes found around an empty cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AdvancedPattern {
+    /// A stone here would make two fours at once
+    DoubleFour { row: usize, col: usize },
+}
+
+/// Kinds of threat a single stone can make, as reported by `Heuristic::threats_at`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AdvancedPatternType {
+    Five,
+    /// Two or more fours and open threes at once
+    Fork,
+    /// Four in a row, one stone from five
+    Four,
+    /// Four stones over five cells with the gap inside, like `XX_XX`
+    SplitFour,
+    /// Three that can still become an open four
+    OpenThree,
+    Capture,
+}
+
+impl AdvancedPatternType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Five => "five",
+            Self::Fork => "fork",
+            Self::Four => "four",
+            Self::SplitFour => "split four",
+            Self::OpenThree => "open three",
+            Self::Capture => "capture",
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum PatternFreedom {
@@ -26,7 +83,7 @@ enum PatternFreedom {
     Flanked,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 struct PatternCounts {
     five_in_row: u8,
     live_four: u8,
@@ -53,106 +110,404 @@ impl PatternCounts {
             half_free_two: 0,
         }
     }
+
+    fn add(&mut self, other: &Self) {
+        self.five_in_row += other.five_in_row;
+        self.live_four += other.live_four;
+        self.half_free_four += other.half_free_four;
+        self.dead_four += other.dead_four;
+        self.live_three += other.live_three;
+        self.half_free_three += other.half_free_three;
+        self.dead_three += other.dead_three;
+        self.live_two += other.live_two;
+        self.half_free_two += other.half_free_two;
+    }
+
+    fn sub(&mut self, other: &Self) {
+        self.five_in_row -= other.five_in_row;
+        self.live_four -= other.live_four;
+        self.half_free_four -= other.half_free_four;
+        self.dead_four -= other.dead_four;
+        self.live_three -= other.live_three;
+        self.half_free_three -= other.half_free_three;
+        self.dead_three -= other.dead_three;
+        self.live_two -= other.live_two;
+        self.half_free_two -= other.half_free_two;
+    }
+}
+
+/// Every board line once, as the `(direction, index)` of `Board::line_bits`
+fn board_lines(board: &Board) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..DIRECTIONS.len()).flat_map(move |dir| (0..board.line_count(dir)).map(move |index| (dir, index)))
+}
+
+/// Line-pattern counts of a position, kept up to date as moves are made and undone so
+/// the evaluation does not rescan the board. A move recounts only the lines through the
+/// cells it changed (the stone and any captures) and saves their old counts, which its
+/// undo puts back. The fork points of `detect_double_four` are kept the same way, a
+/// move only rechecks the cells near the ones it changed.
+///
+/// It remembers the position its counts describe. A board or rules edited directly,
+/// without `make_move`, is noticed and the counts are rebuilt from scratch.
+#[derive(Debug, Clone, Default)]
+pub struct LineEval {
+    /// Counts of each board line, Max then Min, by direction and index like `Board::line_bits`
+    lines: [Vec<[PatternCounts; 2]>; 4],
+    totals: [PatternCounts; 2],
+    /// Lines a move recounted with their counts before it, and where each move's entries start
+    saved: Vec<((usize, usize), [PatternCounts; 2])>,
+    frames: Vec<usize>,
+    /// Fork points of Max then Min, see `Heuristic::detect_double_four`, and their
+    /// lists before each move
+    forks: [Vec<(usize, usize)>; 2],
+    saved_forks: Vec<[Vec<(usize, usize)>; 2]>,
+    max_bits: Vec<u64>,
+    min_bits: Vec<u64>,
+    win_condition: usize,
+    rules: RuleFlags,
+}
+
+// Only a cache of the board: states with the same position are equal whatever it holds
+impl PartialEq for LineEval {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for LineEval {}
+
+impl std::hash::Hash for LineEval {
+    fn hash<H: std::hash::Hasher>(&self, _: &mut H) {}
+}
+
+impl LineEval {
+    /// Whether the counts describe this exact position
+    pub fn is_current(&self, board: &Board, win_condition: usize, rules: &RuleFlags) -> bool {
+        self.win_condition == win_condition
+            && self.rules == *rules
+            && self.max_bits == board.max_bits()
+            && self.min_bits == board.min_bits()
+    }
+
+    pub fn rebuild(&mut self, board: &Board, win_condition: usize, rules: &RuleFlags) {
+        self.lines = std::array::from_fn(|dir| vec![[PatternCounts::new(); 2]; board.line_count(dir)]);
+        self.totals = [PatternCounts::new(); 2];
+        self.saved.clear();
+        self.frames.clear();
+        self.forks = [Player::Max, Player::Min].map(|player| Heuristic::fork_points(board, player, win_condition));
+        self.saved_forks.clear();
+        for (dir, index) in board_lines(board) {
+            let counts = Heuristic::line_counts(board, dir, index, win_condition, rules);
+            self.totals[0].add(&counts[0]);
+            self.totals[1].add(&counts[1]);
+            self.lines[dir][index] = counts;
+        }
+        self.remember(board, win_condition, rules);
+    }
+
+    /// Brings the counts to `board` after a move changed the cells in `changed`.
+    /// `was_current` is `is_current` for the position before the move; without it the
+    /// counts are rebuilt.
+    pub fn after_move(
+        &mut self,
+        was_current: bool,
+        board: &Board,
+        changed: impl Iterator<Item = (usize, usize)>,
+        win_condition: usize,
+        rules: &RuleFlags,
+    ) {
+        if !was_current {
+            self.rebuild(board, win_condition, rules);
+            return;
+        }
+
+        let changed: Vec<(usize, usize)> = changed.collect();
+        let frame = self.saved.len();
+        for &(row, col) in &changed {
+            for dir in 0..DIRECTIONS.len() {
+                let (index, _) = board.line_position(row, col, dir);
+                if self.saved[frame..].iter().any(|&(saved, _)| saved == (dir, index)) {
+                    continue;
+                }
+                let counts = Heuristic::line_counts(board, dir, index, win_condition, rules);
+                self.saved.push(((dir, index), self.lines[dir][index]));
+                self.set_line((dir, index), counts);
+            }
+        }
+        self.frames.push(frame);
+        self.saved_forks.push(self.forks.clone());
+        self.update_forks(board, &changed, win_condition);
+        self.remember(board, win_condition, rules);
+    }
+
+    /// Brings the counts back to `board` after the last move was undone, restoring the
+    /// counts that move saved. Falls back to recounting the lines through `changed`
+    /// when nothing was saved, and to a rebuild when the counts were not current.
+    pub fn after_undo(
+        &mut self,
+        was_current: bool,
+        board: &Board,
+        changed: impl Iterator<Item = (usize, usize)>,
+        win_condition: usize,
+        rules: &RuleFlags,
+    ) {
+        if !was_current {
+            self.rebuild(board, win_condition, rules);
+            return;
+        }
+
+        match self.frames.pop() {
+            Some(frame) => {
+                while self.saved.len() > frame {
+                    if let Some((line, counts)) = self.saved.pop() {
+                        self.set_line(line, counts);
+                    }
+                }
+                if let Some(forks) = self.saved_forks.pop() {
+                    self.forks = forks;
+                }
+                self.remember(board, win_condition, rules);
+            }
+            None => {
+                self.after_move(true, board, changed, win_condition, rules);
+                self.saved.clear();
+                self.frames.clear();
+                self.saved_forks.clear();
+            }
+        }
+    }
+
+    /// Whether `player` has a fork point, when the counts are current
+    pub fn has_fork(&self, player: Player) -> bool {
+        match player {
+            Player::Max => !self.forks[0].is_empty(),
+            Player::Min => !self.forks[1].is_empty(),
+        }
+    }
+
+    /// Rechecks the cells whose fours the `changed` cells can alter: those on a line
+    /// through one of them, close enough to reach it or the cell past a five
+    fn update_forks(&mut self, board: &Board, changed: &[(usize, usize)], win_condition: usize) {
+        let reach = win_condition as isize;
+        for &(row, col) in changed {
+            for &(dr, dc) in &DIRECTIONS {
+                for k in -reach..=reach {
+                    let (r, c) = (row as isize + dr * k, col as isize + dc * k);
+                    if !board.in_bounds(r, c) {
+                        continue;
+                    }
+                    let cell = (r as usize, c as usize);
+                    for (forks, player) in self.forks.iter_mut().zip([Player::Max, Player::Min]) {
+                        let is_fork = board.is_empty_position(cell.0, cell.1)
+                            && Heuristic::has_fork_stones(board, cell, player, win_condition)
+                            && RuleValidator::count_fours(board, cell.0, cell.1, player, win_condition) >= 2;
+                        match forks.iter().position(|&fork| fork == cell) {
+                            Some(i) if !is_fork => {
+                                forks.swap_remove(i);
+                            }
+                            None if is_fork => forks.push(cell),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn set_line(&mut self, (dir, index): (usize, usize), counts: [PatternCounts; 2]) {
+        for ((total, old), new) in self.totals.iter_mut().zip(&self.lines[dir][index]).zip(&counts) {
+            total.sub(old);
+            total.add(new);
+        }
+        self.lines[dir][index] = counts;
+    }
+
+    fn remember(&mut self, board: &Board, win_condition: usize, rules: &RuleFlags) {
+        self.max_bits.clear();
+        self.max_bits.extend_from_slice(board.max_bits());
+        self.min_bits.clear();
+        self.min_bits.extend_from_slice(board.min_bits());
+        self.win_condition = win_condition;
+        self.rules = *rules;
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 struct PatternInfo {
     length: usize,
     freedom: PatternFreedom,
+    /// Longer than `win_condition` before clamping
+    overline: bool,
 }
 
 impl Heuristic {
     pub fn evaluate(state: &GameState, depth: i32) -> i32 {
-        if let Some(winner) = state.check_winner() {
-            return match winner {
-                Player::Max => WINNING_SCORE + depth,
-                Player::Min => -WINNING_SCORE - depth,
-            };
+        if let Some(winner) = state.check_winner().or_else(|| state.check_capture_win()) {
+            return Self::terminal_score(winner, depth);
         }
 
-        if state.max_captures >= 5 {
-            return WINNING_SCORE + depth;
-        }
-        if state.min_captures >= 5 {
-            return -WINNING_SCORE - depth;
-        }
-
-        if state.board.is_full() {
+        if state.is_draw() {
             return 0;
         }
 
-        let (max_counts, min_counts) =
-            Self::analyze_both_players(&state.board, state.win_condition);
+        let [max_counts, min_counts] = Self::line_pattern_counts(state);
 
         if max_counts.five_in_row > 0 || max_counts.live_four > 1 {
-            return WINNING_SCORE + depth;
+            return Self::terminal_score(Player::Max, depth);
         }
         if min_counts.five_in_row > 0 || min_counts.live_four > 1 {
-            return -WINNING_SCORE - depth;
+            return Self::terminal_score(Player::Min, depth);
         }
 
-        let max_score = Self::calculate_pattern_score(max_counts);
-        let min_score = Self::calculate_pattern_score(min_counts);
+        let max_score = Self::calculate_pattern_score(max_counts) + Self::calculate_advanced_pattern_score(state, Player::Max);
+        let min_score = Self::calculate_pattern_score(min_counts) + Self::calculate_advanced_pattern_score(state, Player::Min);
         let capture_bonus = Self::calculate_capture_bonus(state);
         let historical_bonus = Self::calculate_historical_bonus(state);
 
         max_score - min_score + capture_bonus + historical_bonus
     }
 
+    /// Score of a game `winner` has won, five in a row or captures alike. `depth` is the
+    /// search depth left when the win is reached, negative below the horizon, so a win
+    /// found sooner scores further from zero and the search goes for the quickest one.
+    pub fn terminal_score(winner: Player, depth: i32) -> i32 {
+        match winner {
+            Player::Max => WINNING_SCORE + depth,
+            Player::Min => -WINNING_SCORE - depth,
+        }
+    }
+
+    /// Panics unless the colour-swapped position scores the exact negation of `state`.
+    /// The move-history bonus is left out on both sides, it rewards the side to move
+    /// rather than a colour, and renju positions are skipped since renju only binds black.
+    #[cfg(debug_assertions)]
+    pub fn assert_antisymmetric(state: &GameState) {
+        if state.rules.renju {
+            return;
+        }
+        let mut original = state.clone();
+        original.pattern_analyzer.set_enabled(false);
+        let swapped = Self::color_swapped(&original);
+        let (score, swapped_score) = (Self::evaluate(&original, 0), Self::evaluate(&swapped, 0));
+        assert_eq!(
+            swapped_score,
+            -score,
+            "evaluation is not colour-symmetric:\n{}",
+            state.to_ascii()
+        );
+    }
+
+    /// `state` with every stone, capture count, the side to move and any winner handed to
+    /// the other colour
+    #[cfg(debug_assertions)]
+    fn color_swapped(state: &GameState) -> GameState {
+        let mut swapped = state.clone();
+        swapped.board.edit_bits(|max, min, _| max.swap_with_slice(min));
+        std::mem::swap(&mut swapped.max_captures, &mut swapped.min_captures);
+        swapped.current_player = state.current_player.opponent();
+        swapped.winner = state.winner.map(|winner| winner.opponent());
+        swapped
+    }
+
+    /// Number of plies until the forced win behind a terminal score, given the depth the
+    /// search was started at. Terminal scores carry the remaining depth, so a win found
+    /// one ply below the root of a depth-4 search scores `WINNING_SCORE + 3`.
+    pub fn mate_in(score: i32, search_depth: i32) -> Option<i32> {
+        let magnitude = score.checked_abs()?;
+        if magnitude < WINNING_SCORE {
+            return None;
+        }
+        let remaining_depth = magnitude - WINNING_SCORE;
+        Some((search_depth - remaining_depth).max(1))
+    }
+
+    /// Describes a score (positive favours Max) in words for the HUD.
+    pub fn score_to_human(score: i32, search_depth: i32) -> String {
+        let side = if score >= 0 { "Max" } else { "Min" };
+        let magnitude = score.saturating_abs();
+
+        if let Some(plies) = Self::mate_in(score, search_depth) {
+            return format!("{} mate in {}", side, (plies + 1) / 2);
+        }
+
+        match magnitude {
+            m if m >= CLEARLY_WINNING_SCORE => format!("{} winning", side),
+            m if m >= CLEARLY_BETTER_SCORE => format!("{} clearly better", side),
+            m if m >= BETTER_SCORE => format!("{} better", side),
+            m if m >= SLIGHTLY_BETTER_SCORE => format!("{} slightly better", side),
+            _ => "roughly equal".to_string(),
+        }
+    }
+
     fn calculate_historical_bonus(state: &GameState) -> i32 {
         state.pattern_analyzer.calculate_historical_bonus(state)
     }
 
-    fn analyze_both_players(board: &Board, win_condition: usize) -> (PatternCounts, PatternCounts) {
-        let mut max_counts = PatternCounts::new();
-        let mut min_counts = PatternCounts::new();
-        let mut analyzed = vec![vec![0u8; board.size]; board.size];
+    /// Pattern part of the evaluation (Max's line shapes minus Min's), taken from the
+    /// counts `GameState` keeps up to date move by move
+    pub fn line_score(state: &GameState) -> i32 {
+        let [max_counts, min_counts] = Self::line_pattern_counts(state);
+        Self::calculate_pattern_score(max_counts) - Self::calculate_pattern_score(min_counts)
+    }
 
-        for row in 0..board.size {
-            for col in 0..board.size {
-                let idx = board.index(row, col);
-                if !Board::is_bit_set(&board.occupied, idx) {
-                    continue;
-                }
-                
-                let player = if Board::is_bit_set(&board.max_bits, idx) {
-                    Player::Max
-                } else {
-                    Player::Min
+    /// Same as `line_score`, recomputed with a full board scan
+    pub fn line_score_full_scan(state: &GameState) -> i32 {
+        let [max_counts, min_counts] = Self::analyze_both_players(&state.board, state.win_condition, &state.rules);
+        Self::calculate_pattern_score(max_counts) - Self::calculate_pattern_score(min_counts)
+    }
+
+    fn line_pattern_counts(state: &GameState) -> [PatternCounts; 2] {
+        if state.line_eval.is_current(&state.board, state.win_condition, &state.rules) {
+            state.line_eval.totals
+        } else {
+            Self::analyze_both_players(&state.board, state.win_condition, &state.rules)
+        }
+    }
+
+    /// Line-pattern counts of the whole board, for Max then Min: the sum of every
+    /// board line's `line_counts`
+    fn analyze_both_players(board: &Board, win_condition: usize, rules: &RuleFlags) -> [PatternCounts; 2] {
+        let mut totals = [PatternCounts::new(); 2];
+        for (dir, index) in board_lines(board) {
+            let counts = Self::line_counts(board, dir, index, win_condition, rules);
+            totals[0].add(&counts[0]);
+            totals[1].add(&counts[1]);
+        }
+        totals
+    }
+
+    /// Counts of the runs on line `index` of `DIRECTIONS[dir]`, see `Board::line_bits`.
+    /// Each run of two or more stones is scored once, from its first stone.
+    fn line_counts(board: &Board, dir: usize, index: usize, win_condition: usize, rules: &RuleFlags) -> [PatternCounts; 2] {
+        let mut counts = [PatternCounts::new(); 2];
+        let (dx, dy) = DIRECTIONS[dir];
+        let line = board.line_bits(dir, index);
+
+        // Runs begin at a stone not preceded by one of the same colour
+        let mut runs = (line.max & !(line.max << 1)) | (line.min & !(line.min << 1));
+        while runs != 0 {
+            let position = runs.trailing_zeros() as usize;
+            runs &= runs - 1;
+            let (row, col) = board.line_cell(dir, index, position);
+            let Some(player) = line.player_at(position) else {
+                continue;
+            };
+            // An overline that does not win is just a dead line
+            if let Some(pattern_info) = Self::analyze_pattern(board, row, col, dx, dy, player, win_condition)
+                && (!pattern_info.overline || rules.allows_overline(player))
+            {
+                let side = match player {
+                    Player::Max => 0,
+                    Player::Min => 1,
                 };
-                
-                for (dir_idx, &(dx, dy)) in DIRECTIONS.iter().enumerate() {
-                    let bit_mask = 1u8 << dir_idx;
-
-                    if analyzed[row][col] & bit_mask == 0 {
-                        if let Some(pattern_info) = Self::analyze_pattern(
-                            board,
-                            row,
-                            col,
-                            dx,
-                            dy,
-                            player,
-                            win_condition,
-                            &mut analyzed,
-                            bit_mask,
-                        ) {
-                            match player {
-                                Player::Max => {
-                                    Self::update_counts(&mut max_counts, pattern_info)
-                                }
-                                Player::Min => {
-                                    Self::update_counts(&mut min_counts, pattern_info)
-                                }
-                            }
-                        }
-                    }
-                }
+                Self::update_counts(&mut counts[side], pattern_info);
             }
         }
-
-        (max_counts, min_counts)
+        counts
     }
 
+    /// Shape of the run of `player` stones starting at `(start_row, start_col)`, or `None`
+    /// when it is a lone stone or has no room left to become a winning line
     fn analyze_pattern(
         board: &Board,
         start_row: usize,
@@ -161,63 +516,24 @@ impl Heuristic {
         dy: isize,
         player: Player,
         win_condition: usize,
-        analyzed: &mut [Vec<u8>],
-        bit_mask: u8,
     ) -> Option<PatternInfo> {
-        let (pattern_start_row, pattern_start_col) =
-            Self::find_pattern_start(board, start_row, start_col, dx, dy, player);
-
-        if analyzed[pattern_start_row][pattern_start_col] & bit_mask != 0 {
-            return None;
-        }
-
-        let length =
-            Self::count_consecutive(board, pattern_start_row, pattern_start_col, dx, dy, player);
+        let length = Self::count_consecutive(board, start_row, start_col, dx, dy, player);
 
         if length < 2 {
             return None;
         }
 
+        let overline = length > win_condition;
         let length = length.min(win_condition);
         
         // Check if this pattern has sufficient space to develop into a winning line
-        if !Self::has_sufficient_space(
-            board,
-            pattern_start_row,
-            pattern_start_col,
-            dx,
-            dy,
-            length,
-            player,
-            win_condition,
-        ) {
-            // Mark as analyzed but don't score it
-            Self::mark_pattern_analyzed(
-                pattern_start_row,
-                pattern_start_col,
-                dx,
-                dy,
-                length,
-                analyzed,
-                bit_mask,
-            );
+        if !Self::has_sufficient_space(board, start_row, start_col, dx, dy, length, player, win_condition) {
             return None;
         }
         
-        let freedom =
-            Self::analyze_pattern_freedom(board, pattern_start_row, pattern_start_col, dx, dy, length);
-
-        Self::mark_pattern_analyzed(
-            pattern_start_row,
-            pattern_start_col,
-            dx,
-            dy,
-            length,
-            analyzed,
-            bit_mask,
-        );
+        let freedom = Self::analyze_pattern_freedom(board, start_row, start_col, dx, dy, length);
 
-        Some(PatternInfo { length, freedom })
+        Some(PatternInfo { length, freedom, overline })
     }
 
     fn count_consecutive(
@@ -229,18 +545,14 @@ impl Heuristic {
         player: Player,
     ) -> usize {
         let player_bits = match player {
-            Player::Max => &board.max_bits,
-            Player::Min => &board.min_bits,
+            Player::Max => board.max_bits(),
+            Player::Min => board.min_bits(),
         };
         let mut count = 0;
         let mut current_row = row as isize;
         let mut current_col = col as isize;
 
-        while current_row >= 0
-            && current_row < board.size as isize
-            && current_col >= 0
-            && current_col < board.size as isize
-        {
+        while board.in_bounds(current_row, current_col) {
             let idx = board.index(current_row as usize, current_col as usize);
             if Board::is_bit_set(player_bits, idx) {
                 count += 1;
@@ -253,45 +565,6 @@ impl Heuristic {
         count
     }
 
-    fn find_pattern_start(
-        board: &Board,
-        row: usize,
-        col: usize,
-        dx: isize,
-        dy: isize,
-        player: Player,
-    ) -> (usize, usize) {
-        let player_bits = match player {
-            Player::Max => &board.max_bits,
-            Player::Min => &board.min_bits,
-        };
-        let mut current_row = row as isize;
-        let mut current_col = col as isize;
-
-        loop {
-            let prev_row = current_row - dx;
-            let prev_col = current_col - dy;
-
-            if prev_row >= 0
-                && prev_row < board.size as isize
-                && prev_col >= 0
-                && prev_col < board.size as isize
-            {
-                let idx = board.index(prev_row as usize, prev_col as usize);
-                if Board::is_bit_set(player_bits, idx) {
-                    current_row = prev_row;
-                    current_col = prev_col;
-                } else {
-                    break;
-                }
-            } else {
-                break;
-            }
-        }
-
-        (current_row as usize, current_col as usize)
-    }
-
     fn analyze_pattern_freedom(
         board: &Board,
         start_row: usize,
@@ -317,11 +590,11 @@ impl Heuristic {
 
     #[inline(always)]
     fn is_position_empty(board: &Board, row: isize, col: isize) -> bool {
-        if row < 0 || col < 0 || row >= board.size as isize || col >= board.size as isize {
+        if !board.in_bounds(row, col) {
             return false;
         }
         let idx = board.index(row as usize, col as usize);
-        !Board::is_bit_set(&board.occupied, idx)
+        !Board::is_bit_set(board.occupied(), idx)
     }
 
     fn has_sufficient_space(
@@ -334,94 +607,13 @@ impl Heuristic {
         player: Player,
         win_condition: usize,
     ) -> bool {
-        let player_bits = match player {
-            Player::Max => &board.max_bits,
-            Player::Min => &board.min_bits,
-        };
-        let opponent_bits = match player {
-            Player::Max => &board.min_bits,
-            Player::Min => &board.max_bits,
-        };
-
-        // Count total available space in both directions from the pattern
-        let mut total_space = length; // Current pattern length
-        
-        // Count backwards from pattern start
-        let mut pos_row = start_row as isize - dx;
-        let mut pos_col = start_col as isize - dy;
-        let mut backward_space = 0;
-        
-        while pos_row >= 0 
-            && pos_row < board.size as isize 
-            && pos_col >= 0 
-            && pos_col < board.size as isize 
-            && backward_space < win_condition
-        {
-            let idx = board.index(pos_row as usize, pos_col as usize);
-            
-            // Stop if we hit an opponent stone
-            if Board::is_bit_set(opponent_bits, idx) {
-                break;
-            }
-            
-            // Count empty spaces and our own stones
-            if !Board::is_bit_set(&board.occupied, idx) || Board::is_bit_set(player_bits, idx) {
-                backward_space += 1;
-                pos_row -= dx;
-                pos_col -= dy;
-            } else {
-                break;
-            }
-        }
-        
-        // Count forwards from pattern end
-        let mut pos_row = start_row as isize + (length as isize * dx);
-        let mut pos_col = start_col as isize + (length as isize * dy);
-        let mut forward_space = 0;
-        
-        while pos_row >= 0 
-            && pos_row < board.size as isize 
-            && pos_col >= 0 
-            && pos_col < board.size as isize 
-            && forward_space < win_condition
-        {
-            let idx = board.index(pos_row as usize, pos_col as usize);
-            
-            // Stop if we hit an opponent stone
-            if Board::is_bit_set(opponent_bits, idx) {
-                break;
-            }
-            
-            // Count empty spaces and our own stones
-            if !Board::is_bit_set(&board.occupied, idx) || Board::is_bit_set(player_bits, idx) {
-                forward_space += 1;
-                pos_row += dx;
-                pos_col += dy;
-            } else {
-                break;
-            }
-        }
-        
-        total_space += backward_space + forward_space;
-        total_space >= win_condition
-    }
-
-    fn mark_pattern_analyzed(
-        start_row: usize,
-        start_col: usize,
-        dx: isize,
-        dy: isize,
-        length: usize,
-        analyzed: &mut [Vec<u8>],
-        bit_mask: u8,
-    ) {
-        for i in 0..length {
-            let row = (start_row as isize + i as isize * dx) as usize;
-            let col = (start_col as isize + i as isize * dy) as usize;
-            if row < analyzed.len() && col < analyzed[0].len() {
-                analyzed[row][col] |= bit_mask;
-            }
-        }
+        // Room before the pattern's first stone and after its last one, the same count
+        // move generation uses to skip lines that can never reach five
+        let end_row = (start_row as isize + (length as isize - 1) * dx) as usize;
+        let end_col = (start_col as isize + (length as isize - 1) * dy) as usize;
+        let backward = MoveHandler::room_from(board, start_row, start_col, player, -dx, -dy, win_condition);
+        let forward = MoveHandler::room_from(board, end_row, end_col, player, dx, dy, win_condition);
+        length + backward + forward >= win_condition
     }
 
     fn update_counts(counts: &mut PatternCounts, pattern: PatternInfo) {
@@ -459,12 +651,14 @@ impl Heuristic {
             _ => 0,
         };
 
-        if counts.live_three >= 2
-            || counts.dead_four >= 2
-            || (counts.dead_four >= 1 && counts.live_three >= 1)
-            || (counts.half_free_four >= 1 && counts.live_three >= 1)
-            || (counts.half_free_four >= 2)
-        {
+        // Two fours can't both be blocked, and a four alongside an open three leaves the
+        // three free to become an open four while the opponent answers the four.
+        let fours = counts.live_four + counts.half_free_four + counts.dead_four;
+        if fours >= 2 {
+            score += DOUBLE_FOUR_SCORE;
+        } else if fours >= 1 && counts.live_three >= 1 {
+            score += FOUR_THREE_SCORE;
+        } else if counts.live_three >= 2 {
             score += WINNING_THREAT_SCORE;
         }
 
@@ -479,7 +673,180 @@ impl Heuristic {
         score
     }
 
+    /// What a `player` stone on `(row, col)` would make, strongest first and each kind
+    /// once, when `win_condition` in a row wins. Captures are listed whatever the rules,
+    /// an occupied cell makes nothing.
+    pub fn threats_at(
+        board: &Board,
+        row: usize,
+        col: usize,
+        player: Player,
+        win_condition: usize,
+    ) -> Vec<AdvancedPatternType> {
+        if !board.is_empty_position(row, col) {
+            return Vec::new();
+        }
+        let lengths = MoveHandler::line_lengths_through(board, row, col, player);
+        let fours = RuleValidator::four_directions(board, row, col, player, win_condition);
+        let open_threes = RuleValidator::count_free_threes(board, row, col, player);
+        let is_four = |dir: usize| fours[dir] && lengths[dir] == win_condition - 1;
+        let is_split_four = |dir: usize| fours[dir] && lengths[dir] < win_condition - 1;
+        let four_count = RuleValidator::count_fours(board, row, col, player, win_condition);
+
+        let mut threats = Vec::new();
+        if lengths.iter().any(|&length| length >= win_condition) {
+            threats.push(AdvancedPatternType::Five);
+        }
+        if four_count + open_threes >= 2 {
+            threats.push(AdvancedPatternType::Fork);
+        }
+        if (0..4).any(is_four) {
+            threats.push(AdvancedPatternType::Four);
+        }
+        if (0..4).any(is_split_four) {
+            threats.push(AdvancedPatternType::SplitFour);
+        }
+        if open_threes > 0 {
+            threats.push(AdvancedPatternType::OpenThree);
+        }
+        if !CaptureHandler::detect_captures(board, row, col, player).is_empty() {
+            threats.push(AdvancedPatternType::Capture);
+        }
+        threats
+    }
+
+    /// Empty cells where a `player` stone would make two distinct fours
+    pub fn detect_double_four(board: &Board, player: Player, win_condition: usize) -> Vec<AdvancedPattern> {
+        Self::fork_points(board, player, win_condition)
+            .into_iter()
+            .map(|(row, col)| AdvancedPattern::DoubleFour { row, col })
+            .collect()
+    }
+
+    fn fork_points(board: &Board, player: Player, win_condition: usize) -> Vec<(usize, usize)> {
+        Self::fork_candidates(board, player, win_condition)
+            .into_iter()
+            .filter(|&(row, col)| RuleValidator::count_fours(board, row, col, player, win_condition) >= 2)
+            .collect()
+    }
+
+    /// `DOUBLE_FOUR_FORK_SCORE` if `player` has a fork point. The search's states keep
+    /// their fork points up to date in `line_eval`, anything else is scanned.
+    fn calculate_advanced_pattern_score(state: &GameState, player: Player) -> i32 {
+        let has_fork = if state.line_eval.is_current(&state.board, state.win_condition, &state.rules) {
+            state.line_eval.has_fork(player)
+        } else {
+            !Self::fork_points(&state.board, player, state.win_condition).is_empty()
+        };
+        if has_fork { DOUBLE_FOUR_FORK_SCORE } else { 0 }
+    }
+
+    /// The `fork_candidates` test for a single cell, read off the board's line bits
+    fn has_fork_stones(board: &Board, (row, col): (usize, usize), player: Player, win_condition: usize) -> bool {
+        let reach = win_condition - 1;
+        let mut stones = [0u32; 4];
+        for (dir, count) in stones.iter_mut().enumerate() {
+            let (index, position) = board.line_position(row, col, dir);
+            let line = board.line_bits(dir, index);
+            let own = match player {
+                Player::Max => line.max,
+                Player::Min => line.min,
+            };
+            let window = (u64::MAX >> (63 - 2 * reach)) << position >> reach;
+            *count = (own & window).count_ones();
+        }
+        stones.iter().filter(|&&count| count as usize >= FORK_MIN_STONES).count() >= 2
+            || stones.iter().any(|&count| count as usize > FORK_MIN_STONES)
+    }
+
+    /// Cheap filter for `detect_double_four`: empty cells with enough own stones within
+    /// reach, see `FORK_MIN_STONES`. Walks out from the player's stones, which are far fewer
+    /// than the empty cells.
+    fn fork_candidates(board: &Board, player: Player, win_condition: usize) -> Vec<(usize, usize)> {
+        let bits = match player {
+            Player::Max => board.max_bits(),
+            Player::Min => board.min_bits(),
+        };
+        let stones: u32 = bits.iter().map(|word| word.count_ones()).sum();
+        if (stones as usize) <= FORK_MIN_STONES {
+            return Vec::new();
+        }
+
+        let reach = win_condition as isize - 1;
+        let mut nearby = vec![[0u8; 4]; board.total_cells];
+        for (word_idx, &word) in bits.iter().enumerate() {
+            let mut word = word;
+            while word != 0 {
+                let idx = word_idx * 64 + word.trailing_zeros() as usize;
+                word &= word - 1;
+                let (row, col) = ((idx / board.cols) as isize, (idx % board.cols) as isize);
+                for (dir, &(dr, dc)) in DIRECTIONS.iter().enumerate() {
+                    for k in (-reach..=reach).filter(|&k| k != 0) {
+                        let (r, c) = (row + dr * k, col + dc * k);
+                        if Self::is_position_empty(board, r, c) {
+                            nearby[board.index(r as usize, c as usize)][dir] += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        nearby
+            .iter()
+            .enumerate()
+            .filter(|(_, counts)| {
+                counts.iter().filter(|&&count| count as usize >= FORK_MIN_STONES).count() >= 2
+                    || counts.iter().any(|&count| count as usize > FORK_MIN_STONES)
+            })
+            .map(|(idx, _)| (idx / board.cols, idx % board.cols))
+            .collect()
+    }
+
     fn calculate_capture_bonus(state: &GameState) -> i32 {
-        (state.max_captures as i32 - state.min_captures as i32) * CAPTURE_BONUS_MULTIPLIER
+        if !state.rules.captures_enabled {
+            return 0;
+        }
+        let taken = (state.max_captures as i32 - state.min_captures as i32) * CAPTURE_BONUS_MULTIPLIER;
+        let threats = Self::scaled_capture_threats(state, Player::Max, state.max_captures)
+            - Self::scaled_capture_threats(state, Player::Min, state.min_captures);
+        taken + threats
+    }
+
+    /// Capture threats weigh more the closer their owner is to a capture win: from their
+    /// face value with no pair taken up to `capture_win_pairs` times it one pair short
+    fn scaled_capture_threats(state: &GameState, player: Player, captures: usize) -> i32 {
+        let pairs_left = state.capture_win_pairs.saturating_sub(captures).max(1) as i32;
+        Self::evaluate_capture_threats(&state.board, player) * state.capture_win_pairs as i32 / pairs_left
+    }
+
+    /// `CAPTURE_THREAT_SCORE` for each opponent pair `player` could capture with their
+    /// next stone, an `X O O _` line with the gap empty
+    pub fn evaluate_capture_threats(board: &Board, player: Player) -> i32 {
+        let (own, opponent) = match player {
+            Player::Max => (board.max_bits(), board.min_bits()),
+            Player::Min => (board.min_bits(), board.max_bits()),
+        };
+        let mut threats = 0;
+        for (word_idx, &word) in own.iter().enumerate() {
+            let mut word = word;
+            while word != 0 {
+                let idx = word_idx * 64 + word.trailing_zeros() as usize;
+                word &= word - 1;
+                let (row, col) = ((idx / board.cols) as isize, (idx % board.cols) as isize);
+                for &(dr, dc) in &DIRECTIONS {
+                    for sign in [1, -1] {
+                        let (dr, dc) = (dr * sign, dc * sign);
+                        let flanked = (1..=2).all(|k| {
+                            let (r, c) = (row + dr * k, col + dc * k);
+                            board.in_bounds(r, c) && Board::is_bit_set(opponent, board.index(r as usize, c as usize))
+                        });
+                        if flanked && Self::is_position_empty(board, row + dr * 3, col + dc * 3) {
+                            threats += 1;
+                        }
+                    }
+                }
+            }
+        }
+        threats * CAPTURE_THREAT_SCORE
     }
 }
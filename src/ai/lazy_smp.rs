@@ -1,60 +1,156 @@
+use crate::core::board::Player;
+use crate::core::moves::ThreatLevel;
 use crate::core::state::GameState;
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc::Sender, Arc, Mutex};
 use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use rayon::prelude::*;
+use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
 
-use super::{minimax::mtdf, transposition::TranspositionTable};
+use super::{difficulty::pick_by_temperature, heuristic::{Heuristic, WIN_SCORE_THRESHOLD}, minimax::{aspiration_with_context, mtdf_with_context, SearchContext}, move_ordering::MoveHistory, transposition::{SharedTranspositionTable, TranspositionTable}};
 
 /// Search result structure
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct SearchResult {
     pub best_move: Option<(usize, usize)>,
     pub score: i32,
     pub depth_reached: i32,
     pub nodes_searched: u64,
     pub time_elapsed: Duration,
+    /// Expected reply to `best_move`, the next move of the principal variation
+    pub ponder_move: Option<(usize, usize)>,
+    /// Line the search expects, starting with `best_move`
+    pub pv: Vec<(usize, usize)>,
+    /// Nodes per second over the whole search
+    pub nps: u64,
+    /// For each depth the main thread completed: time since the start and nodes searched
+    /// so far by all threads
+    pub depth_times: Vec<(i32, Duration, u64)>,
+}
+
+/// One depth completed by the main search thread, sent while the search goes on
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthProgress {
+    pub depth: i32,
+    pub score: i32,
+    /// Line the search expects at this depth, starting with its best move
+    pub pv: Vec<(usize, usize)>,
+}
+
+/// Parameters for one AI move search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchConfig {
+    pub max_depth: i32,
+    pub time_limit: Option<Duration>,
+    pub num_threads: Option<usize>,
+    /// Seed for tie-breaking between equally rated moves. Only a single-thread search
+    /// bounded by depth or `node_limit` reproduces its move from the seed: with more
+    /// threads or a time limit, timing decides which iteration's move is kept, and the
+    /// seed just varies the order equal moves are tried in.
+    pub seed: Option<u64>,
+    /// Transposition table size of each search thread, in megabytes
+    pub tt_size_mb: usize,
+    /// Whether the evaluation adds the move-history pattern bonus
+    pub advanced_patterns: bool,
+    /// Whether the threads share one transposition table of `tt_size_mb` instead of
+    /// each filling their own
+    pub shared_tt: bool,
+    /// Threat mode: above this threat level the search only looks at moves that extend or
+    /// block fours and fives, `None` always uses the regular candidates
+    pub threat_space_above: Option<ThreatLevel>,
+    /// Search each iteration in a window around the previous score that widens on a fail,
+    /// instead of MTD(f)'s zero-window probes
+    pub aspiration_windows: bool,
+    /// Nodes each search thread may visit, quiescence included. Unlike the time limit this
+    /// gives the same search on every machine, with a single thread.
+    pub node_limit: Option<u64>,
+    /// Variety in the moves played: 0 always plays the best move found, higher values
+    /// sample among near-best root moves, see `pick_by_temperature`. Sampled from `seed`
+    /// when set, so seeded games stay reproducible.
+    pub temperature: f32,
+    /// Search quiet moves late in the ordering one ply shallower, with a full re-search
+    /// when one turns out better than the moves before it
+    pub late_move_reductions: bool,
+    /// Forcing moves (captures, and moves making or blocking a four) searched a ply
+    /// deeper, at most this many times along one line. 0 disables the extension.
+    pub forcing_extensions: i32,
+}
+
+pub const DEFAULT_TT_SIZE_MB: usize = 64;
+/// Longest principal variation reported in a `SearchResult`
+pub const MAX_PV_LENGTH: usize = 32;
+
+impl SearchConfig {
+    pub fn new(max_depth: i32, time_limit: Option<Duration>) -> Self {
+        Self {
+            max_depth,
+            time_limit,
+            num_threads: None,
+            seed: None,
+            tt_size_mb: DEFAULT_TT_SIZE_MB,
+            advanced_patterns: true,
+            shared_tt: false,
+            threat_space_above: None,
+            aspiration_windows: false,
+            node_limit: None,
+            temperature: 0.0,
+            late_move_reductions: false,
+            forcing_extensions: 0,
+        }
+    }
 }
 
 /// Shared search state for Lazy SMP
 pub struct SharedSearchState {
+    /// Whether the side to move at the root is Max, which decides which scores are better
+    pub maximizing: bool,
     pub best_move: Mutex<Option<(usize, usize)>>,
+    /// Starts at the worst score for the side to move, see `worst_score`
     pub best_score: AtomicI32,
     pub nodes_searched: AtomicU64,
     pub depth_reached: AtomicI32,
     pub stop_search: AtomicBool,
+    /// Cutoff history each worker starts its move ordering from
+    pub history: MoveHistory,
+    /// Where the main worker reports each depth it completes
+    pub progress: Option<Sender<DepthProgress>>,
 }
 
 impl SharedSearchState {
-    pub fn new() -> Self {
+    pub fn new(maximizing: bool) -> Self {
         Self {
+            maximizing,
             best_move: Mutex::new(None),
-            best_score: AtomicI32::new(0),
+            best_score: AtomicI32::new(worst_score(maximizing)),
             nodes_searched: AtomicU64::new(0),
             depth_reached: AtomicI32::new(0),
             stop_search: AtomicBool::new(false),
+            history: MoveHistory::new(),
+            progress: None,
         }
     }
 
+    /// Records `score` and `mv` of a search to `depth` if they replace the best so far,
+    /// see `replaces`. Returns whether they did.
     pub fn update_best(&self, score: i32, mv: Option<(usize, usize)>, depth: i32) -> bool {
-        let current_score = self.best_score.load(Ordering::Relaxed);
-        
-        // Try to update if this is a better score
-        if score > current_score {
-            if self.best_score.compare_exchange_weak(
-                current_score, 
-                score, 
-                Ordering::Relaxed, 
-                Ordering::Relaxed
-            ).is_ok() {
-                if let Some(mv) = mv {
-                    *self.best_move.lock().unwrap() = Some(mv);
-                }
-                self.depth_reached.store(depth, Ordering::Relaxed);
-                return true;
+        // The move lock keeps the score, move and depth of one result together
+        let mut best_move = self.best_move.lock().unwrap();
+        let best = (self.depth_reached.load(Ordering::Relaxed), self.best_score.load(Ordering::Relaxed));
+        let updated = replaces(self.maximizing, (depth, score), best);
+        if updated {
+            self.best_score.store(score, Ordering::Relaxed);
+            if let Some(mv) = mv {
+                *best_move = Some(mv);
             }
+            self.depth_reached.store(depth, Ordering::Relaxed);
         }
-        false
+        updated
+    }
+
+    /// Best score so far, 0 before any worker has one
+    pub fn first_guess(&self) -> i32 {
+        let best = self.best_score.load(Ordering::Relaxed);
+        if best == worst_score(self.maximizing) { 0 } else { best }
     }
 
     pub fn should_stop(&self) -> bool {
@@ -70,22 +166,64 @@ impl SharedSearchState {
     }
 }
 
+/// Score no result can be worse than for the side to move
+fn worst_score(maximizing: bool) -> i32 {
+    if maximizing { i32::MIN } else { i32::MAX }
+}
+
+/// Whether `score` is better than `than` for the side to move, Max preferring higher
+fn is_better(maximizing: bool, score: i32, than: i32) -> bool {
+    if maximizing { score > than } else { score < than }
+}
+
+/// Whether a result of `(depth, score)` should replace the one of `(than_depth, than)`: a
+/// deeper search always does, an equally deep one when its score is better
+fn replaces(maximizing: bool, (depth, score): (i32, i32), (than_depth, than): (i32, i32)) -> bool {
+    depth > than_depth || (depth == than_depth && is_better(maximizing, score, than))
+}
+
+struct WorkerResult {
+    score: i32,
+    best_move: Option<(usize, usize)>,
+    depth: i32,
+    pv: Vec<(usize, usize)>,
+    depth_times: Vec<(i32, Duration, u64)>,
+    history: MoveHistory,
+}
+
 /// Lazy SMP worker that runs MTD(f) with slightly different parameters
 fn lazy_smp_worker(
     state: &GameState,
-    max_depth: i32,
+    config: &SearchConfig,
     shared_state: Arc<SharedSearchState>,
     worker_id: usize,
     start_time: Instant,
-    time_limit: Option<Duration>,
-) -> (i32, Option<(usize, usize)>, i32, u64) {
+    stop: Option<&AtomicBool>,
+    shared_tt: Option<&SharedTranspositionTable>,
+) -> WorkerResult {
+    let max_depth = config.max_depth;
+    let time_limit = config.time_limit;
     let mut local_state = state.clone();
-    let mut tt = TranspositionTable::new(1_000_000); // Each worker gets its own TT
+    local_state.pattern_analyzer.set_enabled(config.advanced_patterns);
+    // Each worker gets its own TT, unless they all share one
+    let mut tt = match shared_tt {
+        Some(_) => TranspositionTable::new(1),
+        None => TranspositionTable::with_capacity_mb(config.tt_size_mb),
+    };
+    let mut ctx = SearchContext::new(&mut tt, start_time, time_limit)
+        .with_seed(config.seed.map(|seed| seed.wrapping_add(worker_id as u64)))
+        .with_stop(stop)
+        .with_shared_tt(shared_tt)
+        .with_threat_space(config.threat_space_above)
+        .with_node_limit(config.node_limit)
+        .with_late_move_reductions(config.late_move_reductions)
+        .with_extensions(config.forcing_extensions)
+        .with_history(shared_state.history.clone());
     
     let mut best_move = None;
     let mut best_score = 0;
     let mut depth_reached = 0;
-    let mut total_nodes = 0;
+    let mut depth_times = Vec::new();
 
     // Lazy SMP parameters: different workers use slightly different search parameters
     let depth_offset = match worker_id {
@@ -105,7 +243,7 @@ fn lazy_smp_worker(
     };
 
     for depth in 1..=max_depth {
-        if shared_state.should_stop() {
+        if shared_state.should_stop() || ctx.is_time_up() {
             break;
         }
 
@@ -120,30 +258,52 @@ fn lazy_smp_worker(
         let search_depth = (depth + depth_offset).max(1);
 
         // Use shared best score as first guess, with aspiration offset
-        let first_guess = shared_state.best_score.load(Ordering::Relaxed) + aspiration_offset;
+        let first_guess = shared_state.first_guess() + aspiration_offset;
 
-        let (score, nodes, mv) = mtdf(
+        let search = if config.aspiration_windows { aspiration_with_context } else { mtdf_with_context };
+        let (score, nodes, mv) = search(
             &mut local_state,
             first_guess,
             search_depth,
-            &mut tt,
-            &start_time,
-            time_limit,
+            &mut ctx,
         );
 
-        total_nodes += nodes;
         shared_state.add_nodes(nodes);
 
+        // An iteration cut short by the clock or the stop flag ends on a meaningless
+        // score, so it only counts when no iteration has completed yet
+        if ctx.is_time_up() {
+            if best_move.is_none() {
+                best_move = mv;
+                best_score = score;
+            }
+            break;
+        }
+
         if mv.is_some() {
             best_move = mv;
             best_score = score;
             depth_reached = search_depth;
+            depth_times.push((
+                search_depth,
+                start_time.elapsed(),
+                shared_state.nodes_searched.load(Ordering::Relaxed),
+            ));
 
             // Try to update shared state
             shared_state.update_best(score, mv, search_depth);
 
-            // Stop if we found a winning position
-            if score.abs() >= 1_000_000 {
+            if let (0, Some(progress), Some(mv)) = (worker_id, &shared_state.progress, mv) {
+                // The receiver may be gone already, the search carries on regardless
+                let _ = progress.send(DepthProgress {
+                    depth: search_depth,
+                    score,
+                    pv: line_from(&ctx, &mut local_state, mv),
+                });
+            }
+
+            // Stop once a win within the search depth is found
+            if Heuristic::mate_in(score, search_depth).is_some() {
                 shared_state.signal_stop();
                 break;
             }
@@ -155,7 +315,26 @@ fn lazy_smp_worker(
         }
     }
 
-    (best_score, best_move, depth_reached, total_nodes)
+    // The root entry may come from an iteration cut short, so the line starts from our move
+    let pv = best_move.map_or_else(Vec::new, |mv| line_from(&ctx, &mut local_state, mv));
+
+    WorkerResult {
+        score: best_score,
+        best_move,
+        depth: depth_reached,
+        pv,
+        depth_times,
+        history: ctx.history,
+    }
+}
+
+/// `mv` followed by the line stored in the worker's transposition table after it
+fn line_from(ctx: &SearchContext, state: &mut GameState, mv: (usize, usize)) -> Vec<(usize, usize)> {
+    state.make_move(mv);
+    let mut pv = vec![mv];
+    pv.extend(ctx.extract_pv(state, MAX_PV_LENGTH - 1));
+    state.undo_move(mv);
+    pv
 }
 
 /// Parallel search using Lazy SMP
@@ -164,11 +343,79 @@ pub fn lazy_smp_search(
     max_depth: i32,
     time_limit: Option<Duration>,
     num_threads: Option<usize>,
+) -> SearchResult {
+    let config = SearchConfig {
+        num_threads,
+        ..SearchConfig::new(max_depth, time_limit)
+    };
+    lazy_smp_search_with_config(state, &config)
+}
+
+/// Lazy SMP with `threads` workers sharing one transposition table. Workers search
+/// staggered depths with jittered aspiration windows and stop together on a shared flag.
+pub fn find_best_move_parallel(
+    state: &mut GameState,
+    max_depth: i32,
+    time_limit: Option<Duration>,
+    threads: usize,
+) -> SearchResult {
+    let config = SearchConfig {
+        num_threads: Some(threads.max(1)),
+        shared_tt: true,
+        ..SearchConfig::new(max_depth, time_limit)
+    };
+    lazy_smp_search_with_config(state, &config)
+}
+
+/// Parallel search using Lazy SMP, configured through a `SearchConfig`
+pub fn lazy_smp_search_with_config(state: &mut GameState, config: &SearchConfig) -> SearchResult {
+    lazy_smp_search_with_stop(state, config, None)
+}
+
+/// Same as `lazy_smp_search_with_config`, but also stops as soon as `stop` is set.
+/// The result comes from the deepest iteration that completed before the stop.
+pub fn lazy_smp_search_with_stop(
+    state: &mut GameState,
+    config: &SearchConfig,
+    stop: Option<&AtomicBool>,
+) -> SearchResult {
+    search_with_history(state, config, stop, &mut MoveHistory::new(), None)
+}
+
+/// Same as `lazy_smp_search_with_stop`, and sends each depth the main thread completes to
+/// `progress` as soon as it is done, for a display that follows the search as it deepens
+pub fn lazy_smp_search_with_progress(
+    state: &mut GameState,
+    config: &SearchConfig,
+    stop: Option<&AtomicBool>,
+    progress: Sender<DepthProgress>,
+) -> SearchResult {
+    search_with_history(state, config, stop, &mut MoveHistory::new(), Some(progress))
+}
+
+/// Same as `lazy_smp_search_with_stop`, with move ordering starting from the cutoffs
+/// in `history`. The main worker's cutoffs go back into `history`, aged for the next move,
+/// so the AI's searches through a game keep their ordering warm.
+pub fn lazy_smp_search_with_history(
+    state: &mut GameState,
+    config: &SearchConfig,
+    stop: Option<&AtomicBool>,
+    history: &mut MoveHistory,
+) -> SearchResult {
+    search_with_history(state, config, stop, history, None)
+}
+
+fn search_with_history(
+    state: &mut GameState,
+    config: &SearchConfig,
+    stop: Option<&AtomicBool>,
+    history: &mut MoveHistory,
+    progress: Option<Sender<DepthProgress>>,
 ) -> SearchResult {
     let start_time = Instant::now();
     
     // Use number of CPU cores if not specified
-    let threads = num_threads.unwrap_or_else(|| {
+    let threads = config.num_threads.unwrap_or_else(|| {
         std::thread::available_parallelism()
             .map(|n| n.get())
             .unwrap_or(4)
@@ -183,10 +430,22 @@ pub fn lazy_smp_search(
             depth_reached: 0,
             nodes_searched: 0,
             time_elapsed: start_time.elapsed(),
+            ponder_move: None,
+            pv: Vec::new(),
+            nps: 0,
+            depth_times: Vec::new(),
         };
     }
 
-    let shared_state = Arc::new(SharedSearchState::new());
+    let maximizing = state.current_player == Player::Max;
+    let shared_state = Arc::new(SharedSearchState {
+        history: history.clone(),
+        progress,
+        ..SharedSearchState::new(maximizing)
+    });
+    let shared_tt = config
+        .shared_tt
+        .then(|| SharedTranspositionTable::with_capacity_mb(config.tt_size_mb));
     
     // Launch worker threads
     let workers: Vec<_> = (0..threads).into_par_iter().map(|worker_id| {
@@ -195,41 +454,85 @@ pub fn lazy_smp_search(
         
         lazy_smp_worker(
             &state_clone,
-            max_depth,
+            config,
             shared_state_clone,
             worker_id,
             start_time,
-            time_limit,
+            stop,
+            shared_tt.as_ref(),
         )
     }).collect();
 
-    // Wait for all workers to complete and get the best result
-    let mut best_score = i32::MIN;
+    // Wait for all workers to complete and get the deepest, then best, result
+    let mut best = (0, worst_score(maximizing));
     let mut best_move = None;
     let mut max_depth_reached = 0;
 
-    for (score, mv, depth, _nodes) in workers {
-        if score > best_score && mv.is_some() {
-            best_score = score;
-            best_move = mv;
+    for worker in &workers {
+        if replaces(maximizing, (worker.depth, worker.score), best) && worker.best_move.is_some() {
+            best = (worker.depth, worker.score);
+            best_move = worker.best_move;
         }
-        max_depth_reached = max_depth_reached.max(depth);
+        max_depth_reached = max_depth_reached.max(worker.depth);
     }
 
-    // Use shared state results if they're better
-    let shared_score = shared_state.best_score.load(Ordering::Relaxed);
+    // Use shared state results if they replace it
+    let shared = (
+        shared_state.depth_reached.load(Ordering::Relaxed),
+        shared_state.best_score.load(Ordering::Relaxed),
+    );
     let shared_move = *shared_state.best_move.lock().unwrap();
     
-    if shared_score > best_score && shared_move.is_some() {
-        best_score = shared_score;
+    if replaces(maximizing, shared, best) && shared_move.is_some() {
+        best = shared;
         best_move = shared_move;
     }
+    let best_score = best.1;
+
+    let mut pv = workers
+        .iter()
+        .filter(|worker| worker.best_move == best_move)
+        .map(|worker| worker.pv.clone())
+        .max_by_key(Vec::len)
+        .unwrap_or_else(|| best_move.into_iter().collect());
+
+    // Forced wins and losses are played as found, temperature only varies the quieter moves
+    if config.temperature > 0.0
+        && let Some(best) = best_move
+        && best_score.abs() < WIN_SCORE_THRESHOLD
+    {
+        let sampled = match config.seed {
+            Some(seed) => {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed ^ state.moves.len() as u64);
+                pick_by_temperature(state, best, config.temperature, &mut rng)
+            }
+            None => pick_by_temperature(state, best, config.temperature, &mut rand::rng()),
+        };
+        if sampled != best {
+            best_move = Some(sampled);
+            pv = vec![sampled];
+        }
+    }
+
+    let nodes_searched = shared_state.nodes_searched.load(Ordering::Relaxed);
+    let time_elapsed = start_time.elapsed();
+    let (depth_times, main_history) = workers
+        .into_iter()
+        .next()
+        .map(|main| (main.depth_times, main.history))
+        .unwrap_or_default();
+    *history = main_history;
+    history.age();
 
     SearchResult {
         best_move,
         score: best_score,
         depth_reached: shared_state.depth_reached.load(Ordering::Relaxed).max(max_depth_reached),
-        nodes_searched: shared_state.nodes_searched.load(Ordering::Relaxed),
-        time_elapsed: start_time.elapsed(),
+        nodes_searched,
+        time_elapsed,
+        ponder_move: pv.get(1).copied(),
+        pv,
+        nps: (nodes_searched as f64 / time_elapsed.as_secs_f64().max(f64::EPSILON)) as u64,
+        depth_times,
     }
 }
\ No newline at end of file
@@ -1,8 +1,161 @@
+use crate::core::captures::CaptureHandler;
+use crate::core::moves::{MoveHandler, RuleValidator, ThreatLevel};
+use crate::core::rules::OpeningPhase;
 use crate::core::state::GameState;
 use std::cmp::{max, min};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
-use super::{heuristic::Heuristic, move_ordering::MoveOrdering, transposition::{TranspositionTable, EntryType}};
+use super::{heuristic::Heuristic, move_ordering::{MoveHistory, MoveOrdering}, transposition::{EntryType, SharedTranspositionTable, TTResult, TranspositionTable}};
+
+/// Plies of forcing moves explored past the nominal search depth
+pub const MAX_QUIESCENCE_DEPTH: i32 = 4;
+/// Half-width of the first aspiration window around the previous score
+const ASPIRATION_WINDOW: i32 = 50;
+/// Factor the window side that failed grows by on each retry
+const ASPIRATION_WIDENING: i32 = 4;
+/// Past this half-width a failed side falls back to the full window
+const ASPIRATION_MAX_WINDOW: i32 = 10_000;
+/// Late move reductions: moves searched at full depth before the rest are reduced
+const LMR_FULL_DEPTH_MOVES: usize = 3;
+/// Shallowest remaining depth where late moves are reduced
+const LMR_MIN_DEPTH: i32 = 3;
+/// Plies taken off a reduced move
+const LMR_REDUCTION: i32 = 1;
+
+/// Per-search state shared by every node of one MTD(f) search
+pub struct SearchContext<'a> {
+    pub tt: &'a mut TranspositionTable,
+    pub start_time: Instant,
+    pub time_limit: Option<Duration>,
+    /// Seed for breaking ties between equally ordered moves, `None` keeps the natural order
+    pub seed: Option<u64>,
+    /// External cancellation, checked alongside the time limit
+    pub stop: Option<&'a AtomicBool>,
+    /// Table shared with other search threads; when set it replaces `tt`
+    pub shared_tt: Option<&'a SharedTranspositionTable>,
+    /// Search only threat-space moves in positions whose strongest threat is above this level
+    pub threat_space_above: Option<ThreatLevel>,
+    /// Node budget, checked alongside the time limit
+    pub node_limit: Option<u64>,
+    /// Nodes searched so far through this context, quiescence included
+    pub nodes_searched: u64,
+    /// Search quiet moves late in the ordering at reduced depth first
+    pub late_move_reductions: bool,
+    /// Forcing moves searched a ply deeper along one line, 0 disables the extension
+    pub max_extensions: i32,
+    /// Extensions taken by the line being searched
+    pub extensions_used: i32,
+    /// Cutoff history and killers, raising the moves that cut off earlier in the ordering
+    pub history: MoveHistory,
+}
+
+impl<'a> SearchContext<'a> {
+    pub fn new(tt: &'a mut TranspositionTable, start_time: Instant, time_limit: Option<Duration>) -> Self {
+        Self {
+            tt,
+            start_time,
+            time_limit,
+            seed: None,
+            stop: None,
+            shared_tt: None,
+            threat_space_above: None,
+            node_limit: None,
+            nodes_searched: 0,
+            late_move_reductions: false,
+            max_extensions: 0,
+            extensions_used: 0,
+            history: MoveHistory::new(),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_stop(mut self, stop: Option<&'a AtomicBool>) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    pub fn with_shared_tt(mut self, shared_tt: Option<&'a SharedTranspositionTable>) -> Self {
+        self.shared_tt = shared_tt;
+        self
+    }
+
+    pub fn with_threat_space(mut self, threat_space_above: Option<ThreatLevel>) -> Self {
+        self.threat_space_above = threat_space_above;
+        self
+    }
+
+    pub fn with_node_limit(mut self, node_limit: Option<u64>) -> Self {
+        self.node_limit = node_limit;
+        self
+    }
+
+    pub fn with_late_move_reductions(mut self, late_move_reductions: bool) -> Self {
+        self.late_move_reductions = late_move_reductions;
+        self
+    }
+
+    pub fn with_extensions(mut self, max_extensions: i32) -> Self {
+        self.max_extensions = max_extensions;
+        self
+    }
+
+    pub fn with_history(mut self, history: MoveHistory) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Whether `mv` earns its line an extra ply: forcing, and the line has extensions left
+    fn extends(&self, state: &GameState, mv: (usize, usize)) -> bool {
+        self.extensions_used < self.max_extensions && is_forcing_move(state, mv)
+    }
+
+    pub fn probe(&mut self, key: u64, depth: i32, alpha: i32, beta: i32) -> TTResult {
+        match self.shared_tt {
+            Some(shared) => shared.probe(key, depth, alpha, beta),
+            None => self.tt.probe(key, depth, alpha, beta),
+        }
+    }
+
+    pub fn store(&mut self, key: u64, value: i32, depth: i32, entry_type: EntryType, best_move: Option<(usize, usize)>) {
+        match self.shared_tt {
+            Some(shared) => shared.store(key, value, depth, entry_type, best_move),
+            None => self.tt.store(key, value, depth, entry_type, best_move),
+        }
+    }
+
+    pub fn get_best_move(&self, key: u64) -> Option<(usize, usize)> {
+        match self.shared_tt {
+            Some(shared) => shared.get_best_move(key),
+            None => self.tt.get_best_move(key),
+        }
+    }
+
+    pub fn extract_pv(&self, state: &mut GameState, max_len: usize) -> Vec<(usize, usize)> {
+        match self.shared_tt {
+            Some(shared) => shared.extract_pv(state, max_len),
+            None => self.tt.extract_pv(state, max_len),
+        }
+    }
+
+    /// Whether the search has to stop: on the stop flag, the time limit or the node budget
+    pub fn is_time_up(&self) -> bool {
+        if self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed)) {
+            return true;
+        }
+        if self.node_limit.is_some_and(|limit| self.nodes_searched >= limit) {
+            return true;
+        }
+        match self.time_limit {
+            Some(limit) => self.start_time.elapsed() >= limit,
+            None => false,
+        }
+    }
+}
 
 /// Zero-window alpha-beta search with memory (transposition table)
 /// This is the core search function used by MTD(f)
@@ -12,38 +165,53 @@ fn alpha_beta_with_memory(
     mut alpha: i32,
     mut beta: i32,
     maximizing_player: bool,
-    tt: &mut TranspositionTable,
-    start_time: &Instant,
-    time_limit: Option<Duration>,
+    ctx: &mut SearchContext,
 ) -> (i32, u64) {
     let original_alpha = alpha;
     let original_beta = beta;
     let hash_key = state.hash();
     let mut nodes_visited = 1u64;
     
-    // Time check
-    if let Some(limit) = time_limit {
-        if start_time.elapsed() >= limit {
-            return (0, nodes_visited);
-        }
+    // Time check. A node cut off here is not searched, so it is not counted either.
+    if ctx.is_time_up() {
+        return (0, 0);
     }
+    ctx.nodes_searched += 1;
     
     // Transposition table lookup
-    let tt_result = tt.probe(hash_key, depth, alpha, beta);
+    let tt_result = ctx.probe(hash_key, depth, alpha, beta);
     if tt_result.cutoff {
         return (tt_result.value.unwrap(), nodes_visited);
     }
 
-    // Terminal node or leaf node
-    if depth == 0 || state.is_terminal() {
+    // Terminal node
+    if state.is_terminal() {
         let eval = Heuristic::evaluate(state, depth);
-        tt.store(hash_key, eval, depth, EntryType::Exact, None);
+        ctx.store(hash_key, eval, depth, EntryType::Exact, None);
         return (eval, nodes_visited);
     }
 
-    // Get and order moves
-    let mut moves = state.get_possible_moves();
-    MoveOrdering::order_moves(state, &mut moves);
+    // Leaf node: resolve pending fours and captures before trusting the static eval
+    if depth == 0 {
+        let (eval, q_nodes) = quiescence_search(state, alpha, beta, maximizing_player, 0);
+        ctx.nodes_searched += q_nodes;
+        let entry_type = if eval <= original_alpha {
+            EntryType::UpperBound
+        } else if eval >= original_beta {
+            EntryType::LowerBound
+        } else {
+            EntryType::Exact
+        };
+        ctx.store(hash_key, eval, depth, entry_type, None);
+        return (eval, nodes_visited + q_nodes);
+    }
+
+    // Get and order moves. Moves handing the opponent a winning capture are dropped first.
+    let mut moves = state
+        .capture_defense_moves()
+        .or_else(|| ctx.threat_space_above.and_then(|bound| threat_space_moves(state, bound)))
+        .unwrap_or_else(|| state.get_possible_moves());
+    MoveOrdering::order_moves_with_history(state, &mut moves, ctx.seed, &ctx.history, depth);
     
     // Use TT best move first
     if let Some(best_move) = tt_result.best_move {
@@ -57,13 +225,30 @@ fn alpha_beta_with_memory(
 
     if maximizing_player {
         value = i32::MIN;
-        for move_ in moves {
+        for (index, move_) in moves.into_iter().enumerate() {
+            let reduce = ctx.late_move_reductions && is_late_quiet_move(state, move_, index, depth);
+            let extension = i32::from(ctx.extends(state, move_));
+            ctx.extensions_used += extension;
             state.make_move(move_);
-            let (eval, child_nodes) = alpha_beta_with_memory(
-                state, depth - 1, alpha, beta, false, tt, start_time, time_limit
-            );
+            let mut result = None;
+            if reduce {
+                // Only a reduced move that beats alpha is worth its full depth
+                let (eval, child_nodes) = search_child(state, depth - 1 - LMR_REDUCTION, alpha, alpha.saturating_add(1), false, ctx);
+                nodes_visited += child_nodes;
+                if eval <= alpha {
+                    result = Some(eval);
+                }
+            }
+            let eval = match result {
+                Some(eval) => eval,
+                None => {
+                    let (eval, child_nodes) = search_child(state, depth - 1 + extension, alpha, beta, false, ctx);
+                    nodes_visited += child_nodes;
+                    eval
+                }
+            };
             state.undo_move(move_);
-            nodes_visited += child_nodes;
+            ctx.extensions_used -= extension;
             
             if eval > value {
                 value = eval;
@@ -72,19 +257,37 @@ fn alpha_beta_with_memory(
             
             // Beta cutoff
             if value >= beta {
+                ctx.history.record_cutoff(move_, depth);
                 break;
             }
             alpha = max(alpha, value);
         }
     } else {
         value = i32::MAX;
-        for move_ in moves {
+        for (index, move_) in moves.into_iter().enumerate() {
+            let reduce = ctx.late_move_reductions && is_late_quiet_move(state, move_, index, depth);
+            let extension = i32::from(ctx.extends(state, move_));
+            ctx.extensions_used += extension;
             state.make_move(move_);
-            let (eval, child_nodes) = alpha_beta_with_memory(
-                state, depth - 1, alpha, beta, true, tt, start_time, time_limit
-            );
+            let mut result = None;
+            if reduce {
+                // Only a reduced move that gets under beta is worth its full depth
+                let (eval, child_nodes) = search_child(state, depth - 1 - LMR_REDUCTION, beta.saturating_sub(1), beta, true, ctx);
+                nodes_visited += child_nodes;
+                if eval >= beta {
+                    result = Some(eval);
+                }
+            }
+            let eval = match result {
+                Some(eval) => eval,
+                None => {
+                    let (eval, child_nodes) = search_child(state, depth - 1 + extension, alpha, beta, true, ctx);
+                    nodes_visited += child_nodes;
+                    eval
+                }
+            };
             state.undo_move(move_);
-            nodes_visited += child_nodes;
+            ctx.extensions_used -= extension;
             
             if eval < value {
                 value = eval;
@@ -93,6 +296,7 @@ fn alpha_beta_with_memory(
             
             // Alpha cutoff
             if value <= alpha {
+                ctx.history.record_cutoff(move_, depth);
                 break;
             }
             beta = min(beta, value);
@@ -107,11 +311,198 @@ fn alpha_beta_with_memory(
     } else {
         EntryType::Exact
     };
+
+    // When every move fails for the side to move the returned bounds do not rank the
+    // moves, so keep the move an earlier search preferred
+    let failed_for_mover = if maximizing_player {
+        entry_type == EntryType::UpperBound
+    } else {
+        entry_type == EntryType::LowerBound
+    };
+    if failed_for_mover {
+        best_move = tt_result.best_move.or(best_move);
+    }
     
-    tt.store(hash_key, value, depth, entry_type, best_move);
+    ctx.store(hash_key, value, depth, entry_type, best_move);
     (value, nodes_visited)
 }
 
+/// Threat-space candidates when the strongest threat either side can make from here
+/// is above `bound`. Rule-forbidden cells are dropped like in regular generation.
+fn threat_space_moves(state: &GameState, bound: ThreatLevel) -> Option<Vec<(usize, usize)>> {
+    if state.opening != OpeningPhase::Normal {
+        return None;
+    }
+    let board = &state.board;
+    let player = state.current_player;
+    let win = state.win_condition;
+    let moves = MoveHandler::get_threat_space_moves(board, player, &state.rules, win)?;
+    let strongest = moves
+        .iter()
+        .filter_map(|&(row, col)| {
+            MoveHandler::threat_level(board, row, col, player, win)
+                .max(MoveHandler::threat_level(board, row, col, player.opponent(), win))
+        })
+        .max()?;
+    (strongest > bound).then_some(moves)
+}
+
+/// Whether `mv`, at `index` in the ordered moves, may be searched at reduced depth: past
+/// the first few moves (the table move among them), deep enough to spare a ply, and quiet
+fn is_late_quiet_move(state: &GameState, mv: (usize, usize), index: usize, depth: i32) -> bool {
+    index >= LMR_FULL_DEPTH_MOVES && depth >= LMR_MIN_DEPTH && !is_forcing_move(state, mv)
+}
+
+/// Whether playing `mv` captures, or makes or blocks a four
+fn is_forcing_move(state: &GameState, (row, col): (usize, usize)) -> bool {
+    let player = state.current_player;
+    let board = &state.board;
+    let four = |who| {
+        MoveHandler::threat_level(board, row, col, who, state.win_condition).is_some_and(|level| level >= ThreatLevel::Four)
+    };
+    let captures = state.rules.captures_enabled && !CaptureHandler::detect_captures(board, row, col, player).is_empty();
+    captures || four(player) || four(player.opponent())
+}
+
+/// Searches the position the last move led to. A position repeated within the line, capture
+/// totals included, is scored as a draw instead.
+fn search_child(
+    state: &mut GameState,
+    depth: i32,
+    alpha: i32,
+    beta: i32,
+    maximizing_player: bool,
+    ctx: &mut SearchContext,
+) -> (i32, u64) {
+    if state.is_repetition() {
+        return (0, 1);
+    }
+    alpha_beta_with_memory(state, depth, alpha, beta, maximizing_player, ctx)
+}
+
+/// Extends a leaf with forcing moves only until the position is quiet, then returns
+/// the static evaluation. The side to move may stand pat on the static score unless
+/// the opponent threatens to complete a line, in which case it has to answer it.
+pub fn quiescence(state: &mut GameState, alpha: i32, beta: i32, maximizing: bool) -> i32 {
+    quiescence_search(state, alpha, beta, maximizing, 0).0
+}
+
+fn quiescence_search(
+    state: &mut GameState,
+    mut alpha: i32,
+    mut beta: i32,
+    maximizing: bool,
+    ply: i32,
+) -> (i32, u64) {
+    // Deeper wins score lower, so the search still prefers the shortest one
+    let stand_pat = Heuristic::evaluate(state, -ply);
+    #[cfg(all(debug_assertions, feature = "strict-eval"))]
+    Heuristic::assert_antisymmetric(state);
+    if state.is_terminal() || ply >= MAX_QUIESCENCE_DEPTH {
+        return (stand_pat, 1);
+    }
+
+    let (moves, must_answer) = forcing_moves(state);
+    if moves.is_empty() {
+        return (stand_pat, 1);
+    }
+
+    let mut nodes_visited = 1u64;
+    let mut value = match (must_answer, maximizing) {
+        (true, true) => i32::MIN,
+        (true, false) => i32::MAX,
+        (false, _) => stand_pat,
+    };
+
+    if maximizing {
+        if value >= beta {
+            return (value, nodes_visited);
+        }
+        alpha = max(alpha, value);
+        for move_ in moves {
+            state.make_move(move_);
+            let (eval, child_nodes) = quiescence_search(state, alpha, beta, false, ply + 1);
+            state.undo_move(move_);
+            nodes_visited += child_nodes;
+
+            value = max(value, eval);
+            if value >= beta {
+                break;
+            }
+            alpha = max(alpha, value);
+        }
+    } else {
+        if value <= alpha {
+            return (value, nodes_visited);
+        }
+        beta = min(beta, value);
+        for move_ in moves {
+            state.make_move(move_);
+            let (eval, child_nodes) = quiescence_search(state, alpha, beta, true, ply + 1);
+            state.undo_move(move_);
+            nodes_visited += child_nodes;
+
+            value = min(value, eval);
+            if value <= alpha {
+                break;
+            }
+            beta = min(beta, value);
+        }
+    }
+
+    (value, nodes_visited)
+}
+
+/// Legal moves that make a four, gapped ones included, complete a capture or block an
+/// opponent's winning cell, winning moves first. The flag is set when the opponent threatens to win next move.
+fn forcing_moves(state: &GameState) -> (Vec<(usize, usize)>, bool) {
+    let player = state.current_player;
+    let opponent = player.opponent();
+    let win = state.win_condition;
+    let own_overline = state.rules.allows_overline(player);
+
+    let mut wins = Vec::new();
+    let mut blocks = Vec::new();
+    let mut others = Vec::new();
+
+    for (row, col) in state.get_possible_moves() {
+        let own = MoveHandler::line_lengths_through(&state.board, row, col, player);
+        if own.iter().any(|&length| MoveHandler::is_winning_length(length, win, own_overline)) {
+            wins.push((row, col));
+        } else if MoveHandler::completes_line(
+            &state.board,
+            row,
+            col,
+            opponent,
+            win,
+            state.rules.allows_overline(opponent),
+        ) {
+            blocks.push((row, col));
+        } else if own.iter().any(|&length| length + 1 >= win)
+            || RuleValidator::count_fours(&state.board, row, col, player, win) > 0
+            || (state.rules.captures_enabled
+                && !CaptureHandler::detect_captures(&state.board, row, col, player).is_empty())
+        {
+            others.push((row, col));
+        }
+    }
+
+    let must_answer = !blocks.is_empty();
+    if !wins.is_empty() {
+        return (wins, false);
+    }
+    if must_answer {
+        // Only a block or a capture that breaks the line can save the game
+        if state.rules.captures_enabled {
+            blocks.extend(others.into_iter().filter(|&(row, col)| {
+                !CaptureHandler::detect_captures(&state.board, row, col, player).is_empty()
+            }));
+        }
+        return (blocks, true);
+    }
+    (others, false)
+}
+
 /// MTD(f) - Memory-enhanced Test Driver
 /// Performs a series of zero-window searches to converge on the minimax value
 /// 
@@ -132,6 +523,17 @@ pub fn mtdf(
     tt: &mut TranspositionTable,
     start_time: &Instant,
     time_limit: Option<Duration>,
+) -> (i32, u64, Option<(usize, usize)>) {
+    let mut ctx = SearchContext::new(tt, *start_time, time_limit);
+    mtdf_with_context(state, first_guess, depth, &mut ctx)
+}
+
+/// MTD(f) driven by an explicit `SearchContext`, for callers that need more than a time limit
+pub fn mtdf_with_context(
+    state: &mut GameState,
+    first_guess: i32,
+    depth: i32,
+    ctx: &mut SearchContext,
 ) -> (i32, u64, Option<(usize, usize)>) {
     let mut g = first_guess;
     let mut upper_bound = i32::MAX;
@@ -142,10 +544,8 @@ pub fn mtdf(
     // Iteratively narrow the search window until bounds converge
     while lower_bound < upper_bound {
         // Check time limit
-        if let Some(limit) = time_limit {
-            if start_time.elapsed() >= limit {
-                break;
-            }
+        if ctx.is_time_up() {
+            break;
         }
         
         // Set beta for zero-window search
@@ -160,9 +560,7 @@ pub fn mtdf(
             beta - 1,
             beta,
             is_maximizing,
-            tt,
-            start_time,
-            time_limit,
+            ctx,
         );
         
         total_nodes += nodes;
@@ -181,9 +579,45 @@ pub fn mtdf(
     
     // Get the best move from the transposition table
     let hash_key = state.hash();
-    let best_move = tt.get_best_move(hash_key);
+    let best_move = ctx.get_best_move(hash_key);
     
     (g, total_nodes, best_move)
 }
 
+/// Aspiration search around `guess`, a drop-in alternative to `mtdf_with_context`:
+/// one window of ±`ASPIRATION_WINDOW` instead of a series of zero-window probes.
+/// The side that fails widens ×4 and, once too wide, opens up to the full window,
+/// so the score is exact in the end.
+pub fn aspiration_with_context(
+    state: &mut GameState,
+    guess: i32,
+    depth: i32,
+    ctx: &mut SearchContext,
+) -> (i32, u64, Option<(usize, usize)>) {
+    let is_maximizing = state.current_player == crate::core::board::Player::Max;
+    let mut alpha_width = ASPIRATION_WINDOW;
+    let mut beta_width = ASPIRATION_WINDOW;
+    let mut total_nodes = 0u64;
 
+    let value = loop {
+        let alpha = if alpha_width > ASPIRATION_MAX_WINDOW { i32::MIN + 1 } else { guess.saturating_sub(alpha_width) };
+        let beta = if beta_width > ASPIRATION_MAX_WINDOW { i32::MAX - 1 } else { guess.saturating_add(beta_width) };
+
+        let (value, nodes) = alpha_beta_with_memory(state, depth, alpha, beta, is_maximizing, ctx);
+        total_nodes += nodes;
+
+        if ctx.is_time_up() {
+            break value;
+        }
+        if value <= alpha && alpha > i32::MIN + 1 {
+            alpha_width = alpha_width.saturating_mul(ASPIRATION_WIDENING);
+        } else if value >= beta && beta < i32::MAX - 1 {
+            beta_width = beta_width.saturating_mul(ASPIRATION_WIDENING);
+        } else {
+            break value;
+        }
+    };
+
+    let best_move = ctx.get_best_move(state.hash());
+    (value, total_nodes, best_move)
+}
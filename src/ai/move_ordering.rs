@@ -1,8 +1,26 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
 use crate::core::board::{Board, Player};
+use crate::core::captures::CaptureHandler;
+use crate::core::moves::{MoveHandler, ThreatOrdering};
 use crate::core::state::GameState;
 
 pub struct MoveOrdering;
 
+/// Killer moves remembered per remaining depth
+const KILLERS_PER_DEPTH: usize = 2;
+/// Most a move's cutoff history adds to its priority, below making a three so threats still lead
+const MAX_HISTORY_BONUS: i32 = 500;
+/// Priority of a killer, a move that cut off a sibling node at the same depth
+const KILLER_BONUS: i32 = 400;
+
+/// Priority per pair a move captures: above making a three, below making a four
+const CAPTURE_PAIR_PRIORITY: i32 = 2000;
+/// Priority of a move taking the pair that wins on captures, level with completing five
+const CAPTURE_WIN_PRIORITY: i32 = 10000;
+
 const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
 const ALL_DIRECTIONS: [(isize, isize); 8] = [
     (-1, -1),
@@ -17,18 +35,73 @@ const ALL_DIRECTIONS: [(isize, isize); 8] = [
 
 impl MoveOrdering {
     pub fn order_moves(state: &GameState, moves: &mut [(usize, usize)]) {
-        let center = state.board.size / 2;
+        let center = state.board.center();
         moves.sort_unstable_by_key(|&mv| -Self::calculate_move_priority(state, mv, center));
     }
 
-    fn calculate_move_priority(state: &GameState, mv: (usize, usize), center: usize) -> i32 {
+    /// Same ordering as `order_moves`, with ties between equal priorities broken by `seed`
+    /// so different seeds explore (and pick among) equally rated moves in a different order.
+    pub fn order_moves_seeded(state: &GameState, moves: &mut [(usize, usize)], seed: u64) {
+        let center = state.board.center();
+        moves.sort_unstable_by_key(|&mv| {
+            (
+                -Self::calculate_move_priority(state, mv, center),
+                Self::tie_break_key(seed, mv),
+            )
+        });
+    }
+
+    /// Same ordering as `order_moves_seeded` (or `order_moves` without a seed), with moves
+    /// that caused cutoffs in earlier searches raised by `history`. The threat moves of
+    /// `MoveHandler::find_threat_moves` then lead, most severe first, unless there are
+    /// too many of them to single out.
+    pub fn order_moves_with_history(
+        state: &GameState,
+        moves: &mut [(usize, usize)],
+        seed: Option<u64>,
+        history: &MoveHistory,
+        depth: i32,
+    ) {
+        let center = state.board.center();
+        moves.sort_unstable_by_key(|&mv| {
+            (
+                -(Self::calculate_move_priority(state, mv, center) + history.bonus(mv, depth)),
+                seed.map_or(0, |seed| Self::tie_break_key(seed, mv)),
+            )
+        });
+
+        let threats = MoveHandler::find_threat_moves_among(
+            &state.board,
+            state.current_player,
+            moves,
+            state.win_condition,
+            &ThreatOrdering::default(),
+        );
+        for (front, threat) in threats.unwrap_or_default().into_iter().enumerate() {
+            // Rotating keeps the moves in between in their order
+            if let Some(pos) = moves[front..].iter().position(|&mv| mv == threat) {
+                moves[front..=front + pos].rotate_right(1);
+            }
+        }
+    }
+
+    fn tie_break_key(seed: u64, (row, col): (usize, usize)) -> u64 {
+        // splitmix64 finalizer over the seed and the move coordinates
+        let mut z = seed ^ (((row as u64) << 32) | col as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn calculate_move_priority(state: &GameState, mv: (usize, usize), center: (usize, usize)) -> i32 {
         let (row, col) = mv;
         let mut priority = 0;
 
-        let center_distance = Self::manhattan_distance(row, col, center, center);
+        let center_distance = Self::manhattan_distance(row, col, center.0, center.1);
         priority += 100 - center_distance as i32;
 
         priority += Self::calculate_threat_priority(&state.board, row, col);
+        priority += Self::calculate_capture_priority(state, row, col);
         priority += Self::calculate_adjacency_bonus(&state.board, row, col);
 
         priority
@@ -54,6 +127,27 @@ impl MoveOrdering {
         threat_score
     }
 
+    /// Captures are often the strongest moves in this variant, so the pairs a move would
+    /// take for the side to move raise it ahead of quiet moves
+    fn calculate_capture_priority(state: &GameState, row: usize, col: usize) -> i32 {
+        if !state.rules.captures_enabled {
+            return 0;
+        }
+        let pairs = CaptureHandler::detect_captures(&state.board, row, col, state.current_player).len() / 2;
+        if pairs == 0 {
+            return 0;
+        }
+        let own_captures = match state.current_player {
+            Player::Max => state.max_captures,
+            Player::Min => state.min_captures,
+        };
+        if own_captures + pairs >= state.capture_win_pairs {
+            CAPTURE_WIN_PRIORITY
+        } else {
+            pairs as i32 * CAPTURE_PAIR_PRIORITY
+        }
+    }
+
     fn simulate_move_consecutive(
         board: &Board,
         row: usize,
@@ -76,18 +170,14 @@ impl MoveOrdering {
         player: Player,
     ) -> usize {
         let player_bits = match player {
-            Player::Max => &board.max_bits,
-            Player::Min => &board.min_bits,
+            Player::Max => board.max_bits(),
+            Player::Min => board.min_bits(),
         };
         let mut count = 0;
         let mut current_row = row as isize + dx;
         let mut current_col = col as isize + dy;
 
-        while current_row >= 0
-            && current_row < board.size as isize
-            && current_col >= 0
-            && current_col < board.size as isize
-        {
+        while board.in_bounds(current_row, current_col) {
             let idx = board.index(current_row as usize, current_col as usize);
             if Board::is_bit_set(player_bits, idx) {
                 count += 1;
@@ -107,13 +197,13 @@ impl MoveOrdering {
         for &(dx, dy) in &ALL_DIRECTIONS {
             let nr = row as isize + dx;
             let nc = col as isize + dy;
-            if nr >= 0 && nc >= 0 && nr < board.size as isize && nc < board.size as isize {
+            if board.in_bounds(nr, nc) {
                 let idx = board.index(nr as usize, nc as usize);
                 Board::set_bit(&mut neighbor_mask, idx);
             }
         }
 
-        for (&o, &m) in board.occupied.iter().zip(&neighbor_mask) {
+        for (&o, &m) in board.occupied().iter().zip(&neighbor_mask) {
             num_adjacent += (o & m).count_ones() as i32;
         }
 
@@ -124,3 +214,72 @@ impl MoveOrdering {
         ((row1 as isize - row2 as isize).abs() + (col1 as isize - col2 as isize).abs()) as usize
     }
 }
+
+/// Moves that caused cutoffs: the history heuristic per cell, and the last two killer moves
+/// per remaining depth. Kept across the AI's moves within a game, aged between them.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MoveHistory {
+    scores: HashMap<(usize, usize), i32>,
+    killers: Vec<[Option<(usize, usize)>; KILLERS_PER_DEPTH]>,
+}
+
+impl MoveHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Credits `mv` with a cutoff `depth` plies from the leaves. Deeper cutoffs prune more, so count more.
+    pub fn record_cutoff(&mut self, mv: (usize, usize), depth: i32) {
+        let depth = depth.max(0);
+        let score = self.scores.entry(mv).or_insert(0);
+        *score = score.saturating_add(depth * depth);
+
+        let index = depth as usize;
+        if self.killers.len() <= index {
+            self.killers.resize(index + 1, [None; KILLERS_PER_DEPTH]);
+        }
+        let killers = &mut self.killers[index];
+        if killers[0] != Some(mv) {
+            killers[1] = killers[0];
+            killers[0] = Some(mv);
+        }
+    }
+
+    /// Cutoff history of `mv`, 0 for a move that never caused one
+    pub fn score(&self, mv: (usize, usize)) -> i32 {
+        self.scores.get(&mv).copied().unwrap_or(0)
+    }
+
+    /// Whether `mv` is one of the killers at `depth`
+    pub fn is_killer(&self, mv: (usize, usize), depth: i32) -> bool {
+        usize::try_from(depth)
+            .ok()
+            .and_then(|index| self.killers.get(index))
+            .is_some_and(|killers| killers.contains(&Some(mv)))
+    }
+
+    /// Ordering priority `mv` earns at `depth`
+    pub fn bonus(&self, mv: (usize, usize), depth: i32) -> i32 {
+        let killer = if self.is_killer(mv, depth) { KILLER_BONUS } else { 0 };
+        self.score(mv).min(MAX_HISTORY_BONUS) + killer
+    }
+
+    /// Halves every score so the last move's cutoffs outweigh older ones, dropping the
+    /// cells that fall to 0. Killers stay until newer cutoffs replace them.
+    pub fn age(&mut self) {
+        self.scores.retain(|_, score| {
+            *score /= 2;
+            *score > 0
+        });
+    }
+
+    /// Forgets everything, for a new game
+    pub fn clear(&mut self) {
+        self.scores.clear();
+        self.killers.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty() && self.killers.is_empty()
+    }
+}
@@ -30,6 +30,7 @@ pub struct PatternHistoryAnalyzer {
     move_history: Vec<MoveAnalysis>,
     tempo_score: i32,
     initiative_player: Option<Player>,
+    enabled: bool,
 }
 
 impl PatternHistoryAnalyzer {
@@ -38,9 +39,15 @@ impl PatternHistoryAnalyzer {
             move_history: Vec::new(),
             tempo_score: 0,
             initiative_player: None,
+            enabled: true,
         }
     }
 
+    /// Turns the historical bonus on or off; a disabled analyzer always scores 0
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
     /// Undo the last move analysis (for search rollback)
     pub fn undo_last_move(&mut self) {
         if !self.move_history.is_empty() {
@@ -95,6 +102,10 @@ impl PatternHistoryAnalyzer {
     }
 
     pub fn calculate_historical_bonus(&self, state: &GameState) -> i32 {
+        if !self.enabled {
+            return 0;
+        }
+
         let mut bonus = 0;
 
         if let Some(initiative_player) = self.initiative_player {
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::core::state::GameState;
+
+use super::lazy_smp::{lazy_smp_search_with_stop, SearchConfig, SearchResult};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Background search of the position after the opponent's expected reply
+pub struct Ponderer {
+    predicted: (usize, usize),
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<SearchResult>>,
+}
+
+impl Ponderer {
+    /// Starts searching `state` with `predicted` played, without a time limit, until
+    /// the real move arrives. Returns `None` if the prediction is not a legal move or
+    /// leaves nothing to search.
+    pub fn start(state: &GameState, predicted: (usize, usize), config: &SearchConfig) -> Option<Self> {
+        if state.is_terminal() || !state.get_possible_moves().contains(&predicted) {
+            return None;
+        }
+        let mut pondered = state.clone();
+        pondered.make_move(predicted);
+        if pondered.is_terminal() {
+            return None;
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let config = SearchConfig {
+            time_limit: None,
+            ..*config
+        };
+        let handle = thread::spawn(move || lazy_smp_search_with_stop(&mut pondered, &config, Some(&thread_stop)));
+
+        Some(Self {
+            predicted,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn predicted_move(&self) -> (usize, usize) {
+        self.predicted
+    }
+
+    /// On a ponder hit, gives the background search `budget` more time and returns its
+    /// result, with `time_elapsed` counted from the hit: the time spent while the
+    /// opponent thought is not the engine's. On a miss the search is cancelled and
+    /// `None` is returned.
+    pub fn finish(mut self, actual: (usize, usize), budget: Option<Duration>) -> Option<SearchResult> {
+        if actual != self.predicted {
+            return None;
+        }
+        let handle = self.handle.take()?;
+
+        let hit = Instant::now();
+        let deadline = budget.map(|budget| hit + budget);
+        while !handle.is_finished() && deadline.is_some_and(|deadline| Instant::now() < deadline) {
+            thread::sleep(POLL_INTERVAL);
+        }
+        self.stop.store(true, Ordering::Relaxed);
+        let mut result = handle.join().ok()?;
+        result.time_elapsed = hit.elapsed();
+        Some(result)
+    }
+}
+
+impl Drop for Ponderer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
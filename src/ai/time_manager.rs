@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+/// Held back from every allocation for move generation, I/O and the manager's clock granularity
+const SAFETY_MARGIN: Duration = Duration::from_millis(50);
+/// Smallest budget handed out, so a nearly flagged engine still plays its first iteration
+const MIN_BUDGET: Duration = Duration::from_millis(1);
+/// A game is expected to fill about a quarter of the board's cells
+const EXPECTED_FILL_DIVISOR: usize = 4;
+/// The bank is always spread over at least this many of our own moves
+const MIN_MOVES_TO_PLAN: usize = 10;
+/// Plies during which moves get the early-game bonus, while the shape of the game is decided
+const EARLY_GAME_PLIES: usize = 12;
+
+/// Search time for the next move out of a `remaining` bank, `moves_played` plies into a
+/// game on a `board_size` board: the bank less a safety margin, shared evenly over the
+/// moves the engine expects to still play, with half as much again in the early game.
+/// Shrinks as the bank runs down and never exceeds what is left.
+pub fn allocate(remaining: Duration, moves_played: usize, board_size: usize) -> Duration {
+    let bank = remaining.saturating_sub(SAFETY_MARGIN);
+    let expected_plies = board_size * board_size / EXPECTED_FILL_DIVISOR;
+    // Every other ply is ours
+    let moves_to_plan = (expected_plies.saturating_sub(moves_played) / 2).max(MIN_MOVES_TO_PLAN);
+    let mut budget = bank / moves_to_plan as u32;
+    if moves_played < EARLY_GAME_PLIES {
+        budget = budget * 3 / 2;
+    }
+    budget.max(MIN_BUDGET).min(remaining)
+}
@@ -1,6 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
 use bevy::prelude::*;
 
+use crate::core::state::GameState;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EntryType {
     Exact,
@@ -26,7 +29,15 @@ pub struct TranspositionTable {
     misses: u64,
 }
 
+/// Approximate memory taken by one stored entry, key and hash map bookkeeping included
+const ENTRY_FOOTPRINT_BYTES: usize = std::mem::size_of::<(u64, TranspositionEntry)>() + 1;
+
 impl TranspositionTable {
+    /// Table holding the largest power-of-two number of entries that fits in `mb` megabytes
+    pub fn with_capacity_mb(mb: usize) -> Self {
+        Self::new(entries_for_mb(mb))
+    }
+
     pub fn new(max_size: usize) -> Self {
         Self {
             table: HashMap::with_capacity(max_size.min(1024 * 1024)),
@@ -96,6 +107,13 @@ impl TranspositionTable {
         self.table.get(&key).and_then(|entry| entry.best_move)
     }
     
+    /// Principal variation from `state`: follows the stored best moves, at most `max_len`
+    /// of them, until a miss, an illegal move or a repeated position. `state` is left as it was.
+    pub fn extract_pv(&self, state: &mut GameState, max_len: usize) -> Vec<(usize, usize)> {
+        walk_pv(state, max_len, |key| self.get_best_move(key))
+    }
+
+    /// Drops every entry and resets the age and hit counters, as for a fresh table
     pub fn clear(&mut self) {
         self.table.clear();
         self.current_age = 0;
@@ -121,6 +139,11 @@ impl TranspositionTable {
     pub fn size(&self) -> usize {
         self.table.len()
     }
+
+    /// Maximum number of entries kept
+    pub fn capacity(&self) -> usize {
+        self.max_size
+    }
     
     pub fn get_stats(&self) -> (u64, u64) {
         (
@@ -150,6 +173,80 @@ impl TranspositionTable {
     }
 }
 
+fn entries_for_mb(mb: usize) -> usize {
+    let entries = (mb.max(1) * 1024 * 1024 / ENTRY_FOOTPRINT_BYTES).max(1);
+    1usize << entries.ilog2()
+}
+
+fn walk_pv(
+    state: &mut GameState,
+    max_len: usize,
+    best_move: impl Fn(u64) -> Option<(usize, usize)>,
+) -> Vec<(usize, usize)> {
+    let redo_history = std::mem::take(&mut state.redo_history);
+    let mut seen = HashSet::new();
+    let mut pv = Vec::new();
+
+    while pv.len() < max_len && !state.is_terminal() && seen.insert(state.hash()) {
+        let Some(mv) = best_move(state.hash()).filter(|mv| state.get_possible_moves().contains(mv)) else {
+            break;
+        };
+        state.make_move(mv);
+        pv.push(mv);
+    }
+
+    for &mv in pv.iter().rev() {
+        state.undo_move(mv);
+    }
+    state.redo_history = redo_history;
+    pv
+}
+
+const SHARED_TT_SHARDS: usize = 64;
+
+/// Transposition table shared by parallel search threads. It is split into shards with
+/// their own lock, so threads only wait on each other when they hit the same shard.
+pub struct SharedTranspositionTable {
+    shards: Vec<Mutex<TranspositionTable>>,
+}
+
+impl SharedTranspositionTable {
+    /// Same total capacity as `TranspositionTable::with_capacity_mb`, spread over the shards
+    pub fn with_capacity_mb(mb: usize) -> Self {
+        let per_shard = (entries_for_mb(mb) / SHARED_TT_SHARDS).max(1);
+        Self {
+            shards: (0..SHARED_TT_SHARDS)
+                .map(|_| Mutex::new(TranspositionTable::new(per_shard)))
+                .collect(),
+        }
+    }
+
+    fn shard(&self, key: u64) -> MutexGuard<'_, TranspositionTable> {
+        self.shards[(key % SHARED_TT_SHARDS as u64) as usize].lock().unwrap()
+    }
+
+    pub fn store(&self, key: u64, value: i32, depth: i32, entry_type: EntryType, best_move: Option<(usize, usize)>) {
+        self.shard(key).store(key, value, depth, entry_type, best_move);
+    }
+
+    pub fn probe(&self, key: u64, depth: i32, alpha: i32, beta: i32) -> TTResult {
+        self.shard(key).probe(key, depth, alpha, beta)
+    }
+
+    pub fn get_best_move(&self, key: u64) -> Option<(usize, usize)> {
+        self.shard(key).get_best_move(key)
+    }
+
+    /// Same walk as `TranspositionTable::extract_pv`
+    pub fn extract_pv(&self, state: &mut GameState, max_len: usize) -> Vec<(usize, usize)> {
+        walk_pv(state, max_len, |key| self.get_best_move(key))
+    }
+
+    pub fn size(&self) -> usize {
+        self.shards.iter().map(|shard| shard.lock().unwrap().size()).sum()
+    }
+}
+
 impl Default for TranspositionTable {
     fn default() -> Self {
         Self::new(1_000_000)
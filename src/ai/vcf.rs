@@ -0,0 +1,141 @@
+use crate::core::board::{Board, Player};
+use crate::core::captures::CaptureHandler;
+use crate::core::moves::MoveHandler;
+use crate::core::state::GameState;
+
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Searches for a victory by continuous fours for the side to move.
+///
+/// The attacker only plays moves that threaten to complete a five next turn, and the
+/// defender only answers with the blocking cell(s) or a capture that may break the
+/// threat. `max_depth` is counted in plies. Returns the full forcing line, ending with
+/// the winning move, or `None` if no forced win exists within the depth.
+pub fn solve_vcf(state: &mut GameState, max_depth: i32) -> Option<Vec<(usize, usize)>> {
+    if state.winner.is_some() {
+        return None;
+    }
+
+    let attacker = state.current_player;
+    let mut line = Vec::new();
+    if attack(state, attacker, max_depth, &mut line) {
+        Some(line)
+    } else {
+        None
+    }
+}
+
+fn attack(state: &mut GameState, attacker: Player, depth: i32, line: &mut Vec<(usize, usize)>) -> bool {
+    if depth <= 0 || state.winner.is_some() {
+        return false;
+    }
+
+    let legal = state.get_possible_moves();
+    let overline = state.rules.allows_overline(attacker);
+    if let Some(&win) = MoveHandler::find_winning_moves(&state.board, attacker, state.win_condition, overline)
+        .iter()
+        .find(|mv| legal.contains(mv))
+    {
+        line.push(win);
+        return true;
+    }
+
+    // A four, the reply and the winning move
+    if depth < 3 {
+        return false;
+    }
+
+    for mv in legal {
+        if !makes_four(&mut state.board, mv, attacker, state.win_condition, overline) {
+            continue;
+        }
+
+        let checkpoint = line.len();
+        line.push(mv);
+        state.make_move(mv);
+        let won = state.winner == Some(attacker) || defend(state, attacker, depth - 1, line);
+        state.undo_move(mv);
+
+        if won {
+            return true;
+        }
+        line.truncate(checkpoint);
+    }
+
+    false
+}
+
+/// Every defender reply must lose for the four to count; the line keeps the first one
+fn defend(state: &mut GameState, attacker: Player, depth: i32, line: &mut Vec<(usize, usize)>) -> bool {
+    let defender = attacker.opponent();
+    let legal = state.get_possible_moves();
+
+    let defender_overline = state.rules.allows_overline(defender);
+    if MoveHandler::find_winning_moves(&state.board, defender, state.win_condition, defender_overline)
+        .iter()
+        .any(|mv| legal.contains(mv))
+    {
+        return false;
+    }
+
+    let attacker_overline = state.rules.allows_overline(attacker);
+    let threats = MoveHandler::find_winning_moves(&state.board, attacker, state.win_condition, attacker_overline);
+    if threats.is_empty() {
+        return false;
+    }
+
+    let replies: Vec<(usize, usize)> = legal
+        .into_iter()
+        .filter(|&(row, col)| {
+            threats.contains(&(row, col))
+                || !CaptureHandler::detect_captures(&state.board, row, col, defender).is_empty()
+        })
+        .collect();
+    if replies.is_empty() {
+        return false;
+    }
+
+    let checkpoint = line.len();
+    for (i, &reply) in replies.iter().enumerate() {
+        let mut sub_line = Vec::new();
+        state.make_move(reply);
+        let won = state.winner.is_none() && attack(state, attacker, depth - 1, &mut sub_line);
+        state.undo_move(reply);
+
+        if !won {
+            line.truncate(checkpoint);
+            return false;
+        }
+        if i == 0 {
+            line.push(reply);
+            line.extend(sub_line);
+        }
+    }
+
+    true
+}
+
+/// Whether a stone at `mv` leaves `player` one move away from a five through `mv`
+fn makes_four(
+    board: &mut Board,
+    mv: (usize, usize),
+    player: Player,
+    win_condition: usize,
+    allow_overline: bool,
+) -> bool {
+    board.place_stone(mv.0, mv.1, player);
+    let reach = win_condition as isize - 1;
+
+    let found = DIRECTIONS.iter().any(|&(dr, dc)| {
+        (-reach..=reach).any(|k| {
+            let r = mv.0 as isize + dr * k;
+            let c = mv.1 as isize + dc * k;
+            board.in_bounds(r, c)
+                && board.is_empty_position(r as usize, c as usize)
+                && MoveHandler::completes_line(board, r as usize, c as usize, player, win_condition, allow_overline)
+        })
+    });
+
+    board.remove_stone(mv.0, mv.1);
+    found
+}
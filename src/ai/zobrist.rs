@@ -7,13 +7,33 @@ use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
 pub struct ZobristHash {
     position_keys: Vec<[u64; 2]>,
     player_key: u64,
-    board_size: usize,
+    rows: usize,
+    cols: usize,
 }
 
+/// Seed of the default keys. Fixed so hashes, and anything stored by hash, stay valid
+/// from one run to the next.
+pub const DEFAULT_ZOBRIST_SEED: u64 = 0x123456789ABCDEF0;
+
 impl ZobristHash {
     pub fn new(board_size: usize) -> Self {
-        let total_positions = board_size * board_size;
-        let mut rng = ChaCha8Rng::seed_from_u64(0x123456789ABCDEF0);
+        Self::from_seed(board_size, DEFAULT_ZOBRIST_SEED)
+    }
+
+    pub fn new_rect(rows: usize, cols: usize) -> Self {
+        Self::from_seed_rect(rows, cols, DEFAULT_ZOBRIST_SEED)
+    }
+
+    /// Keys drawn from a ChaCha8 stream seeded with `seed`: the same seed and board size
+    /// always give the same keys
+    pub fn from_seed(board_size: usize, seed: u64) -> Self {
+        Self::from_seed_rect(board_size, board_size, seed)
+    }
+
+    /// Keys for a `rows` x `cols` board. A square board gets the same keys as `from_seed`.
+    pub fn from_seed_rect(rows: usize, cols: usize, seed: u64) -> Self {
+        let total_positions = rows * cols;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
         
         let mut position_keys = Vec::with_capacity(total_positions);
         
@@ -26,17 +46,19 @@ impl ZobristHash {
         Self {
             position_keys,
             player_key,
-            board_size,
+            rows,
+            cols,
         }
     }
     
+    /// Side of the board the keys were drawn for, the longer side when it is not square
     pub fn board_size(&self) -> usize {
-        self.board_size
+        self.rows.max(self.cols)
     }
     
     #[inline]
     fn position_index(&self, row: usize, col: usize) -> usize {
-        row * self.board_size + col
+        row * self.cols + col
     }
     
     #[inline]
@@ -51,8 +73,8 @@ impl ZobristHash {
         let mut hash = 0u64;
         
         for u64_idx in 0..state.board.u64_count {
-            let max_bits = state.board.max_bits[u64_idx];
-            let min_bits = state.board.min_bits[u64_idx];
+            let max_bits = state.board.max_bits()[u64_idx];
+            let min_bits = state.board.min_bits()[u64_idx];
             
             let mut remaining_max = max_bits;
             while remaining_max != 0 {
@@ -0,0 +1,7 @@
+use std::io;
+
+use gomoku::interface::gomocup::run_protocol;
+
+fn main() -> io::Result<()> {
+	run_protocol(io::stdin().lock(), io::stdout().lock())
+}
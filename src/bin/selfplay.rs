@@ -0,0 +1,32 @@
+use std::env;
+use std::time::Duration;
+
+use gomoku::ai::lazy_smp::SearchConfig;
+use gomoku::interface::selfplay::run_match;
+
+// Usage: selfplay [games] [depth_a] [depth_b] [board_size] [move_ms]
+const DEFAULTS: [u64; 5] = [10, 4, 4, 15, 500];
+
+fn main() {
+	let args: Vec<String> = env::args().skip(1).collect();
+	let mut values = DEFAULTS;
+	for (value, arg) in values.iter_mut().zip(&args) {
+		match arg.parse() {
+			Ok(parsed) => *value = parsed,
+			Err(_) => {
+				eprintln!("usage: selfplay [games] [depth_a] [depth_b] [board_size] [move_ms]");
+				std::process::exit(2);
+			}
+		}
+	}
+	let [games, depth_a, depth_b, board_size, move_ms] = values;
+
+	let time_limit = Some(Duration::from_millis(move_ms));
+	let config_a = SearchConfig::new(depth_a as i32, time_limit);
+	let config_b = SearchConfig::new(depth_b as i32, time_limit);
+	println!(
+		"{} games on {}x{}: A depth {} vs B depth {}, {} ms per move",
+		games, board_size, board_size, depth_a, depth_b, move_ms
+	);
+	print!("{}", run_match(&config_a, &config_b, games as usize, board_size as usize, 5));
+}
@@ -0,0 +1,31 @@
+use std::env;
+use std::io;
+
+use gomoku::ai::difficulty_params;
+use gomoku::ai::lazy_smp::SearchConfig;
+use gomoku::core::state::MAX_BOARD_SIZE;
+use gomoku::interface::shell_game::new_game;
+
+// Usage: shell [easy|medium|hard] [board_size]
+const DEFAULT_BOARD_SIZE: usize = 15;
+
+fn main() -> io::Result<()> {
+	let args: Vec<String> = env::args().skip(1).collect();
+	let difficulty = args.first().map_or("medium", String::as_str);
+	let size = match args.get(1).map(|arg| arg.parse()) {
+		None => DEFAULT_BOARD_SIZE,
+		Some(Ok(size)) if (1..=MAX_BOARD_SIZE).contains(&size) => size,
+		Some(_) => {
+			eprintln!("usage: shell [easy|medium|hard] [board_size]");
+			std::process::exit(2);
+		}
+	};
+
+	let (max_depth, time_limit, advanced_patterns) = difficulty_params(difficulty);
+	let config = SearchConfig {
+		advanced_patterns,
+		..SearchConfig::new(max_depth, Some(time_limit))
+	};
+	new_game(io::stdin().lock(), io::stdout().lock(), size, config)?;
+	Ok(())
+}
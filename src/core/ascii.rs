@@ -0,0 +1,142 @@
+use std::fmt::Write;
+
+use crate::core::board::{Board, Player};
+use crate::core::state::{GameOutcome, GameState, MAX_BOARD_SIZE};
+
+const EMPTY_CELL: char = '.';
+/// Width of one column, enough for two-digit column numbers and a space
+const CELL_WIDTH: usize = 3;
+
+fn stone_char(player: Player) -> char {
+    match player {
+        Player::Max => 'X',
+        Player::Min => 'O',
+    }
+}
+
+fn parse_cell(cell: char) -> Result<Option<Player>, String> {
+    match cell {
+        EMPTY_CELL => Ok(None),
+        'X' => Ok(Some(Player::Max)),
+        'O' => Ok(Some(Player::Min)),
+        _ => Err(format!("unexpected '{}' in the diagram, cells are '.', 'X' or 'O'", cell)),
+    }
+}
+
+/// Cells of each row of a diagram, see `Board::from_ascii`
+fn parse_rows(diagram: &str) -> Result<Vec<Vec<Option<Player>>>, String> {
+    let mut lines: Vec<&str> = diagram.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    // Column numbers over the grid, as `to_ascii` writes them
+    if lines
+        .first()
+        .is_some_and(|header| header.split_whitespace().all(|label| label.parse::<usize>().is_ok()))
+    {
+        lines.remove(0);
+    }
+
+    let mut rows = Vec::with_capacity(lines.len());
+    for line in lines {
+        let mut labels = line.split_whitespace().peekable();
+        // A row number, cells are never digits
+        labels.next_if(|label| label.parse::<usize>().is_ok());
+        let cells = labels
+            .flat_map(str::chars)
+            .map(parse_cell)
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.push(cells);
+    }
+
+    let cols = rows.first().map_or(0, Vec::len);
+    if cols == 0 {
+        return Err("the diagram has no cells".to_string());
+    }
+    if let Some((index, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != cols) {
+        return Err(format!("row {} has {} cells, the first one has {}", index, row.len(), cols));
+    }
+    Ok(rows)
+}
+
+impl Board {
+    /// Plain-text diagram for logs and bug reports: one line per row, `.` for empty cells,
+    /// `X` for Max and `O` for Min, with row and column numbers from 0. Lines carry no
+    /// trailing spaces and each ends with a newline.
+    pub fn to_ascii(&self) -> String {
+        let label_width = (self.rows.max(1) - 1).to_string().len();
+        let mut out = " ".repeat(label_width);
+        for col in 0..self.cols {
+            let _ = write!(out, "{:>CELL_WIDTH$}", col);
+        }
+        out.push('\n');
+
+        for row in 0..self.rows {
+            let _ = write!(out, "{:>label_width$}", row);
+            for col in 0..self.cols {
+                let cell = self.get_player(row, col).map_or(EMPTY_CELL, stone_char);
+                let _ = write!(out, "{:>CELL_WIDTH$}", cell);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Board drawn by `diagram`, one line per row with `.` for empty cells, `X` for Max
+    /// and `O` for Min. Cells may be separated by spaces, and the row and column numbers
+    /// of `to_ascii` are skipped, so `from_ascii(&board.to_ascii())` gives `board` back.
+    /// The size comes from the diagram; rows of different lengths are an error.
+    pub fn from_ascii(diagram: &str) -> Result<Board, String> {
+        let rows = parse_rows(diagram)?;
+        let mut board = Board::new_rect(rows.len(), rows[0].len());
+        for (row, cells) in rows.iter().enumerate() {
+            for (col, &cell) in cells.iter().enumerate() {
+                if let Some(player) = cell {
+                    board.place_stone(row, col, player);
+                }
+            }
+        }
+        Ok(board)
+    }
+}
+
+impl GameState {
+    /// Position drawn by `diagram`, see `Board::from_ascii`, played to `win_condition`.
+    /// X moves first, so X is to move when both sides have as many stones. The game
+    /// starts there with no move history and no captures.
+    pub fn from_ascii(diagram: &str, win_condition: usize) -> Result<GameState, String> {
+        let board = Board::from_ascii(diagram)?;
+        if board.size > MAX_BOARD_SIZE {
+            return Err(format!(
+                "a {}x{} board is larger than the supported {}x{}",
+                board.rows, board.cols, MAX_BOARD_SIZE, MAX_BOARD_SIZE
+            ));
+        }
+        let mut state = GameState::new_rect(board.rows, board.cols, win_condition);
+        let max_stones = board.count_player_stones(Player::Max);
+        let min_stones = board.count_player_stones(Player::Min);
+        state.current_player = if max_stones == min_stones { Player::Max } else { Player::Min };
+        state.board = board;
+        state.current_hash = state.zobrist_hash.compute_hash(&state);
+        Ok(state)
+    }
+
+    /// `Board::to_ascii` followed by the captured pairs and the side to move, or the
+    /// result once the game is over
+    pub fn to_ascii(&self) -> String {
+        let mut out = self.board.to_ascii();
+        let _ = writeln!(
+            out,
+            "Captures: X {}, O {} (of {})",
+            self.max_captures, self.min_captures, self.capture_win_pairs
+        );
+        let _ = match self.outcome() {
+            None => writeln!(out, "{} to move", stone_char(self.current_player)),
+            Some(
+                GameOutcome::FiveInARow(winner)
+                | GameOutcome::Captures(winner)
+                | GameOutcome::Resignation(winner)
+                | GameOutcome::Timeout(winner),
+            ) => writeln!(out, "{} wins", stone_char(winner)),
+            Some(GameOutcome::Draw | GameOutcome::DrawAgreed) => writeln!(out, "Draw"),
+        };
+        out
+    }
+}
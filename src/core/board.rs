@@ -1,8 +1,13 @@
 use std::hash::Hash;
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// Largest supported board side. Search cost and UI entity counts grow with the
+/// square of the size, so bigger boards are refused rather than left to crawl.
+pub const MAX_BOARD_SIZE: usize = 25;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Player {
     Max,
     Min,
@@ -17,32 +22,271 @@ impl Player {
     }
 }
 
-#[derive(Resource, Component, Clone, Debug, PartialEq, Eq, Hash)]
+/// Stones that differ between two boards, see `Board::diff`. A stone that changed colour
+/// shows up in both lists.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoardDiff {
+    pub added: Vec<(usize, usize, Player)>,
+    pub removed: Vec<(usize, usize, Player)>,
+}
+
+/// The four line directions as `(row, col)` steps: columns, rows, diagonals running down
+/// to the right, and diagonals running down to the left. `Board::line_bits` indexes them.
+pub const LINE_DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+/// Stones of each player along one board line, bit `i` for the `i`th cell from where the
+/// line enters the board
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LineBits {
+    pub max: u64,
+    pub min: u64,
+}
+
+impl LineBits {
+    pub fn occupied(&self) -> u64 {
+        self.max | self.min
+    }
+
+    pub fn player_at(&self, position: usize) -> Option<Player> {
+        if self.max >> position & 1 == 1 {
+            Some(Player::Max)
+        } else if self.min >> position & 1 == 1 {
+            Some(Player::Min)
+        } else {
+            None
+        }
+    }
+}
+
+/// `LineBits` of every line in each of the four directions, kept up to date by
+/// `place_stone` and `remove_stone`
+#[derive(Clone, Debug, Default)]
+struct LineCache {
+    lines: [Vec<LineBits>; 4],
+}
+
+// Only a cache of the bitboards: boards with the same stones are equal whatever it holds
+impl PartialEq for LineCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for LineCache {}
+
+impl Hash for LineCache {
+    fn hash<H: std::hash::Hasher>(&self, _: &mut H) {}
+}
+
+/// Serialized as its bitboard words; `u64_count` and `total_cells` are derived from the
+/// dimensions. Cells are stored row by row, `cols` bits per row.
+#[derive(Resource, Component, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "BoardData", try_from = "BoardData")]
 pub struct Board {
-    pub max_bits: Vec<u64>,
-    pub min_bits: Vec<u64>,
-    pub occupied: Vec<u64>,
+    /// Private so that every change goes through a method that keeps `lines` in step
+    max_bits: Vec<u64>,
+    min_bits: Vec<u64>,
+    occupied: Vec<u64>,
+    /// Side of a square board; the longer side of a rectangular one. Bounds checks use
+    /// `rows` and `cols`.
     pub size: usize,
+    pub rows: usize,
+    pub cols: usize,
     pub u64_count: usize,
     pub total_cells: usize,
+    lines: LineCache,
+}
+
+/// `rows` and `cols` are left out of older saves, which only had square boards
+#[derive(Serialize, Deserialize)]
+struct BoardData {
+    size: usize,
+    #[serde(default)]
+    rows: Option<usize>,
+    #[serde(default)]
+    cols: Option<usize>,
+    max_bits: Vec<u64>,
+    min_bits: Vec<u64>,
+    occupied: Vec<u64>,
+}
+
+impl From<Board> for BoardData {
+    fn from(board: Board) -> Self {
+        BoardData {
+            size: board.size,
+            rows: Some(board.rows),
+            cols: Some(board.cols),
+            max_bits: board.max_bits,
+            min_bits: board.min_bits,
+            occupied: board.occupied,
+        }
+    }
+}
+
+impl TryFrom<BoardData> for Board {
+    type Error = String;
+
+    fn try_from(data: BoardData) -> Result<Self, Self::Error> {
+        let (rows, cols) = (data.rows.unwrap_or(data.size), data.cols.unwrap_or(data.size));
+        if !(1..=MAX_BOARD_SIZE).contains(&rows) || !(1..=MAX_BOARD_SIZE).contains(&cols) {
+            return Err(format!("unsupported board size {}x{}", rows, cols));
+        }
+        if data.size != rows.max(cols) {
+            return Err(format!("size {} does not match a {}x{} board", data.size, rows, cols));
+        }
+        let mut board = Board::new_rect(rows, cols);
+        for (name, bits) in [("max_bits", &data.max_bits), ("min_bits", &data.min_bits), ("occupied", &data.occupied)] {
+            if bits.len() != board.u64_count {
+                return Err(format!("{} has {} words, expected {}", name, bits.len(), board.u64_count));
+            }
+        }
+
+        for i in 0..board.u64_count {
+            let (max, min) = (data.max_bits[i], data.min_bits[i]);
+            if max & min != 0 {
+                return Err("a cell holds stones of both players".to_string());
+            }
+            if data.occupied[i] != max | min {
+                return Err("occupied bits do not match the players' stones".to_string());
+            }
+        }
+
+        // Bits past the last cell would never be seen by the move generator
+        let tail = board.total_cells % 64;
+        if tail != 0 && data.occupied[board.u64_count - 1] >> tail != 0 {
+            return Err("stones outside the board".to_string());
+        }
+
+        board.max_bits = data.max_bits;
+        board.min_bits = data.min_bits;
+        board.occupied = data.occupied;
+        board.rebuild_lines();
+        Ok(board)
+    }
 }
 
 impl Board {
     pub fn new(size: usize) -> Self {
-        let total_cells = size * size;
+        Self::new_rect(size, size)
+    }
+
+    pub fn new_rect(rows: usize, cols: usize) -> Self {
+        let total_cells = rows * cols;
         let u64_count = (total_cells + 63) / 64;
+        let diagonals = (rows + cols).saturating_sub(1);
         Board {
             max_bits: vec![0u64; u64_count],
             min_bits: vec![0u64; u64_count],
             occupied: vec![0u64; u64_count],
-            size,
+            size: rows.max(cols),
+            rows,
+            cols,
             u64_count,
             total_cells,
+            lines: LineCache {
+                lines: [cols, rows, diagonals, diagonals].map(|count| vec![LineBits::default(); count]),
+            },
+        }
+    }
+
+    /// Number of lines in `LINE_DIRECTIONS[direction]`
+    pub fn line_count(&self, direction: usize) -> usize {
+        self.lines.lines.get(direction).map_or(0, Vec::len)
+    }
+
+    /// Line through `(row, col)` in `LINE_DIRECTIONS[direction]` and the cell's position
+    /// along it, as `(index, position)` for `line_bits`
+    pub fn line_position(&self, row: usize, col: usize, direction: usize) -> (usize, usize) {
+        match direction {
+            0 => (col, row),
+            1 => (row, col),
+            2 => (row + self.cols - 1 - col, row.min(col)),
+            _ => (row + col, row.min(self.cols - 1 - col)),
+        }
+    }
+
+    /// Cell at `position` along line `index` of `LINE_DIRECTIONS[direction]`, the
+    /// inverse of `line_position`
+    pub fn line_cell(&self, direction: usize, index: usize, position: usize) -> (usize, usize) {
+        let last_col = self.cols - 1;
+        match direction {
+            0 => (position, index),
+            1 => (index, position),
+            2 => (index.saturating_sub(last_col) + position, last_col.saturating_sub(index) + position),
+            _ => (index.saturating_sub(last_col) + position, index.min(last_col) - position),
+        }
+    }
+
+    /// Stones along line `index` of `LINE_DIRECTIONS[direction]`, empty past the last line.
+    /// Lets a scan jump between stones with bit operations instead of reading every cell.
+    pub fn line_bits(&self, direction: usize, index: usize) -> LineBits {
+        self.lines
+            .lines
+            .get(direction)
+            .and_then(|lines| lines.get(index))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn set_line_cell(&mut self, row: usize, col: usize, player: Option<Player>) {
+        for direction in 0..LINE_DIRECTIONS.len() {
+            let (index, position) = self.line_position(row, col, direction);
+            let line = &mut self.lines.lines[direction][index];
+            let bit = 1u64 << position;
+            line.max &= !bit;
+            line.min &= !bit;
+            match player {
+                Some(Player::Max) => line.max |= bit,
+                Some(Player::Min) => line.min |= bit,
+                None => {}
+            }
+        }
+    }
+
+    /// Max's stones, one bit per cell row by row
+    pub fn max_bits(&self) -> &[u64] {
+        &self.max_bits
+    }
+
+    pub fn min_bits(&self) -> &[u64] {
+        &self.min_bits
+    }
+
+    /// Every stone, `max_bits | min_bits` on a consistent board
+    pub fn occupied(&self) -> &[u64] {
+        &self.occupied
+    }
+
+    /// Hands the Max, Min and occupied bitboards to `edit` for changes the stone methods
+    /// do not cover, then rebuilds the line cache from them. Nothing checks that the
+    /// three still agree, see `GameState::validate_integrity`.
+    pub fn edit_bits(&mut self, edit: impl FnOnce(&mut [u64], &mut [u64], &mut [u64])) {
+        edit(&mut self.max_bits, &mut self.min_bits, &mut self.occupied);
+        self.rebuild_lines();
+    }
+
+    /// Recomputes the line cache from the bitboards
+    fn rebuild_lines(&mut self) {
+        for lines in &mut self.lines.lines {
+            lines.fill(LineBits::default());
+        }
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let player = self.get_player(row, col);
+                if player.is_some() {
+                    self.set_line_cell(row, col, player);
+                }
+            }
         }
     }
 
     pub fn index(&self, row: usize, col: usize) -> usize {
-        row * self.size + col
+        row * self.cols + col
+    }
+
+    /// Whether `(row, col)` is on the board, for coordinates stepped off an edge
+    pub fn in_bounds(&self, row: isize, col: isize) -> bool {
+        row >= 0 && col >= 0 && row < self.rows as isize && col < self.cols as isize
     }
 
     pub fn set_bit(bits: &mut [u64], idx: usize) {
@@ -76,24 +320,27 @@ impl Board {
         self.occupied.iter().all(|&b| b == 0)
     }
 
+    fn popcount(bits: &[u64]) -> usize {
+        bits.iter().map(|&b| b.count_ones() as usize).sum()
+    }
+
     pub fn count_stones(&self) -> usize {
-        self.occupied.iter().map(|&bits| bits.count_ones() as usize).sum()
+        Self::popcount(&self.occupied)
     }
 
     pub fn count_player_stones(&self, player: Player) -> usize {
-        let bits = match player {
-            Player::Max => &self.max_bits,
-            Player::Min => &self.min_bits,
-        };
-        bits.iter().map(|&b| b.count_ones() as usize).sum()
+        match player {
+            Player::Max => Self::popcount(&self.max_bits),
+            Player::Min => Self::popcount(&self.min_bits),
+        }
     }
 
     pub fn center(&self) -> (usize, usize) {
-        (self.size / 2, self.size / 2)
+        (self.rows / 2, self.cols / 2)
     }
 
     pub fn is_empty_position(&self, row: usize, col: usize) -> bool {
-        if row >= self.size || col >= self.size {
+        if row >= self.rows || col >= self.cols {
             return false;
         }
         let idx = self.index(row, col);
@@ -101,7 +348,7 @@ impl Board {
     }
 
     pub fn get_player(&self, row: usize, col: usize) -> Option<Player> {
-        if row >= self.size || col >= self.size {
+        if row >= self.rows || col >= self.cols {
             return None;
         }
         let idx = self.index(row, col);
@@ -115,7 +362,7 @@ impl Board {
     }
 
     pub fn place_stone(&mut self, row: usize, col: usize, player: Player) {
-        if row >= self.size || col >= self.size {
+        if row >= self.rows || col >= self.cols {
             return;
         }
         let idx = self.index(row, col);
@@ -124,20 +371,71 @@ impl Board {
             Player::Min => Self::set_bit(&mut self.min_bits, idx),
         }
         Self::set_bit(&mut self.occupied, idx);
+        self.set_line_cell(row, col, Some(player));
     }
 
     pub fn remove_stone(&mut self, row: usize, col: usize) {
-        if row >= self.size || col >= self.size {
+        if row >= self.rows || col >= self.cols {
             return;
         }
         let idx = self.index(row, col);
         Self::clear_bit(&mut self.max_bits, idx);
         Self::clear_bit(&mut self.min_bits, idx);
         Self::clear_bit(&mut self.occupied, idx);
+        self.set_line_cell(row, col, None);
+    }
+
+    /// Removes every stone inside the rectangle spanned by the two corners (inclusive).
+    /// Corners outside the board are clamped to its edges.
+    pub fn clear_region(&mut self, top_left: (usize, usize), bottom_right: (usize, usize)) {
+        if self.total_cells == 0 {
+            return;
+        }
+        let (top, left) = top_left;
+        let bottom = bottom_right.0.min(self.rows - 1);
+        let right = bottom_right.1.min(self.cols - 1);
+        if top > bottom || left > right {
+            return;
+        }
+
+        for row in top..=bottom {
+            let start = self.index(row, left);
+            let end = self.index(row, right);
+            Self::clear_bit_range(&mut self.max_bits, start, end);
+            Self::clear_bit_range(&mut self.min_bits, start, end);
+            Self::clear_bit_range(&mut self.occupied, start, end);
+        }
+        self.rebuild_lines();
+    }
+
+    /// Removes every stone from the board.
+    pub fn clear(&mut self) {
+        self.max_bits.fill(0);
+        self.min_bits.fill(0);
+        self.occupied.fill(0);
+        for lines in &mut self.lines.lines {
+            lines.fill(LineBits::default());
+        }
+    }
+
+    fn clear_bit_range(bits: &mut [u64], start: usize, end: usize) {
+        let mut idx = start;
+        while idx <= end {
+            let array_idx = idx / 64;
+            if array_idx >= bits.len() {
+                return;
+            }
+            let low = idx % 64;
+            let high = (end - array_idx * 64).min(63);
+            let width = high - low + 1;
+            let mask = if width == 64 { u64::MAX } else { ((1u64 << width) - 1) << low };
+            bits[array_idx] &= !mask;
+            idx = (array_idx + 1) * 64;
+        }
     }
 
     pub fn is_adjacent_to_stone(&self, row: usize, col: usize) -> bool {
-        if row >= self.size || col >= self.size {
+        if row >= self.rows || col >= self.cols {
             return false;
         }
         
@@ -149,7 +447,7 @@ impl Board {
                 }
                 let nr = row as isize + dr;
                 let nc = col as isize + dc;
-                if nr >= 0 && nc >= 0 && nr < self.size as isize && nc < self.size as isize {
+                if self.in_bounds(nr, nc) {
                     let idx = self.index(nr as usize, nc as usize);
                     if Self::is_bit_set(&self.occupied, idx) {
                         return true;
@@ -188,7 +486,7 @@ impl Board {
         direction: (isize, isize),
         length: usize,
     ) -> usize {
-        if row >= self.size || col >= self.size {
+        if row >= self.rows || col >= self.cols {
             return 0;
         }
         let bits = match player {
@@ -199,11 +497,7 @@ impl Board {
         let mut current_row = row as isize;
         let mut current_col = col as isize;
         for _ in 0..length {
-            if current_row < 0
-                || current_col < 0
-                || current_row >= self.size as isize
-                || current_col >= self.size as isize
-            {
+            if !self.in_bounds(current_row, current_col) {
                 break;
             }
             let idx = self.index(current_row as usize, current_col as usize);
@@ -232,8 +526,8 @@ impl Board {
                 let bit_pos = bits.trailing_zeros() as usize;
                 let global_idx = array_idx * 64 + bit_pos;
                 if global_idx < self.total_cells {
-                    let row = global_idx / self.size;
-                    let col = global_idx % self.size;
+                    let row = global_idx / self.cols;
+                    let col = global_idx % self.cols;
                     empties.push((row, col));
                 }
                 bits &= bits - 1;
@@ -242,6 +536,32 @@ impl Board {
         empties
     }
 
+    /// Stones placed and taken away going from `self` to `other`, in board order. Both
+    /// boards must have the same dimensions.
+    pub fn diff(&self, other: &Board) -> BoardDiff {
+        debug_assert_eq!((self.rows, self.cols), (other.rows, other.cols), "diff of boards of different sizes");
+        let mut diff = BoardDiff::default();
+        for (player, before, after) in [
+            (Player::Max, &self.max_bits, &other.max_bits),
+            (Player::Min, &self.min_bits, &other.min_bits),
+        ] {
+            for (array_idx, (&old, &new)) in before.iter().zip(after).enumerate() {
+                let changed = old ^ new;
+                for (bits, list) in [(changed & new, &mut diff.added), (changed & old, &mut diff.removed)] {
+                    let mut bits = bits;
+                    while bits != 0 {
+                        let global_idx = array_idx * 64 + bits.trailing_zeros() as usize;
+                        list.push((global_idx / self.cols, global_idx % self.cols, player));
+                        bits &= bits - 1;
+                    }
+                }
+            }
+        }
+        diff.added.sort_unstable_by_key(|&(row, col, _)| (row, col));
+        diff.removed.sort_unstable_by_key(|&(row, col, _)| (row, col));
+        diff
+    }
+
     pub fn get_occupied_positions(&self) -> Vec<((usize, usize), Player)> {
         let mut positions = Vec::new();
         for array_idx in 0..self.u64_count {
@@ -252,8 +572,8 @@ impl Board {
                 let bit_pos = occupied_bits.trailing_zeros() as usize;
                 let global_idx = array_idx * 64 + bit_pos;
                 if global_idx < self.total_cells {
-                    let row = global_idx / self.size;
-                    let col = global_idx % self.size;
+                    let row = global_idx / self.cols;
+                    let col = global_idx % self.cols;
                     let player = if (max_bits & (1u64 << bit_pos)) != 0 {
                         Player::Max
                     } else {
@@ -13,12 +13,12 @@ impl CaptureHandler {
         let directions = [(1, 0), (0, 1), (1, 1), (1, -1)];
         let opponent = player.opponent();
         let player_bits = match player {
-            Player::Max => &board.max_bits,
-            Player::Min => &board.min_bits,
+            Player::Max => board.max_bits(),
+            Player::Min => board.min_bits(),
         };
         let opponent_bits = match opponent {
-            Player::Max => &board.max_bits,
-            Player::Min => &board.min_bits,
+            Player::Max => board.max_bits(),
+            Player::Min => board.min_bits(),
         };
 
         for &(dx, dy) in &directions {
@@ -28,11 +28,7 @@ impl CaptureHandler {
 
                 let pos1_x = row as isize + actual_dx;
                 let pos1_y = col as isize + actual_dy;
-                if pos1_x < 0
-                    || pos1_y < 0
-                    || pos1_x >= board.size as isize
-                    || pos1_y >= board.size as isize
-                {
+                if !board.in_bounds(pos1_x, pos1_y) {
                     continue;
                 }
                 let idx1 = board.index(pos1_x as usize, pos1_y as usize);
@@ -42,11 +38,7 @@ impl CaptureHandler {
 
                 let pos2_x = pos1_x + actual_dx;
                 let pos2_y = pos1_y + actual_dy;
-                if pos2_x < 0
-                    || pos2_y < 0
-                    || pos2_x >= board.size as isize
-                    || pos2_y >= board.size as isize
-                {
+                if !board.in_bounds(pos2_x, pos2_y) {
                     continue;
                 }
                 let idx2 = board.index(pos2_x as usize, pos2_y as usize);
@@ -56,11 +48,7 @@ impl CaptureHandler {
 
                 let pos3_x = pos2_x + actual_dx;
                 let pos3_y = pos2_y + actual_dy;
-                if pos3_x < 0
-                    || pos3_y < 0
-                    || pos3_x >= board.size as isize
-                    || pos3_y >= board.size as isize
-                {
+                if !board.in_bounds(pos3_x, pos3_y) {
                     continue;
                 }
                 let idx3 = board.index(pos3_x as usize, pos3_y as usize);
@@ -76,10 +64,7 @@ impl CaptureHandler {
 
     pub fn execute_captures(board: &mut Board, captures: &[(usize, usize)]) {
         for &(r, c) in captures {
-            let idx = board.index(r, c);
-            Board::clear_bit(&mut board.max_bits, idx);
-            Board::clear_bit(&mut board.min_bits, idx);
-            Board::clear_bit(&mut board.occupied, idx);
+            board.remove_stone(r, c);
         }
     }
 }
@@ -0,0 +1,91 @@
+use serde::Deserialize;
+
+use crate::ai::heuristic::LineEval;
+use crate::ai::pattern_history::PatternHistoryAnalyzer;
+use crate::ai::zobrist::ZobristHash;
+use crate::core::board::{Board, Player};
+use crate::core::rules::{OpeningPhase, OpeningRestriction, RuleFlags};
+use crate::core::state::{GameOutcome, GameState, WinReason};
+
+/// Serialized form of a `GameState`: everything but the fields rebuilt on load
+#[derive(Deserialize)]
+pub(crate) struct GameStateData {
+    board: Board,
+    current_player: Player,
+    win_condition: usize,
+    winner: Option<Player>,
+    #[serde(default)]
+    win_reason: Option<WinReason>,
+    max_captures: usize,
+    min_captures: usize,
+    capture_history: Vec<Vec<(usize, usize)>>,
+    move_history: Vec<(usize, usize)>,
+    moves: Vec<(usize, usize, Player)>,
+    capture_win_pairs: usize,
+    rules: RuleFlags,
+    opening: OpeningPhase,
+    #[serde(default)]
+    opening_restriction: OpeningRestriction,
+    colors_swapped: bool,
+    redo_history: Vec<(usize, usize)>,
+    #[serde(default)]
+    ended_early: Option<GameOutcome>,
+}
+
+impl TryFrom<GameStateData> for GameState {
+    type Error = String;
+
+    fn try_from(data: GameStateData) -> Result<Self, Self::Error> {
+        let (rows, cols) = (data.board.rows, data.board.cols);
+        let on_board = |&(row, col): &(usize, usize)| row < rows && col < cols;
+        if !data.move_history.iter().all(on_board)
+            || !data.redo_history.iter().all(on_board)
+            || !data.moves.iter().all(|&(row, col, _)| on_board(&(row, col)))
+            || !data.capture_history.iter().flatten().all(on_board)
+        {
+            return Err("move outside the board".to_string());
+        }
+
+        let zobrist_hash = ZobristHash::new_rect(rows, cols);
+        let mut state = GameState {
+            board: data.board,
+            current_player: data.current_player,
+            win_condition: data.win_condition,
+            winner: data.winner,
+            win_reason: data.win_reason,
+            max_captures: data.max_captures,
+            min_captures: data.min_captures,
+            capture_history: data.capture_history,
+            move_history: data.move_history,
+            moves: data.moves,
+            pattern_analyzer: PatternHistoryAnalyzer::new(),
+            zobrist_hash: zobrist_hash.clone(),
+            current_hash: 0,
+            capture_win_pairs: data.capture_win_pairs,
+            rules: data.rules,
+            opening: data.opening,
+            opening_restriction: data.opening_restriction,
+            colors_swapped: data.colors_swapped,
+            redo_history: data.redo_history,
+            position_history: Vec::new(),
+            ended_early: data.ended_early,
+            line_eval: LineEval::default(),
+        };
+        state.current_hash = zobrist_hash.compute_hash(&state);
+        state.validate_integrity().map_err(|e| e.to_string())?;
+        Ok(state)
+    }
+}
+
+impl GameState {
+    /// Full game as JSON. The Zobrist keys and hash are left out and rebuilt by `from_json`.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("game state has no non-string map keys")
+    }
+
+    /// Loads a game written by `to_json`, checking that the board is consistent, see
+    /// `validate_integrity`
+    pub fn from_json(json: &str) -> Result<GameState, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
@@ -1,13 +1,107 @@
+use std::fmt;
+
 use crate::core::board::{Board, Player};
+use crate::core::captures::CaptureHandler;
+use crate::core::rules::RuleFlags;
 
 const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
 const FREE_THREE_LENGTH: usize = 3;
 const MAX_SEARCH_DISTANCE: isize = 4;
+const DEFAULT_MAX_THREAT_MOVES: usize = 30;
+// Renju is always played to exactly five
+const RENJU_FIVE: usize = 5;
+
+/// Why a move is forbidden for black under renju rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ForbiddenReason {
+    DoubleThree,
+    DoubleFour,
+    Overline,
+}
+
+/// Why a move cannot be played, from `MoveHandler::check_move` or `GameState::is_legal_move`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IllegalMove {
+    OutOfBounds,
+    Occupied,
+    /// The first stone goes in the center
+    OffCenterOpening,
+    /// Once stones are down, moves must touch one of them
+    NotAdjacentOnNonEmptyBoard,
+    DoubleThree,
+    DoubleFour,
+    Overline,
+    /// Pro and long-pro openings keep black's second stone away from the center
+    TooCloseToCenter,
+    /// The Swap2 opening is waiting for the second player to pick a color
+    AwaitingSwap2Choice,
+    GameOver,
+}
+
+impl From<ForbiddenReason> for IllegalMove {
+    fn from(reason: ForbiddenReason) -> Self {
+        match reason {
+            ForbiddenReason::DoubleThree => IllegalMove::DoubleThree,
+            ForbiddenReason::DoubleFour => IllegalMove::DoubleFour,
+            ForbiddenReason::Overline => IllegalMove::Overline,
+        }
+    }
+}
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            IllegalMove::OutOfBounds => "cell is off the board",
+            IllegalMove::Occupied => "cell is occupied",
+            IllegalMove::OffCenterOpening => "the first stone goes in the center",
+            IllegalMove::NotAdjacentOnNonEmptyBoard => "move is not next to any stone",
+            IllegalMove::DoubleThree => "move makes a forbidden double-three",
+            IllegalMove::DoubleFour => "move makes a forbidden double-four",
+            IllegalMove::Overline => "move makes a forbidden overline",
+            IllegalMove::TooCloseToCenter => "black's second stone is too close to the center",
+            IllegalMove::AwaitingSwap2Choice => "a Swap2 color choice is pending",
+            IllegalMove::GameOver => "game is already over",
+        };
+        f.write_str(reason)
+    }
+}
+
+impl std::error::Error for IllegalMove {}
+
+/// How much a move changes the longest line through its cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ThreatLevel {
+    Three,
+    Four,
+    Five,
+}
+
+/// Ordering preferences for `MoveHandler::find_threat_moves`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThreatOrdering {
+    /// At equal severity, list our own threats before blocks of the opponent's
+    pub prefer_offense: bool,
+    /// Give up (return `None`) when more threat moves than this are found
+    pub max_moves: usize,
+}
+
+impl Default for ThreatOrdering {
+    fn default() -> Self {
+        Self {
+            prefer_offense: true,
+            max_moves: DEFAULT_MAX_THREAT_MOVES,
+        }
+    }
+}
 
 pub struct MoveHandler;
 
 impl MoveHandler {
     pub fn get_possible_moves(board: &Board, player: Player) -> Vec<(usize, usize)> {
+        Self::get_possible_moves_with_rules(board, player, &RuleFlags::default())
+    }
+
+    pub fn get_possible_moves_with_rules(board: &Board, player: Player, rules: &RuleFlags) -> Vec<(usize, usize)> {
         if board.is_empty() {
             return vec![board.center()];
         }
@@ -15,23 +109,433 @@ impl MoveHandler {
         board
             .get_empty_positions()
             .into_iter()
-            .filter(|&(i, j)| {
-                board.is_adjacent_to_stone(i, j)
-                    && !RuleValidator::creates_double_three(board, i, j, player)
+            .filter(|&(i, j)| Self::check_move(board, i, j, player, rules).is_ok())
+            .collect()
+    }
+
+    /// The rule every generated move obeys in normal play: on the board, empty, the
+    /// center on an empty board and next to a stone otherwise, and not forbidden for `player`
+    pub fn check_move(board: &Board, row: usize, col: usize, player: Player, rules: &RuleFlags) -> Result<(), IllegalMove> {
+        if row >= board.rows || col >= board.cols {
+            return Err(IllegalMove::OutOfBounds);
+        }
+        if !board.is_empty_position(row, col) {
+            return Err(IllegalMove::Occupied);
+        }
+        if board.is_empty() {
+            return if (row, col) == board.center() { Ok(()) } else { Err(IllegalMove::OffCenterOpening) };
+        }
+        if !board.is_adjacent_to_stone(row, col) {
+            return Err(IllegalMove::NotAdjacentOnNonEmptyBoard);
+        }
+        match RuleValidator::forbidden_reason(board, row, col, player, rules) {
+            Some(reason) => Err(reason.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Same answer as `!get_possible_moves_with_rules(..).is_empty()`, but stops at the
+    /// first legal cell instead of collecting them all
+    pub fn has_any_legal_move(board: &Board, player: Player, rules: &RuleFlags) -> bool {
+        if board.is_empty() {
+            return true;
+        }
+
+        for (word_idx, &occupied) in board.occupied().iter().enumerate() {
+            let mut empty = !occupied;
+            let cells_in_word = board.total_cells - word_idx * 64;
+            if cells_in_word < 64 {
+                empty &= (1u64 << cells_in_word) - 1;
+            }
+
+            while empty != 0 {
+                let idx = word_idx * 64 + empty.trailing_zeros() as usize;
+                empty &= empty - 1;
+                let (row, col) = (idx / board.cols, idx % board.cols);
+                if Self::check_move(board, row, col, player, rules).is_ok() {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Moves legal under `rules` that make a three or longer for `player` (offense) or stop
+    /// the opponent from making one (defense), most severe first. Returns `None` when there
+    /// are no such moves or more than `ordering.max_moves`, so the caller can fall back to
+    /// regular move generation.
+    pub fn find_threat_moves(
+        board: &Board,
+        player: Player,
+        rules: &RuleFlags,
+        win_condition: usize,
+        ordering: &ThreatOrdering,
+    ) -> Option<Vec<(usize, usize)>> {
+        let candidates = Self::get_possible_moves_with_rules(board, player, rules);
+        Self::find_threat_moves_among(board, player, &candidates, win_condition, ordering)
+    }
+
+    /// `find_threat_moves` over the given `candidates` only, for a caller that already
+    /// generated its moves under the game's rules
+    pub fn find_threat_moves_among(
+        board: &Board,
+        player: Player,
+        candidates: &[(usize, usize)],
+        win_condition: usize,
+        ordering: &ThreatOrdering,
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut threats: Vec<((usize, usize), ThreatLevel, bool)> = candidates
+            .iter()
+            .filter_map(|&mv| {
+                let offense = Self::live_threat_level(board, mv.0, mv.1, player, win_condition);
+                let defense = Self::live_threat_level(board, mv.0, mv.1, player.opponent(), win_condition);
+                match (offense, defense) {
+                    (None, None) => None,
+                    (Some(level), None) => Some((mv, level, true)),
+                    (None, Some(level)) => Some((mv, level, false)),
+                    (Some(attack), Some(block)) => {
+                        if attack > block || (attack == block && ordering.prefer_offense) {
+                            Some((mv, attack, true))
+                        } else {
+                            Some((mv, block, false))
+                        }
+                    }
+                }
+            })
+            .collect();
+
+        if threats.is_empty() || threats.len() > ordering.max_moves {
+            return None;
+        }
+
+        threats.sort_by_key(|&(_, level, offensive)| {
+            (std::cmp::Reverse(level), offensive != ordering.prefer_offense)
+        });
+        Some(threats.into_iter().map(|(mv, _, _)| mv).collect())
+    }
+
+    /// Narrow candidate set for tactical positions: once `player` has a three or a four,
+    /// only the moves that turn it into a four or five, or that stop the opponent from
+    /// doing the same, most severe first. Returns `None` when `player` has nothing to
+    /// extend, so the caller can fall back to regular move generation. Cells `rules`
+    /// forbid for `player` are left out.
+    pub fn get_threat_space_moves(
+        board: &Board,
+        player: Player,
+        rules: &RuleFlags,
+        win_condition: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut threats: Vec<((usize, usize), ThreatLevel, bool)> = Self::get_possible_moves_with_rules(board, player, rules)
+            .into_iter()
+            .filter_map(|mv| {
+                let attack = Self::live_threat_level(board, mv.0, mv.1, player, win_condition)
+                    .filter(|&level| level >= ThreatLevel::Four);
+                let block = Self::live_threat_level(board, mv.0, mv.1, player.opponent(), win_condition)
+                    .filter(|&level| level >= ThreatLevel::Four);
+                Some((mv, attack.max(block)?, attack.is_some()))
+            })
+            .collect();
+
+        if !threats.iter().any(|&(_, _, offensive)| offensive) {
+            return None;
+        }
+
+        threats.sort_by_key(|&(_, level, offensive)| (std::cmp::Reverse(level), !offensive));
+        Some(threats.into_iter().map(|(mv, _, _)| mv).collect())
+    }
+
+    /// Empty cells where `player` would capture at least one pair
+    pub fn find_capturing_moves(board: &Board, player: Player) -> Vec<(usize, usize)> {
+        board
+            .get_empty_positions()
+            .into_iter()
+            .filter(|&(row, col)| {
+                board.is_adjacent_to_stone(row, col) && !CaptureHandler::detect_captures(board, row, col, player).is_empty()
             })
             .collect()
     }
+
+    /// Whether the opponent could capture a pair right after `player` takes the empty cell
+    /// `(row, col)`, counting the pairs that move captures itself
+    pub fn allows_capture_reply(board: &Board, row: usize, col: usize, player: Player) -> bool {
+        let mut after = board.clone();
+        after.place_stone(row, col, player);
+        for (r, c) in CaptureHandler::detect_captures(board, row, col, player) {
+            after.remove_stone(r, c);
+        }
+        !Self::find_capturing_moves(&after, player.opponent()).is_empty()
+    }
+
+    /// Threat `player` would get by placing a stone on the empty cell `(row, col)`. A
+    /// broken four such as `XX_XX`, one stone from a win across a gap, counts as a four.
+    /// Levels are relative to `win_condition`, see `level_for_length`.
+    pub fn threat_level(board: &Board, row: usize, col: usize, player: Player, win_condition: usize) -> Option<ThreatLevel> {
+        let level = Self::line_lengths_through(board, row, col, player)
+            .into_iter()
+            .filter_map(|length| Self::level_for_length(length, win_condition))
+            .max();
+        if level < Some(ThreatLevel::Four) && RuleValidator::count_fours(board, row, col, player, win_condition) > 0 {
+            return Some(ThreatLevel::Four);
+        }
+        level
+    }
+
+    /// Same as `threat_level`, leaving out the lines that can never reach a win: those
+    /// closed in by the board edge or opponent stones before there is room for one
+    fn live_threat_level(board: &Board, row: usize, col: usize, player: Player, win_condition: usize) -> Option<ThreatLevel> {
+        let level = DIRECTIONS
+            .into_iter()
+            .zip(Self::line_lengths_through(board, row, col, player))
+            .filter(|&(direction, _)| Self::has_room_for_win(board, row, col, player, direction, win_condition))
+            .filter_map(|(_, length)| Self::level_for_length(length, win_condition))
+            .max();
+        if level < Some(ThreatLevel::Four) && RuleValidator::count_fours(board, row, col, player, win_condition) > 0 {
+            return Some(ThreatLevel::Four);
+        }
+        level
+    }
+
+    /// Whether the line through `(row, col)` along `direction` has `win_condition` cells in
+    /// a row free of the opponent, so `player` could still make a win on it from there
+    pub fn has_room_for_win(
+        board: &Board,
+        row: usize,
+        col: usize,
+        player: Player,
+        (dr, dc): (isize, isize),
+        win_condition: usize,
+    ) -> bool {
+        let backward = Self::room_from(board, row, col, player, -dr, -dc, win_condition);
+        let forward = Self::room_from(board, row, col, player, dr, dc, win_condition);
+        1 + backward + forward >= win_condition
+    }
+
+    /// Cells past `(row, col)` in direction `(dr, dc)` that are empty or `player`'s, up to
+    /// the first opponent stone or the edge, counted up to `limit`
+    pub fn room_from(
+        board: &Board,
+        row: usize,
+        col: usize,
+        player: Player,
+        dr: isize,
+        dc: isize,
+        limit: usize,
+    ) -> usize {
+        let opponent = player.opponent();
+        let mut room = 0;
+        let mut r = row as isize + dr;
+        let mut c = col as isize + dc;
+        while room < limit
+            && RuleValidator::is_valid_pos(board, r, c)
+            && board.get_player(r as usize, c as usize) != Some(opponent)
+        {
+            room += 1;
+            r += dr;
+            c += dc;
+        }
+        room
+    }
+
+    /// Length of the line `player` would have through `(row, col)` in each direction
+    /// if a stone were placed there
+    pub fn line_lengths_through(board: &Board, row: usize, col: usize, player: Player) -> [usize; 4] {
+        DIRECTIONS.map(|(dr, dc)| {
+            1 + Self::count_stones_from(board, row, col, player, dr, dc)
+                + Self::count_stones_from(board, row, col, player, -dr, -dc)
+        })
+    }
+
+    /// Empty cells where `player` would complete `win_condition` in a row
+    pub fn find_winning_moves(
+        board: &Board,
+        player: Player,
+        win_condition: usize,
+        allow_overline: bool,
+    ) -> Vec<(usize, usize)> {
+        board
+            .get_empty_positions()
+            .into_iter()
+            .filter(|&(row, col)| Self::completes_line(board, row, col, player, win_condition, allow_overline))
+            .collect()
+    }
+
+    pub fn find_winning_move(
+        board: &Board,
+        player: Player,
+        win_condition: usize,
+        allow_overline: bool,
+    ) -> Option<(usize, usize)> {
+        Self::find_winning_moves(board, player, win_condition, allow_overline).into_iter().next()
+    }
+
+    /// Whether a stone on the empty cell `(row, col)` gives `player` a line of `win_condition`.
+    /// Without `allow_overline` a longer line does not count.
+    pub fn completes_line(
+        board: &Board,
+        row: usize,
+        col: usize,
+        player: Player,
+        win_condition: usize,
+        allow_overline: bool,
+    ) -> bool {
+        Self::line_lengths_through(board, row, col, player)
+            .into_iter()
+            .any(|length| Self::is_winning_length(length, win_condition, allow_overline))
+    }
+
+    pub fn is_winning_length(length: usize, win_condition: usize, allow_overline: bool) -> bool {
+        length == win_condition || (allow_overline && length > win_condition)
+    }
+
+    /// Threat a line of `length` stones makes when `win_condition` in a row wins: a win,
+    /// one stone short of it (a four) or two short (a three). A lone stone is no threat.
+    pub fn level_for_length(length: usize, win_condition: usize) -> Option<ThreatLevel> {
+        match win_condition.checked_sub(length) {
+            None | Some(0) => Some(ThreatLevel::Five),
+            Some(1) => Some(ThreatLevel::Four),
+            Some(2) if length > 1 => Some(ThreatLevel::Three),
+            _ => None,
+        }
+    }
+
+    fn count_stones_from(
+        board: &Board,
+        row: usize,
+        col: usize,
+        player: Player,
+        dr: isize,
+        dc: isize,
+    ) -> usize {
+        let mut count = 0;
+        let mut r = row as isize + dr;
+        let mut c = col as isize + dc;
+        while RuleValidator::is_valid_pos(board, r, c)
+            && board.get_player(r as usize, c as usize) == Some(player)
+        {
+            count += 1;
+            r += dr;
+            c += dc;
+        }
+        count
+    }
 }
 
 pub struct RuleValidator;
 
 impl RuleValidator {
+    /// Whether `rules` forbid `player` from playing on the empty cell `(row, col)`.
+    /// Under renju black gets the full set of restrictions, see `is_forbidden_for_black`.
+    pub fn is_forbidden(board: &Board, row: usize, col: usize, player: Player, rules: &RuleFlags) -> bool {
+        Self::forbidden_reason(board, row, col, player, rules).is_some()
+    }
+
+    /// Which restriction of `rules` the move breaks, if any, see `is_forbidden`. Renju
+    /// black keeps the double-four and overline bans with the double-three allowed.
+    pub fn forbidden_reason(board: &Board, row: usize, col: usize, player: Player, rules: &RuleFlags) -> Option<ForbiddenReason> {
+        if rules.renju && player == Player::Max {
+            return Self::is_forbidden_for_black(board, row, col)
+                .filter(|&reason| reason != ForbiddenReason::DoubleThree || rules.forbid_double_three);
+        }
+        (rules.forbids_double_three(player) && Self::creates_double_three(board, row, col, player))
+            .then_some(ForbiddenReason::DoubleThree)
+    }
+
+    /// Renju restrictions on black (`Player::Max`) for the empty cell `(row, col)`.
+    /// A move that makes exactly five is always allowed.
+    pub fn is_forbidden_for_black(board: &Board, row: usize, col: usize) -> Option<ForbiddenReason> {
+        let player = Player::Max;
+        let lengths = MoveHandler::line_lengths_through(board, row, col, player);
+        if lengths.contains(&RENJU_FIVE) {
+            return None;
+        }
+        if lengths.iter().any(|&length| length > RENJU_FIVE) {
+            return Some(ForbiddenReason::Overline);
+        }
+
+        if Self::count_fours(board, row, col, player, RENJU_FIVE) >= 2 {
+            return Some(ForbiddenReason::DoubleFour);
+        }
+        if Self::creates_double_three(board, row, col, player) {
+            return Some(ForbiddenReason::DoubleThree);
+        }
+        None
+    }
+
+    /// Number of fours a stone on the empty cell `(row, col)` would give `player`: lines
+    /// one stone short of exactly `win_condition`. Two fours on the same line, as in
+    /// `X_XXX_X` with the new stone in the middle, count twice.
+    pub fn count_fours(board: &Board, row: usize, col: usize, player: Player, win_condition: usize) -> usize {
+        DIRECTIONS
+            .iter()
+            .map(|&dir| Self::fours_in_direction(board, row, col, player, dir, win_condition))
+            .sum()
+    }
+
+    /// Whether the stone makes at least one four in each direction, in the order of
+    /// `line_lengths_through`
+    pub fn four_directions(board: &Board, row: usize, col: usize, player: Player, win_condition: usize) -> [bool; 4] {
+        DIRECTIONS.map(|dir| Self::fours_in_direction(board, row, col, player, dir, win_condition) > 0)
+    }
+
+    /// Distinct fours a stone on `(row, col)` makes on this line, each one a set of four
+    /// stones that an empty cell of the line would complete to exactly `win_condition`.
+    /// The two completing cells of an open four complete the same stones, so count once.
+    fn fours_in_direction(
+        board: &Board,
+        row: usize,
+        col: usize,
+        player: Player,
+        (dr, dc): (isize, isize),
+        win_condition: usize,
+    ) -> usize {
+        let reach = win_condition as isize - 1;
+        let mut fours: Vec<u64> = Vec::new();
+        for k in (-reach..=reach).filter(|&k| k != 0) {
+            let r = row as isize + dr * k;
+            let c = col as isize + dc * k;
+            if !Self::is_valid_pos(board, r, c) || !board.is_empty_position(r as usize, c as usize) {
+                continue;
+            }
+
+            // Line through the completing cell, with both new stones in place
+            let is_own = |i: isize| {
+                let (r, c) = (row as isize + dr * i, col as isize + dc * i);
+                i == 0
+                    || i == k
+                    || (Self::is_valid_pos(board, r, c) && board.get_player(r as usize, c as usize) == Some(player))
+            };
+            let mut low = k;
+            while is_own(low - 1) {
+                low -= 1;
+            }
+            let mut high = k;
+            while is_own(high + 1) {
+                high += 1;
+            }
+            // The five must run through the new stone, not just sit on the same line
+            if low <= 0 && 0 <= high && (high - low + 1) as usize == win_condition {
+                // Offsets from -reach, the five lies within reach of the new stone
+                let five = ((1u64 << win_condition) - 1) << (low + reach);
+                let stones = five & !(1u64 << (k + reach));
+                if !fours.contains(&stones) {
+                    fours.push(stones);
+                }
+            }
+        }
+        fours.len()
+    }
+
     pub fn creates_double_three(board: &Board, row: usize, col: usize, player: Player) -> bool {
+        Self::count_free_threes(board, row, col, player) >= 2
+    }
+
+    /// Number of directions in which a stone on the empty cell `(row, col)` would give
+    /// `player` a free three, solid (`XXX`) or with one gap (`X_XX`, `XX_X`)
+    pub fn count_free_threes(board: &Board, row: usize, col: usize, player: Player) -> usize {
         DIRECTIONS
             .iter()
             .filter(|&&dir| Self::is_free_three_in_direction(board, row, col, player, dir))
             .count()
-            >= 2
     }
 
     fn is_free_three_in_direction(
@@ -43,7 +547,37 @@ impl RuleValidator {
     ) -> bool {
         let (stones, left_open, right_open) = Self::analyze_line(board, row, col, player, dr, dc);
 
-        stones == FREE_THREE_LENGTH && Self::can_form_open_four(left_open, right_open)
+        (stones == FREE_THREE_LENGTH && Self::can_form_open_four(left_open, right_open))
+            || Self::is_gapped_free_three(board, row, col, player, (dr, dc))
+    }
+
+    /// Three stones over four cells with the gap inside (`X_XX` or `XX_X`), one of them the
+    /// new stone, and an empty cell at both ends: filling the gap makes an open four
+    fn is_gapped_free_three(
+        board: &Board,
+        row: usize,
+        col: usize,
+        player: Player,
+        (dr, dc): (isize, isize),
+    ) -> bool {
+        // `None` off the board, otherwise the cell's owner with the new stone in place
+        let cell = |k: isize| {
+            let (r, c) = (row as isize + dr * k, col as isize + dc * k);
+            if k == 0 {
+                return Some(Some(player));
+            }
+            Self::is_valid_pos(board, r, c).then(|| board.get_player(r as usize, c as usize))
+        };
+        let own = Some(Some(player));
+        let empty = Some(None);
+
+        (-3..=0).any(|start| {
+            let window = [cell(start), cell(start + 1), cell(start + 2), cell(start + 3)];
+            let gapped = window[0] == own
+                && window[3] == own
+                && ((window[1] == empty && window[2] == own) || (window[1] == own && window[2] == empty));
+            gapped && cell(start - 1) == empty && cell(start + 4) == empty
+        })
     }
 
     fn analyze_line(
@@ -73,12 +607,12 @@ impl RuleValidator {
         dc: isize,
     ) -> (usize, bool) {
         let player_bits = match player {
-            Player::Max => &board.max_bits,
-            Player::Min => &board.min_bits,
+            Player::Max => board.max_bits(),
+            Player::Min => board.min_bits(),
         };
         let opponent_bits = match player.opponent() {
-            Player::Max => &board.max_bits,
-            Player::Min => &board.min_bits,
+            Player::Max => board.max_bits(),
+            Player::Min => board.min_bits(),
         };
 
         let mut stones = 0;
@@ -99,7 +633,7 @@ impl RuleValidator {
                     break;
                 }
                 stones += 1;
-            } else if !Board::is_bit_set(&board.occupied, idx) {
+            } else if !Board::is_bit_set(board.occupied(), idx) {
                 if !empty_found && stones > 0 {
                     is_open = true;
                 }
@@ -120,6 +654,6 @@ impl RuleValidator {
     }
 
     fn is_valid_pos(board: &Board, row: isize, col: isize) -> bool {
-        (0..board.size as isize).contains(&row) && (0..board.size as isize).contains(&col)
+        board.in_bounds(row, col)
     }
 }
@@ -0,0 +1,69 @@
+use crate::core::state::GameState;
+
+const BOARD_SIZE: usize = 19;
+const WIN_CONDITION: usize = 5;
+
+const OPENING: [(usize, usize); 12] = [
+    (9, 9), (9, 8), (10, 10), (8, 8), (8, 7), (9, 11), (8, 12), (7, 12), (10, 11), (7, 11), (11, 10), (10, 8),
+];
+
+const MIDGAME: [(usize, usize); 24] = [
+    (9, 9), (9, 8), (9, 7), (10, 6), (8, 8), (11, 7), (12, 7), (10, 5), (10, 4), (10, 9), (8, 9), (9, 5),
+    (13, 7), (12, 8), (7, 10), (12, 6), (8, 6), (13, 5), (13, 4), (8, 10), (11, 10), (12, 11), (14, 7), (14, 6),
+];
+
+const CROWDED: [(usize, usize); 60] = [
+    (9, 9), (8, 8), (10, 10), (7, 9), (8, 10), (9, 11), (9, 12), (7, 7), (6, 8), (5, 9), (5, 7), (8, 11),
+    (5, 10), (11, 10), (7, 8), (4, 10), (5, 6), (6, 10), (8, 6), (4, 5), (4, 7), (8, 13), (7, 12), (6, 13),
+    (7, 14), (10, 12), (10, 13), (5, 13), (11, 9), (9, 10), (5, 4), (9, 5), (6, 11), (5, 5), (5, 8), (8, 7),
+    (7, 13), (7, 5), (5, 14), (7, 4), (11, 14), (6, 14), (4, 11), (10, 6), (4, 6), (8, 9), (6, 4), (9, 14),
+    (4, 4), (12, 14), (12, 8), (6, 12), (9, 7), (13, 14), (12, 13), (12, 9), (12, 11), (13, 13), (9, 13), (8, 4),
+];
+
+/// Black's open three on row 9, with White to answer it
+const OPEN_THREE: [(usize, usize); 5] = [(9, 9), (10, 10), (9, 10), (10, 11), (9, 11)];
+
+/// Plays `moves` from the empty 19x19 board, alternating colours. Fixtures are built move
+/// by move so hashes and pattern history are what a real game leaves, and the zobrist keys
+/// come from the default seed, so benchmarks and tests always see the same positions.
+pub fn from_moves(moves: &[(usize, usize)]) -> GameState {
+    let mut state = GameState::new(BOARD_SIZE, WIN_CONDITION);
+    for &mv in moves {
+        state.make_move(mv);
+    }
+    state
+}
+
+pub fn empty() -> GameState {
+    from_moves(&[])
+}
+
+/// A dozen moves around the centre
+pub fn opening() -> GameState {
+    from_moves(&OPENING)
+}
+
+/// Two dozen moves, several short lines for each side
+pub fn midgame() -> GameState {
+    from_moves(&MIDGAME)
+}
+
+/// Sixty stones packed into the middle of the board, with no captures or wins
+pub fn crowded() -> GameState {
+    from_moves(&CROWDED)
+}
+
+pub fn open_three() -> GameState {
+    from_moves(&OPEN_THREE)
+}
+
+/// Every fixture by name, in the order the search benchmarks run them
+pub fn canonical() -> Vec<(&'static str, GameState)> {
+    vec![
+        ("empty", empty()),
+        ("opening", opening()),
+        ("midgame", midgame()),
+        ("crowded", crowded()),
+        ("open_three", open_three()),
+    ]
+}
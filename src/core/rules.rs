@@ -1,22 +1,180 @@
+use serde::{Deserialize, Serialize};
+
 use crate::core::board::{Board, Player};
 
+pub const DEFAULT_CAPTURE_WIN_PAIRS: usize = 5;
+
+/// Optional rules in force for a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RuleFlags {
+    pub captures_enabled: bool,
+    pub forbid_double_three: bool,
+    /// Whether six or more in a row counts as a win
+    pub allow_overline: bool,
+    /// Renju: the forbidden-move and overline rules only bind black (`Player::Max`)
+    pub renju: bool,
+}
+
+impl Default for RuleFlags {
+    fn default() -> Self {
+        RulePreset::Standard.flags()
+    }
+}
+
+impl RuleFlags {
+    pub fn forbids_double_three(&self, player: Player) -> bool {
+        self.forbid_double_three && (!self.renju || player == Player::Max)
+    }
+
+    pub fn allows_overline(&self, player: Player) -> bool {
+        self.allow_overline || (self.renju && player == Player::Min)
+    }
+}
+
+/// Named rule sets offered in the settings menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum RulePreset {
+    /// Captures and no double-three for either side
+    #[default]
+    Standard,
+    /// Plain five in a row: no captures, no forbidden moves, overlines win
+    ClassicGomoku,
+    /// Captures on, no forbidden moves
+    Ninuki,
+    /// No captures; black may not make a double-three, a double-four or an overline
+    Renju,
+}
+
+impl RulePreset {
+    pub const ALL: [RulePreset; 4] = [
+        RulePreset::Standard,
+        RulePreset::ClassicGomoku,
+        RulePreset::Ninuki,
+        RulePreset::Renju,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RulePreset::Standard => "Standard",
+            RulePreset::ClassicGomoku => "Classic Gomoku",
+            RulePreset::Ninuki => "Ninuki/Pente",
+            RulePreset::Renju => "Renju",
+        }
+    }
+
+    pub fn flags(&self) -> RuleFlags {
+        let (captures_enabled, forbid_double_three, allow_overline, renju) = match self {
+            RulePreset::Standard => (true, true, true, false),
+            RulePreset::ClassicGomoku => (false, false, true, false),
+            RulePreset::Ninuki => (true, false, true, false),
+            RulePreset::Renju => (false, true, false, true),
+        };
+        RuleFlags {
+            captures_enabled,
+            forbid_double_three,
+            allow_overline,
+            renju,
+        }
+    }
+
+    pub fn win_condition(&self) -> usize {
+        5
+    }
+}
+
+/// Where the game is in its opening protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum OpeningPhase {
+    /// Swap2: stones are placed freely, black/white alternating, up to 3 (then 5) stones
+    Swap2Placement,
+    /// Swap2: the chooser picks a color, or asks to place two more stones
+    Swap2Choice,
+    #[default]
+    Normal,
+}
+
+/// Where the first stones of a game may go, for tournament openings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum OpeningRestriction {
+    /// First stone in the center, then anywhere next to a stone
+    #[default]
+    Center,
+    /// Center first; black's second stone at least 3 cells from the center
+    Pro,
+    /// Center first; black's second stone at least 4 cells from the center
+    LongPro,
+    /// The first stone may go anywhere
+    Free,
+}
+
+impl OpeningRestriction {
+    /// Chebyshev distance from the center black's second stone must keep, if any
+    pub fn second_move_radius(&self) -> Option<usize> {
+        match self {
+            OpeningRestriction::Pro => Some(3),
+            OpeningRestriction::LongPro => Some(4),
+            OpeningRestriction::Center | OpeningRestriction::Free => None,
+        }
+    }
+}
+
+/// Fixed first stones for a quick game, black, white, black, as (row, col) offsets from the
+/// center. The direct renju openings keep every stone touching another, the pro ones put
+/// black's second stone where `OpeningRestriction::Pro` and `LongPro` want it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NamedOpening {
+    pub name: &'static str,
+    pub stones: [(isize, isize); 3],
+}
+
+pub const NAMED_OPENINGS: [NamedOpening; 7] = [
+    NamedOpening { name: "Kansei", stones: [(0, 0), (0, -1), (0, -2)] },
+    NamedOpening { name: "Keigetsu", stones: [(0, 0), (0, -1), (1, -2)] },
+    NamedOpening { name: "Kagetsu", stones: [(0, 0), (0, -1), (1, -1)] },
+    NamedOpening { name: "Ugetsu", stones: [(0, 0), (0, -1), (1, 0)] },
+    NamedOpening { name: "Shogetsu", stones: [(0, 0), (0, -1), (1, 1)] },
+    NamedOpening { name: "Pro", stones: [(0, 0), (0, -1), (3, 0)] },
+    NamedOpening { name: "Long pro", stones: [(0, 0), (0, -1), (4, 0)] },
+];
+
+impl NamedOpening {
+    /// The opening called `name`, ignoring case
+    pub fn find(name: &str) -> Option<&'static NamedOpening> {
+        NAMED_OPENINGS.iter().find(|opening| opening.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Decision taken during `OpeningPhase::Swap2Choice`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Swap2Option {
+    /// The chooser takes white, which is to move
+    PlayWhite,
+    /// The chooser takes black
+    PlayBlack,
+    /// The chooser places a white and a black stone and hands the choice back (first choice only)
+    PlaceTwo,
+}
+
+pub const SWAP2_FIRST_STONES: usize = 3;
+pub const SWAP2_EXTRA_STONES: usize = 2;
+
 pub struct WinChecker;
 
 impl WinChecker {
     pub fn check_win_around(board: &Board, row: usize, col: usize, win_condition: usize) -> bool {
-        if row >= board.size || col >= board.size {
+        if row >= board.rows || col >= board.cols {
             return false;
         }
         let idx = board.index(row, col);
-        let is_max = Board::is_bit_set(&board.max_bits, idx);
-        let is_min = Board::is_bit_set(&board.min_bits, idx);
+        let is_max = Board::is_bit_set(board.max_bits(), idx);
+        let is_min = Board::is_bit_set(board.min_bits(), idx);
         if !is_max && !is_min {
             return false;
         }
         let player_bits = if is_max {
-            &board.max_bits
+            board.max_bits()
         } else {
-            &board.min_bits
+            board.min_bits()
         };
 
         let directions = [(1, 0), (0, 1), (1, 1), (1, -1)];
@@ -28,7 +186,7 @@ impl WinChecker {
             loop {
                 let x = row as isize + dx as isize * step;
                 let y = col as isize + dy as isize * step;
-                if x < 0 || y < 0 || x >= board.size as isize || y >= board.size as isize {
+                if !board.in_bounds(x, y) {
                     break;
                 }
                 let check_idx = board.index(x as usize, y as usize);
@@ -47,7 +205,7 @@ impl WinChecker {
             loop {
                 let x = row as isize - dx as isize * step;
                 let y = col as isize - dy as isize * step;
-                if x < 0 || y < 0 || x >= board.size as isize || y >= board.size as isize {
+                if !board.in_bounds(x, y) {
                     break;
                 }
                 let check_idx = board.index(x as usize, y as usize);
@@ -70,10 +228,42 @@ impl WinChecker {
         false
     }
 
+    /// Like `check_win_around`, but only a line of exactly `win_condition` stones counts
+    pub fn check_exact_win_around(board: &Board, row: usize, col: usize, win_condition: usize) -> bool {
+        let Some(player) = board.get_player(row, col) else {
+            return false;
+        };
+        let directions = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+        directions.iter().any(|&(dx, dy)| {
+            let mut count = 1;
+            for sign in [1, -1] {
+                let mut x = row as isize + dx * sign;
+                let mut y = col as isize + dy * sign;
+                while board.in_bounds(x, y)
+                    && board.get_player(x as usize, y as usize) == Some(player)
+                {
+                    count += 1;
+                    x += dx * sign;
+                    y += dy * sign;
+                }
+            }
+            count == win_condition
+        })
+    }
+
     pub fn check_capture_win(max_captures: usize, min_captures: usize) -> Option<Player> {
-        if max_captures >= 5 {
+        Self::check_capture_win_at(max_captures, min_captures, DEFAULT_CAPTURE_WIN_PAIRS)
+    }
+
+    pub fn check_capture_win_at(
+        max_captures: usize,
+        min_captures: usize,
+        pairs_to_win: usize,
+    ) -> Option<Player> {
+        if max_captures >= pairs_to_win {
             Some(Player::Max)
-        } else if min_captures >= 5 {
+        } else if min_captures >= pairs_to_win {
             Some(Player::Min)
         } else {
             None
@@ -0,0 +1,321 @@
+use std::fmt;
+
+use crate::core::board::{Board, Player};
+use crate::core::state::{GameState, IntegrityError};
+
+// Gomoku/renju game id and the private properties used for the rules this engine
+// adds on top of plain five-in-a-row.
+const SGF_GAME_GOMOKU: &str = "4";
+const PROP_WIN_CONDITION: &str = "WC";
+const PROP_CAPTURE_PAIRS: &str = "CP";
+const PROP_BLACK_CAPTURES: &str = "CB";
+const PROP_WHITE_CAPTURES: &str = "CW";
+const PROP_CAPTURES_ENABLED: &str = "CE";
+const PROP_FORBID_DOUBLE_THREE: &str = "FT";
+const PROP_ALLOW_OVERLINE: &str = "OL";
+const PROP_RENJU: &str = "RJ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SgfError {
+    Malformed(String),
+    UnsupportedGame(String),
+    InvalidProperty { property: String, value: String },
+    IllegalMove { move_number: usize, position: String, reason: String },
+    CaptureMismatch { expected: (usize, usize), replayed: (usize, usize) },
+    Inconsistent(IntegrityError),
+}
+
+impl fmt::Display for SgfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SgfError::Malformed(reason) => write!(f, "malformed SGF: {}", reason),
+            SgfError::UnsupportedGame(game) => write!(f, "unsupported SGF game type GM[{}]", game),
+            SgfError::InvalidProperty { property, value } => {
+                write!(f, "invalid value '{}' for property {}", value, property)
+            }
+            SgfError::IllegalMove { move_number, position, reason } => {
+                write!(f, "illegal move {} at [{}]: {}", move_number, position, reason)
+            }
+            SgfError::CaptureMismatch { expected, replayed } => write!(
+                f,
+                "capture counts {:?} do not match replayed counts {:?}",
+                expected, replayed
+            ),
+            SgfError::Inconsistent(error) => write!(f, "inconsistent game: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SgfError {}
+
+type SgfNode = Vec<(String, Vec<String>)>;
+
+impl GameState {
+    /// Exports the game as an SGF record. Black is `Player::Max`, who moves first.
+    /// Captures are not stored as setup stones: they are re-derived when the moves are replayed.
+    pub fn to_sgf(&self) -> String {
+        // Rectangular boards are written columns first, as `SZ[cols:rows]`
+        let size = if self.board.rows == self.board.cols {
+            self.board.rows.to_string()
+        } else {
+            format!("{}:{}", self.board.cols, self.board.rows)
+        };
+        let mut sgf = format!(
+            "(;FF[4]GM[{}]SZ[{}]{}[{}]{}[{}]{}[{}]{}[{}]",
+            SGF_GAME_GOMOKU,
+            size,
+            PROP_WIN_CONDITION,
+            self.win_condition,
+            PROP_CAPTURE_PAIRS,
+            self.capture_win_pairs,
+            PROP_BLACK_CAPTURES,
+            self.max_captures,
+            PROP_WHITE_CAPTURES,
+            self.min_captures,
+        );
+        for (ident, flag) in [
+            (PROP_CAPTURES_ENABLED, self.rules.captures_enabled),
+            (PROP_FORBID_DOUBLE_THREE, self.rules.forbid_double_three),
+            (PROP_ALLOW_OVERLINE, self.rules.allow_overline),
+            (PROP_RENJU, self.rules.renju),
+        ] {
+            sgf.push_str(&format!("{}[{}]", ident, u8::from(flag)));
+        }
+
+        for &(row, col, player) in &self.moves {
+            let color = match player {
+                Player::Max => 'B',
+                Player::Min => 'W',
+            };
+            sgf.push_str(&format!(";{}[{}{}]", color, sgf_coord(col), sgf_coord(row)));
+        }
+
+        sgf.push(')');
+        sgf
+    }
+
+    /// Rebuilds a game from an SGF record by replaying its moves.
+    pub fn from_sgf(sgf: &str) -> Result<GameState, SgfError> {
+        let nodes = parse_nodes(sgf)?;
+        let (root, move_nodes) = nodes
+            .split_first()
+            .ok_or_else(|| SgfError::Malformed("no root node".to_string()))?;
+
+        if let Some(game) = property(root, "GM")
+            && game != SGF_GAME_GOMOKU
+        {
+            return Err(SgfError::UnsupportedGame(game.to_string()));
+        }
+
+        let (rows, cols) = board_size(root)?;
+        let win_condition = numeric_property(root, PROP_WIN_CONDITION)?.unwrap_or(5);
+
+        let mut state = GameState::try_new_rect(rows, cols, win_condition).map_err(|_| SgfError::InvalidProperty {
+            property: "SZ".to_string(),
+            value: property(root, "SZ").unwrap_or_default().to_string(),
+        })?;
+        if let Some(pairs) = numeric_property(root, PROP_CAPTURE_PAIRS)? {
+            state.capture_win_pairs = pairs;
+        }
+        // Records without the rule properties keep the default rules
+        for (ident, flag) in [
+            (PROP_CAPTURES_ENABLED, &mut state.rules.captures_enabled),
+            (PROP_FORBID_DOUBLE_THREE, &mut state.rules.forbid_double_three),
+            (PROP_ALLOW_OVERLINE, &mut state.rules.allow_overline),
+            (PROP_RENJU, &mut state.rules.renju),
+        ] {
+            if let Some(value) = flag_property(root, ident)? {
+                *flag = value;
+            }
+        }
+
+        let mut move_number = 0;
+        for node in move_nodes {
+            for (ident, values) in node {
+                let player = match ident.as_str() {
+                    "B" => Player::Max,
+                    "W" => Player::Min,
+                    _ => continue,
+                };
+                move_number += 1;
+                let value = values.first().map(String::as_str).unwrap_or("");
+                let illegal = |reason: &str| SgfError::IllegalMove {
+                    move_number,
+                    position: value.to_string(),
+                    reason: reason.to_string(),
+                };
+
+                let (row, col) = parse_point(value, &state.board).ok_or_else(|| illegal("out of bounds"))?;
+                if state.winner.is_some() {
+                    return Err(illegal("game is already over"));
+                }
+                if player != state.current_player {
+                    return Err(illegal("played out of turn"));
+                }
+                if !state.board.is_empty_position(row, col) {
+                    return Err(illegal("cell is occupied"));
+                }
+                state.make_move((row, col));
+            }
+        }
+
+        let expected_black = numeric_property(root, PROP_BLACK_CAPTURES)?;
+        let expected_white = numeric_property(root, PROP_WHITE_CAPTURES)?;
+        if let (Some(black), Some(white)) = (expected_black, expected_white)
+            && (black, white) != (state.max_captures, state.min_captures)
+        {
+            return Err(SgfError::CaptureMismatch {
+                expected: (black, white),
+                replayed: (state.max_captures, state.min_captures),
+            });
+        }
+
+        state.validate_integrity().map_err(SgfError::Inconsistent)?;
+        Ok(state)
+    }
+}
+
+fn sgf_coord(value: usize) -> char {
+    if value < 26 {
+        (b'a' + value as u8) as char
+    } else {
+        (b'A' + (value - 26) as u8) as char
+    }
+}
+
+fn sgf_value(c: char) -> Option<usize> {
+    match c {
+        'a'..='z' => Some(c as usize - 'a' as usize),
+        'A'..='Z' => Some(c as usize - 'A' as usize + 26),
+        _ => None,
+    }
+}
+
+/// SGF points are written column first, the engine uses `(row, col)`
+fn parse_point(value: &str, board: &Board) -> Option<(usize, usize)> {
+    let mut chars = value.chars();
+    let col = sgf_value(chars.next()?)?;
+    let row = sgf_value(chars.next()?)?;
+    if chars.next().is_some() || row >= board.rows || col >= board.cols {
+        return None;
+    }
+    Some((row, col))
+}
+
+fn property<'a>(node: &'a SgfNode, ident: &str) -> Option<&'a str> {
+    node.iter()
+        .find(|(id, _)| id == ident)
+        .and_then(|(_, values)| values.first())
+        .map(String::as_str)
+}
+
+fn numeric_property(node: &SgfNode, ident: &str) -> Result<Option<usize>, SgfError> {
+    match property(node, ident) {
+        None => Ok(None),
+        Some(value) => value.trim().parse().map(Some).map_err(|_| SgfError::InvalidProperty {
+            property: ident.to_string(),
+            value: value.to_string(),
+        }),
+    }
+}
+
+/// A rule switch, written `1` or `0`
+fn flag_property(node: &SgfNode, ident: &str) -> Result<Option<bool>, SgfError> {
+    match numeric_property(node, ident)? {
+        None => Ok(None),
+        Some(0) => Ok(Some(false)),
+        Some(1) => Ok(Some(true)),
+        Some(value) => Err(SgfError::InvalidProperty {
+            property: ident.to_string(),
+            value: value.to_string(),
+        }),
+    }
+}
+
+/// Board rows and columns from `SZ`: one number for a square board, `cols:rows` otherwise.
+/// Defaults to 15 like the standard Gomoku board.
+fn board_size(root: &SgfNode) -> Result<(usize, usize), SgfError> {
+    let Some(value) = property(root, "SZ") else {
+        return Ok((15, 15));
+    };
+    let invalid = || SgfError::InvalidProperty {
+        property: "SZ".to_string(),
+        value: value.to_string(),
+    };
+    let side = |text: &str| text.trim().parse::<usize>().map_err(|_| invalid());
+    match value.split_once(':') {
+        Some((cols, rows)) => Ok((side(rows)?, side(cols)?)),
+        None => side(value).map(|size| (size, size)),
+    }
+}
+
+/// Parses the main line of a single-game collection into its nodes
+fn parse_nodes(sgf: &str) -> Result<Vec<SgfNode>, SgfError> {
+    let mut chars = sgf.trim().chars().peekable();
+    if chars.next() != Some('(') {
+        return Err(SgfError::Malformed("expected '(' at start of game tree".to_string()));
+    }
+
+    let mut nodes: Vec<SgfNode> = Vec::new();
+    let mut ident = String::new();
+    let mut closed = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => {
+                if !ident.is_empty() {
+                    return Err(SgfError::Malformed(format!("property {} has no value", ident)));
+                }
+                nodes.push(Vec::new());
+            }
+            '[' => {
+                let node = nodes
+                    .last_mut()
+                    .ok_or_else(|| SgfError::Malformed("property before first node".to_string()))?;
+                let mut value = String::new();
+                let mut terminated = false;
+                while let Some(v) = chars.next() {
+                    match v {
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                value.push(escaped);
+                            }
+                        }
+                        ']' => {
+                            terminated = true;
+                            break;
+                        }
+                        _ => value.push(v),
+                    }
+                }
+                if !terminated {
+                    return Err(SgfError::Malformed("unterminated property value".to_string()));
+                }
+                if ident.is_empty() {
+                    match node.last_mut() {
+                        Some((_, values)) => values.push(value),
+                        None => return Err(SgfError::Malformed("value without property".to_string())),
+                    }
+                } else {
+                    node.push((std::mem::take(&mut ident), vec![value]));
+                }
+            }
+            ')' => {
+                closed = true;
+                break;
+            }
+            '(' => return Err(SgfError::Malformed("variations are not supported".to_string())),
+            c if c.is_ascii_uppercase() => ident.push(c),
+            c if c.is_whitespace() => {}
+            c => return Err(SgfError::Malformed(format!("unexpected character '{}'", c))),
+        }
+    }
+
+    if !closed {
+        return Err(SgfError::Malformed("missing ')' at end of game tree".to_string()));
+    }
+    if nodes.is_empty() {
+        return Err(SgfError::Malformed("game tree has no nodes".to_string()));
+    }
+    Ok(nodes)
+}
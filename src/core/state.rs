@@ -1,54 +1,575 @@
+use crate::ai::heuristic::{Heuristic, LineEval};
 use crate::ai::zobrist::ZobristHash;
 use crate::ai::pattern_history::PatternHistoryAnalyzer;
 use crate::core::board::{Board, Player};
 use crate::core::captures::CaptureHandler;
-use crate::core::moves::MoveHandler;
-use crate::core::rules::WinChecker;
+use crate::core::json::GameStateData;
+use crate::core::moves::{IllegalMove, MoveHandler, RuleValidator};
+use crate::core::rules::{
+    NamedOpening, OpeningPhase, OpeningRestriction, RuleFlags, RulePreset, Swap2Option, WinChecker, DEFAULT_CAPTURE_WIN_PAIRS,
+    NAMED_OPENINGS, SWAP2_EXTRA_STONES, SWAP2_FIRST_STONES,
+};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::hash::Hash;
 
-#[derive(Resource, Component, Clone, Debug, PartialEq, Eq, Hash)]
+pub use crate::core::board::MAX_BOARD_SIZE;
+
+/// Material and progress of a position, for the HUD and analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardStats {
+    pub max_stones: usize,
+    pub min_stones: usize,
+    pub empty: usize,
+    pub max_captures: usize,
+    pub min_captures: usize,
+    /// Number of the move about to be played, starting at 1
+    pub move_number: usize,
+}
+
+/// How a finished game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameOutcome {
+    FiveInARow(Player),
+    Captures(Player),
+    /// No legal move left and no winner
+    Draw,
+    /// The other player resigned; holds the winner
+    Resignation(Player),
+    /// Both players agreed to a draw
+    DrawAgreed,
+    /// The other player's clock ran out; holds the winner
+    Timeout(Player),
+}
+
+/// What decided a game `make_move` ended, see `GameState::win_reason`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WinReason {
+    FiveInARow,
+    Captures,
+}
+
+/// Inconsistency found by `GameState::validate_integrity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntegrityError {
+    /// A cell holds stones of both players
+    OverlappingStones,
+    /// The occupied cells are not exactly the players' stones
+    OccupiedMismatch,
+    /// Both players have a completed line, which no game can reach
+    BothPlayersHaveFive,
+    /// The player has a completed line but is not recorded as the winner
+    UnrecordedWin(Player),
+    /// The recorded winner has neither a completed line nor the captures to win
+    UnsupportedWinner(Player),
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::OverlappingStones => f.write_str("a cell holds stones of both players"),
+            IntegrityError::OccupiedMismatch => f.write_str("occupied cells do not match the players' stones"),
+            IntegrityError::BothPlayersHaveFive => f.write_str("both players have a winning line"),
+            IntegrityError::UnrecordedWin(player) => {
+                write!(f, "{:?} has a winning line but is not the winner", player)
+            }
+            IntegrityError::UnsupportedWinner(player) => {
+                write!(f, "{:?} is the winner without a winning line or enough captures", player)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+#[derive(Resource, Component, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "GameStateData")]
 pub struct GameState {
     pub board: Board,
     pub current_player: Player,
     pub win_condition: usize,
     pub winner: Option<Player>,
+    /// How `winner` won, set along with it by `make_move`. A move that takes the last pair
+    /// and completes a line at once is a capture win: captures are checked first.
+    pub win_reason: Option<WinReason>,
     pub max_captures: usize,
     pub min_captures: usize,
     pub capture_history: Vec<Vec<(usize, usize)>>,
     pub move_history: Vec<(usize, usize)>,
+    pub moves: Vec<(usize, usize, Player)>,
+    #[serde(skip_serializing)]
     pub pattern_analyzer: PatternHistoryAnalyzer,
+    #[serde(skip_serializing)]
     pub zobrist_hash: ZobristHash,
+    /// Zobrist hash kept in step by `make_move`/`undo_move`, captured stones included
+    #[serde(skip_serializing)]
     pub current_hash: u64,
+    pub capture_win_pairs: usize,
+    pub rules: RuleFlags,
+    pub opening: OpeningPhase,
+    /// Limits on the first stones in normal play, see `OpeningRestriction`
+    pub opening_restriction: OpeningRestriction,
+    /// Set when the player who opened the game (placed the first stones) ended up with white
+    pub colors_swapped: bool,
+    /// Moves taken back with `undo_last_move`, most recent last. Captures are not stored:
+    /// replaying a move on the restored board detects the same ones again.
+    pub redo_history: Vec<(usize, usize)>,
+    /// `position_key` before each move of the current line, for repetition checks.
+    /// Not saved: a loaded game starts a fresh line.
+    #[serde(skip_serializing)]
+    pub position_history: Vec<(u64, usize, usize)>,
+    /// Set by `resign`, `agree_draw` or `lose_on_time` when the game ends without a decisive move
+    pub ended_early: Option<GameOutcome>,
+    /// Line-pattern counts updated by `make_move`/`undo_move`, see `incremental_eval`
+    #[serde(skip_serializing)]
+    pub line_eval: LineEval,
 }
 
 impl GameState {
     pub fn new(board_size: usize, win_condition: usize) -> Self {
-        let zobrist_hash = ZobristHash::new(board_size);
-        let board = Board::new(board_size);
+        Self::new_rect(board_size, board_size, win_condition)
+    }
+
+    /// Game on a board of `rows` by `cols` intersections
+    pub fn new_rect(rows: usize, cols: usize, win_condition: usize) -> Self {
+        let zobrist_hash = ZobristHash::new_rect(rows, cols);
+        let board = Board::new_rect(rows, cols);
         let current_player = Player::Max;
         let mut state = GameState {
             board,
             current_player,
             win_condition,
             winner: None,
+            win_reason: None,
             max_captures: 0,
             min_captures: 0,
             capture_history: Vec::new(),
             move_history: Vec::new(),
+            moves: Vec::new(),
             pattern_analyzer: PatternHistoryAnalyzer::new(),
             zobrist_hash: zobrist_hash.clone(),
             current_hash: 0,
+            capture_win_pairs: DEFAULT_CAPTURE_WIN_PAIRS,
+            rules: RuleFlags::default(),
+            opening: OpeningPhase::Normal,
+            opening_restriction: OpeningRestriction::default(),
+            colors_swapped: false,
+            redo_history: Vec::new(),
+            position_history: Vec::new(),
+            ended_early: None,
+            line_eval: LineEval::default(),
         };
         state.current_hash = zobrist_hash.compute_hash(&state);
         state
     }
 
+    /// Same as `new`, but refuses board sizes outside `1..=MAX_BOARD_SIZE`.
+    pub fn try_new(board_size: usize, win_condition: usize) -> Result<Self, String> {
+        if board_size == 0 || board_size > MAX_BOARD_SIZE {
+            return Err(format!(
+                "board size {} is outside the supported range 1..={}",
+                board_size, MAX_BOARD_SIZE
+            ));
+        }
+        Ok(Self::new(board_size, win_condition))
+    }
+
+    /// Same as `new_rect`, with both sides checked like `try_new` checks the size
+    pub fn try_new_rect(rows: usize, cols: usize, win_condition: usize) -> Result<Self, String> {
+        for side in [rows, cols] {
+            if side == 0 || side > MAX_BOARD_SIZE {
+                return Err(format!(
+                    "board side {} is outside the supported range 1..={}",
+                    side, MAX_BOARD_SIZE
+                ));
+            }
+        }
+        Ok(Self::new_rect(rows, cols, win_condition))
+    }
+
+    /// Fresh game using the win condition and rule flags of `preset`
+    pub fn with_preset(board_size: usize, preset: RulePreset) -> Self {
+        let mut state = Self::new(board_size, preset.win_condition());
+        state.rules = preset.flags();
+        state
+    }
+
+    /// Builds a state and plays `opening_moves` in order, alternating players from Max.
+    /// Every move must be legal at the point it is played (first stone in the center,
+    /// following stones adjacent and not creating a double-three).
+    pub fn new_with_opening(
+        board_size: usize,
+        win_condition: usize,
+        capture_pairs: usize,
+        opening_moves: &[(usize, usize)],
+    ) -> Result<Self, String> {
+        let mut state = Self::try_new(board_size, win_condition)?;
+        state.capture_win_pairs = capture_pairs;
+
+        for (ply, &mv) in opening_moves.iter().enumerate() {
+            if state.winner.is_some() {
+                return Err(format!("opening move {} {:?} played after the game ended", ply + 1, mv));
+            }
+            if !state.get_possible_moves().contains(&mv) {
+                return Err(format!("opening move {} {:?} is not a legal move", ply + 1, mv));
+            }
+            state.make_move(mv);
+        }
+
+        Ok(state)
+    }
+
     pub fn get_possible_moves(&self) -> Vec<(usize, usize)> {
-        MoveHandler::get_possible_moves(&self.board, self.current_player)
+        match self.opening {
+            OpeningPhase::Swap2Placement => self.board.get_empty_positions(),
+            OpeningPhase::Swap2Choice => Vec::new(),
+            OpeningPhase::Normal if self.opening_restriction_applies() => self
+                .board
+                .get_empty_positions()
+                .into_iter()
+                .filter(|&mv| self.check_restricted_opening(mv).is_ok())
+                .collect(),
+            OpeningPhase::Normal => {
+                MoveHandler::get_possible_moves_with_rules(&self.board, self.current_player, &self.rules)
+            }
+        }
+    }
+
+    /// Whether `mv` may be played now, with the reason when it may not. Agrees with
+    /// `get_possible_moves`: a move is legal exactly when that list holds it.
+    pub fn is_legal_move(&self, mv: (usize, usize)) -> Result<(), IllegalMove> {
+        if self.winner.is_some() || self.ended_early.is_some() {
+            return Err(IllegalMove::GameOver);
+        }
+        match self.opening {
+            OpeningPhase::Swap2Placement if mv.0 >= self.board.rows || mv.1 >= self.board.cols => {
+                Err(IllegalMove::OutOfBounds)
+            }
+            OpeningPhase::Swap2Placement if !self.board.is_empty_position(mv.0, mv.1) => Err(IllegalMove::Occupied),
+            OpeningPhase::Swap2Placement => Ok(()),
+            OpeningPhase::Swap2Choice => Err(IllegalMove::AwaitingSwap2Choice),
+            OpeningPhase::Normal if self.opening_restriction_applies() => self.check_restricted_opening(mv),
+            OpeningPhase::Normal => MoveHandler::check_move(&self.board, mv.0, mv.1, self.current_player, &self.rules),
+        }
+    }
+
+    /// Stones `player` would capture by playing `mv`, without playing it. Empty when the
+    /// cell is off the board, taken, or a move the rules refuse `player`, and whenever
+    /// captures are off.
+    pub fn captures_for_move(&self, mv: (usize, usize), player: Player) -> Vec<(usize, usize)> {
+        if !self.rules.captures_enabled
+            || MoveHandler::check_move(&self.board, mv.0, mv.1, player, &self.rules).is_err()
+        {
+            return Vec::new();
+        }
+        CaptureHandler::detect_captures(&self.board, mv.0, mv.1, player)
+    }
+
+    /// Whether the next move is one `opening_restriction` decides: the first stone under
+    /// `Free`, black's second stone under `Pro` and `LongPro`
+    fn opening_restriction_applies(&self) -> bool {
+        match self.opening_restriction.second_move_radius() {
+            Some(_) => self.moves.len() == 2 && self.current_player == Player::Max,
+            None => self.opening_restriction == OpeningRestriction::Free && self.board.is_empty(),
+        }
+    }
+
+    /// Legality of `mv` when the opening restriction applies. It replaces the center and
+    /// adjacency rules, so a pro second stone may stand apart from the others.
+    fn check_restricted_opening(&self, (row, col): (usize, usize)) -> Result<(), IllegalMove> {
+        if row >= self.board.rows || col >= self.board.cols {
+            return Err(IllegalMove::OutOfBounds);
+        }
+        if !self.board.is_empty_position(row, col) {
+            return Err(IllegalMove::Occupied);
+        }
+        if let Some(radius) = self.opening_restriction.second_move_radius() {
+            let (center_row, center_col) = self.board.center();
+            if row.abs_diff(center_row).max(col.abs_diff(center_col)) < radius {
+                return Err(IllegalMove::TooCloseToCenter);
+            }
+        }
+        match RuleValidator::forbidden_reason(&self.board, row, col, self.current_player, &self.rules) {
+            Some(reason) => Err(reason.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether playing `mv` lets the opponent win on their very next move, by five in a row
+    /// or by captures. Only checked in normal play, the Swap2 opening has no opponent yet.
+    pub fn move_allows_opponent_win(&self, mv: (usize, usize)) -> bool {
+        if self.opening != OpeningPhase::Normal || self.is_legal_move(mv).is_err() {
+            return false;
+        }
+        let opponent = self.current_player.opponent();
+        let mut after = self.clone();
+        after.make_move(mv);
+        if after.winner.is_some() {
+            return after.winner == Some(opponent);
+        }
+
+        after.get_possible_moves().into_iter().any(|reply| {
+            after.make_move(reply);
+            let wins = after.winner == Some(opponent);
+            after.undo_move(reply);
+            wins
+        })
+    }
+
+    /// Every legal move that wins at once for `player`, by five in a row or by captures,
+    /// checked as if it were their turn. Empty in the Swap2 opening or once the game is over.
+    pub fn find_all_winning_moves(&self, player: Player) -> Vec<(usize, usize)> {
+        if self.opening != OpeningPhase::Normal || self.outcome().is_some() {
+            return Vec::new();
+        }
+        let mut probe = self.clone();
+        probe.current_player = player;
+        let allow_overline = self.rules.allows_overline(player);
+        let line_possible = self.line_win_possible();
+        let candidates: Vec<_> = probe
+            .get_possible_moves()
+            .into_iter()
+            .filter(|&(row, col)| {
+                (line_possible
+                    && MoveHandler::completes_line(&self.board, row, col, player, self.win_condition, allow_overline))
+                    || (self.rules.captures_enabled
+                        && !CaptureHandler::detect_captures(&self.board, row, col, player).is_empty())
+            })
+            .collect();
+
+        // Playing the move settles what the quick filter cannot, like a capture that
+        // falls short of the target
+        candidates
+            .into_iter()
+            .filter(|&mv| {
+                probe.make_move(mv);
+                let wins = probe.winner == Some(player);
+                probe.undo_move(mv);
+                wins
+            })
+            .collect()
+    }
+
+    /// Moves for the opponent of `five_player` that capture a pair holding a stone of one
+    /// of `five_player`'s winning lines, breaking it. Empty with the capture rule off or
+    /// when no such line is on the board. The board is read as it stands, so this also
+    /// works on a position set up with a five already in place.
+    pub fn find_five_breaking_captures(&self, five_player: Player) -> Vec<(usize, usize)> {
+        if !self.rules.captures_enabled {
+            return Vec::new();
+        }
+        let allow_overline = self.rules.allows_overline(five_player);
+        let in_five = |row: usize, col: usize| {
+            self.board.get_player(row, col) == Some(five_player)
+                && MoveHandler::line_lengths_through(&self.board, row, col, five_player)
+                    .into_iter()
+                    .any(|length| MoveHandler::is_winning_length(length, self.win_condition, allow_overline))
+        };
+
+        let breaker = five_player.opponent();
+        self.board
+            .get_empty_positions()
+            .into_iter()
+            .filter(|&(row, col)| !RuleValidator::is_forbidden(&self.board, row, col, breaker, &self.rules))
+            .filter(|&(row, col)| {
+                CaptureHandler::detect_captures(&self.board, row, col, breaker)
+                    .into_iter()
+                    .any(|(r, c)| in_five(r, c))
+            })
+            .collect()
+    }
+
+    /// Moves to choose from when the opponent is one pair away from a capture win and some
+    /// legal move would let them capture: the ones that do not, plus any that win outright.
+    /// `None` when there is no such danger, or when no move avoids it.
+    pub fn capture_defense_moves(&self) -> Option<Vec<(usize, usize)>> {
+        if self.opening != OpeningPhase::Normal || !self.is_about_to_lose_by_capture(self.current_player) {
+            return None;
+        }
+        let player = self.current_player;
+        let moves = self.get_possible_moves();
+        let (safe, exposed): (Vec<_>, Vec<_>) = moves
+            .into_iter()
+            .partition(|&(row, col)| !MoveHandler::allows_capture_reply(&self.board, row, col, player));
+        if exposed.is_empty() || safe.is_empty() {
+            return None;
+        }
+
+        let own_captures = match player {
+            Player::Max => self.max_captures,
+            Player::Min => self.min_captures,
+        };
+        let allow_overline = self.rules.allows_overline(player);
+        let wins_outright = |&(row, col): &(usize, usize)| {
+            MoveHandler::completes_line(&self.board, row, col, player, self.win_condition, allow_overline)
+                || own_captures + CaptureHandler::detect_captures(&self.board, row, col, player).len() / 2
+                    >= self.capture_win_pairs
+        };
+        Some(safe.into_iter().chain(exposed.into_iter().filter(wins_outright)).collect())
+    }
+
+    /// Starts the game with a Swap2 opening instead of normal play
+    pub fn start_swap2(&mut self) {
+        self.opening = OpeningPhase::Swap2Placement;
+    }
+
+    /// Places the stones of the current Swap2 placement step in one go: black, white, black
+    /// for the opening three, white then black for the two extra stones.
+    pub fn apply_swap2_placement(&mut self, stones: &[(usize, usize)]) -> Result<(), String> {
+        if self.opening != OpeningPhase::Swap2Placement {
+            return Err(format!("cannot place opening stones during {:?}", self.opening));
+        }
+        let expected = self.swap2_target() - self.moves.len();
+        if stones.len() != expected {
+            return Err(format!("expected {} opening stones, got {}", expected, stones.len()));
+        }
+        for (i, &(row, col)) in stones.iter().enumerate() {
+            if row >= self.board.rows || col >= self.board.cols {
+                return Err(format!("opening stone {:?} is off the board", (row, col)));
+            }
+            if !self.board.is_empty_position(row, col) || stones[..i].contains(&(row, col)) {
+                return Err(format!("opening stone {:?} is on an occupied cell", (row, col)));
+            }
+        }
+
+        for &mv in stones {
+            self.make_move(mv);
+        }
+        Ok(())
+    }
+
+    pub fn apply_swap2_choice(&mut self, option: Swap2Option) -> Result<(), String> {
+        if self.opening != OpeningPhase::Swap2Choice {
+            return Err(format!("no Swap2 choice to make during {:?}", self.opening));
+        }
+        // The second person chooses first; after two extra stones the opener chooses
+        let opener_chooses = self.moves.len() == SWAP2_FIRST_STONES + SWAP2_EXTRA_STONES;
+
+        match option {
+            Swap2Option::PlayWhite => {
+                self.colors_swapped = opener_chooses;
+                self.opening = OpeningPhase::Normal;
+            }
+            Swap2Option::PlayBlack => {
+                self.colors_swapped = !opener_chooses;
+                self.opening = OpeningPhase::Normal;
+            }
+            Swap2Option::PlaceTwo => {
+                if opener_chooses {
+                    return Err("two extra stones were already placed".to_string());
+                }
+                self.opening = OpeningPhase::Swap2Placement;
+            }
+        }
+        Ok(())
+    }
+
+    /// Plays the stones of the opening called `name` from an empty board. Each stone must
+    /// be legal under the current rules and opening restriction, otherwise nothing is played.
+    pub fn apply_named_opening(&mut self, name: &str) -> Result<(), String> {
+        let opening = NamedOpening::find(name).ok_or_else(|| format!("unknown opening {}", name))?;
+        if !self.moves.is_empty() || self.opening != OpeningPhase::Normal {
+            return Err("an opening can only be played on an empty board".to_string());
+        }
+        let (center_row, center_col) = self.board.center();
+        let mut played = self.clone();
+        for (i, &(d_row, d_col)) in opening.stones.iter().enumerate() {
+            let mv = match (center_row.checked_add_signed(d_row), center_col.checked_add_signed(d_col)) {
+                (Some(row), Some(col)) => (row, col),
+                _ => return Err(format!("{} stone {} is off the board", opening.name, i + 1)),
+            };
+            played
+                .is_legal_move(mv)
+                .map_err(|reason| format!("{} stone {} at {:?}: {}", opening.name, i + 1, mv, reason))?;
+            played.make_move(mv);
+        }
+        *self = played;
+        Ok(())
+    }
+
+    /// Names of the openings `apply_named_opening` would accept here
+    pub fn available_openings(&self) -> Vec<&'static str> {
+        NAMED_OPENINGS
+            .iter()
+            .filter(|opening| self.clone().apply_named_opening(opening.name).is_ok())
+            .map(|opening| opening.name)
+            .collect()
+    }
+
+    /// Color of the player who opened the game
+    pub fn opener_color(&self) -> Player {
+        if self.colors_swapped { Player::Min } else { Player::Max }
     }
 
+    /// Whether the opener, rather than the second player, acts next
+    pub fn opener_to_act(&self) -> bool {
+        match self.opening {
+            OpeningPhase::Swap2Placement => self.moves.len() < SWAP2_FIRST_STONES,
+            OpeningPhase::Swap2Choice => self.moves.len() > SWAP2_FIRST_STONES,
+            OpeningPhase::Normal => self.current_player == self.opener_color(),
+        }
+    }
+
+    fn swap2_target(&self) -> usize {
+        if self.moves.len() < SWAP2_FIRST_STONES {
+            SWAP2_FIRST_STONES
+        } else {
+            SWAP2_FIRST_STONES + SWAP2_EXTRA_STONES
+        }
+    }
+
+    /// Plays `mv` for the side to move. A new move ends any redo line.
+    /// Ignored once the game was resigned or drawn by agreement.
     pub fn make_move(&mut self, mv: (usize, usize)) {
+        if self.ended_early.is_some() {
+            return;
+        }
+        // Tests and tools set up positions with moves the rules would refuse, so the
+        // check is opt-in
+        #[cfg(all(debug_assertions, feature = "strict-moves"))]
+        if let Err(reason) = self.is_legal_move(mv) {
+            panic!("illegal move {:?}: {}", mv, reason);
+        }
+        self.redo_history.clear();
+        self.apply_move(mv);
+    }
+
+    /// Takes back the last move and keeps it for `redo_move`. This also withdraws a
+    /// resignation or draw agreement, like it clears a winner.
+    pub fn undo_last_move(&mut self) -> Option<(usize, usize)> {
+        let &(row, col, _) = self.moves.last()?;
+        self.ended_early = None;
+        self.undo_move((row, col));
+        self.redo_history.push((row, col));
+        Some((row, col))
+    }
+
+    /// Takes back the last two moves, so the player who made the earlier one is to move
+    /// again, as when a human takes back their move and the AI's answer. Both stay on the
+    /// redo line, latest first in the result. Nothing is undone with fewer than two moves.
+    pub fn undo_full_turn(&mut self) -> Option<[(usize, usize); 2]> {
+        if self.moves.len() < 2 {
+            return None;
+        }
+        let last = self.undo_last_move()?;
+        let previous = self.undo_last_move()?;
+        Some([last, previous])
+    }
+
+    /// Replays the most recently undone move, captures included
+    pub fn redo_move(&mut self) -> Option<(usize, usize)> {
+        if self.ended_early.is_some() {
+            return None;
+        }
+        let mv = self.redo_history.pop()?;
+        self.apply_move(mv);
+        Some(mv)
+    }
+
+    fn apply_move(&mut self, mv: (usize, usize)) {
+        let line_eval_current = self.line_eval.is_current(&self.board, self.win_condition, &self.rules);
+        self.position_history.push(self.position_key());
         self.current_hash = self.zobrist_hash.update_hash_make_move(
             self.current_hash,
             mv.0,
@@ -58,8 +579,11 @@ impl GameState {
 
         self.board.place_stone(mv.0, mv.1, self.current_player);
 
-        let captures =
-            CaptureHandler::detect_captures(&self.board, mv.0, mv.1, self.current_player);
+        let captures = if self.rules.captures_enabled {
+            CaptureHandler::detect_captures(&self.board, mv.0, mv.1, self.current_player)
+        } else {
+            Vec::new()
+        };
         
         if !captures.is_empty() {
             let captured_player = self.current_player.opponent();
@@ -71,10 +595,21 @@ impl GameState {
         }
         
         self.execute_captures(captures);
+        let changed = std::iter::once(mv).chain(self.capture_history.last().into_iter().flatten().copied());
+        self.line_eval
+            .after_move(line_eval_current, &self.board, changed, self.win_condition, &self.rules);
         self.move_history.push(mv);
+        self.moves.push((mv.0, mv.1, self.current_player));
         self.check_for_wins(mv);
         self.switch_player();
         self.update_pattern_analysis(mv);
+
+        let placed = self.moves.len();
+        if self.opening == OpeningPhase::Swap2Placement
+            && (placed == SWAP2_FIRST_STONES || placed == SWAP2_FIRST_STONES + SWAP2_EXTRA_STONES)
+        {
+            self.opening = OpeningPhase::Swap2Choice;
+        }
     }
 
     fn update_pattern_analysis(&mut self, last_move: (usize, usize)) {
@@ -94,6 +629,8 @@ impl GameState {
 
     pub fn undo_move(&mut self, move_: (usize, usize)) {
         let move_player = self.current_player.opponent();
+        let line_eval_current = self.line_eval.is_current(&self.board, self.win_condition, &self.rules);
+        let captured = self.capture_history.last().cloned().unwrap_or_default();
         
         if let Some(last_captures) = self.capture_history.last() {
             if !last_captures.is_empty() {
@@ -116,6 +653,7 @@ impl GameState {
 
         self.current_player = move_player;
         self.winner = None;
+        self.win_reason = None;
 
         if let Some(last_move) = self.move_history.last() {
             if *last_move == move_ {
@@ -123,34 +661,144 @@ impl GameState {
                 self.pattern_analyzer.undo_last_move();
             }
         }
+        if let Some(&(row, col, _)) = self.moves.last()
+            && (row, col) == move_
+        {
+            self.moves.pop();
+            self.position_history.pop();
+            if self.opening == OpeningPhase::Swap2Choice {
+                self.opening = OpeningPhase::Swap2Placement;
+            }
+        }
 
         self.restore_captured_stones();
+        let changed = std::iter::once(move_).chain(captured);
+        self.line_eval
+            .after_undo(line_eval_current, &self.board, changed, self.win_condition, &self.rules);
+    }
+
+    /// Line-pattern part of the evaluation from the counts kept up to date by
+    /// `make_move` and `undo_move`. Equal to `Heuristic::line_score_full_scan`.
+    pub fn incremental_eval(&self) -> i32 {
+        Heuristic::line_score(self)
+    }
+
+    /// Whether a line of `win_condition` stones fits on the board at all. When it does not,
+    /// the game can only end by captures or a draw.
+    pub fn line_win_possible(&self) -> bool {
+        self.board.rows.max(self.board.cols) >= self.win_condition
     }
 
     pub fn is_terminal(&self) -> bool {
-        self.winner.is_some() || self.get_possible_moves().is_empty()
+        self.winner.is_some() || self.ended_early.is_some() || !self.has_any_legal_move()
+    }
+
+    /// `player` gives up and the opponent wins. Returns false if the game was already over.
+    pub fn resign(&mut self, player: Player) -> bool {
+        if self.outcome().is_some() {
+            return false;
+        }
+        let winner = player.opponent();
+        self.winner = Some(winner);
+        self.ended_early = Some(GameOutcome::Resignation(winner));
+        true
+    }
+
+    /// `player`'s clock ran out and the opponent wins. Returns false if the game was already over.
+    pub fn lose_on_time(&mut self, player: Player) -> bool {
+        if self.outcome().is_some() {
+            return false;
+        }
+        let winner = player.opponent();
+        self.winner = Some(winner);
+        self.ended_early = Some(GameOutcome::Timeout(winner));
+        true
+    }
+
+    /// Ends the game as a draw both players accepted. Returns false if it was already over.
+    pub fn agree_draw(&mut self) -> bool {
+        if self.outcome().is_some() {
+            return false;
+        }
+        self.ended_early = Some(GameOutcome::DrawAgreed);
+        true
+    }
+
+    /// Cheap check for `!get_possible_moves().is_empty()`; a pending Swap2 choice counts as a move
+    pub fn has_any_legal_move(&self) -> bool {
+        match self.opening {
+            OpeningPhase::Swap2Placement => !self.board.is_full(),
+            OpeningPhase::Swap2Choice => true,
+            OpeningPhase::Normal if self.opening_restriction_applies() => !self.get_possible_moves().is_empty(),
+            OpeningPhase::Normal => {
+                MoveHandler::has_any_legal_move(&self.board, self.current_player, &self.rules)
+            }
+        }
     }
 
     pub fn check_winner(&self) -> Option<Player> {
         self.winner
     }
 
+    /// Whether the game ended drawn: no winner by line or captures, and a full board or no
+    /// legal move left for the side to move. An agreed draw counts, a resignation does not.
+    pub fn is_draw(&self) -> bool {
+        if let Some(outcome) = self.ended_early {
+            return outcome == GameOutcome::DrawAgreed;
+        }
+        self.winner.is_none()
+            && self.check_capture_win().is_none()
+            && (self.board.is_full() || !self.has_any_legal_move())
+    }
+
+    /// Result of the game, or `None` while it is still going
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        if self.ended_early.is_some() {
+            return self.ended_early;
+        }
+        // Positions set up by hand may have a winner without a recorded reason
+        match (self.winner, self.win_reason) {
+            (Some(winner), Some(WinReason::Captures)) => Some(GameOutcome::Captures(winner)),
+            (Some(winner), Some(WinReason::FiveInARow)) => Some(GameOutcome::FiveInARow(winner)),
+            (Some(winner), None) if self.check_capture_win() == Some(winner) => Some(GameOutcome::Captures(winner)),
+            (Some(winner), None) => Some(GameOutcome::FiveInARow(winner)),
+            (None, _) if !self.has_any_legal_move() => Some(GameOutcome::Draw),
+            (None, _) => None,
+        }
+    }
+
     pub fn hash(&self) -> u64 {
         self.current_hash
     }
 
+    /// The hash with both capture totals: the hash leaves them out, yet two boards a
+    /// capture apart are not the same position
+    pub fn position_key(&self) -> (u64, usize, usize) {
+        (self.current_hash, self.max_captures, self.min_captures)
+    }
+
+    /// Whether the current position, capture totals included, already occurred earlier in
+    /// this line. A capture and recapture can bring the stones back, but not the totals.
+    pub fn is_repetition(&self) -> bool {
+        self.position_history.contains(&self.position_key())
+    }
+
     fn switch_player(&mut self) {
         self.current_player = self.current_player.opponent();
     }
 
+    /// Captures take precedence: a move reaching the capture goal and a line together is
+    /// recorded as `WinReason::Captures`
     fn check_for_wins(&mut self, mv: (usize, usize)) -> bool {
         if let Some(winner) = self.check_capture_win() {
             self.winner = Some(winner);
+            self.win_reason = Some(WinReason::Captures);
             return true;
         }
 
-        if self.check_win_around(mv) {
+        if self.line_win_possible() && self.check_win_around(mv) {
             self.winner = Some(self.current_player);
+            self.win_reason = Some(WinReason::FiveInARow);
             return true;
         }
 
@@ -164,12 +812,7 @@ impl GameState {
         }
 
         for &(row, col) in &captures {
-            if row < self.board.size && col < self.board.size {
-                let idx = self.board.index(row, col);
-                Board::clear_bit(&mut self.board.max_bits, idx);
-                Board::clear_bit(&mut self.board.min_bits, idx);
-                Board::clear_bit(&mut self.board.occupied, idx);
-            }
+            self.board.remove_stone(row, col);
         }
 
         let pairs_captured = captures.len() / 2;
@@ -185,19 +828,8 @@ impl GameState {
         if let Some(last_captures) = self.capture_history.pop() {
             if !last_captures.is_empty() {
                 let opponent = self.current_player.opponent();
-                let size = self.board.size;
-
-                let opponent_bits = match opponent {
-                    Player::Max => &mut self.board.max_bits,
-                    Player::Min => &mut self.board.min_bits,
-                };
-
                 for &(row, col) in &last_captures {
-                    if row < size && col < size {
-                        let idx = row * size + col;
-                        Board::set_bit(opponent_bits, idx);
-                        Board::set_bit(&mut self.board.occupied, idx);
-                    }
+                    self.board.place_stone(row, col, opponent);
                 }
 
                 let pairs_captured = last_captures.len() / 2;
@@ -218,10 +850,93 @@ impl GameState {
     }
 
     fn check_win_around(&self, mv: (usize, usize)) -> bool {
-        WinChecker::check_win_around(&self.board, mv.0, mv.1, self.win_condition)
+        if self.rules.allows_overline(self.current_player) {
+            WinChecker::check_win_around(&self.board, mv.0, mv.1, self.win_condition)
+        } else {
+            WinChecker::check_exact_win_around(&self.board, mv.0, mv.1, self.win_condition)
+        }
+    }
+
+    pub fn stats(&self) -> BoardStats {
+        let max_stones = self.board.count_player_stones(Player::Max);
+        let min_stones = self.board.count_player_stones(Player::Min);
+        BoardStats {
+            max_stones,
+            min_stones,
+            empty: self.board.total_cells - max_stones - min_stones,
+            max_captures: self.max_captures,
+            min_captures: self.min_captures,
+            move_number: self.moves.len() + 1,
+        }
+    }
+
+    /// Whether `player`'s opponent is one captured pair away from a capture win
+    pub fn is_about_to_lose_by_capture(&self, player: Player) -> bool {
+        let opponent_captures = match player {
+            Player::Max => self.min_captures,
+            Player::Min => self.max_captures,
+        };
+        self.rules.captures_enabled && opponent_captures + 1 == self.capture_win_pairs
     }
 
+    /// Checks a loaded position could come from a real game: the bitboards agree with each
+    /// other, at most one player has a winning line, and `winner` matches the board. A
+    /// game ended by resignation or on time may have a winner the board does not show.
+    pub fn validate_integrity(&self) -> Result<(), IntegrityError> {
+        let board = &self.board;
+        for i in 0..board.u64_count {
+            let (max, min) = (board.max_bits()[i], board.min_bits()[i]);
+            if max & min != 0 {
+                return Err(IntegrityError::OverlappingStones);
+            }
+            if board.occupied()[i] != max | min {
+                return Err(IntegrityError::OccupiedMismatch);
+            }
+        }
+
+        let (max_five, min_five) = (self.has_winning_line(Player::Max), self.has_winning_line(Player::Min));
+        if max_five && min_five {
+            return Err(IntegrityError::BothPlayersHaveFive);
+        }
+        for (player, five) in [(Player::Max, max_five), (Player::Min, min_five)] {
+            if five && self.winner != Some(player) {
+                return Err(IntegrityError::UnrecordedWin(player));
+            }
+        }
+        if let Some(winner) = self.winner
+            && self.ended_early.is_none()
+            && !(winner == Player::Max && max_five)
+            && !(winner == Player::Min && min_five)
+            && self.check_capture_win() != Some(winner)
+        {
+            return Err(IntegrityError::UnsupportedWinner(winner));
+        }
+        Ok(())
+    }
+
+    /// Whether any of `player`'s stones is part of a line that wins under the rules
+    fn has_winning_line(&self, player: Player) -> bool {
+        if !self.line_win_possible() {
+            return false;
+        }
+        let overline = self.rules.allows_overline(player);
+        (0..self.board.rows)
+            .flat_map(|row| (0..self.board.cols).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.board.get_player(row, col) == Some(player))
+            .any(|(row, col)| {
+                if overline {
+                    WinChecker::check_win_around(&self.board, row, col, self.win_condition)
+                } else {
+                    WinChecker::check_exact_win_around(&self.board, row, col, self.win_condition)
+                }
+            })
+    }
+
+    /// Capture winner, never one when the capture rule is off
     pub fn check_capture_win(&self) -> Option<Player> {
-        WinChecker::check_capture_win(self.max_captures, self.min_captures)
+        if !self.rules.captures_enabled {
+            return None;
+        }
+        WinChecker::check_capture_win_at(self.max_captures, self.min_captures, self.capture_win_pairs)
     }
 }
@@ -0,0 +1,134 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::board::Player;
+use crate::core::state::{GameOutcome, GameState};
+
+/// Something that happened on the board, as a spectator sees it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameEvent {
+    /// `player` put a stone on `pos`, taking the stones in `captures`
+    Move {
+        player: Player,
+        pos: (usize, usize),
+        captures: Vec<(usize, usize)>,
+    },
+    /// The last move, `player`'s stone on `pos`, was taken back and the stones it took
+    /// are back on the board. Reopens a finished game.
+    Undo {
+        player: Player,
+        pos: (usize, usize),
+        restored: Vec<(usize, usize)>,
+    },
+    /// A new empty board of `rows` by `cols`, the moves of the game follow
+    Reset { rows: usize, cols: usize },
+    /// The game ended, sent once each time it does
+    GameOver(GameOutcome),
+}
+
+/// A move as the spectators last heard of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SentMove {
+    player: Player,
+    pos: (usize, usize),
+    captures: Vec<(usize, usize)>,
+}
+
+/// Sends every change of a game, and the way it ends, to every subscriber. Knows nothing
+/// of the transport: a stream or network spectator reads its own receiver and serializes
+/// the events as it likes.
+///
+/// The game controller calls `sync` after changing the state, however it did: moves,
+/// undo and redo, a new game or a game ended by resignation or on time. The broadcaster
+/// works out the events from the line it last sent.
+#[derive(Resource, Default)]
+pub struct Broadcaster {
+    subscribers: Vec<Sender<GameEvent>>,
+    board: Option<(usize, usize)>,
+    sent: Vec<SentMove>,
+    outcome: Option<GameOutcome>,
+}
+
+impl Broadcaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A receiver for every event from now on
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// `GameState::make_move`, followed by the events it produced
+    pub fn make_move(&mut self, state: &mut GameState, mv: (usize, usize)) {
+        state.make_move(mv);
+        self.sync(state);
+    }
+
+    /// Sends what changed since the last call: the moves taken back, then the moves played,
+    /// then the end of the game. A new board, or a line with nothing in common with the one
+    /// already sent, starts over with `Reset`. Subscribers that hung up are dropped.
+    pub fn sync(&mut self, state: &GameState) {
+        let mut events = Vec::new();
+        let board = (state.board.rows, state.board.cols);
+        let moves: Vec<SentMove> = state
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(i, &(row, col, player))| SentMove {
+                player,
+                pos: (row, col),
+                captures: state.capture_history.get(i).cloned().unwrap_or_default(),
+            })
+            .collect();
+
+        let common = self.sent.iter().zip(&moves).take_while(|(sent, played)| sent == played).count();
+        if self.board != Some(board) || (common == 0 && !self.sent.is_empty()) {
+            self.board = Some(board);
+            self.sent.clear();
+            self.outcome = None;
+            events.push(GameEvent::Reset { rows: board.0, cols: board.1 });
+        }
+        while self.sent.len() > common {
+            let taken_back = self.sent.pop().expect("more moves sent than played");
+            events.push(GameEvent::Undo {
+                player: taken_back.player,
+                pos: taken_back.pos,
+                restored: taken_back.captures,
+            });
+        }
+        for played in &moves[self.sent.len()..] {
+            events.push(GameEvent::Move {
+                player: played.player,
+                pos: played.pos,
+                captures: played.captures.clone(),
+            });
+        }
+        self.sent = moves;
+
+        let outcome = state.outcome();
+        if outcome != self.outcome {
+            events.extend(outcome.map(GameEvent::GameOver));
+            self.outcome = outcome;
+        }
+
+        if events.is_empty() {
+            return;
+        }
+        self.subscribers
+            .retain(|subscriber| events.iter().all(|event| subscriber.send(event.clone()).is_ok()));
+    }
+}
+
+/// Tells the spectators about every change the game screen makes to the game
+pub fn broadcast_game_changes(game_state: Res<GameState>, mut broadcaster: ResMut<Broadcaster>) {
+    broadcaster.sync(&game_state);
+}
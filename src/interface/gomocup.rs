@@ -0,0 +1,266 @@
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use crate::ai::lazy_smp::{lazy_smp_search_with_config, SearchConfig};
+use crate::ai::time_manager;
+use crate::core::board::{Board, Player};
+use crate::core::rules::RulePreset;
+use crate::core::state::GameState;
+
+const WIN_CONDITION: usize = 5;
+const MAX_SEARCH_DEPTH: i32 = 10;
+const DEFAULT_TURN_TIMEOUT_MS: u64 = 5000;
+
+/// Time limits sent by the manager through `INFO`, all in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    pub timeout_turn: u64,
+    /// 0 means the match itself is not time limited
+    pub timeout_match: u64,
+    pub time_left: Option<u64>,
+}
+
+impl Default for TimeControl {
+    fn default() -> Self {
+        Self {
+            timeout_turn: DEFAULT_TURN_TIMEOUT_MS,
+            timeout_match: 0,
+            time_left: None,
+        }
+    }
+}
+
+impl TimeControl {
+    /// Budget for the next move, `moves_played` plies into a game on a `board_size` board:
+    /// the per-turn limit less a margin for move generation and I/O, capped by what
+    /// `time_manager::allocate` gives out of the match clock
+    pub fn move_budget(&self, moves_played: usize, board_size: usize) -> Duration {
+        let budget = Duration::from_millis((self.timeout_turn - self.timeout_turn / 5).max(1));
+        match self.time_left {
+            Some(left) if self.timeout_match > 0 => {
+                budget.min(time_manager::allocate(Duration::from_millis(left), moves_played, board_size))
+            }
+            _ => budget,
+        }
+    }
+}
+
+/// One engine session driven by Gomocup commands
+pub struct GomocupSession {
+    pub state: Option<GameState>,
+    pub time_control: TimeControl,
+    board_lines: Option<Vec<(usize, usize, u8)>>,
+}
+
+impl Default for GomocupSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GomocupSession {
+    pub fn new() -> Self {
+        Self {
+            state: None,
+            time_control: TimeControl::default(),
+            board_lines: None,
+        }
+    }
+
+    /// Handles one input line. Returns the reply lines and whether the session should end.
+    pub fn handle_line(&mut self, line: &str) -> (Vec<String>, bool) {
+        let line = line.trim();
+        if line.is_empty() {
+            return (Vec::new(), false);
+        }
+
+        if self.board_lines.is_some() {
+            return (self.handle_board_line(line), false);
+        }
+
+        let (command, args) = match line.split_once(char::is_whitespace) {
+            Some((command, args)) => (command, args.trim()),
+            None => (line, ""),
+        };
+
+        let reply = match command.to_ascii_uppercase().as_str() {
+            "START" => self.start(args),
+            "RESTART" => match &self.state {
+                Some(state) => {
+                    self.state = Some(freestyle_state(state.board.rows, state.board.cols));
+                    vec!["OK".to_string()]
+                }
+                None => vec!["ERROR no game started".to_string()],
+            },
+            "BEGIN" => self.play(),
+            "TURN" => self.turn(args),
+            "BOARD" => {
+                if self.state.is_none() {
+                    vec!["ERROR no game started".to_string()]
+                } else {
+                    self.board_lines = Some(Vec::new());
+                    Vec::new()
+                }
+            }
+            "INFO" => {
+                self.info(args);
+                Vec::new()
+            }
+            "ABOUT" => vec![format!(
+                "name=\"{}\", version=\"{}\"",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            )],
+            "END" => return (Vec::new(), true),
+            _ => vec![format!("UNKNOWN command {}", command)],
+        };
+
+        (reply, false)
+    }
+
+    fn start(&mut self, args: &str) -> Vec<String> {
+        let size = match args.parse::<usize>() {
+            Ok(size) => size,
+            Err(_) => return vec![format!("ERROR invalid board size '{}'", args)],
+        };
+        match GameState::try_new(size, WIN_CONDITION) {
+            Ok(mut state) => {
+                state.rules = RulePreset::ClassicGomoku.flags();
+                self.state = Some(state);
+                vec!["OK".to_string()]
+            }
+            Err(e) => vec![format!("ERROR {}", e)],
+        }
+    }
+
+    fn turn(&mut self, args: &str) -> Vec<String> {
+        let Some(state) = self.state.as_mut() else {
+            return vec!["ERROR no game started".to_string()];
+        };
+        let Some((row, col)) = parse_point(args, &state.board) else {
+            return vec![format!("ERROR invalid move '{}'", args)];
+        };
+        if !state.board.is_empty_position(row, col) {
+            return vec![format!("ERROR cell {} is occupied", args)];
+        }
+        state.make_move((row, col));
+        self.play()
+    }
+
+    fn info(&mut self, args: &str) {
+        let Some((key, value)) = args.split_once(char::is_whitespace) else {
+            return;
+        };
+        let Ok(value) = value.trim().parse::<u64>() else {
+            return;
+        };
+        match key.to_ascii_lowercase().as_str() {
+            "timeout_turn" => self.time_control.timeout_turn = value,
+            "timeout_match" => self.time_control.timeout_match = value,
+            "time_left" => self.time_control.time_left = Some(value),
+            _ => {}
+        }
+    }
+
+    /// Collects `x,y,field` lines until `DONE`, then rebuilds the position and moves.
+    /// Field 1 is the engine's own stone, anything else the opponent's.
+    fn handle_board_line(&mut self, line: &str) -> Vec<String> {
+        if !line.eq_ignore_ascii_case("DONE") {
+            let parsed = line.rsplit_once(',').and_then(|(point, field)| {
+                let board = &self.state.as_ref()?.board;
+                Some((parse_point(point, board)?, field.trim().parse::<u8>().ok()?))
+            });
+            let lines = self.board_lines.as_mut().expect("collecting board lines");
+            return match parsed {
+                Some(((row, col), field)) => {
+                    lines.push((row, col, field));
+                    Vec::new()
+                }
+                None => vec![format!("ERROR invalid board line '{}'", line)],
+            };
+        }
+
+        let lines = self.board_lines.take().unwrap_or_default();
+        let Some((rows, cols)) = self.state.as_ref().map(|state| (state.board.rows, state.board.cols)) else {
+            return vec!["ERROR no game started".to_string()];
+        };
+
+        // With equal stone counts the engine moved first, so it plays Max
+        let own = lines.iter().filter(|&&(_, _, field)| field == 1).count();
+        let engine = if own == lines.len() - own {
+            Player::Max
+        } else {
+            Player::Min
+        };
+
+        let mut state = freestyle_state(rows, cols);
+        for &(row, col, field) in &lines {
+            let player = if field == 1 { engine } else { engine.opponent() };
+            state.board.place_stone(row, col, player);
+        }
+        state.current_player = engine;
+        state.current_hash = state.zobrist_hash.compute_hash(&state);
+        self.state = Some(state);
+
+        self.play()
+    }
+
+    /// Searches the current position, plays the chosen move and answers with `x,y`
+    fn play(&mut self) -> Vec<String> {
+        let time_control = self.time_control;
+        let Some(state) = self.state.as_mut() else {
+            return vec!["ERROR no game started".to_string()];
+        };
+        let budget = time_control.move_budget(state.moves.len(), state.board.size);
+        if state.is_terminal() {
+            return vec!["ERROR game is over".to_string()];
+        }
+
+        let config = SearchConfig::new(MAX_SEARCH_DEPTH, Some(budget));
+        let result = lazy_smp_search_with_config(state, &config);
+        let Some((row, col)) = result
+            .best_move
+            .or_else(|| state.get_possible_moves().first().copied())
+        else {
+            return vec!["ERROR no legal move".to_string()];
+        };
+
+        state.make_move((row, col));
+        vec![format!("{},{}", col, row)]
+    }
+}
+
+/// Gomocup managers play plain freestyle: no captures and no forbidden moves, so the
+/// engine's board never loses stones the manager still has
+fn freestyle_state(rows: usize, cols: usize) -> GameState {
+    let mut state = GameState::new_rect(rows, cols, WIN_CONDITION);
+    state.rules = RulePreset::ClassicGomoku.flags();
+    state
+}
+
+/// Gomocup points are `x,y`, i.e. column first; the engine uses `(row, col)`
+fn parse_point(value: &str, board: &Board) -> Option<(usize, usize)> {
+    let (x, y) = value.split_once(',')?;
+    let col = x.trim().parse::<usize>().ok()?;
+    let row = y.trim().parse::<usize>().ok()?;
+    if row >= board.rows || col >= board.cols {
+        return None;
+    }
+    Some((row, col))
+}
+
+/// Runs the protocol loop until `END` or end of input
+pub fn run_protocol<R: BufRead, W: Write>(input: R, mut output: W) -> io::Result<()> {
+    let mut session = GomocupSession::new();
+    for line in input.lines() {
+        let (replies, done) = session.handle_line(&line?);
+        for reply in replies {
+            writeln!(output, "{}", reply)?;
+        }
+        output.flush()?;
+        if done {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::ai::lazy_smp::{lazy_smp_search_with_config, SearchConfig};
+use crate::core::board::Player;
+use crate::core::state::{GameOutcome, GameState};
+
+/// Search totals of one engine over a match
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngineStats {
+    pub moves: usize,
+    pub total_time: Duration,
+    pub total_nodes: u64,
+}
+
+impl EngineStats {
+    pub fn average_move_time(&self) -> Duration {
+        self.total_time / self.moves.max(1) as u32
+    }
+
+    pub fn average_nodes(&self) -> u64 {
+        self.total_nodes / self.moves.max(1) as u64
+    }
+}
+
+/// One finished game of a match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    /// Colour engine A played
+    pub a_color: Player,
+    pub outcome: GameOutcome,
+    pub moves: Vec<(usize, usize)>,
+    /// Set when an engine answered with a move the rules do not allow, or none at all.
+    /// That engine loses the game.
+    pub illegal_move: Option<(Player, Option<(usize, usize)>)>,
+}
+
+impl GameRecord {
+    pub fn winner(&self) -> Option<Player> {
+        match self.outcome {
+            GameOutcome::FiveInARow(winner)
+            | GameOutcome::Captures(winner)
+            | GameOutcome::Resignation(winner)
+            | GameOutcome::Timeout(winner) => Some(winner),
+            GameOutcome::Draw | GameOutcome::DrawAgreed => None,
+        }
+    }
+}
+
+/// Score of a match, counted from engine A's side
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchResult {
+    pub a_wins: usize,
+    pub b_wins: usize,
+    pub draws: usize,
+    pub a_stats: EngineStats,
+    pub b_stats: EngineStats,
+    pub games: Vec<GameRecord>,
+}
+
+impl fmt::Display for MatchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "A {} - B {} - draws {}", self.a_wins, self.b_wins, self.draws)?;
+        for (name, stats) in [("A", &self.a_stats), ("B", &self.b_stats)] {
+            writeln!(
+                f,
+                "{}: {} moves, {:?} per move, {} nodes per move",
+                name,
+                stats.moves,
+                stats.average_move_time(),
+                stats.average_nodes()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays `games` games between two search configurations, engine A taking black in the
+/// even games and white in the odd ones. A seeded configuration gets its seed shifted by
+/// the game number so repeated pairings do not replay the same game.
+pub fn run_match(
+    config_a: &SearchConfig,
+    config_b: &SearchConfig,
+    games: usize,
+    board_size: usize,
+    win_condition: usize,
+) -> MatchResult {
+    let mut result = MatchResult::default();
+
+    for game in 0..games {
+        let a_color = if game % 2 == 0 { Player::Max } else { Player::Min };
+        let reseed = |config: &SearchConfig| SearchConfig {
+            seed: config.seed.map(|seed| seed.wrapping_add(game as u64)),
+            ..*config
+        };
+        let record = play_game(
+            &reseed(config_a),
+            &reseed(config_b),
+            a_color,
+            GameState::new(board_size, win_condition),
+            &mut result,
+        );
+
+        match record.winner() {
+            Some(winner) if winner == a_color => result.a_wins += 1,
+            Some(_) => result.b_wins += 1,
+            None => result.draws += 1,
+        }
+        result.games.push(record);
+    }
+    result
+}
+
+/// Drives one game to its end. Captures can in principle let a game cycle, so one
+/// running past twice the board's cells is scored a draw.
+fn play_game(
+    config_a: &SearchConfig,
+    config_b: &SearchConfig,
+    a_color: Player,
+    mut state: GameState,
+    result: &mut MatchResult,
+) -> GameRecord {
+    let max_plies = 2 * state.board.total_cells;
+    let mut moves = Vec::new();
+
+    let outcome = loop {
+        if let Some(outcome) = state.outcome() {
+            break outcome;
+        }
+        if moves.len() >= max_plies {
+            break GameOutcome::Draw;
+        }
+
+        let mover = state.current_player;
+        let (config, stats) = if mover == a_color {
+            (config_a, &mut result.a_stats)
+        } else {
+            (config_b, &mut result.b_stats)
+        };
+        let search = lazy_smp_search_with_config(&mut state.clone(), config);
+        stats.moves += 1;
+        stats.total_time += search.time_elapsed;
+        stats.total_nodes += search.nodes_searched;
+
+        match search.best_move {
+            Some(mv) if state.get_possible_moves().contains(&mv) => {
+                state.make_move(mv);
+                moves.push(mv);
+            }
+            illegal => {
+                return GameRecord {
+                    a_color,
+                    outcome: GameOutcome::Resignation(mover.opponent()),
+                    moves,
+                    illegal_move: Some((mover, illegal)),
+                };
+            }
+        }
+    };
+
+    GameRecord {
+        a_color,
+        outcome,
+        moves,
+        illegal_move: None,
+    }
+}
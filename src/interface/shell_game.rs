@@ -0,0 +1,154 @@
+use std::io::{self, BufRead, Write};
+
+use crate::ai::commentary::describe_move;
+use crate::ai::lazy_smp::{lazy_smp_search_with_config, SearchConfig};
+use crate::core::board::{Board, Player};
+use crate::core::state::{GameOutcome, GameState};
+
+const WIN_CONDITION: usize = 5;
+const HELP: &str = "Moves: h8 (column letter, row number from 1) or 7,7 (row,col from 0). \
+Commands: undo, resign, board, quit";
+
+/// Human against the AI in a terminal. The human plays black (`X`) and moves first.
+pub struct ShellGame {
+    pub state: GameState,
+    config: SearchConfig,
+}
+
+impl ShellGame {
+    pub fn new(size: usize, config: SearchConfig) -> Self {
+        Self {
+            state: GameState::new(size, WIN_CONDITION),
+            config,
+        }
+    }
+
+    /// Handles one input line. Returns the lines to print and whether to quit.
+    pub fn handle_line(&mut self, line: &str) -> (Vec<String>, bool) {
+        let line = line.trim();
+        match line.to_ascii_lowercase().as_str() {
+            "" => (Vec::new(), false),
+            "quit" | "exit" => (vec!["Bye".to_string()], true),
+            "board" => (vec![self.state.to_ascii()], false),
+            "help" => (vec![HELP.to_string()], false),
+            "undo" => (self.undo(), false),
+            "resign" => {
+                if self.state.resign(Player::Max) {
+                    (vec!["You resign.".to_string(), self.state.to_ascii()], false)
+                } else {
+                    (vec!["The game is already over.".to_string()], false)
+                }
+            }
+            _ => (self.play(line), false),
+        }
+    }
+
+    fn play(&mut self, input: &str) -> Vec<String> {
+        if self.state.outcome().is_some() {
+            return vec!["The game is over: undo, board or quit.".to_string()];
+        }
+        let Some(mv) = parse_move(input, &self.state.board) else {
+            return vec![format!("Cannot read move '{}'. {}", input, HELP)];
+        };
+        if let Err(reason) = self.state.is_legal_move(mv) {
+            return vec![format!("Illegal move {}: {}", format_move(mv), reason)];
+        }
+
+        self.state.make_move(mv);
+        if self.state.outcome().is_some() {
+            return vec![self.state.to_ascii()];
+        }
+
+        let result = lazy_smp_search_with_config(&mut self.state, &self.config);
+        let Some(reply) = result.best_move else {
+            return vec!["The AI has no move left.".to_string(), self.state.to_ascii()];
+        };
+        let commentary = describe_move(&self.state, reply, self.state.current_player);
+        self.state.make_move(reply);
+        vec![
+            format!(
+                "AI plays {} in {} ms (depth {}): {}",
+                format_move(reply),
+                result.time_elapsed.as_millis(),
+                result.depth_reached,
+                commentary
+            ),
+            self.state.to_ascii(),
+        ]
+    }
+
+    /// Takes back the AI's reply and the human move before it, so it is the human's turn again
+    fn undo(&mut self) -> Vec<String> {
+        if self.state.undo_last_move().is_none() {
+            return vec!["Nothing to undo.".to_string()];
+        }
+        if self.state.current_player != Player::Max {
+            self.state.undo_last_move();
+        }
+        vec![self.state.to_ascii()]
+    }
+}
+
+/// Reads `h8` (column letter from `a`, row number from 1) or `7,7` (row and column from 0)
+pub fn parse_move(input: &str, board: &Board) -> Option<(usize, usize)> {
+    let input = input.trim();
+    let mv = match input.split_once(',') {
+        Some((row, col)) => (row.trim().parse().ok()?, col.trim().parse().ok()?),
+        None => {
+            let mut chars = input.chars();
+            let letter = chars.next()?.to_ascii_lowercase();
+            if !letter.is_ascii_lowercase() {
+                return None;
+            }
+            let number: usize = chars.as_str().parse().ok()?;
+            (number.checked_sub(1)?, (letter as u8 - b'a') as usize)
+        }
+    };
+    (mv.0 < board.rows && mv.1 < board.cols).then_some(mv)
+}
+
+/// Both notations `parse_move` reads, e.g. `h8 (7,7)`. Past `z` only the numeric one.
+pub fn format_move((row, col): (usize, usize)) -> String {
+    match u8::try_from(col).ok().filter(|&col| col < 26) {
+        Some(col) => format!("{}{} ({},{})", (b'a' + col) as char, row + 1, row, col),
+        None => format!("({},{})", row, col),
+    }
+}
+
+fn describe_outcome(outcome: GameOutcome) -> &'static str {
+    match outcome {
+        GameOutcome::FiveInARow(Player::Max) | GameOutcome::Captures(Player::Max) => "You win!",
+        GameOutcome::FiveInARow(Player::Min) | GameOutcome::Captures(Player::Min) => "The AI wins.",
+        GameOutcome::Resignation(_) => "The AI wins by resignation.",
+        GameOutcome::Timeout(_) => "Lost on time.",
+        GameOutcome::Draw | GameOutcome::DrawAgreed => "Draw.",
+    }
+}
+
+/// Plays one game on a `size` board, reading the human's lines from `input` until `quit`
+/// or end of input. Returns the final position.
+pub fn new_game<R: BufRead, W: Write>(input: R, mut output: W, size: usize, config: SearchConfig) -> io::Result<GameState> {
+    let mut game = ShellGame::new(size, config);
+    writeln!(output, "{}", HELP)?;
+    write!(output, "{}", game.state.to_ascii())?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let was_over = game.state.outcome().is_some();
+        let (replies, done) = game.handle_line(&line?);
+        for reply in replies {
+            write!(output, "{}", reply)?;
+            if !reply.ends_with('\n') {
+                writeln!(output)?;
+            }
+        }
+        if !was_over && let Some(outcome) = game.state.outcome() {
+            writeln!(output, "{}", describe_outcome(outcome))?;
+        }
+        output.flush()?;
+        if done {
+            break;
+        }
+    }
+    Ok(game.state)
+}
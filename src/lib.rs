@@ -1,21 +1,40 @@
 pub mod ai {
+    pub mod analysis;
+    pub mod commentary;
+    pub mod difficulty;
     pub mod heuristic;
     pub mod lazy_smp;
     pub mod minimax;
     pub mod move_ordering;
     pub mod pattern_history;
+    pub mod ponder;
+    pub mod time_manager;
     pub mod transposition;
+    pub mod vcf;
     pub mod zobrist;
+
+    pub use difficulty::difficulty_params;
 }
 
 pub mod core {
+    pub mod ascii;
     pub mod board;
     pub mod captures;
+    pub mod json;
     pub mod moves;
+    pub mod positions;
     pub mod rules;
+    pub mod sgf;
     pub mod state;
 }
 
+pub mod interface {
+    pub mod broadcast;
+    pub mod gomocup;
+    pub mod selfplay;
+    pub mod shell_game;
+}
+
 pub mod ui {
     pub mod app;
     pub mod display {
@@ -23,12 +42,30 @@ pub mod ui {
     }
     pub mod screens {
         pub mod game {
+            pub mod ai_turn;
+            pub mod animation;
+            pub mod analysis;
+            pub mod blunder;
             pub mod board;
+            pub mod clock;
+            pub mod cursor;
             pub mod game;
+            pub mod game_over;
+            pub mod hint;
+            pub mod hover;
+            pub mod layout;
+            pub mod opening;
+            pub mod resign;
+            pub mod restart;
+            pub mod save;
             pub mod settings;
+            pub mod sound;
+            pub mod takeback;
         }
         pub mod menu;
+        pub mod replay;
         pub mod splash;
         pub mod utils;
     }
+    pub mod saves;
 }
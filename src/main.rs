@@ -3,6 +3,12 @@ use gomoku::ui::app::GomokuApp;
 fn main() {
 	let mut gomoku = GomokuApp::new();
 	gomoku.init();
+	// A saved game given on the command line opens in the replay viewer
+	if let Some(path) = std::env::args().nth(1)
+		&& let Err(e) = gomoku.load_replay(&path)
+	{
+		eprintln!("Could not load {}: {}", path, e);
+	}
 	gomoku.start();
 }
 
@@ -1,13 +1,19 @@
+use std::path::Path;
+
 use bevy::prelude::*;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
 
 use bevy::window::{PresentMode, WindowTheme};
 use bevy::color::palettes::css::CRIMSON;
 
-use crate::core::state::GameState;
+use crate::core::rules::RulePreset;
+use crate::core::state::{GameState, MAX_BOARD_SIZE};
+use crate::ai::{difficulty::{EngineKind, DIFFICULTY_LEVELS}, difficulty_params};
 use crate::ai::transposition::TranspositionTable;
 use crate::ui::display::display::make_visible;
 use crate::ui::screens::game::game::game_plugin;
 use crate::ui::screens::menu::menu_plugin;
+use crate::ui::screens::replay::{load_game, replay_plugin, Replay};
 use crate::ui::screens::splash::splash_plugin;
 
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
@@ -17,6 +23,7 @@ pub enum AppState {
 	Menu,
 	GameOptions,
 	Game,
+	Replay,
 	Credit
 }
 
@@ -27,31 +34,175 @@ struct ColorScheme {
 	title_text_color: Srgba,
 }
 
-#[derive(Resource, Debug, Component, PartialEq, Eq, Clone, Copy)]
+/// One of `DIFFICULTY_LEVELS`. Behind an alias so serde does not take the field for a
+/// string borrowed from the input, which would tie `GameSettings` to `'static` input.
+type DifficultyLevel = &'static str;
+
+/// Highest value of the AI depth cap, the depth the hard level searches to
+pub const MAX_DEPTH_CAP: usize = 16;
+
+/// Saved with each game in progress, see `ui::saves`
+#[derive(Resource, Debug, Component, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub struct GameSettings {
-	pub board_size: usize, //default to 19
+	#[serde(deserialize_with = "board_size")]
+	pub board_size: usize, //default to 19, 1..=MAX_BOARD_SIZE
 	pub total_capture_to_win: usize, //default to 10
 	pub minimum_chain_to_win: usize, //5 pallet 
+	#[serde(deserialize_with = "difficulty_level")]
+	pub ai_difficulty: DifficultyLevel, // "easy", "medium" or "hard", sets the two fields below
 	pub ai_depth: i32, //default to 2
 	pub alpha_beta_enabled: bool, //wether deep checking is enabled or not
 	pub versus_ai: bool, //if the user is against an AI or multiplayer
 	pub time_limit: Option<usize>, // time limit in milliseconds, optional
+	pub seed: u64, // AI tie-breaking seed, shown in the game panel. The timed, multi-threaded AI does not replay from it, see `SearchConfig::seed`
+	pub rule_preset: RulePreset, // captures and forbidden moves, picked in the settings menu
+	pub swap2: bool, // start games with the Swap2 opening
+	pub tt_size_mb: usize, // transposition table size of each AI search thread
+	pub ponder: bool, // let the AI think during the human's turn
+	pub show_coordinates: bool, // letters and numbers around the board
+	pub coordinates_skip_i: bool, // leave I out of the column letters, as on printed boards
+	pub sound_volume: usize, // game sound volume in percent, 0 mutes
+	pub show_move_numbers: bool, // print the ply number on each stone
+	pub clock_minutes: usize, // starting time on each player's clock, 0 for untimed games
+	pub clock_increment_secs: usize, // time added back to a clock after each move
+	pub captures_enabled: bool, // pair captures, follows the rule preset unless toggled
+	pub blunder_check: bool, // ask before playing a move that lets the opponent win at once
+	#[serde(default)]
+	pub colorblind_mode: bool, // stones told apart by shape and outline as well as colour
+	#[serde(default = "default_max_depth", deserialize_with = "max_depth")]
+	pub max_depth: usize, // cap on the AI's search depth whatever the difficulty, 1..=MAX_DEPTH_CAP
+	#[serde(default)]
+	pub engine: EngineKind, // search the AI plays with
+	#[serde(default = "default_forbid_double_three")]
+	pub forbid_double_three: bool, // no move making two open threes, follows the rule preset unless toggled
+	#[serde(default = "default_animate_stones")]
+	pub animate_stones: bool, // stones grow in when placed and fade out when captured
 }
 
 impl GameSettings {
 	pub fn new() -> Self {
+		let (ai_depth, time_limit, _) = difficulty_params("medium");
 		GameSettings {
 			board_size: 19,
 			total_capture_to_win: 10,
 			minimum_chain_to_win: 5,
-			ai_depth: 10, // Increased since iterative deepening can handle higher depths
+			ai_difficulty: "medium",
+			ai_depth,
 			alpha_beta_enabled: true,
 			versus_ai: true,
-			time_limit: Some(500), // 500ms time limit for AI by default
+			time_limit: Some(time_limit.as_millis() as usize),
+			seed: rand::random(),
+			rule_preset: RulePreset::Standard,
+			swap2: false,
+			tt_size_mb: 32, // Kept smaller than the engine default, the UI runs alongside rendering
+			ponder: true,
+			show_coordinates: true,
+			coordinates_skip_i: true,
+			sound_volume: 100,
+			show_move_numbers: false,
+			clock_minutes: 0,
+			clock_increment_secs: 0,
+			captures_enabled: RulePreset::Standard.flags().captures_enabled,
+			blunder_check: true,
+			colorblind_mode: false,
+			max_depth: MAX_DEPTH_CAP,
+			engine: EngineKind::Advanced,
+			forbid_double_three: RulePreset::Standard.flags().forbid_double_three,
+			animate_stones: true,
 		}
 	}
+
+	/// Switches to `preset` along with the win condition, capture and double-three rules it implies
+	pub fn set_rule_preset(&mut self, preset: RulePreset) {
+		self.rule_preset = preset;
+		self.minimum_chain_to_win = preset.win_condition();
+		self.captures_enabled = preset.flags().captures_enabled;
+		self.forbid_double_three = preset.flags().forbid_double_three;
+	}
+
+	/// Switches the AI level, along with the search depth and time limit it implies
+	pub fn set_ai_difficulty(&mut self, level: &'static str) {
+		let (ai_depth, time_limit, _) = difficulty_params(level);
+		self.ai_difficulty = level;
+		self.ai_depth = ai_depth;
+		self.time_limit = Some(time_limit.as_millis() as usize);
+	}
+
+	/// Sets the AI depth cap, clamped to `1..=MAX_DEPTH_CAP`
+	pub fn set_max_depth(&mut self, max_depth: usize) {
+		self.max_depth = max_depth.clamp(1, MAX_DEPTH_CAP);
+	}
+
+	/// Depth the AI searches to: the difficulty's depth, no deeper than the cap
+	pub fn ai_search_depth(&self) -> i32 {
+		self.ai_depth.min(self.max_depth as i32)
+	}
+
+	/// Fresh game state for these settings, refused when the board size is out of range
+	pub fn new_game_state(&self) -> Result<GameState, String> {
+		let mut state = GameState::try_new(self.board_size, self.minimum_chain_to_win)?;
+		state.rules = self.rule_preset.flags();
+		state.rules.captures_enabled = self.captures_enabled;
+		state.rules.forbid_double_three = self.forbid_double_three;
+		// The setting counts captured stones, the engine counts pairs
+		state.capture_win_pairs = (self.total_capture_to_win / 2).max(1);
+		if self.swap2 {
+			state.start_swap2();
+		}
+		Ok(state)
+	}
+
+	/// Whether the chain to win fits on the board, see `GameState::line_win_possible`
+	pub fn line_win_possible(&self) -> bool {
+		self.board_size >= self.minimum_chain_to_win
+	}
+
+	/// Sets the board size, clamped to what the engine supports. A board too small for the
+	/// chain to win is allowed, see `line_win_possible`.
+	pub fn set_board_size(&mut self, board_size: usize) {
+		self.board_size = board_size.clamp(1, MAX_BOARD_SIZE);
+	}
+}
+
+/// Reads a difficulty name back as one of `DIFFICULTY_LEVELS`
+fn difficulty_level<'de, D: Deserializer<'de>>(deserializer: D) -> Result<&'static str, D::Error> {
+	let level = String::deserialize(deserializer)?;
+	DIFFICULTY_LEVELS
+		.into_iter()
+		.find(|&known| known == level)
+		.ok_or_else(|| D::Error::custom(format!("unknown AI difficulty {}", level)))
+}
+
+fn default_max_depth() -> usize {
+	MAX_DEPTH_CAP
+}
+
+/// Saves from before the toggle played with the standard rules
+fn default_forbid_double_three() -> bool {
+	RulePreset::Standard.flags().forbid_double_three
+}
+
+fn default_animate_stones() -> bool {
+	true
+}
+
+/// Reads the board size back, refusing sizes the engine does not support
+fn board_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+	let board_size = usize::deserialize(deserializer)?;
+	if !(1..=MAX_BOARD_SIZE).contains(&board_size) {
+		return Err(D::Error::custom(format!("board size {} is outside 1..={}", board_size, MAX_BOARD_SIZE)));
+	}
+	Ok(board_size)
 }
 
+/// Reads the depth cap back, refusing values `set_max_depth` would not give
+fn max_depth<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+	let max_depth = usize::deserialize(deserializer)?;
+	if !(1..=MAX_DEPTH_CAP).contains(&max_depth) {
+		return Err(D::Error::custom(format!("max depth {} is outside 1..={}", max_depth, MAX_DEPTH_CAP)));
+	}
+	Ok(max_depth)
+}
 
 impl ColorScheme {
 	pub fn new() -> Self {
@@ -73,13 +224,14 @@ pub struct GomokuApp {
 
 impl GomokuApp {
 	pub fn new() -> Self {
-		println!("Initializing Gomoku App");
 		let app = App::new();
 		GomokuApp { app }
 	}
 
 	pub fn init(&mut self) {
+		// The window plugins bring the logger, nothing is logged before them
 		self.init_window();
+		info!("Initializing Gomoku App");
 		self.init_resources();
 		self.init_plugins();
 	}
@@ -110,7 +262,7 @@ impl GomokuApp {
 	fn init_resources(&mut self) {
 		let settings = GameSettings::new();
 		self.app
-		.insert_resource(GameState::new(settings.board_size, settings.minimum_chain_to_win))
+		.insert_resource(settings.new_game_state().expect("the default settings make a valid game"))
         .insert_resource(settings)
         .insert_resource(ColorScheme::new())
         .init_resource::<TranspositionTable>();
@@ -127,11 +279,19 @@ impl GomokuApp {
                 make_visible,
             ),
         )
-        .add_plugins((splash_plugin, menu_plugin, game_plugin));
+        .add_plugins((splash_plugin, menu_plugin, game_plugin, replay_plugin));
+	}
+
+	/// Opens a saved game in the replay viewer instead of the main menu
+	pub fn load_replay(&mut self, path: &str) -> Result<(), String> {
+		let mut state = load_game(Path::new(path))?;
+		let replay = Replay::rewind(&mut state);
+		self.app.insert_resource(state).insert_resource(replay);
+		Ok(())
 	}
 
 	pub fn start(&mut self) {
-		println!("Gomoku App Started.");
+		info!("Gomoku App Started.");
 		self.app.run();
 	}
 }
\ No newline at end of file
@@ -0,0 +1,109 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{core::state::GameState, ui::app::GameSettings};
+
+const SAVE_EXTENSION: &str = "json";
+
+/// Game in progress with the settings it was started under
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub state: GameState,
+    pub settings: GameSettings,
+}
+
+/// Save picked on the resume screen, taken by the game screen in place of a new game
+#[derive(Resource, Default)]
+pub struct SavedGameToResume(pub Option<GameState>);
+
+/// `$XDG_DATA_HOME/gomoku/saves`, falling back to `~/.local/share/gomoku/saves`, or
+/// `saves` in the working directory without a home
+pub fn saves_dir() -> PathBuf {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")));
+    match data_home {
+        Some(dir) => dir.join("gomoku").join("saves"),
+        None => PathBuf::from("saves"),
+    }
+}
+
+/// Writes the game to a new file in `dir`, created if missing, and returns its path
+pub fn save_game(dir: &Path, state: &GameState, settings: &GameSettings) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    // Two saves within the same second get a suffix instead of overwriting each other
+    let path = (0..)
+        .map(|n| match n {
+            0 => dir.join(format!("game-{}.{}", secs, SAVE_EXTENSION)),
+            n => dir.join(format!("game-{}-{}.{}", secs, n, SAVE_EXTENSION)),
+        })
+        .find(|path| !path.exists())
+        .expect("an unused file name");
+
+    let saved = SavedGame {
+        state: state.clone(),
+        settings: *settings,
+    };
+    let json = serde_json::to_string(&saved).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Reads a file written by `save_game`. The repetition and pattern history the JSON
+/// leaves out are rebuilt from the moves, so play goes on as if the game never stopped.
+pub fn load_saved_game(path: &Path) -> Result<SavedGame, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut saved: SavedGame = serde_json::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if saved.state.capture_history.len() != saved.state.moves.len() {
+        return Err(format!("{}: capture history does not match the moves", path.display()));
+    }
+    restore_line(&mut saved.state);
+    Ok(saved)
+}
+
+/// Save files in `dir`, newest first. A missing directory has none.
+pub fn list_saved_games(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut saves: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == SAVE_EXTENSION))
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|meta| meta.modified()).unwrap_or(UNIX_EPOCH);
+            (modified, path)
+        })
+        .collect();
+    saves.sort_by(|a, b| b.cmp(a));
+    saves.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Position keys before each move and the pattern analysis `make_move` would have recorded,
+/// found by taking the moves back on a copy
+fn restore_line(state: &mut GameState) {
+    let mut rewound = state.clone();
+    let mut position_history = Vec::with_capacity(state.moves.len());
+    for &(row, col, player) in state.moves.iter().rev() {
+        // Set from the recorded mover, a Swap2 colour choice can hand over the turn without a move
+        rewound.current_player = player.opponent();
+        rewound.undo_move((row, col));
+        let hash = rewound.zobrist_hash.compute_hash(&rewound);
+        position_history.push((hash, rewound.max_captures, rewound.min_captures));
+    }
+    position_history.reverse();
+    state.position_history = position_history;
+
+    state.pattern_analyzer.reset();
+    for (&(row, col, player), captured) in state.moves.iter().zip(&state.capture_history) {
+        state.pattern_analyzer.analyze_move_simple((row, col), player, captured.len() / 2);
+    }
+}
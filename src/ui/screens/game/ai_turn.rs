@@ -0,0 +1,114 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use bevy::{
+    prelude::*,
+    tasks::{futures::check_ready, AsyncComputeTaskPool, Task, TaskPool},
+};
+
+use crate::{
+    ai::{lazy_smp::{SearchConfig, SearchResult}, move_ordering::MoveHistory},
+    core::state::GameState,
+};
+
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+/// Seconds each spinner frame stays up
+const SPINNER_FRAME_TIME: f32 = 0.15;
+
+/// Move the AI settled on, with the search behind it
+#[derive(Debug)]
+pub struct AiMove {
+    pub chosen: Option<(usize, usize)>,
+    pub result: SearchResult,
+    /// Settings the search ran with, reused to ponder on the reply
+    pub config: SearchConfig,
+    /// Cutoff history after the search, ordering the AI's next one
+    pub history: MoveHistory,
+}
+
+/// AI search running on the async compute pool, so frames keep coming while it thinks.
+/// Idle without a task, thinking while one runs, back to idle once `poll` hands out its move.
+#[derive(Resource, Default)]
+pub struct AiTurn {
+    task: Option<Task<AiMove>>,
+    /// Hash of the position the search started from
+    position: u64,
+    /// Stop flag handed to the search in flight
+    stop: Arc<AtomicBool>,
+}
+
+#[derive(Component)]
+pub struct AiThinkingText;
+
+impl AiTurn {
+    /// Runs `search` on a copy of `state`, replacing any search still in flight. The search
+    /// should give up once the flag it is handed is set.
+    pub fn start(
+        &mut self,
+        state: &GameState,
+        search: impl FnOnce(GameState, &AtomicBool) -> AiMove + Send + 'static,
+    ) {
+        self.cancel();
+        let pool = AsyncComputeTaskPool::get_or_init(TaskPool::default);
+        let state = state.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        self.stop = Arc::clone(&stop);
+        self.position = state.hash();
+        self.task = Some(pool.spawn(async move { search(state, &stop) }));
+    }
+
+    pub fn is_thinking(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// The finished search, handed out once. A result for another position than `state`,
+    /// left by an undo while the AI was thinking, is dropped.
+    pub fn poll(&mut self, state: &GameState) -> Option<AiMove> {
+        let finished = check_ready(self.task.as_mut()?)?;
+        self.task = None;
+        (state.hash() == self.position).then_some(finished)
+    }
+
+    /// Stops the search in flight, whose move is never played
+    pub fn cancel(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.task = None;
+    }
+}
+
+/// Indicator text after `elapsed` seconds of thinking
+pub fn thinking_label(elapsed: f32) -> String {
+    let frame = (elapsed.max(0.0) / SPINNER_FRAME_TIME) as usize % SPINNER_FRAMES.len();
+    format!("{} AI is thinking...", SPINNER_FRAMES[frame])
+}
+
+pub fn spawn_thinking_indicator(builder: &mut ChildSpawnerCommands) {
+    builder.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.85, 0.75, 0.55)),
+        Visibility::Hidden,
+        AiThinkingText,
+    ));
+}
+
+/// Spins the indicator while the AI thinks and hides it otherwise
+pub fn update_thinking_indicator(
+    ai_turn: Res<AiTurn>,
+    time: Res<Time<Real>>,
+    mut texts: Query<(&mut Text, &mut Visibility), With<AiThinkingText>>,
+) {
+    for (mut text, mut visibility) in &mut texts {
+        if ai_turn.is_thinking() {
+            text.0 = thinking_label(time.elapsed_secs());
+            visibility.set_if_neq(Visibility::Inherited);
+        } else {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+    }
+}
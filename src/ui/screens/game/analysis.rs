@@ -0,0 +1,240 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    ai::{
+        analysis::{Analysis, Analyzer},
+        heuristic::{AdvancedPatternType, Heuristic},
+        lazy_smp::SearchConfig,
+    },
+    core::state::GameState,
+    ui::{app::GameSettings, screens::game::board::BoardUtils},
+};
+
+/// Short enough to keep up with play, and only one thread so the AI's own search is not slowed much
+const ANALYSIS_TIME_LIMIT: Duration = Duration::from_millis(500);
+const ANALYSIS_MAX_DEPTH: i32 = 8;
+/// Moves of the expected line shown under the best move
+pub const PV_DISPLAY_LENGTH: usize = 5;
+const EVAL_BAR_HEIGHT: f32 = 300.0;
+const ANALYSIS_BUTTON: Color = Color::srgb(0.2, 0.2, 0.2);
+const ANALYSIS_BUTTON_ACTIVE: Color = Color::srgb(0.25, 0.4, 0.25);
+
+/// Analysis overlay state: whether it is shown, the search in flight and its last result
+#[derive(Resource, Default)]
+pub struct AnalysisMode {
+    pub enabled: bool,
+    pub analyzer: Option<Analyzer>,
+    pub latest: Option<Analysis>,
+}
+
+#[derive(Component)]
+pub struct AnalysisButton;
+
+#[derive(Component)]
+pub struct AnalysisPanel;
+
+/// Black part of the evaluation bar, grown from the bottom as Max gets ahead
+#[derive(Component)]
+pub struct EvalBarFill;
+
+#[derive(Component)]
+pub struct AnalysisText;
+
+fn analysis_config() -> SearchConfig {
+    SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(ANALYSIS_MAX_DEPTH, Some(ANALYSIS_TIME_LIMIT))
+    }
+}
+
+/// Share of the bar filled for Max, from the normalized evaluation
+pub fn eval_bar_fill(normalized: f32) -> f32 {
+    ((normalized + 1.0) / 2.0).clamp(0.0, 1.0)
+}
+
+/// First `PV_DISPLAY_LENGTH` moves of the expected line, in board coordinates
+pub fn format_line(pv: &[(usize, usize)], state: &GameState, skip_i: bool) -> String {
+    let (columns, rows) = BoardUtils::board_labels(&state.board, skip_i);
+    pv.iter()
+        .take(PV_DISPLAY_LENGTH)
+        .map(|&(x, y)| format!("{}{}", columns[x], rows[y]))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Score in hundredths, like centipawns in chess engines, with the plain-words verdict,
+/// the expected line and what the best move threatens
+pub fn format_analysis(analysis: &Analysis, state: &GameState, skip_i: bool) -> String {
+    let best = match analysis.best_move {
+        Some(mv) => format_line(&[mv], state, skip_i),
+        None => "-".to_string(),
+    };
+    let mut description = format!(
+        "{:+.2}\n{}\nBest: {} (depth {})",
+        analysis.score as f32 / 100.0,
+        Heuristic::score_to_human(analysis.score, analysis.depth),
+        best,
+        analysis.depth
+    );
+    if analysis.pv.len() > 1 {
+        description.push_str(&format!("\nLine: {}", format_line(&analysis.pv, state, skip_i)));
+    }
+    if let Some((x, y)) = analysis.best_move {
+        let threats: Vec<_> = Heuristic::threats_at(&state.board, x, y, state.current_player, state.win_condition)
+            .into_iter()
+            .filter(|&threat| threat != AdvancedPatternType::Capture || state.rules.captures_enabled)
+            .map(|threat| threat.label())
+            .collect();
+        if !threats.is_empty() {
+            description.push_str(&format!("\nMakes: {}", threats.join(", ")));
+        }
+    }
+    description
+}
+
+pub fn spawn_analysis_button(builder: &mut ChildSpawnerCommands) {
+    builder
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(120.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(ANALYSIS_BUTTON),
+            BorderRadius::all(Val::Px(6.0)),
+            AnalysisButton,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Analysis"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// Evaluation bar with the score and best move under it, hidden until analysis is on
+pub fn spawn_analysis_panel(builder: &mut ChildSpawnerCommands) {
+    builder
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(10.0),
+                width: Val::Px(140.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            AnalysisPanel,
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn((
+                    Node {
+                        width: Val::Px(24.0),
+                        height: Val::Px(EVAL_BAR_HEIGHT),
+                        flex_direction: FlexDirection::ColumnReverse,
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.92, 0.92, 0.92)),
+                    BorderColor(Color::srgb(0.4, 0.4, 0.4)),
+                ))
+                .with_children(|builder| {
+                    builder.spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(50.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::BLACK),
+                        EvalBarFill,
+                    ));
+                });
+            builder.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                TextLayout::new_with_justify(JustifyText::Center),
+                AnalysisText,
+            ));
+        });
+}
+
+/// Turns analysis on (starting on the current position) or off
+pub fn analysis_button_action(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &AnalysisButton), Changed<Interaction>>,
+    game_state: Res<GameState>,
+    mut analysis: ResMut<AnalysisMode>,
+) {
+    for (interaction, mut background, _) in &mut interaction_query {
+        if *interaction == Interaction::Pressed {
+            analysis.enabled = !analysis.enabled;
+            analysis.latest = None;
+            analysis.analyzer = if analysis.enabled {
+                Analyzer::start(&game_state, &analysis_config())
+            } else {
+                None
+            };
+        }
+        *background = if analysis.enabled { ANALYSIS_BUTTON_ACTIVE } else { ANALYSIS_BUTTON }.into();
+    }
+}
+
+/// Replacing the analyzer stops the search of the previous position
+pub fn restart_analysis(game_state: Res<GameState>, mut analysis: ResMut<AnalysisMode>) {
+    if analysis.enabled {
+        analysis.analyzer = Analyzer::start(&game_state, &analysis_config());
+    }
+}
+
+/// Picks up each depth the analysis completes, then its final result, without blocking the frame
+pub fn poll_analysis(mut analysis: ResMut<AnalysisMode>) {
+    let Some(analyzer) = analysis.bypass_change_detection().analyzer.as_mut() else {
+        return;
+    };
+    let progress = analyzer.try_progress();
+    let finished = analyzer.try_finish();
+    if finished.is_some() {
+        analysis.analyzer = None;
+    }
+    if let Some(result) = finished.or(progress) {
+        analysis.latest = Some(result);
+    }
+}
+
+pub fn update_analysis_panel(
+    analysis: Res<AnalysisMode>,
+    (game_state, settings): (Res<GameState>, Res<GameSettings>),
+    mut panels: Query<&mut Visibility, With<AnalysisPanel>>,
+    mut fills: Query<&mut Node, With<EvalBarFill>>,
+    mut texts: Query<&mut Text, With<AnalysisText>>,
+) {
+    for mut visibility in &mut panels {
+        *visibility = if analysis.enabled { Visibility::Inherited } else { Visibility::Hidden };
+    }
+
+    let fill = analysis.latest.as_ref().map_or(0.5, |latest| eval_bar_fill(latest.normalized));
+    for mut node in &mut fills {
+        node.height = Val::Percent(fill * 100.0);
+    }
+
+    let description = match &analysis.latest {
+        Some(latest) => format_analysis(latest, &game_state, settings.coordinates_skip_i),
+        None => "Analysing...".to_string(),
+    };
+    for mut text in &mut texts {
+        text.0 = description.clone();
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::ui::app::GameSettings;
+
+/// Time a stone takes to grow in, or to fade out once captured
+pub const STONE_ANIMATION_SECS: f32 = 0.15;
+
+/// Slow start, fast middle, slow finish. `t` is clamped to 0..=1.
+pub fn ease_in_out_cubic(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+/// Scale and opacity of a new stone `t` of the way through its appearance
+pub fn appear_scale(t: f32) -> f32 {
+    ease_in_out_cubic(t)
+}
+
+/// Scale and opacity of a captured stone `t` of the way through its fade
+pub fn capture_scale(t: f32) -> f32 {
+    1.0 - ease_in_out_cubic(t)
+}
+
+/// Grows a newly placed stone in, removed once it reaches full size
+#[derive(Component)]
+pub struct StoneAppear {
+    pub timer: Timer,
+}
+
+/// Fades a captured stone out, which is despawned at the end. The `Stone` marker is taken
+/// off beforehand, so the rest of the game screen no longer counts it.
+#[derive(Component)]
+pub struct StoneCapture {
+    pub timer: Timer,
+}
+
+impl StoneAppear {
+    pub fn new() -> Self {
+        Self { timer: Timer::from_seconds(STONE_ANIMATION_SECS, TimerMode::Once) }
+    }
+}
+
+impl Default for StoneAppear {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StoneCapture {
+    pub fn new() -> Self {
+        Self { timer: Timer::from_seconds(STONE_ANIMATION_SECS, TimerMode::Once) }
+    }
+}
+
+impl Default for StoneCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn fade(transform: &mut Transform, fill: &mut BackgroundColor, border: &mut BorderColor, amount: f32) {
+    transform.scale = Vec3::new(amount, amount, 1.0);
+    fill.0.set_alpha(amount);
+    border.0.set_alpha(amount);
+}
+
+/// Steps the stone animations, or finishes them at once when they are turned off
+pub fn animate_stones(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<GameSettings>,
+    mut appearing: Query<(Entity, &mut StoneAppear, &mut Transform, &mut BackgroundColor, &mut BorderColor), Without<StoneCapture>>,
+    mut captured: Query<(Entity, &mut StoneCapture, &mut Transform, &mut BackgroundColor, &mut BorderColor), Without<StoneAppear>>,
+) {
+    for (entity, mut appear, mut transform, mut fill, mut border) in &mut appearing {
+        appear.timer.tick(time.delta());
+        let t = if settings.animate_stones { appear.timer.fraction() } else { 1.0 };
+        fade(&mut transform, &mut fill, &mut border, appear_scale(t));
+        if t >= 1.0 {
+            commands.entity(entity).remove::<StoneAppear>();
+        }
+    }
+
+    for (entity, mut capture, mut transform, mut fill, mut border) in &mut captured {
+        capture.timer.tick(time.delta());
+        let t = if settings.animate_stones { capture.timer.fraction() } else { 1.0 };
+        fade(&mut transform, &mut fill, &mut border, capture_scale(t));
+        if t >= 1.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::{board::Player, state::GameState},
+    ui::{app::GameSettings, screens::game::game::{OnGameScreen, StonePlacement}},
+};
+
+const DIALOG_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const DIALOG_BUTTON_HOVERED: Color = Color::srgb(0.25, 0.25, 0.25);
+
+/// Human move held back until the player confirms it, because it lets the opponent win
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingBlunder(pub Option<(usize, usize)>);
+
+#[derive(Component)]
+pub struct BlunderDialog;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlunderChoice {
+    PlayAnyway,
+    Cancel,
+}
+
+/// Sends a human move on, unless the blunder check holds it for confirmation.
+/// Nothing is sent while an earlier move still waits for an answer.
+pub fn submit_human_move(
+    cell: (usize, usize),
+    (state, settings): (&GameState, &GameSettings),
+    pending: &mut PendingBlunder,
+    stone_placement: &mut EventWriter<StonePlacement>,
+) {
+    if pending.0.is_some() {
+        return;
+    }
+    if settings.blunder_check && state.move_allows_opponent_win(cell) {
+        info!("Move {:?} lets the opponent win, asking for confirmation", cell);
+        pending.0 = Some(cell);
+    } else {
+        stone_placement.write(StonePlacement { x: cell.0, y: cell.1 });
+    }
+}
+
+pub fn blunder_warning(opponent: Player) -> String {
+    let name = match opponent {
+        Player::Max => "Black",
+        Player::Min => "White",
+    };
+    format!("{} can win right after this move.\nPlay it anyway?", name)
+}
+
+/// Shows the confirmation while a move is pending, and removes it once answered
+pub fn update_blunder_dialog(
+    mut commands: Commands,
+    pending: Res<PendingBlunder>,
+    game_state: Res<GameState>,
+    dialogs: Query<Entity, With<BlunderDialog>>,
+) {
+    for entity in &dialogs {
+        commands.entity(entity).despawn();
+    }
+    if pending.0.is_none() {
+        return;
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            GlobalZIndex(90),
+            OnGameScreen,
+            BlunderDialog,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new(blunder_warning(game_state.current_player.opponent())),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+            builder
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    for (label, choice) in [("Play it", BlunderChoice::PlayAnyway), ("Cancel", BlunderChoice::Cancel)] {
+                        builder
+                            .spawn((
+                                Button,
+                                Node {
+                                    width: Val::Px(160.0),
+                                    height: Val::Px(50.0),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                BackgroundColor(DIALOG_BUTTON),
+                                choice,
+                            ))
+                            .with_children(|builder| {
+                                builder.spawn((
+                                    Text::new(label),
+                                    TextFont {
+                                        font_size: 24.0,
+                                        ..default()
+                                    },
+                                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+/// Plays the held move or drops it. The move is checked again in case it was undone meanwhile.
+pub fn blunder_dialog_action(
+    mut interaction_query: Query<(&Interaction, &BlunderChoice, &mut BackgroundColor), Changed<Interaction>>,
+    game_state: Res<GameState>,
+    mut pending: ResMut<PendingBlunder>,
+    mut stone_placement: EventWriter<StonePlacement>,
+) {
+    for (interaction, choice, mut background) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                if let Some((x, y)) = pending.0.take()
+                    && *choice == BlunderChoice::PlayAnyway
+                    && game_state.is_legal_move((x, y)).is_ok()
+                {
+                    stone_placement.write(StonePlacement { x, y });
+                }
+            }
+            Interaction::Hovered => *background = DIALOG_BUTTON_HOVERED.into(),
+            Interaction::None => *background = DIALOG_BUTTON.into(),
+        }
+    }
+}
+
+/// A move held back over an undo or redo no longer fits the board
+pub fn clear_pending_blunder(mut pending: ResMut<PendingBlunder>) {
+    if pending.0.is_some() {
+        pending.0 = None;
+    }
+}
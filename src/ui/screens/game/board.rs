@@ -1,5 +1,8 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
-use crate::ui::{app::GameSettings, screens::game::game::{GridCell, OnGameScreen}};
+use crate::core::{board::{Board, Player}, state::GameState};
+use crate::ui::{app::GameSettings, screens::game::{cursor::spawn_cursor_marker, game::{GridCell, OnGameScreen}, hint::spawn_hint_marker, hover::spawn_hover_preview}};
 
 #[derive(Component)]
 pub struct BoardRoot;
@@ -7,6 +10,70 @@ pub struct BoardRoot;
 #[derive(Component)]
 pub struct PreviewDot;
 
+/// Cell of the move on top of the move stack, `None` before the first move
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LastMove(pub Option<(usize, usize)>);
+
+/// Coordinate text around the board, tagged with the axis it labels
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoordinateLabel {
+    Column,
+    Row,
+}
+
+/// Ring drawn around the last played stone
+#[derive(Component)]
+pub struct LastMoveMarker;
+
+/// Ply number printed on a stone
+#[derive(Component)]
+pub struct MoveNumberLabel;
+
+/// Fill, outline and shape of a stone. The colorblind set tells the players apart by shape
+/// as well as colour: Max keeps round stones with a sky blue ring, Min gets rounded squares
+/// with a vermillion border, both from the Okabe-Ito palette.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StoneStyle {
+    pub fill: Color,
+    pub border: Color,
+    pub border_width: f32,
+    /// Corner radius in percent of the stone size, 50 for a disc
+    pub corner_radius: f32,
+}
+
+impl StoneStyle {
+    pub fn for_player(owner: Player, colorblind: bool) -> Self {
+        match (owner, colorblind) {
+            (Player::Max, false) => Self {
+                fill: Color::BLACK,
+                border: Color::NONE,
+                border_width: 0.0,
+                corner_radius: 50.0,
+            },
+            (Player::Min, false) => Self {
+                fill: Color::WHITE,
+                border: Color::NONE,
+                border_width: 0.0,
+                corner_radius: 50.0,
+            },
+            (Player::Max, true) => Self {
+                fill: Color::srgb(0.1, 0.1, 0.1),
+                border: Color::srgb(0.34, 0.71, 0.91),
+                border_width: 3.0,
+                corner_radius: 50.0,
+            },
+            (Player::Min, true) => Self {
+                fill: Color::srgb(0.95, 0.95, 0.95),
+                border: Color::srgb(0.84, 0.37, 0.0),
+                border_width: 3.0,
+                corner_radius: 20.0,
+            },
+        }
+    }
+}
+
+/// Screen x follows the engine's row index and screen y its column, so a board is drawn
+/// `rows` cells wide and `cols` cells tall.
 pub struct BoardUtils;
 
 impl BoardUtils {
@@ -14,15 +81,25 @@ impl BoardUtils {
     pub const LINE_THICKNESS: f32 = 2.0;
     pub const STONE_SIZE: f32 = 24.0;
     pub const PREVIEW_SIZE: f32 = 16.0;
+    pub const MARKER_SIZE: f32 = 30.0;
+    pub const MARKER_THICKNESS: f32 = 3.0;
+    pub const COORDINATE_FONT_SIZE: f32 = 14.0;
+    pub const COORDINATE_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+    pub const MOVE_NUMBER_FONT_SIZE: f32 = 11.0;
+    /// Boards larger than this skip the per-cell preview dots to keep the entity count down
+    pub const LIGHT_RENDER_THRESHOLD: usize = 19;
     
-    pub fn spawn_board(builder: &mut ChildSpawnerCommands, game_settings: &GameSettings) {
+    pub fn spawn_board(builder: &mut ChildSpawnerCommands, board: &Board, game_settings: &GameSettings) {
+        let (across, down) = (board.rows, board.cols);
         builder
             .spawn((
                 Node {
                     display: Display::Grid,
-                    width: Val::Px((game_settings.board_size as f32) * Self::CELL_SIZE),
-                    height: Val::Px((game_settings.board_size as f32) * Self::CELL_SIZE),
+                    width: Val::Px(across as f32 * Self::CELL_SIZE),
+                    height: Val::Px(down as f32 * Self::CELL_SIZE),
                     position_type: PositionType::Relative,
+                    // Room for the coordinate labels drawn outside the grid
+                    margin: UiRect::all(Val::Px(Self::CELL_SIZE)),
                     ..default()
                 },
                 BackgroundColor(Color::srgb(0.95, 0.85, 0.7)),
@@ -30,37 +107,44 @@ impl BoardUtils {
                 BoardRoot,
             ))
             .with_children(|builder| {
-                Self::draw_board(builder, game_settings.board_size);
-                Self::insert_intersection_hitboxes(builder, game_settings.board_size);
+                Self::draw_board(builder, across, down);
+                Self::insert_intersection_hitboxes(builder, across, down);
+                Self::spawn_last_move_marker(builder);
+                spawn_hint_marker(builder);
+                spawn_hover_preview(builder);
+                spawn_cursor_marker(builder);
+                if game_settings.show_coordinates {
+                    Self::draw_coordinates_rect(builder, across, down, game_settings.coordinates_skip_i);
+                }
             });
         
-        info!("Board initialized with size {}x{}", game_settings.board_size, game_settings.board_size);
+        info!("Board initialized with size {}x{}", across, down);
     }
     
-    fn draw_board(builder: &mut ChildSpawnerCommands, board_size: usize) {
+    fn draw_board(builder: &mut ChildSpawnerCommands, across: usize, down: usize) {
         info!("Drawing board grid lines...");
         
-        for i in 0..board_size {
+        for i in 0..across {
             builder.spawn((
                 Node {
                     position_type: PositionType::Absolute,
                     left: Val::Px(i as f32 * Self::CELL_SIZE + Self::CELL_SIZE / 2.0 - Self::LINE_THICKNESS / 2.0),
                     top: Val::Px(0.0),
                     width: Val::Px(Self::LINE_THICKNESS),
-                    height: Val::Px(Self::CELL_SIZE * board_size as f32),
+                    height: Val::Px(Self::CELL_SIZE * down as f32),
                     ..default()
                 },
                 BackgroundColor(Color::BLACK),
             ));
         }
         
-        for i in 0..board_size {
+        for i in 0..down {
             builder.spawn((
                 Node {
                     position_type: PositionType::Absolute,
                     left: Val::Px(0.0),
                     top: Val::Px(i as f32 * Self::CELL_SIZE + Self::CELL_SIZE / 2.0 - Self::LINE_THICKNESS / 2.0),
-                    width: Val::Px(Self::CELL_SIZE * board_size as f32),
+                    width: Val::Px(Self::CELL_SIZE * across as f32),
                     height: Val::Px(Self::LINE_THICKNESS),
                     ..default()
                 },
@@ -69,11 +153,15 @@ impl BoardUtils {
         }
     }
     
-    fn insert_intersection_hitboxes(builder: &mut ChildSpawnerCommands, board_size: usize) {
+    fn insert_intersection_hitboxes(builder: &mut ChildSpawnerCommands, across: usize, down: usize) {
         info!("Creating interactive hitboxes...");
+        let show_preview = across.max(down) <= Self::LIGHT_RENDER_THRESHOLD;
+        if !show_preview {
+            info!("Large board, skipping move previews");
+        }
         
-        for y in 0..board_size {
-            for x in 0..board_size {
+        for y in 0..down {
+            for x in 0..across {
                 builder
                     .spawn((
                         Node {
@@ -90,6 +178,9 @@ impl BoardUtils {
                         GridCell { x, y },
                     ))
                     .with_children(|builder| {
+                        if !show_preview {
+                            return;
+                        }
                         builder.spawn((
                             Node {
                                 position_type: PositionType::Absolute,
@@ -110,6 +201,107 @@ impl BoardUtils {
         }
     }
     
+    /// Column letters and row numbers for a board, left to right and top to bottom.
+    /// Rows count up from the bottom edge; `skip_i` leaves out the letter I, as is usual
+    /// on printed boards.
+    pub fn coordinate_labels(board_size: usize, skip_i: bool) -> (Vec<String>, Vec<String>) {
+        let columns = ('A'..='Z')
+            .filter(|&letter| !(skip_i && letter == 'I'))
+            .take(board_size)
+            .map(String::from)
+            .collect();
+        let rows = (1..=board_size).rev().map(|row| row.to_string()).collect();
+        (columns, rows)
+    }
+
+    /// Column letters and row numbers of `board` as drawn on screen
+    pub fn board_labels(board: &Board, skip_i: bool) -> (Vec<String>, Vec<String>) {
+        let (columns, _) = Self::coordinate_labels(board.rows, skip_i);
+        let (_, rows) = Self::coordinate_labels(board.cols, skip_i);
+        (columns, rows)
+    }
+
+    /// Name of a cell as the board labels it, like `K10`. Numbers when letters run out.
+    pub fn cell_label(board: &Board, (x, y): (usize, usize), skip_i: bool) -> String {
+        let (columns, rows) = Self::board_labels(board, skip_i);
+        match (columns.get(x), rows.get(y)) {
+            (Some(column), Some(row)) => format!("{}{}", column, row),
+            _ => format!("({}, {})", x, y),
+        }
+    }
+
+    /// Letters along the top and bottom edges and numbers along both sides, one per line
+    pub fn draw_coordinates(builder: &mut ChildSpawnerCommands, board_size: usize, skip_i: bool) {
+        Self::draw_coordinates_rect(builder, board_size, board_size, skip_i);
+    }
+
+    /// `draw_coordinates` for a board `across` cells wide and `down` cells tall
+    pub fn draw_coordinates_rect(builder: &mut ChildSpawnerCommands, across: usize, down: usize, skip_i: bool) {
+        let (columns, _) = Self::coordinate_labels(across, skip_i);
+        let (_, rows) = Self::coordinate_labels(down, skip_i);
+        let (right_edge, bottom_edge) = (across as f32 * Self::CELL_SIZE, down as f32 * Self::CELL_SIZE);
+
+        for (i, letter) in columns.into_iter().enumerate() {
+            for top in [-Self::CELL_SIZE, bottom_edge] {
+                builder.spawn((
+                    Self::label_node(i as f32 * Self::CELL_SIZE, top),
+                    Text::new(letter.clone()),
+                    TextFont {
+                        font_size: Self::COORDINATE_FONT_SIZE,
+                        ..default()
+                    },
+                    TextColor(Self::COORDINATE_COLOR),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                    CoordinateLabel::Column,
+                ));
+            }
+        }
+
+        for (i, number) in rows.into_iter().enumerate() {
+            for left in [-Self::CELL_SIZE, right_edge] {
+                builder.spawn((
+                    Self::label_node(left, i as f32 * Self::CELL_SIZE),
+                    Text::new(number.clone()),
+                    TextFont {
+                        font_size: Self::COORDINATE_FONT_SIZE,
+                        ..default()
+                    },
+                    TextColor(Self::COORDINATE_COLOR),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                    CoordinateLabel::Row,
+                ));
+            }
+        }
+    }
+
+    /// One cell-sized box, text centered on the line it labels
+    fn label_node(left: f32, top: f32) -> Node {
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(left),
+            top: Val::Px(top),
+            width: Val::Px(Self::CELL_SIZE),
+            height: Val::Px(Self::CELL_SIZE),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        }
+    }
+
+    fn spawn_last_move_marker(builder: &mut ChildSpawnerCommands) {
+        builder.spawn((
+            Node {
+                border: UiRect::all(Val::Px(Self::MARKER_THICKNESS)),
+                ..Self::stone_node(0, 0, Self::MARKER_SIZE)
+            },
+            BorderColor(Color::srgb(0.9, 0.2, 0.2)),
+            BorderRadius::all(Val::Percent(50.0)),
+            ZIndex(21),
+            Visibility::Hidden,
+            LastMoveMarker,
+        ));
+    }
+
     pub fn stone_node(x: usize, y: usize, size: f32) -> Node {
         let offset = (Self::CELL_SIZE - size) / 2.0;
         
@@ -122,4 +314,78 @@ impl BoardUtils {
             ..default()
         }
     }
-}
\ No newline at end of file
+}
+
+/// Ply (counting from 1) that put each stone still on the board in place. A cell that was
+/// captured and played again shows its latest move.
+pub fn move_numbers(state: &GameState) -> HashMap<(usize, usize), usize> {
+    let mut numbers = HashMap::new();
+    for (ply, &(row, col, _)) in state.moves.iter().enumerate() {
+        numbers.insert((row, col), ply + 1);
+    }
+    numbers.retain(|&(row, col), _| !state.board.is_empty_position(row, col));
+    numbers
+}
+
+/// Redraws the move numbers from the move history, so undo and captures renumber too
+pub fn update_move_numbers(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    game_state: Res<GameState>,
+    board_query: Query<Entity, With<BoardRoot>>,
+    labels: Query<Entity, With<MoveNumberLabel>>,
+) {
+    for entity in &labels {
+        commands.entity(entity).despawn();
+    }
+    if !settings.show_move_numbers {
+        return;
+    }
+    let Ok(board_entity) = board_query.single() else {
+        return;
+    };
+
+    commands.entity(board_entity).with_children(|builder| {
+        for ((x, y), number) in move_numbers(&game_state) {
+            // Light text on black stones, dark on white ones
+            let color = match game_state.board.get_player(x, y) {
+                Some(Player::Max) => Color::WHITE,
+                _ => Color::BLACK,
+            };
+            builder.spawn((
+                Node {
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..BoardUtils::stone_node(x, y, BoardUtils::STONE_SIZE)
+                },
+                Text::new(number.to_string()),
+                TextFont {
+                    font_size: BoardUtils::MOVE_NUMBER_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(color),
+                TextLayout::new_with_justify(JustifyText::Center),
+                ZIndex(21),
+                MoveNumberLabel,
+            ));
+        }
+    });
+}
+
+/// Moves the marker onto the last move, or hides it when no move has been played
+pub fn update_last_move_marker(
+    last_move: Res<LastMove>,
+    mut markers: Query<(&mut Node, &mut Visibility), With<LastMoveMarker>>,
+) {
+    for (mut node, mut visibility) in &mut markers {
+        match last_move.0 {
+            Some((x, y)) => {
+                let target = BoardUtils::stone_node(x, y, BoardUtils::MARKER_SIZE);
+                node.left = target.left;
+                node.top = target.top;
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
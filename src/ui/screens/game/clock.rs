@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    core::{board::Player, state::GameState},
+    ui::{app::GameSettings, screens::game::game::{GameEnded, GameStatus, PonderState, StonePlaced}},
+};
+
+// Rough number of moves the AI expects to still play, to spread its clock over them
+const AI_MOVES_TO_PLAN: u32 = 20;
+
+/// Chess-style clocks: the side to move counts down, and gets `increment` back after each move
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameClock {
+    /// Untimed games keep the clocks stopped
+    pub enabled: bool,
+    pub max_time_remaining: Duration,
+    pub min_time_remaining: Duration,
+    pub increment: Duration,
+}
+
+/// Time left in the clock text, updated by `update_clock_text`
+#[derive(Component)]
+pub struct ClockText(pub Player);
+
+impl GameClock {
+    pub fn new(initial: Duration, increment: Duration) -> Self {
+        Self {
+            enabled: true,
+            max_time_remaining: initial,
+            min_time_remaining: initial,
+            increment,
+        }
+    }
+
+    /// Clock for the time control picked in the settings, stopped when it is off
+    pub fn from_settings(settings: &GameSettings) -> Self {
+        if settings.clock_minutes == 0 {
+            return Self::default();
+        }
+        Self::new(
+            Duration::from_secs(settings.clock_minutes as u64 * 60),
+            Duration::from_secs(settings.clock_increment_secs as u64),
+        )
+    }
+
+    pub fn remaining(&self, player: Player) -> Duration {
+        match player {
+            Player::Max => self.max_time_remaining,
+            Player::Min => self.min_time_remaining,
+        }
+    }
+
+    fn remaining_mut(&mut self, player: Player) -> &mut Duration {
+        match player {
+            Player::Max => &mut self.max_time_remaining,
+            Player::Min => &mut self.min_time_remaining,
+        }
+    }
+
+    /// Runs `player`'s clock down by `elapsed`. Returns true when it reaches zero.
+    pub fn tick(&mut self, player: Player, elapsed: Duration) -> bool {
+        let remaining = self.remaining_mut(player);
+        *remaining = remaining.saturating_sub(elapsed);
+        remaining.is_zero()
+    }
+
+    pub fn add_increment(&mut self, player: Player) {
+        let increment = self.increment;
+        *self.remaining_mut(player) += increment;
+    }
+
+    /// Search time the AI can spend on this move without flagging: a share of what is
+    /// left plus the increment it gets back, never more than half the clock
+    pub fn move_budget(&self, player: Player) -> Duration {
+        let remaining = self.remaining(player);
+        (remaining / AI_MOVES_TO_PLAN + self.increment).min(remaining / 2)
+    }
+}
+
+/// `m:ss`, with tenths of a second once under a minute
+pub fn format_clock(remaining: Duration) -> String {
+    let secs = remaining.as_secs();
+    if secs < 60 {
+        format!("0:{:02}.{}", secs, remaining.subsec_millis() / 100)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Counts down the side to move in real time, the AI included while its search runs.
+/// A flag ends the game with the opponent winning on time.
+pub fn tick_game_clock(
+    time: Res<Time<Real>>,
+    mut clock: ResMut<GameClock>,
+    mut game_state: ResMut<GameState>,
+    mut game_status: ResMut<GameStatus>,
+    mut ponder: ResMut<PonderState>,
+    mut game_ended: EventWriter<GameEnded>,
+) {
+    if !clock.enabled || !matches!(*game_status, GameStatus::AwaitingUserInput | GameStatus::AiThinking) || game_state.outcome().is_some() {
+        return;
+    }
+
+    let player = game_state.current_player;
+    if clock.tick(player, time.delta()) && game_state.lose_on_time(player) {
+        info!("{:?} ran out of time", player);
+        ponder.0 = None;
+        *game_status = GameStatus::GameOver;
+        game_ended.write(GameEnded { winner: game_state.winner });
+    }
+}
+
+pub fn add_clock_increment(mut stones: EventReader<StonePlaced>, mut clock: ResMut<GameClock>) {
+    for stone in stones.read() {
+        if clock.enabled {
+            clock.add_increment(stone.player);
+        }
+    }
+}
+
+pub fn update_clock_text(clock: Res<GameClock>, mut texts: Query<(&mut Text, &ClockText)>) {
+    for (mut text, owner) in &mut texts {
+        text.0 = if clock.enabled {
+            let name = match owner.0 {
+                Player::Max => "Black",
+                Player::Min => "White",
+            };
+            format!("{} {}", name, format_clock(clock.remaining(owner.0)))
+        } else {
+            String::new()
+        };
+    }
+}
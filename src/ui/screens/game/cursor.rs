@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use bevy::{audio::Pitch, prelude::*};
+
+use crate::{
+    core::{board::Board, state::GameState},
+    ui::{
+        app::GameSettings,
+        screens::game::{blunder::{submit_human_move, PendingBlunder}, board::BoardUtils, game::{GameStatus, StonePlacement}, sound::scaled_volume},
+    },
+};
+
+const CURSOR_COLOR: Color = Color::srgb(0.2, 0.5, 0.95);
+const CURSOR_REJECTED_COLOR: Color = Color::srgb(0.95, 0.2, 0.2);
+const REJECT_FLASH_SECS: f32 = 0.3;
+const REJECT_FREQUENCY: f32 = 110.0;
+const REJECT_DURATION: Duration = Duration::from_millis(90);
+const REJECT_VOLUME: f32 = 0.3;
+
+/// Intersection picked with the arrow keys, in the same coordinates as `GridCell`
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardCursor {
+    pub x: usize,
+    pub y: usize,
+    /// Hidden until an arrow key is pressed, so mouse players never see it
+    pub shown: bool,
+}
+
+/// Square frame drawn around the cursor intersection
+#[derive(Component)]
+pub struct CursorMarker;
+
+/// Red flash on the cursor after a placement was refused
+#[derive(Component)]
+pub struct CursorRejection(Timer);
+
+impl BoardCursor {
+    pub fn centered(board_size: usize) -> Self {
+        Self {
+            x: board_size / 2,
+            y: board_size / 2,
+            shown: false,
+        }
+    }
+
+    /// On the center intersection of `board`, which need not be square
+    pub fn centered_on(board: &Board) -> Self {
+        let (x, y) = board.center();
+        Self { x, y, shown: false }
+    }
+
+    /// Moves by `(dx, dy)` cells, stopping at the board edges
+    pub fn step(&mut self, dx: isize, dy: isize, board_size: usize) {
+        self.step_rect(dx, dy, board_size, board_size);
+    }
+
+    /// `step` on a board `across` cells wide and `down` cells tall
+    pub fn step_rect(&mut self, dx: isize, dy: isize, across: usize, down: usize) {
+        self.x = (self.x as isize + dx).clamp(0, across.saturating_sub(1) as isize) as usize;
+        self.y = (self.y as isize + dy).clamp(0, down.saturating_sub(1) as isize) as usize;
+        self.shown = true;
+    }
+}
+
+/// The cursor cell when a stone may go there, with the same check as a mouse click:
+/// only cells `get_possible_moves` offers, so occupied and forbidden ones are refused
+pub fn cursor_placement(state: &GameState, cursor: &BoardCursor) -> Option<(usize, usize)> {
+    let cell = (cursor.x, cursor.y);
+    (state.board.is_empty_position(cell.0, cell.1) && state.get_possible_moves().contains(&cell)).then_some(cell)
+}
+
+pub fn spawn_cursor_marker(builder: &mut ChildSpawnerCommands) {
+    builder.spawn((
+        Node {
+            border: UiRect::all(Val::Px(BoardUtils::MARKER_THICKNESS)),
+            ..BoardUtils::stone_node(0, 0, BoardUtils::MARKER_SIZE)
+        },
+        BorderColor(CURSOR_COLOR),
+        BorderRadius::all(Val::Px(4.0)),
+        ZIndex(23),
+        Visibility::Hidden,
+        CursorMarker,
+    ));
+}
+
+/// Every game starts with the cursor in the middle of the board
+pub fn center_board_cursor(game_state: Res<GameState>, mut cursor: ResMut<BoardCursor>) {
+    *cursor = BoardCursor::centered_on(&game_state.board);
+}
+
+pub fn move_board_cursor(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_state: Res<GameState>,
+    mut cursor: ResMut<BoardCursor>,
+) {
+    for (key, dx, dy) in [
+        (KeyCode::ArrowLeft, -1, 0),
+        (KeyCode::ArrowRight, 1, 0),
+        (KeyCode::ArrowUp, 0, -1),
+        (KeyCode::ArrowDown, 0, 1),
+    ] {
+        if keyboard_input.just_pressed(key) {
+            cursor.step_rect(dx, dy, game_state.board.rows, game_state.board.cols);
+        }
+    }
+}
+
+/// Enter or Space plays on the cursor through the same `StonePlacement` event as a click.
+/// A refused cell flashes the cursor red with a low buzz.
+pub fn place_at_cursor(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    (game_state, game_status, settings): (Res<GameState>, Res<GameStatus>, Res<GameSettings>),
+    (mut cursor, mut pending_blunder): (ResMut<BoardCursor>, ResMut<PendingBlunder>),
+    mut stone_placement: EventWriter<StonePlacement>,
+    markers: Query<Entity, With<CursorMarker>>,
+    mut pitches: ResMut<Assets<Pitch>>,
+) {
+    if !keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space])
+        || !matches!(*game_status, GameStatus::AwaitingUserInput)
+    {
+        return;
+    }
+    cursor.shown = true;
+
+    if let Some(cell) = cursor_placement(&game_state, &cursor) {
+        submit_human_move(cell, (&game_state, &settings), &mut pending_blunder, &mut stone_placement);
+        return;
+    }
+
+    for entity in &markers {
+        commands
+            .entity(entity)
+            .insert(CursorRejection(Timer::from_seconds(REJECT_FLASH_SECS, TimerMode::Once)));
+    }
+    if let Some(volume) = scaled_volume(&settings, REJECT_VOLUME) {
+        commands.spawn((
+            AudioPlayer(pitches.add(Pitch::new(REJECT_FREQUENCY, REJECT_DURATION))),
+            PlaybackSettings {
+                volume,
+                ..PlaybackSettings::DESPAWN
+            },
+        ));
+    }
+}
+
+/// Follows the cursor, fading from red back to blue after a refused placement
+pub fn update_cursor_marker(
+    time: Res<Time>,
+    cursor: Res<BoardCursor>,
+    mut markers: Query<(&mut Node, &mut Visibility, &mut BorderColor), With<CursorMarker>>,
+    mut rejections: Query<&mut CursorRejection>,
+) {
+    let rejected = rejections
+        .iter_mut()
+        .map(|mut rejection| {
+            rejection.0.tick(time.delta());
+            1.0 - rejection.0.fraction()
+        })
+        .fold(0.0, f32::max);
+
+    for (mut node, mut visibility, mut border) in &mut markers {
+        let target = BoardUtils::stone_node(cursor.x, cursor.y, BoardUtils::MARKER_SIZE);
+        node.left = target.left;
+        node.top = target.top;
+        *visibility = if cursor.shown { Visibility::Visible } else { Visibility::Hidden };
+        *border = CURSOR_COLOR.mix(&CURSOR_REJECTED_COLOR, rejected).into();
+    }
+}
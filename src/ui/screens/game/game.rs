@@ -1,33 +1,56 @@
 use std::time::Duration;
 
-use bevy::prelude::*;
-use crate::{ai::lazy_smp::lazy_smp_search, core::{board::Player, state::GameState}, ui::{app::{AppState, GameSettings}, screens::{game::{board::{BoardRoot, BoardUtils, PreviewDot}, settings::spawn_settings_panel}, utils::despawn_screen}}};
+use bevy::{audio::Pitch, prelude::*, window::WindowResized};
+use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
+use crate::{ai::{commentary::describe_move, transposition::TranspositionTable, difficulty::{pick_near_equal_move, EASY_SCORE_MARGIN}, difficulty_params, heuristic::Heuristic, lazy_smp::{lazy_smp_search_with_history, SearchConfig}, move_ordering::MoveHistory, ponder::Ponderer}, core::{board::Player, rules::{OpeningPhase, Swap2Option}, state::GameState}, interface::broadcast::{broadcast_game_changes, Broadcaster}, ui::{app::{AppState, GameSettings}, saves::SavedGameToResume, screens::{game::{animation::{animate_stones, StoneAppear, StoneCapture}, ai_turn::{spawn_thinking_indicator, update_thinking_indicator, AiMove, AiTurn}, analysis::{analysis_button_action, poll_analysis, restart_analysis, spawn_analysis_button, spawn_analysis_panel, update_analysis_panel, AnalysisMode}, blunder::{blunder_dialog_action, clear_pending_blunder, submit_human_move, update_blunder_dialog, PendingBlunder}, clock::{add_clock_increment, tick_game_clock, update_clock_text, ClockText, GameClock}, cursor::{center_board_cursor, move_board_cursor, place_at_cursor, update_cursor_marker, BoardCursor}, board::{update_last_move_marker, update_move_numbers, BoardRoot, BoardUtils, LastMove, PreviewDot, StoneStyle}, game_over::{game_over_action, spawn_game_over_overlay, GameOverOverlay}, hint::{clear_hint, clear_winning_moves, hint_button_action, spawn_hint_button, update_hint_marker, update_winning_markers, Hint, WinningMoves}, hover::{update_capture_preview, update_hover_preview}, layout::{fit_board_to_window, reset_ui_scale}, opening::{opening_button_action, spawn_opening_button}, takeback::{spawn_takeback_button, takeback_button_action}, resign::{game_end_button_action, spawn_game_end_buttons, DrawOffer}, restart::{restart_button_action, spawn_restart_button}, save::{reset_save_label, save_button_action, spawn_save_button}, settings::spawn_settings_panel, sound::{play_stone_sounds, scaled_volume}}, utils::despawn_screen}}};
 
 // Game status resource
 #[derive(Resource, Default)]
 pub enum GameStatus {
     #[default]
     AwaitingUserInput,
+    /// The AI's search is running, human input is ignored until it plays
+    AiThinking,
     Paused,
     GameOver,
 }
 
+/// Background search started after the AI's move, resolved when the human answers
+#[derive(Resource, Default)]
+pub struct PonderState(pub Option<Ponderer>);
+
 #[derive(Component)]
 pub struct OnGameScreen;
 #[derive(Component)]
-pub struct Stone(Player);
+pub struct Stone(pub Player);
 #[derive(Component)]
 pub struct AvailableArea;
 #[derive(Event)]
 pub struct StonePlacement {
-    x: usize,
-    y: usize,
+    pub x: usize,
+    pub y: usize,
 }
 #[derive(Event)]
 pub struct MovePlayed;
+/// Fired when moves are taken back or replayed, so the stones are redrawn from the state
+#[derive(Event)]
+pub struct BoardRewound;
+/// Fired when a move raises a player's capture count, in pairs
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureProgressEvent {
+    pub player: Player,
+    pub new_count: usize,
+    pub needed: usize,
+}
+/// Fired for every stone put on the board, with the pairs it captured
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StonePlaced {
+    pub player: Player,
+    pub pairs_captured: usize,
+}
 #[derive(Event)]
 pub struct GameEnded {
-    winner: Option<Player>,
+    pub winner: Option<Player>,
 }
 #[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct GridCell {
@@ -37,30 +60,174 @@ pub struct GridCell {
 
 pub fn game_plugin(app: &mut App) {
     app.init_resource::<GameStatus>()
+        .init_resource::<PonderState>()
+        .init_resource::<AiTurn>()
+        .init_resource::<MoveHistory>()
+        .init_resource::<Broadcaster>()
+        .init_resource::<LastMove>()
+        .init_resource::<Hint>()
+        .init_resource::<WinningMoves>()
+        .init_resource::<DrawOffer>()
+        .init_resource::<GameClock>()
+        .init_resource::<AnalysisMode>()
+        .init_resource::<BoardCursor>()
+        .init_resource::<PendingBlunder>()
+        .init_resource::<SavedGameToResume>()
         .init_resource::<AITimeTaken>()
         .init_resource::<AIDepthReached>()
+        .init_resource::<AIEvaluation>()
+        // Both come with the window and UI plugins, registered here too for headless apps
+        .init_resource::<UiScale>()
+        .add_event::<WindowResized>()
         .add_event::<GameEnded>()
         .add_event::<StonePlacement>()
         .add_event::<MovePlayed>()
+        .add_event::<BoardRewound>()
+        .add_event::<CaptureProgressEvent>()
+        .add_event::<StonePlaced>()
         .add_event::<UpdateAITimeDisplay>()
         .add_event::<UpdateAIDepthDisplay>()
-        .add_systems(OnEnter(AppState::Game), (setup_game_ui, update_available_placement).chain())
+        .add_event::<UpdateAIEvalDisplay>()
+        .add_systems(OnEnter(AppState::Game), (reset_game_state, center_board_cursor, setup_game_ui, sync_stones, update_available_placement, fit_board_to_window).chain())
         .add_systems(
             Update,
             (
                 handle_player_placement,
                 place_stone.run_if(on_event::<StonePlacement>),
                 process_next_round.run_if(on_event::<MovePlayed>),
-                update_available_placement.run_if(on_event::<MovePlayed>),
+                update_available_placement.run_if(on_event::<MovePlayed>).after(process_next_round),
                 toggle_pause,
-                update_ai_time_display.run_if(on_event::<UpdateAITimeDisplay>),
-                update_ai_depth_display.run_if(on_event::<UpdateAIDepthDisplay>),
+                fit_board_to_window.run_if(on_event::<WindowResized>),
+                handle_swap2_choice,
+                handle_undo_redo,
+                sync_stones.run_if(on_event::<BoardRewound>).after(handle_undo_redo),
+                update_move_numbers.run_if(on_event::<MovePlayed>).after(place_stone).after(sync_stones),
+                style_stones.after(place_stone).after(sync_stones),
+                (
+                    poll_ai_turn.after(handle_player_placement).after(place_at_cursor).before(place_stone),
+                    update_thinking_indicator,
+                    update_ai_time_display.run_if(on_event::<UpdateAITimeDisplay>),
+                    update_ai_depth_display.run_if(on_event::<UpdateAIDepthDisplay>),
+                    update_ai_eval_display.run_if(on_event::<UpdateAIEvalDisplay>),
+                ),
+                capture_progress_feedback.run_if(on_event::<CaptureProgressEvent>),
+                (fade_capture_flash, animate_stones.after(place_stone).after(style_stones)),
+                play_stone_sounds.run_if(on_event::<StonePlaced>),
+                (
+                    update_last_move_marker.run_if(resource_changed::<LastMove>),
+                    move_board_cursor,
+                    place_at_cursor.after(move_board_cursor).before(place_stone),
+                    update_cursor_marker,
+                    update_hover_preview,
+                    update_capture_preview,
+                    clear_pending_blunder.run_if(on_event::<BoardRewound>).after(handle_undo_redo),
+                    blunder_dialog_action.before(place_stone),
+                    update_blunder_dialog.run_if(resource_changed::<PendingBlunder>),
+                ),
+                (
+                    hint_button_action,
+                    clear_hint.run_if(on_event::<MovePlayed>),
+                    update_hint_marker.run_if(resource_changed::<Hint>),
+                    (clear_winning_moves.run_if(on_event::<MovePlayed>), update_winning_markers.run_if(resource_changed::<WinningMoves>)).chain(),
+                    analysis_button_action,
+                    restart_analysis.run_if(on_event::<MovePlayed>).after(place_stone).after(sync_stones),
+                    poll_analysis,
+                    update_analysis_panel.run_if(resource_changed::<AnalysisMode>),
+                ),
+                (update_game_hud, broadcast_game_changes).run_if(resource_changed::<GameState>),
+                (
+                    tick_game_clock.before(place_stone),
+                    add_clock_increment.run_if(on_event::<StonePlaced>),
+                    update_clock_text.run_if(resource_changed::<GameClock>),
+                ),
+                (
+                    save_button_action,
+                    restart_button_action,
+                    opening_button_action.before(sync_stones),
+                    takeback_button_action.before(sync_stones),
+                    reset_save_label.run_if(on_event::<MovePlayed>),
+                    game_end_button_action,
+                    spawn_game_over_overlay.run_if(on_event::<GameEnded>).after(process_next_round),
+                    game_over_action,
+                ),
             ).run_if(in_state(AppState::Game)),
         )
-        .add_systems(OnExit(AppState::Game), despawn_screen::<OnGameScreen>);
+        // Rematch and Restart set the game state again, which only runs the identity transition
+        .add_systems(
+            OnTransition { exited: AppState::Game, entered: AppState::Game },
+            (
+                despawn_screen::<OnGameScreen>,
+                despawn_screen::<GameOverOverlay>,
+                reset_game_state,
+                center_board_cursor,
+                setup_game_ui,
+                update_available_placement,
+                fit_board_to_window,
+            ).chain(),
+        )
+        .add_systems(
+            OnExit(AppState::Game),
+            (stop_background_search, despawn_screen::<OnGameScreen>, despawn_screen::<GameOverOverlay>, reset_ui_scale),
+        );
+}
+
+/// Dropping the ponderer or analyzer raises its stop flag and waits for the search thread to return.
+/// An AI move still being searched is dropped without waiting.
+fn stop_background_search(mut ponder: ResMut<PonderState>, mut analysis: ResMut<AnalysisMode>, mut ai_turn: ResMut<AiTurn>) {
+    ponder.0 = None;
+    analysis.analyzer = None;
+    ai_turn.cancel();
+}
+
+/// Every game starts from the board size and rule preset currently in the settings, or
+/// from the save picked on the resume screen
+fn reset_game_state(
+    mut game_state: ResMut<GameState>,
+    (game_settings, mut resume, mut move_played): (Res<GameSettings>, ResMut<SavedGameToResume>, EventWriter<MovePlayed>),
+    mut ponder: ResMut<PonderState>,
+    mut game_status: ResMut<GameStatus>,
+    (mut last_move, mut hint, mut draw_offer, mut clock): (ResMut<LastMove>, ResMut<Hint>, ResMut<DrawOffer>, ResMut<GameClock>),
+    (mut analysis, mut pending_blunder, mut tt, mut winning_moves): (ResMut<AnalysisMode>, ResMut<PendingBlunder>, ResMut<TranspositionTable>, ResMut<WinningMoves>),
+    (mut ai_turn, mut history): (ResMut<AiTurn>, ResMut<MoveHistory>),
+) {
+    ponder.0 = None;
+    ai_turn.cancel();
+    let resumed = resume.0.take();
+    let is_resumed = resumed.is_some();
+    let new_state = match resumed {
+        Some(state) => Ok(state),
+        None => game_settings.new_game_state(),
+    };
+    *game_state = match new_state {
+        Ok(state) => state,
+        Err(err) => {
+            error!("Cannot start a game with these settings: {}", err);
+            return;
+        }
+    };
+    // Entries from the last game may come from another board size or rule set
+    tt.clear();
+    history.clear();
+    *clock = GameClock::from_settings(&game_settings);
+    // Analysis stays on across games, the new position is analysed from its first move
+    analysis.analyzer = None;
+    analysis.latest = None;
+    *last_move = LastMove(game_state.moves.last().map(|&(x, y, _)| (x, y)));
+    *hint = Hint::default();
+    *winning_moves = WinningMoves::default();
+    *draw_offer = DrawOffer::default();
+    *pending_blunder = PendingBlunder::default();
+    *game_status = GameStatus::AwaitingUserInput;
+    if is_resumed {
+        // Hands the turn to the AI if it was to move, or ends a game saved on its last move
+        move_played.write(MovePlayed);
+        info!("Resumed game at move {}", game_state.moves.len());
+    } else {
+        info!("New game with {} rules", game_settings.rule_preset.name());
+    }
 }
 
-fn setup_game_ui(mut commands: Commands, game_settings: Res<GameSettings>) {
+fn setup_game_ui(mut commands: Commands, game_settings: Res<GameSettings>, game_state: Res<GameState>) {
     commands
         .spawn((
             Node {
@@ -77,6 +244,7 @@ fn setup_game_ui(mut commands: Commands, game_settings: Res<GameSettings>) {
             OnGameScreen,
         ))
         .with_children(|builder| {
+            spawn_analysis_panel(builder);
             builder.spawn((
                 Node {
                     display: Display::Flex,
@@ -86,7 +254,24 @@ fn setup_game_ui(mut commands: Commands, game_settings: Res<GameSettings>) {
                     ..default()
                 },
             )).with_children(|builder| {
-                BoardUtils::spawn_board(builder, &game_settings);
+                spawn_game_hud(builder);
+                BoardUtils::spawn_board(builder, &game_state.board, &game_settings);
+                builder
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(12.0),
+                        ..default()
+                    })
+                    .with_children(|builder| {
+                        spawn_hint_button(builder);
+                        spawn_analysis_button(builder);
+                        spawn_save_button(builder);
+                        spawn_restart_button(builder);
+                        spawn_opening_button(builder);
+                        spawn_takeback_button(builder);
+                        spawn_game_end_buttons(builder);
+                        spawn_thinking_indicator(builder);
+                    });
             });
             spawn_settings_panel(builder, &game_settings);
         });
@@ -96,7 +281,7 @@ pub fn update_available_placement(
     mut commands: Commands,
     mut ev_board_update: EventReader<MovePlayed>,
     game_state: Res<GameState>,
-    parents: Query<(Entity, &Children, &GridCell), With<GridCell>>,
+    parents: Query<(Entity, Option<&Children>, &GridCell), With<GridCell>>,
     mut dots: Query<(&mut BackgroundColor, &mut Visibility), With<PreviewDot>>,
 ) {
     // Consume events
@@ -105,20 +290,21 @@ pub fn update_available_placement(
     let possible_moves = game_state.get_possible_moves();
     info!("Updating stone preview...");
     for (entity, children, cell) in parents.iter() {
-        if possible_moves.contains(&(cell.x, cell.y)) {
-            for &child in children {
-                if let Ok((mut bg, mut visibility)) = dots.get_mut(child) {
+        let available = possible_moves.contains(&(cell.x, cell.y));
+        // Large boards are rendered without preview dots, the cell stays clickable
+        if available {
+            commands.entity(entity).insert(AvailableArea);
+        } else {
+            commands.entity(entity).remove::<AvailableArea>();
+        }
+        for &child in children.into_iter().flatten() {
+            if let Ok((mut bg, mut visibility)) = dots.get_mut(child) {
+                if available {
                     *bg = BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.4));
                     *visibility = Visibility::Visible;
-                    commands.entity(entity).insert(AvailableArea);
-                }
-            }
-        } else {
-            for &child in children {
-                if let Ok((mut bg, mut visibility)) = dots.get_mut(child) {
+                } else {
                     *bg = BackgroundColor(Color::NONE);
                     *visibility = Visibility::Hidden;
-                    commands.entity(entity).remove::<AvailableArea>();
                 }
             }
         }
@@ -130,46 +316,159 @@ pub fn place_stone(
     board_query: Query<Entity, With<BoardRoot>>,
     mut game_state: ResMut<GameState>,
     mut ev_stone_placement: EventReader<StonePlacement>,
-    mut move_played: EventWriter<MovePlayed>,
+    (mut move_played, mut capture_progress, mut stone_placed): (
+        EventWriter<MovePlayed>,
+        EventWriter<CaptureProgressEvent>,
+        EventWriter<StonePlaced>,
+    ),
+    mut last_move: ResMut<LastMove>,
     stones: Query<(Entity, &GridCell, &Stone)>,
 ) {
     for ev in ev_stone_placement.read() {
         info!("Stone placed at x: {}, y: {}", ev.x, ev.y);
+        let mover = game_state.current_player;
+        let captures_of = |state: &GameState| match mover {
+            Player::Max => state.max_captures,
+            Player::Min => state.min_captures,
+        };
+        let captures_before = captures_of(&game_state);
+        let board_before = game_state.board.clone();
         game_state.make_move((ev.x, ev.y));
+        // Read back from the move stack so the marker always shows its top
+        last_move.0 = game_state.moves.last().map(|&(x, y, _)| (x, y));
 
-        let player = game_state.current_player;
-        let color = match player {
-            Player::Min => Color::BLACK,
-            Player::Max => Color::WHITE,
-        };
+        let new_count = captures_of(&game_state);
+        stone_placed.write(StonePlaced {
+            player: mover,
+            pairs_captured: new_count.saturating_sub(captures_before),
+        });
+        if new_count > captures_before {
+            capture_progress.write(CaptureProgressEvent {
+                player: mover,
+                new_count,
+                needed: game_state.capture_win_pairs,
+            });
+        }
 
-        // Despawn captured stones
-        //let captured_positions = game_state.get_captured_positions().unwrap_or_default();
+        // Fade out captured stones, `animate_stones` despawns them
+        let captured = board_before.diff(&game_state.board).removed;
         for (stone_entity, stone_cell, _) in stones.iter() {
-            if game_state.board.is_empty_position(stone_cell.x, stone_cell.y) {
+            if captured.iter().any(|&(x, y, _)| (x, y) == (stone_cell.x, stone_cell.y)) {
                 info!("Despawning captured stone at x: {}, y: {}", stone_cell.x, stone_cell.y);
-                commands.entity(stone_entity).despawn();
+                commands
+                    .entity(stone_entity)
+                    .remove::<(Stone, StoneAppear)>()
+                    .insert(StoneCapture::new());
             }
         }
 
         // Spawn new stone
         if let Ok(board_entity) = board_query.single() {
             commands.entity(board_entity).with_children(|builder| {
-                builder.spawn((
-                    BoardUtils::stone_node(ev.x, ev.y, BoardUtils::STONE_SIZE),
-                    BackgroundColor(color),
-                    Stone(player),
-                    BorderRadius::all(Val::Percent(50.0)),
-                    ZIndex(20),
-                    OnGameScreen,
-                    GridCell { x: ev.x, y: ev.y },
-                ));
+                builder.spawn((stone_bundle(ev.x, ev.y, mover), StoneAppear::new()));
             });
         }
         move_played.write(MovePlayed);
     }
 }
 
+/// Stone in the regular colours, restyled by `style_stones` in colorblind mode
+pub fn stone_bundle(x: usize, y: usize, owner: Player) -> impl Bundle {
+    let style = StoneStyle::for_player(owner, false);
+    (
+        BoardUtils::stone_node(x, y, BoardUtils::STONE_SIZE),
+        BackgroundColor(style.fill),
+        BorderColor(style.border),
+        Stone(owner),
+        BorderRadius::all(Val::Percent(style.corner_radius)),
+        ZIndex(20),
+        OnGameScreen,
+        GridCell { x, y },
+    )
+}
+
+/// Ctrl+Z takes back moves and Ctrl+Y replays them. Against the AI both stop on the
+/// human's turn, so its reply is undone (or redone) along with the human's move.
+pub fn handle_undo_redo(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    mut game_state: ResMut<GameState>,
+    mut ponder: ResMut<PonderState>,
+    mut last_move: ResMut<LastMove>,
+    (mut move_played, mut board_rewound): (EventWriter<MovePlayed>, EventWriter<BoardRewound>),
+    ai_turn: Res<AiTurn>,
+) {
+    // The AI's move would land on a board it was not searched for
+    if ai_turn.is_thinking() {
+        return;
+    }
+    let ctrl = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let step: fn(&mut GameState) -> Option<(usize, usize)> = if ctrl && keyboard_input.just_pressed(KeyCode::KeyZ) {
+        GameState::undo_last_move
+    } else if ctrl && keyboard_input.just_pressed(KeyCode::KeyY) {
+        GameState::redo_move
+    } else {
+        return;
+    };
+
+    if step(&mut game_state).is_none() {
+        return;
+    }
+    if settings.versus_ai && !game_state.opener_to_act() {
+        step(&mut game_state);
+    }
+
+    ponder.0 = None;
+    last_move.0 = game_state.moves.last().map(|&(x, y, _)| (x, y));
+    board_rewound.write(BoardRewound);
+    move_played.write(MovePlayed);
+}
+
+/// Rebuilds the stones from the game state after an undo or redo
+pub fn sync_stones(
+    mut commands: Commands,
+    board_query: Query<Entity, With<BoardRoot>>,
+    game_state: Res<GameState>,
+    stones: Query<Entity, With<Stone>>,
+    overlays: Query<Entity, With<GameOverOverlay>>,
+) {
+    for entity in stones.iter().chain(overlays.iter()) {
+        commands.entity(entity).despawn();
+    }
+
+    let Ok(board_entity) = board_query.single() else {
+        return;
+    };
+    let board = &game_state.board;
+    commands.entity(board_entity).with_children(|builder| {
+        for x in 0..board.rows {
+            for y in 0..board.cols {
+                if let Some(owner) = board.get_player(x, y) {
+                    builder.spawn(stone_bundle(x, y, owner));
+                }
+            }
+        }
+    });
+}
+
+/// Applies the stone style for the colorblind setting to new stones, and to every stone
+/// when the setting changes
+pub fn style_stones(
+    settings: Res<GameSettings>,
+    mut stones: Query<(Ref<Stone>, &mut Node, &mut BackgroundColor, &mut BorderColor, &mut BorderRadius)>,
+) {
+    for (stone, mut node, mut fill, mut border, mut radius) in &mut stones {
+        if !settings.is_changed() && !stone.is_added() {
+            continue;
+        }
+        let style = StoneStyle::for_player(stone.0, settings.colorblind_mode);
+        node.border = UiRect::all(Val::Px(style.border_width));
+        fill.0 = style.fill;
+        border.0 = style.border;
+        *radius = BorderRadius::all(Val::Percent(style.corner_radius));
+    }
+}
+
 pub fn handle_player_placement(
     mut stone_placement: EventWriter<StonePlacement>,
     buttons: Res<ButtonInput<MouseButton>>,
@@ -179,16 +478,19 @@ pub fn handle_player_placement(
     >,
     game_state: ResMut<GameState>,
     game_status: Res<GameStatus>,
+    (settings, mut pending_blunder): (Res<GameSettings>, ResMut<PendingBlunder>),
 ) {
     if matches!(*game_status, GameStatus::AwaitingUserInput) && buttons.just_pressed(MouseButton::Left) {
         for (interaction, cell) in interaction_query.iter_mut() {
             if *interaction == Interaction::Pressed
                 && game_state.board.get_player(cell.x, cell.y).is_none()
             {
-                stone_placement.write(StonePlacement {
-                    x: cell.x,
-                    y: cell.y,
-                });
+                submit_human_move(
+                    (cell.x, cell.y),
+                    (&game_state, &settings),
+                    &mut pending_blunder,
+                    &mut stone_placement,
+                );
             }
         }
     }
@@ -196,15 +498,12 @@ pub fn handle_player_placement(
 
 pub fn process_next_round(
     mut move_played: EventReader<MovePlayed>,
-    mut stone_placement: EventWriter<StonePlacement>,
     mut game_event: EventWriter<GameEnded>,
     settings: Res<GameSettings>,
     mut game_state: ResMut<GameState>,
     mut game_status: ResMut<GameStatus>,
-    mut ai_time: ResMut<AITimeTaken>,
-    mut ai_depth: ResMut<AIDepthReached>,
-    mut update_ai_time: EventWriter<UpdateAITimeDisplay>,
-    mut update_ai_depth: EventWriter<UpdateAIDepthDisplay>,
+    (mut ponder, mut ai_turn, clock): (ResMut<PonderState>, ResMut<AiTurn>, Res<GameClock>),
+    history: Res<MoveHistory>,
 ) {
     for _ in move_played.read() {
         // Check for game end first
@@ -212,50 +511,185 @@ pub fn process_next_round(
             let winner = game_state.check_winner();
             game_event.write(GameEnded { winner });
             *game_status = GameStatus::GameOver;
+            ponder.0 = None;
             
             match winner {
-                Some(player) => println!("Game Over! Winner: {:?}", player),
-                None => println!("Game Over! It's a draw."),
+                Some(player) => info!("Game Over! Winner: {:?}", player),
+                None => info!("Game Over! It's a draw."),
             }
             return;
         }
 
+        // The AI is always the second player, so it makes the first Swap2 choice
+        if game_state.opening == OpeningPhase::Swap2Choice {
+            if settings.versus_ai && !game_state.opener_to_act() {
+                let option = choose_swap2_color(&game_state);
+                info!("AI Swap2 choice: {:?}", option);
+                if let Err(e) = game_state.apply_swap2_choice(option) {
+                    warn!("Invalid Swap2 choice: {}", e);
+                }
+            } else {
+                info!("Swap2: press B to play black, W to play white, or T to place two more stones");
+                *game_status = GameStatus::AwaitingUserInput;
+                return;
+            }
+        }
+
         // Handle next player's turn
-        if game_state.current_player == Player::Max || (game_state.current_player == Player::Min && !settings.versus_ai) {
+        if !settings.versus_ai || game_state.opener_to_act() {
             info!("Awaiting user click");
             *game_status = GameStatus::AwaitingUserInput;
         } else if settings.versus_ai {
-            // AI's turn
+            // AI's turn, searched off the main thread and played by `poll_ai_turn`
             
             if !game_state.is_terminal() {
-                let placement = if let Some(time_limit_ms) = settings.time_limit {
-                    let time_limit = Duration::from_millis(time_limit_ms as u64);
-                    info!("AI using Lazy SMP search with {}ms limit", time_limit_ms);
-                    lazy_smp_search(&mut game_state, settings.ai_depth, Some(time_limit), None)
+                let (_, time_limit, advanced_patterns) = difficulty_params(settings.ai_difficulty);
+                let max_depth = settings.ai_search_depth();
+                info!(
+                    "AI ({}, {} engine) using Lazy SMP search to depth {} with {}ms limit",
+                    settings.ai_difficulty,
+                    settings.engine.name(),
+                    max_depth,
+                    time_limit.as_millis()
+                );
+                // On a clock the AI also has to leave itself time for the rest of the game
+                let time_limit = if clock.enabled {
+                    Some(time_limit.min(clock.move_budget(game_state.current_player)))
                 } else {
-                    info!("AI using Lazy SMP search to depth {}", settings.ai_depth);
-                    lazy_smp_search(&mut game_state, settings.ai_depth, None, None)
+                    Some(time_limit)
                 };
-                ai_time.micros = placement.time_elapsed.as_micros();
-                ai_depth.depth = placement.depth_reached;
-                update_ai_time.write(UpdateAITimeDisplay);
-                update_ai_depth.write(UpdateAIDepthDisplay);
-
-                if let Some((x, y)) = placement.best_move {
-                    stone_placement.write(StonePlacement { x, y });
-                    *game_status = GameStatus::AwaitingUserInput;
-                } else {
-                    // AI has no moves but game isn't terminal - this shouldn't happen
-                    // But if it does, it means the game is likely a draw
-                    println!("AI has no valid moves available");
-                    game_event.write(GameEnded { winner: None });
-                    *game_status = GameStatus::GameOver;
-                }
+                let config = settings.engine.configure(SearchConfig {
+                    seed: Some(settings.seed),
+                    tt_size_mb: settings.tt_size_mb,
+                    advanced_patterns,
+                    ..SearchConfig::new(max_depth, time_limit)
+                });
+                // Easy plays any move about as good as the best one, seeded for replays
+                let easy_seed = (settings.ai_difficulty == "easy").then(|| settings.seed ^ game_state.moves.len() as u64);
+                let ponderer = ponder.0.take();
+                let mut history = history.clone();
+                ai_turn.start(&game_state, move |mut state, stop| {
+                    let pondered = match (ponderer, state.moves.last()) {
+                        (Some(ponderer), Some(&(row, col, _))) => ponderer.finish((row, col), time_limit),
+                        _ => None,
+                    };
+                    let result = match pondered {
+                        Some(result) => {
+                            info!("Ponder hit, reusing background search");
+                            result
+                        }
+                        None => lazy_smp_search_with_history(&mut state, &config, Some(stop), &mut history),
+                    };
+                    let chosen = match easy_seed {
+                        Some(seed) => {
+                            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                            pick_near_equal_move(&mut state, EASY_SCORE_MARGIN, &mut rng).or(result.best_move)
+                        }
+                        None => result.best_move,
+                    };
+                    AiMove { chosen, result, config, history }
+                });
+                *game_status = GameStatus::AiThinking;
             }
         }
     }
 }
 
+/// Plays the AI's move once its search is done. A search outlived by its turn, after a
+/// resign or a flag fall, is dropped.
+pub fn poll_ai_turn(
+    mut ai_turn: ResMut<AiTurn>,
+    (settings, game_state, mut game_status, mut ponder): (Res<GameSettings>, Res<GameState>, ResMut<GameStatus>, ResMut<PonderState>),
+    (mut stone_placement, mut game_event): (EventWriter<StonePlacement>, EventWriter<GameEnded>),
+    (mut ai_time, mut ai_depth, mut ai_eval): (ResMut<AITimeTaken>, ResMut<AIDepthReached>, ResMut<AIEvaluation>),
+    (mut update_ai_time, mut update_ai_depth, mut update_ai_eval): (
+        EventWriter<UpdateAITimeDisplay>,
+        EventWriter<UpdateAIDepthDisplay>,
+        EventWriter<UpdateAIEvalDisplay>,
+    ),
+    mut history: ResMut<MoveHistory>,
+) {
+    if !ai_turn.is_thinking() {
+        return;
+    }
+    if !matches!(*game_status, GameStatus::AiThinking) {
+        ai_turn.cancel();
+        return;
+    }
+    let Some(AiMove { chosen, result: placement, config, history: warmed }) = ai_turn.poll(&game_state) else {
+        return;
+    };
+    *history = warmed;
+
+    ai_time.micros = placement.time_elapsed.as_micros();
+    ai_depth.depth = placement.depth_reached;
+    ai_eval.score = placement.score;
+    ai_eval.depth = placement.depth_reached;
+    update_ai_time.write(UpdateAITimeDisplay);
+    update_ai_depth.write(UpdateAIDepthDisplay);
+    update_ai_eval.write(UpdateAIEvalDisplay);
+
+    if let Some((x, y)) = chosen {
+        info!("AI: {}", describe_move(&game_state, (x, y), game_state.current_player));
+        if settings.ponder
+            && chosen == placement.best_move
+            && let Some(predicted) = placement.ponder_move
+        {
+            let mut after_ai = game_state.clone();
+            after_ai.make_move((x, y));
+            ponder.0 = Ponderer::start(&after_ai, predicted, &config);
+        }
+        stone_placement.write(StonePlacement { x, y });
+        *game_status = GameStatus::AwaitingUserInput;
+    } else {
+        // AI has no moves but game isn't terminal - this shouldn't happen
+        // But if it does, it means the game is likely a draw
+        warn!("AI has no valid moves available");
+        game_event.write(GameEnded { winner: None });
+        *game_status = GameStatus::GameOver;
+    }
+}
+
+/// Takes black if the heuristic favors it, white (and the next move) otherwise
+fn choose_swap2_color(game_state: &GameState) -> Swap2Option {
+    if Heuristic::evaluate(game_state, 0) > 0 {
+        Swap2Option::PlayBlack
+    } else {
+        Swap2Option::PlayWhite
+    }
+}
+
+/// Keyboard answer to a Swap2 choice made by a human
+pub fn handle_swap2_choice(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    mut game_state: ResMut<GameState>,
+    mut move_played: EventWriter<MovePlayed>,
+) {
+    if game_state.opening != OpeningPhase::Swap2Choice || (settings.versus_ai && !game_state.opener_to_act()) {
+        return;
+    }
+
+    let option = if keyboard_input.just_pressed(KeyCode::KeyB) {
+        Swap2Option::PlayBlack
+    } else if keyboard_input.just_pressed(KeyCode::KeyW) {
+        Swap2Option::PlayWhite
+    } else if keyboard_input.just_pressed(KeyCode::KeyT) {
+        Swap2Option::PlaceTwo
+    } else {
+        return;
+    };
+
+    match game_state.apply_swap2_choice(option) {
+        Ok(()) => {
+            info!("Swap2 choice: {:?}", option);
+            // Hand the turn over as if a move had been played
+            move_played.write(MovePlayed);
+        }
+        Err(e) => info!("Swap2 choice refused: {}", e),
+    }
+}
+
 pub fn update_ai_time_display(
     mut query: Query<&mut Text, With<AITimeText>>,
     ai_time: Res<AITimeTaken>,
@@ -283,6 +717,20 @@ pub fn update_ai_depth_display(
     }
 }
 
+pub fn update_ai_eval_display(
+    mut query: Query<&mut Text, With<AIEvalText>>,
+    ai_eval: Res<AIEvaluation>,
+    mut events: EventReader<UpdateAIEvalDisplay>,
+) {
+    for _ in events.read() {
+        let description = Heuristic::score_to_human(ai_eval.score, ai_eval.depth);
+        info!("Updating AI evaluation display: {} ({})", description, ai_eval.score);
+        for mut text in query.iter_mut() {
+            text.0 = description.clone();
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct AITimeText;
 
@@ -305,6 +753,196 @@ pub struct AIDepthReached {
 #[derive(Event)]
 pub struct UpdateAIDepthDisplay;
 
+#[derive(Component)]
+pub struct AIEvalText;
+
+#[derive(Resource, Default)]
+pub struct AIEvaluation {
+    pub score: i32,
+    pub depth: i32,
+}
+
+#[derive(Event)]
+pub struct UpdateAIEvalDisplay;
+
+/// Live game info shown above the board, filled in by `update_game_hud`
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HudText {
+    Captures(Player),
+    Turn,
+    Rules,
+    Stones,
+}
+
+const HUD_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const HUD_WARNING_COLOR: Color = Color::srgb(0.95, 0.3, 0.25);
+
+pub fn spawn_game_hud(builder: &mut ChildSpawnerCommands) {
+    builder
+        .spawn(Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(24.0),
+            ..default()
+        })
+        .with_children(|builder| {
+            for hud in [
+                HudText::Turn,
+                HudText::Captures(Player::Max),
+                HudText::Captures(Player::Min),
+                HudText::Rules,
+                HudText::Stones,
+            ] {
+                builder.spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    TextColor(HUD_TEXT_COLOR),
+                    hud,
+                ));
+            }
+            for player in [Player::Max, Player::Min] {
+                builder.spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    TextColor(HUD_TEXT_COLOR),
+                    ClockText(player),
+                ));
+            }
+        });
+}
+
+/// Text of one HUD entry and whether it should be highlighted
+pub fn hud_text(state: &GameState, hud: HudText) -> (String, bool) {
+    let name = |player: Player| match player {
+        Player::Max => "Black",
+        Player::Min => "White",
+    };
+    match hud {
+        HudText::Turn if state.winner.is_some() || state.ended_early.is_some() => ("Game over".to_string(), false),
+        HudText::Turn => (format!("{} to move", name(state.current_player)), false),
+        HudText::Captures(player) => {
+            let count = match player {
+                Player::Max => state.max_captures,
+                Player::Min => state.min_captures,
+            };
+            (
+                format!("{} captures: {}/{}", name(player), count, state.capture_win_pairs),
+                state.is_about_to_lose_by_capture(player.opponent()),
+            )
+        }
+        HudText::Rules => (
+            format!("{} in a row, {}x{}", state.win_condition, state.board.rows, state.board.cols),
+            false,
+        ),
+        HudText::Stones => {
+            let stats = state.stats();
+            (
+                format!("Move {}, stones {}-{}", stats.move_number, stats.max_stones, stats.min_stones),
+                false,
+            )
+        }
+    }
+}
+
+pub fn update_game_hud(game_state: Res<GameState>, mut texts: Query<(&mut Text, &mut TextColor, &HudText)>) {
+    for (mut text, mut color, hud) in texts.iter_mut() {
+        let (value, warning) = hud_text(&game_state, *hud);
+        text.0 = value;
+        color.0 = if warning { HUD_WARNING_COLOR } else { HUD_TEXT_COLOR };
+    }
+}
+
+#[derive(Component)]
+pub struct CaptureProgressText(pub Player);
+
+#[derive(Component)]
+pub struct CaptureProgressBar(pub Player);
+
+/// Highlight left on a capture bar after a capture, faded back by `fade_capture_flash`
+#[derive(Component)]
+pub struct CaptureFlash {
+    timer: Timer,
+    intensity: f32,
+}
+
+const CAPTURE_BAR_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+
+pub fn capture_progress_feedback(
+    mut commands: Commands,
+    mut events: EventReader<CaptureProgressEvent>,
+    mut texts: Query<(&mut Text, &CaptureProgressText)>,
+    mut bars: Query<(Entity, &mut Node, &mut BackgroundColor, &CaptureProgressBar)>,
+    mut pitches: ResMut<Assets<Pitch>>,
+    settings: Res<GameSettings>,
+) {
+    for ev in events.read() {
+        // 0 on the first capture, 1 on the winning one
+        let urgency = if ev.needed > 1 {
+            (ev.new_count.saturating_sub(1) as f32 / (ev.needed - 1) as f32).min(1.0)
+        } else {
+            1.0
+        };
+        info!("{:?} captures: {}/{}", ev.player, ev.new_count, ev.needed);
+
+        for (mut text, owner) in texts.iter_mut() {
+            if owner.0 == ev.player {
+                text.0 = format!("{}/{}", ev.new_count, ev.needed);
+            }
+        }
+        for (entity, mut node, mut color, owner) in bars.iter_mut() {
+            if owner.0 == ev.player {
+                let progress = (ev.new_count as f32 / ev.needed.max(1) as f32).min(1.0);
+                node.width = Val::Percent(progress * 100.0);
+                let intensity = 0.3 + 0.7 * urgency;
+                *color = BackgroundColor(flash_color(intensity));
+                commands.entity(entity).insert(CaptureFlash {
+                    timer: Timer::from_seconds(intensity, TimerMode::Once),
+                    intensity,
+                });
+            }
+        }
+
+        // Rising pitch and volume as the player closes in on a capture win
+        let frequency = 330.0 * 2.0_f32.powf(urgency * 1.5);
+        if let Some(volume) = scaled_volume(&settings, 0.3 + 0.5 * urgency) {
+            commands.spawn((
+                AudioPlayer(pitches.add(Pitch::new(frequency, Duration::from_millis(120 + (200.0 * urgency) as u64)))),
+                PlaybackSettings {
+                    volume,
+                    ..PlaybackSettings::DESPAWN
+                },
+            ));
+        }
+    }
+}
+
+pub fn fade_capture_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bars: Query<(Entity, &mut BackgroundColor, &mut CaptureFlash)>,
+) {
+    for (entity, mut color, mut flash) in bars.iter_mut() {
+        flash.timer.tick(time.delta());
+        if flash.timer.finished() {
+            *color = BackgroundColor(CAPTURE_BAR_COLOR);
+            commands.entity(entity).remove::<CaptureFlash>();
+        } else {
+            let remaining = 1.0 - flash.timer.fraction();
+            *color = BackgroundColor(flash_color(flash.intensity * remaining));
+        }
+    }
+}
+
+fn flash_color(intensity: f32) -> Color {
+    CAPTURE_BAR_COLOR.mix(&Color::srgb(1.0, 0.2, 0.1), intensity)
+}
+
 
 pub fn toggle_pause(
     mut game_status: ResMut<GameStatus>,
@@ -313,13 +951,14 @@ pub fn toggle_pause(
     if keyboard_input.just_pressed(KeyCode::KeyP) {
         *game_status = match *game_status {
             GameStatus::Paused => {
-                println!("Game unpaused !");
+                info!("Game unpaused !");
                 GameStatus::AwaitingUserInput
             }
             GameStatus::AwaitingUserInput => {
-                println!("Game paused !");
+                info!("Game paused !");
                 GameStatus::Paused
             }
+            GameStatus::AiThinking => GameStatus::AiThinking,
             GameStatus::GameOver => GameStatus::GameOver,
         };
     }
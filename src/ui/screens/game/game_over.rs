@@ -0,0 +1,197 @@
+use bevy::prelude::*;
+
+use crate::{core::{board::Player, state::{GameOutcome, GameState}}, ui::{app::{AppState, GameSettings}, screens::game::{board::BoardUtils, game::GameEnded}}};
+
+const OVERLAY_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const OVERLAY_BUTTON_HOVERED: Color = Color::srgb(0.25, 0.25, 0.25);
+
+#[derive(Component)]
+pub struct GameOverOverlay;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameOverAction {
+    Rematch,
+    MainMenu,
+}
+
+/// Headline and detail line shown when the game ends
+pub fn game_over_message(outcome: GameOutcome) -> (String, String) {
+    match outcome {
+        GameOutcome::FiveInARow(player) => (
+            format!("{} wins!", color_name(player)),
+            "Five in a row".to_string(),
+        ),
+        GameOutcome::Captures(player) => (
+            format!("{} wins!", color_name(player)),
+            "By captures".to_string(),
+        ),
+        GameOutcome::Draw => ("Draw".to_string(), "No legal moves left".to_string()),
+        GameOutcome::Resignation(player) => (
+            format!("{} wins!", color_name(player)),
+            format!("{} resigned", color_name(player.opponent())),
+        ),
+        GameOutcome::Timeout(player) => (
+            format!("{} wins!", color_name(player)),
+            format!("{} ran out of time", color_name(player.opponent())),
+        ),
+        GameOutcome::DrawAgreed => ("Draw".to_string(), "Agreed by both players".to_string()),
+    }
+}
+
+/// Winning moves the losing side had on its last turn and did not play, with that side.
+/// `None` for draws and when the loser never had a win in one.
+pub fn missed_wins(state: &GameState) -> Option<(Player, Vec<(usize, usize)>)> {
+    let outcome = state.outcome()?;
+    let loser = match outcome {
+        GameOutcome::FiveInARow(winner)
+        | GameOutcome::Captures(winner)
+        | GameOutcome::Resignation(winner)
+        | GameOutcome::Timeout(winner) => winner.opponent(),
+        GameOutcome::Draw | GameOutcome::DrawAgreed => return None,
+    };
+    let mut before = state.clone();
+    match outcome {
+        // Back to before the winning move
+        GameOutcome::FiveInARow(_) | GameOutcome::Captures(_) => {
+            before.undo_last_move()?;
+        }
+        _ => {
+            before.ended_early = None;
+            before.winner = None;
+        }
+    }
+    if before.current_player != loser {
+        before.undo_last_move()?;
+    }
+    let wins = before.find_all_winning_moves(loser);
+    (!wins.is_empty()).then_some((loser, wins))
+}
+
+/// "White had a win at K10, L11", for the game-over overlay
+pub fn missed_wins_message(state: &GameState, skip_i: bool) -> Option<String> {
+    let (loser, wins) = missed_wins(state)?;
+    let cells: Vec<_> = wins
+        .into_iter()
+        .map(|cell| BoardUtils::cell_label(&state.board, cell, skip_i))
+        .collect();
+    Some(format!("{} had a win at {}", color_name(loser), cells.join(", ")))
+}
+
+fn color_name(player: Player) -> &'static str {
+    match player {
+        Player::Max => "Black",
+        Player::Min => "White",
+    }
+}
+
+/// Covers the board with the result and the rematch / menu buttons
+pub fn spawn_game_over_overlay(
+    mut commands: Commands,
+    mut game_ended: EventReader<GameEnded>,
+    game_state: Res<GameState>,
+    overlays: Query<Entity, With<GameOverOverlay>>,
+    settings: Option<Res<GameSettings>>,
+) {
+    if game_ended.read().last().is_none() || !overlays.is_empty() {
+        return;
+    }
+    let Some(outcome) = game_state.outcome() else {
+        return;
+    };
+    let (headline, detail) = game_over_message(outcome);
+    let captures = format!(
+        "Captures - Black: {}  White: {}",
+        game_state.max_captures, game_state.min_captures
+    );
+    let skip_i = settings.is_none_or(|settings| settings.coordinates_skip_i);
+    let missed = missed_wins_message(&game_state, skip_i);
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+            GlobalZIndex(100),
+            GameOverOverlay,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new(headline),
+                TextFont {
+                    font_size: 56.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            for line in [Some(detail), Some(captures), missed].into_iter().flatten() {
+                builder.spawn((
+                    Text::new(line),
+                    TextFont {
+                        font_size: 24.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                ));
+            }
+
+            builder
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(20.0),
+                    margin: UiRect::top(Val::Px(20.0)),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    for (label, action) in [("Rematch", GameOverAction::Rematch), ("Main Menu", GameOverAction::MainMenu)] {
+                        builder
+                            .spawn((
+                                Button,
+                                Node {
+                                    width: Val::Px(200.0),
+                                    height: Val::Px(60.0),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                BackgroundColor(OVERLAY_BUTTON),
+                                action,
+                            ))
+                            .with_children(|builder| {
+                                builder.spawn((
+                                    Text::new(label),
+                                    TextFont {
+                                        font_size: 28.0,
+                                        ..default()
+                                    },
+                                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+/// Rematch re-enters the game screen, Main Menu goes back to the menu
+pub fn game_over_action(
+    mut interaction_query: Query<(&Interaction, &GameOverAction, &mut BackgroundColor), Changed<Interaction>>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, action, mut background) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => match action {
+                GameOverAction::Rematch => app_state.set(AppState::Game),
+                GameOverAction::MainMenu => app_state.set(AppState::Menu),
+            },
+            Interaction::Hovered => *background = OVERLAY_BUTTON_HOVERED.into(),
+            Interaction::None => *background = OVERLAY_BUTTON.into(),
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    ai::lazy_smp::{lazy_smp_search_with_config, SearchConfig},
+    core::state::GameState,
+    ui::{app::GameSettings, screens::game::{board::{BoardRoot, BoardUtils}, game::GameStatus}},
+};
+
+/// Hints are quick: a short search is plenty to spot the tactics a casual player misses
+pub const HINT_TIME_LIMIT: Duration = Duration::from_millis(300);
+const HINT_MAX_DEPTH: i32 = 8;
+const HINT_BUTTON: Color = Color::srgb(0.2, 0.2, 0.2);
+const HINT_BUTTON_HOVERED: Color = Color::srgb(0.3, 0.3, 0.3);
+
+/// Move suggested to the human, shown until they play
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hint(pub Option<(usize, usize)>);
+
+#[derive(Component)]
+pub struct HintButton;
+
+/// Dot drawn on the suggested intersection
+#[derive(Component)]
+pub struct HintMarker;
+
+/// Every move that wins at once for the side asking for a hint, shown alongside it
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct WinningMoves(pub Vec<(usize, usize)>);
+
+/// Ring drawn on each of the `WinningMoves`
+#[derive(Component)]
+pub struct WinningMoveMarker;
+
+/// Best move for the side to move after a short search, always one `get_possible_moves`
+/// allows, so forbidden double-threes are never suggested
+pub fn suggest_move(state: &GameState) -> Option<(usize, usize)> {
+    let legal = state.get_possible_moves();
+    let config = SearchConfig::new(HINT_MAX_DEPTH, Some(HINT_TIME_LIMIT));
+    lazy_smp_search_with_config(&mut state.clone(), &config)
+        .best_move
+        .filter(|mv| legal.contains(mv))
+        .or_else(|| legal.first().copied())
+}
+
+pub fn spawn_hint_button(builder: &mut ChildSpawnerCommands) {
+    builder
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(120.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(HINT_BUTTON),
+            BorderRadius::all(Val::Px(6.0)),
+            HintButton,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Hint"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// Same look as the move preview dots, in green so it stands out from them
+pub fn spawn_hint_marker(builder: &mut ChildSpawnerCommands) {
+    builder.spawn((
+        BoardUtils::stone_node(0, 0, BoardUtils::PREVIEW_SIZE),
+        BorderRadius::all(Val::Percent(50.0)),
+        BackgroundColor(Color::srgba(0.2, 0.8, 0.3, 0.9)),
+        ZIndex(22),
+        Visibility::Hidden,
+        HintMarker,
+    ));
+}
+
+/// Asks for a hint when it is the human's turn to place a stone
+pub fn hint_button_action(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &HintButton), Changed<Interaction>>,
+    game_state: Res<GameState>,
+    game_status: Res<GameStatus>,
+    settings: Res<GameSettings>,
+    (mut hint, mut winning_moves): (ResMut<Hint>, ResMut<WinningMoves>),
+) {
+    for (interaction, mut background, _) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                let human_to_move = !settings.versus_ai || game_state.opener_to_act();
+                if matches!(*game_status, GameStatus::AwaitingUserInput) && human_to_move && !game_state.is_terminal() {
+                    hint.0 = suggest_move(&game_state);
+                    winning_moves.0 = game_state.find_all_winning_moves(game_state.current_player);
+                }
+            }
+            Interaction::Hovered => *background = HINT_BUTTON_HOVERED.into(),
+            Interaction::None => *background = HINT_BUTTON.into(),
+        }
+    }
+}
+
+/// Any move, undo or redo makes the suggestion stale
+pub fn clear_hint(mut hint: ResMut<Hint>) {
+    if hint.0.is_some() {
+        hint.0 = None;
+    }
+}
+
+pub fn clear_winning_moves(mut winning_moves: ResMut<WinningMoves>) {
+    if !winning_moves.0.is_empty() {
+        winning_moves.0.clear();
+    }
+}
+
+/// One gold ring per winning move, redrawn whenever the list changes
+pub fn update_winning_markers(
+    mut commands: Commands,
+    winning_moves: Res<WinningMoves>,
+    board_query: Query<Entity, With<BoardRoot>>,
+    markers: Query<Entity, With<WinningMoveMarker>>,
+) {
+    for entity in &markers {
+        commands.entity(entity).despawn();
+    }
+    let Ok(board_entity) = board_query.single() else {
+        return;
+    };
+    commands.entity(board_entity).with_children(|builder| {
+        for &(x, y) in &winning_moves.0 {
+            builder.spawn((
+                Node {
+                    border: UiRect::all(Val::Px(BoardUtils::MARKER_THICKNESS)),
+                    ..BoardUtils::stone_node(x, y, BoardUtils::MARKER_SIZE)
+                },
+                BorderColor(Color::srgb(0.95, 0.75, 0.2)),
+                BorderRadius::all(Val::Percent(50.0)),
+                ZIndex(22),
+                WinningMoveMarker,
+            ));
+        }
+    });
+}
+
+pub fn update_hint_marker(hint: Res<Hint>, mut markers: Query<(&mut Node, &mut Visibility), With<HintMarker>>) {
+    for (mut node, mut visibility) in &mut markers {
+        match hint.0 {
+            Some((x, y)) => {
+                let target = BoardUtils::stone_node(x, y, BoardUtils::PREVIEW_SIZE);
+                node.left = target.left;
+                node.top = target.top;
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
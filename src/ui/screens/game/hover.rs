@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::state::GameState,
+    ui::{
+        app::GameSettings,
+        screens::game::{
+            board::{BoardUtils, StoneStyle},
+            game::{GameStatus, GridCell},
+        },
+    },
+};
+
+/// Opacity of the stone shown under the pointer
+const HOVER_ALPHA: f32 = 0.5;
+/// Shown instead on an empty cell the side to move may not take
+const INVALID_HOVER: Color = Color::srgba(0.9, 0.2, 0.2, HOVER_ALPHA);
+
+/// Ring over each stone the hovered move would capture
+const CAPTURE_RING: Color = Color::srgba(0.95, 0.25, 0.2, 0.9);
+const CAPTURE_RING_WIDTH: f32 = 3.0;
+/// A move takes at most one pair in each of the eight directions
+const MAX_CAPTURE_RINGS: usize = 16;
+
+/// Translucent stone following the pointer over the board
+#[derive(Component)]
+pub struct HoverPreview;
+
+/// Ring marking a stone the hovered move would capture
+#[derive(Component)]
+pub struct CapturePreview;
+
+/// How the hover preview looks over `cell`: the side to move's stone, see-through, where
+/// `get_possible_moves` allows a move, a red disc on other empty cells such as a forbidden
+/// double-three, and nothing over a stone or once the game is over
+pub fn hover_preview_style(state: &GameState, cell: (usize, usize), colorblind: bool) -> Option<StoneStyle> {
+    if state.is_terminal() || state.board.get_player(cell.0, cell.1).is_some() {
+        return None;
+    }
+    if !state.get_possible_moves().contains(&cell) {
+        return Some(StoneStyle {
+            fill: INVALID_HOVER,
+            border: Color::NONE,
+            border_width: 0.0,
+            corner_radius: 50.0,
+        });
+    }
+    let style = StoneStyle::for_player(state.current_player, colorblind);
+    Some(StoneStyle {
+        fill: style.fill.with_alpha(HOVER_ALPHA),
+        border: style.border.with_alpha(style.border.alpha() * HOVER_ALPHA),
+        ..style
+    })
+}
+
+/// Stones the side to move would capture by playing on `cell`, empty where it may not play
+pub fn hover_capture_preview(state: &GameState, cell: (usize, usize)) -> Vec<(usize, usize)> {
+    if state.is_terminal() {
+        return Vec::new();
+    }
+    let captured = state.captures_for_move(cell, state.current_player);
+    if captured.is_empty() || !state.get_possible_moves().contains(&cell) {
+        return Vec::new();
+    }
+    captured
+}
+
+/// Intersection under the pointer while a human is to place a stone
+fn hovered_cell(
+    cells: &Query<(&Interaction, &GridCell)>,
+    game_state: &GameState,
+    game_status: &GameStatus,
+    settings: &GameSettings,
+) -> Option<(usize, usize)> {
+    let human_to_move = !settings.versus_ai || game_state.opener_to_act();
+    if !matches!(game_status, GameStatus::AwaitingUserInput) || !human_to_move {
+        return None;
+    }
+    cells
+        .iter()
+        .find(|(interaction, _)| **interaction != Interaction::None)
+        .map(|(_, cell)| (cell.x, cell.y))
+}
+
+pub fn spawn_hover_preview(builder: &mut ChildSpawnerCommands) {
+    for _ in 0..MAX_CAPTURE_RINGS {
+        builder.spawn((
+            Node {
+                border: UiRect::all(Val::Px(CAPTURE_RING_WIDTH)),
+                ..BoardUtils::stone_node(0, 0, BoardUtils::STONE_SIZE)
+            },
+            BorderColor(CAPTURE_RING),
+            BorderRadius::all(Val::Percent(50.0)),
+            ZIndex(12),
+            Visibility::Hidden,
+            CapturePreview,
+        ));
+    }
+    builder.spawn((
+        BoardUtils::stone_node(0, 0, BoardUtils::STONE_SIZE),
+        BorderColor(Color::NONE),
+        BorderRadius::all(Val::Percent(50.0)),
+        BackgroundColor(Color::NONE),
+        ZIndex(11),
+        Visibility::Hidden,
+        HoverPreview,
+    ));
+}
+
+/// Moves the preview to the hovered intersection while the human is to place a stone
+pub fn update_hover_preview(
+    cells: Query<(&Interaction, &GridCell)>,
+    mut previews: Query<
+        (&mut Node, &mut BackgroundColor, &mut BorderColor, &mut BorderRadius, &mut Visibility),
+        With<HoverPreview>,
+    >,
+    (game_state, game_status, settings): (Res<GameState>, Res<GameStatus>, Res<GameSettings>),
+) {
+    let look = hovered_cell(&cells, &game_state, &game_status, &settings)
+        .and_then(|cell| Some((cell, hover_preview_style(&game_state, cell, settings.colorblind_mode)?)));
+
+    for (mut node, mut fill, mut border, mut radius, mut visibility) in &mut previews {
+        let Some(((x, y), style)) = look else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        let target = BoardUtils::stone_node(x, y, BoardUtils::STONE_SIZE);
+        node.left = target.left;
+        node.top = target.top;
+        node.border = UiRect::all(Val::Px(style.border_width));
+        fill.0 = style.fill;
+        border.0 = style.border;
+        *radius = BorderRadius::all(Val::Percent(style.corner_radius));
+        *visibility = Visibility::Visible;
+    }
+}
+
+/// Rings the stones the hovered move would capture, so the player sees what it takes
+pub fn update_capture_preview(
+    cells: Query<(&Interaction, &GridCell)>,
+    mut rings: Query<(&mut Node, &mut Visibility), With<CapturePreview>>,
+    (game_state, game_status, settings): (Res<GameState>, Res<GameStatus>, Res<GameSettings>),
+) {
+    let captured = hovered_cell(&cells, &game_state, &game_status, &settings)
+        .map(|cell| hover_capture_preview(&game_state, cell))
+        .unwrap_or_default();
+
+    for (index, (mut node, mut visibility)) in rings.iter_mut().enumerate() {
+        let Some(&(x, y)) = captured.get(index) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        let target = BoardUtils::stone_node(x, y, BoardUtils::STONE_SIZE);
+        node.left = target.left;
+        node.top = target.top;
+        visibility.set_if_neq(Visibility::Visible);
+    }
+}
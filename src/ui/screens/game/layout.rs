@@ -0,0 +1,52 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{core::state::GameState, ui::screens::game::board::BoardUtils};
+
+/// Width taken beside the board by the analysis and settings panels and the gaps between them
+pub const SIDE_PANELS_WIDTH: f32 = 470.0;
+/// Height taken above and below the board by the HUD and the button row
+pub const HUD_HEIGHT: f32 = 160.0;
+/// The game screen never shrinks below this, a 19x19 board still fits a 320x240 window
+pub const MIN_UI_SCALE: f32 = 0.25;
+
+/// Size of the board on screen at scale 1, the grid plus the coordinate margin on each side
+pub fn board_footprint(across: usize, down: usize) -> Vec2 {
+    Vec2::new(
+        (across + 2) as f32 * BoardUtils::CELL_SIZE,
+        (down + 2) as f32 * BoardUtils::CELL_SIZE,
+    )
+}
+
+/// Scale at which a board `across` cells wide and `down` tall fits `window` along with the
+/// panels around it. Never enlarges the board, and stops at `MIN_UI_SCALE` for tiny windows.
+pub fn board_fit_scale(window: Vec2, across: usize, down: usize) -> f32 {
+    let needed = board_footprint(across, down) + Vec2::new(SIDE_PANELS_WIDTH, HUD_HEIGHT);
+    let fit = (window.x / needed.x).min(window.y / needed.y);
+    if fit.is_finite() {
+        fit.clamp(MIN_UI_SCALE, 1.0)
+    } else {
+        1.0
+    }
+}
+
+/// Shrinks the game screen when the window is too small for the board. The layout keeps
+/// the board centered, and `UiScale` keeps the cells' hitboxes under the pointer.
+pub fn fit_board_to_window(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    game_state: Res<GameState>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let scale = board_fit_scale(window.size(), game_state.board.rows, game_state.board.cols);
+    if ui_scale.0 != scale {
+        info!("Game screen scaled to {:.2} for a {}x{} window", scale, window.width(), window.height());
+        ui_scale.0 = scale;
+    }
+}
+
+/// Menus are laid out for scale 1
+pub fn reset_ui_scale(mut ui_scale: ResMut<UiScale>) {
+    ui_scale.0 = 1.0;
+}
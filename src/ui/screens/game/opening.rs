@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::state::GameState,
+    ui::screens::game::{
+        ai_turn::AiTurn,
+        board::LastMove,
+        game::{BoardRewound, GameStatus, MovePlayed},
+    },
+};
+
+const OPENING_BUTTON: Color = Color::srgb(0.2, 0.2, 0.2);
+const OPENING_BUTTON_HOVERED: Color = Color::srgb(0.3, 0.3, 0.3);
+
+#[derive(Component)]
+pub struct OpeningButton;
+
+pub fn spawn_opening_button(builder: &mut ChildSpawnerCommands) {
+    builder
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(120.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(OPENING_BUTTON),
+            BorderRadius::all(Val::Px(6.0)),
+            OpeningButton,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Opening"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// Plays a random named opening the rules and opening restriction allow, before the first
+/// move only. The stones are redrawn from the state and the side to move takes over.
+pub fn opening_button_action(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &OpeningButton), Changed<Interaction>>,
+    mut game_state: ResMut<GameState>,
+    (game_status, ai_turn): (Res<GameStatus>, Res<AiTurn>),
+    mut last_move: ResMut<LastMove>,
+    (mut move_played, mut board_rewound): (EventWriter<MovePlayed>, EventWriter<BoardRewound>),
+) {
+    for (interaction, mut background, _) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                if !matches!(*game_status, GameStatus::AwaitingUserInput) || ai_turn.is_thinking() {
+                    continue;
+                }
+                let openings = game_state.available_openings();
+                if openings.is_empty() {
+                    info!("No named opening fits this position");
+                    continue;
+                }
+                let name = openings[rand::random_range(0..openings.len())];
+                if let Err(err) = game_state.apply_named_opening(name) {
+                    warn!("Opening {} refused: {}", name, err);
+                    continue;
+                }
+                info!("Played the {} opening", name);
+                last_move.0 = game_state.moves.last().map(|&(x, y, _)| (x, y));
+                board_rewound.write(BoardRewound);
+                move_played.write(MovePlayed);
+            }
+            Interaction::Hovered => *background = OPENING_BUTTON_HOVERED.into(),
+            Interaction::None => *background = OPENING_BUTTON.into(),
+        }
+    }
+}
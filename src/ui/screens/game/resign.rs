@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use crate::{
+    ai::heuristic::Heuristic,
+    core::{board::Player, state::GameState},
+    ui::{app::GameSettings, screens::game::game::{GameEnded, GameStatus, PonderState}},
+};
+
+const END_BUTTON: Color = Color::srgb(0.2, 0.2, 0.2);
+const END_BUTTON_HOVERED: Color = Color::srgb(0.3, 0.3, 0.3);
+
+/// Standing draw offer: who made it and how many moves had been played then
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrawOffer(pub Option<(Player, usize)>);
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEndButton {
+    Resign,
+    OfferDraw,
+}
+
+/// Records `player`'s draw offer, or agrees to the draw when it answers the opponent's.
+/// An offer lapses once the opponent plays a move instead of answering it.
+/// Returns true when both sides have now agreed.
+pub fn offer_draw(offer: &mut DrawOffer, state: &GameState, player: Player) -> bool {
+    if let Some((by, at_move)) = offer.0
+        && by == player.opponent()
+        && state.moves.len() <= at_move + 1
+    {
+        offer.0 = None;
+        return true;
+    }
+    offer.0 = Some((player, state.moves.len()));
+    false
+}
+
+/// The AI takes a draw unless its evaluation says it is ahead
+pub fn ai_accepts_draw(state: &GameState, ai: Player) -> bool {
+    let score = Heuristic::evaluate(state, 0);
+    match ai {
+        Player::Max => score <= 0,
+        Player::Min => score >= 0,
+    }
+}
+
+pub fn spawn_game_end_buttons(builder: &mut ChildSpawnerCommands) {
+    for (label, action) in [("Resign", GameEndButton::Resign), ("Offer Draw", GameEndButton::OfferDraw)] {
+        builder
+            .spawn((
+                Button,
+                Node {
+                    width: Val::Px(120.0),
+                    height: Val::Px(40.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(END_BUTTON),
+                BorderRadius::all(Val::Px(6.0)),
+                action,
+            ))
+            .with_children(|builder| {
+                builder.spawn((
+                    Text::new(label),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                ));
+            });
+    }
+}
+
+/// Resigns or offers a draw for the human (the side to move in a two-player game),
+/// then hands over to the game-over overlay if the game ended
+pub fn game_end_button_action(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &GameEndButton), Changed<Interaction>>,
+    settings: Res<GameSettings>,
+    mut game_state: ResMut<GameState>,
+    mut game_status: ResMut<GameStatus>,
+    (mut draw_offer, mut ponder): (ResMut<DrawOffer>, ResMut<PonderState>),
+    mut game_ended: EventWriter<GameEnded>,
+) {
+    for (interaction, mut background, action) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                if matches!(*game_status, GameStatus::GameOver) || game_state.outcome().is_some() {
+                    continue;
+                }
+                let player = if settings.versus_ai {
+                    game_state.opener_color()
+                } else {
+                    game_state.current_player
+                };
+
+                let ended = match action {
+                    GameEndButton::Resign => game_state.resign(player),
+                    GameEndButton::OfferDraw if settings.versus_ai => {
+                        let accepted = ai_accepts_draw(&game_state, player.opponent());
+                        info!("AI {} the draw offer", if accepted { "accepts" } else { "declines" });
+                        accepted && game_state.agree_draw()
+                    }
+                    GameEndButton::OfferDraw => {
+                        let agreed = offer_draw(&mut draw_offer, &game_state, player);
+                        if !agreed {
+                            info!("{:?} offers a draw", player);
+                        }
+                        agreed && game_state.agree_draw()
+                    }
+                };
+
+                if ended {
+                    ponder.0 = None;
+                    *game_status = GameStatus::GameOver;
+                    game_ended.write(GameEnded { winner: game_state.winner });
+                }
+            }
+            Interaction::Hovered => *background = END_BUTTON_HOVERED.into(),
+            Interaction::None => *background = END_BUTTON.into(),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+
+use crate::ui::app::AppState;
+
+const RESTART_BUTTON: Color = Color::srgb(0.2, 0.2, 0.2);
+const RESTART_BUTTON_HOVERED: Color = Color::srgb(0.3, 0.3, 0.3);
+
+#[derive(Component)]
+pub struct RestartButton;
+
+pub fn spawn_restart_button(builder: &mut ChildSpawnerCommands) {
+    builder
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(120.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(RESTART_BUTTON),
+            BorderRadius::all(Val::Px(6.0)),
+            RestartButton,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Restart"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// Starts over with the current settings, like Rematch: setting the game state again tears
+/// down the screen with its stones, then resets the game, search table, clock and HUD
+pub fn restart_button_action(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &RestartButton), Changed<Interaction>>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, mut background, _) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                info!("Restarting the game");
+                app_state.set(AppState::Game);
+            }
+            Interaction::Hovered => *background = RESTART_BUTTON_HOVERED.into(),
+            Interaction::None => *background = RESTART_BUTTON.into(),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::state::GameState,
+    ui::{app::GameSettings, saves::{save_game, saves_dir}},
+};
+
+const SAVE_BUTTON: Color = Color::srgb(0.2, 0.2, 0.2);
+const SAVE_BUTTON_HOVERED: Color = Color::srgb(0.3, 0.3, 0.3);
+const SAVE_LABEL: &str = "Save";
+
+#[derive(Component)]
+pub struct SaveGameButton;
+
+pub fn spawn_save_button(builder: &mut ChildSpawnerCommands) {
+    builder
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(120.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(SAVE_BUTTON),
+            BorderRadius::all(Val::Px(6.0)),
+            SaveGameButton,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new(SAVE_LABEL),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// Writes the game to the saves directory, resumed later from the main menu. Finished
+/// games are not saved, the replay viewer is there for those.
+pub fn save_button_action(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &Children, &SaveGameButton), Changed<Interaction>>,
+    mut texts: Query<&mut Text>,
+    game_state: Res<GameState>,
+    settings: Res<GameSettings>,
+) {
+    for (interaction, mut background, children, _) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                let label = if game_state.outcome().is_some() {
+                    "Game over".to_string()
+                } else {
+                    match save_game(&saves_dir(), &game_state, &settings) {
+                        Ok(path) => {
+                            info!("Game saved to {}", path.display());
+                            "Saved".to_string()
+                        }
+                        Err(e) => {
+                            warn!("Could not save the game: {}", e);
+                            "Save failed".to_string()
+                        }
+                    }
+                };
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = label.clone();
+                    }
+                }
+            }
+            Interaction::Hovered => *background = SAVE_BUTTON_HOVERED.into(),
+            Interaction::None => *background = SAVE_BUTTON.into(),
+        }
+    }
+}
+
+/// A new move leaves the last save behind
+pub fn reset_save_label(buttons: Query<&Children, With<SaveGameButton>>, mut texts: Query<&mut Text>) {
+    for children in &buttons {
+        for &child in children {
+            if let Ok(mut text) = texts.get_mut(child)
+                && text.0 != SAVE_LABEL
+            {
+                text.0 = SAVE_LABEL.to_string();
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::ui::{app::GameSettings, screens::game::game::{AITimeText, AIDepthText}};
+use crate::{core::board::Player, ui::{app::GameSettings, screens::game::game::{AITimeText, AIDepthText, AIEvalText, CaptureProgressBar, CaptureProgressText}}};
 
 #[derive(Component)]
 pub struct GameSettingsPanel;
@@ -36,23 +36,48 @@ pub fn spawn_settings_panel(builder: &mut ChildSpawnerCommands, game_settings: &
                 },
             ));
 
+            spawn_setting_row(builder, "Rules", game_settings.rule_preset.name());
+
             spawn_setting_row(builder, "Board Size", &format!("{}x{}", game_settings.board_size, game_settings.board_size));
 
             spawn_setting_row(builder, "Chain to Win", &game_settings.minimum_chain_to_win.to_string());
 
+            if !game_settings.line_win_possible() {
+                builder.spawn((
+                    Text::new("No line fits on this board: only captures or a draw end the game"),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.95, 0.7, 0.2)),
+                    Node {
+                        max_width: Val::Px(250.0),
+                        ..default()
+                    },
+                ));
+            }
+
             spawn_setting_row(builder, "Captures to Win", &game_settings.total_capture_to_win.to_string());
 
+            if game_settings.captures_enabled {
+                spawn_capture_row(builder, "Black Captures", Player::Max);
+                spawn_capture_row(builder, "White Captures", Player::Min);
+            }
+
             let game_mode = if game_settings.versus_ai { "vs AI" } else { "Multiplayer" };
             spawn_setting_row(builder, "Game Mode", game_mode);
 
             if game_settings.versus_ai {
-                spawn_setting_row(builder, "AI Depth", &game_settings.ai_depth.to_string());
+                spawn_setting_row(builder, "Difficulty", game_settings.ai_difficulty);
+                spawn_setting_row(builder, "AI Depth", &game_settings.ai_search_depth().to_string());
+                spawn_setting_row(builder, "Engine", game_settings.engine.name());
                 
                 let alpha_beta = if game_settings.alpha_beta_enabled { "Enabled" } else { "Disabled" };
                 spawn_setting_row(builder, "Alpha-Beta", alpha_beta);
 
 				spawn_timer_row(builder, "AI Time", "");
 				spawn_depth_row(builder, "Max Depth", "");
+				spawn_eval_row(builder, "Evaluation");
             }
 
 
@@ -62,6 +87,8 @@ pub fn spawn_settings_panel(builder: &mut ChildSpawnerCommands, game_settings: &
                 None => "Unlimited".to_string(),
             };
             spawn_setting_row(builder, "Time Limit", &time_limit);
+
+            spawn_setting_row(builder, "Seed", &format!("{:016x}", game_settings.seed));
         });
 }
 
@@ -181,4 +208,99 @@ fn spawn_depth_row(builder: &mut ChildSpawnerCommands, label: &str, _value: &str
                     },
                 ));
         });
-}
\ No newline at end of file
+}
+fn spawn_eval_row(builder: &mut ChildSpawnerCommands, label: &str) {
+    builder
+        .spawn((
+            Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                width: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.08, 0.08, 0.08)),
+            BorderRadius::all(Val::Px(4.0)),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+
+            builder.spawn((
+                Text::new("roughly equal"),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                AIEvalText,
+            ));
+        });
+}
+
+fn spawn_capture_row(builder: &mut ChildSpawnerCommands, label: &str, player: Player) {
+    builder
+        .spawn((
+            Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                width: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                column_gap: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.08, 0.08, 0.08)),
+            BorderRadius::all(Val::Px(4.0)),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+
+            builder
+                .spawn((
+                    Node {
+                        width: Val::Px(80.0),
+                        height: Val::Px(8.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                ))
+                .with_children(|builder| {
+                    builder.spawn((
+                        Node {
+                            width: Val::Percent(0.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.6, 0.6, 0.6)),
+                        CaptureProgressBar(player),
+                    ));
+                });
+
+            builder.spawn((
+                Text::new("0"),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                CaptureProgressText(player),
+            ));
+        });
+}
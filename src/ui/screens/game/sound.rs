@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use bevy::{audio::{Pitch, Volume}, prelude::*};
+
+use crate::ui::{app::GameSettings, screens::game::game::StonePlaced};
+
+const CLICK_FREQUENCY: f32 = 1400.0;
+const CLICK_DURATION: Duration = Duration::from_millis(30);
+const CLICK_VOLUME: f32 = 0.25;
+const CAPTURE_FREQUENCY: f32 = 180.0;
+const CAPTURE_DURATION: Duration = Duration::from_millis(160);
+/// Volume of a single captured pair; each extra pair adds as much, up to full volume
+const CAPTURE_VOLUME_PER_PAIR: f32 = 0.35;
+
+/// `base` scaled by the sound setting, `None` when the game sounds are muted
+pub fn scaled_volume(settings: &GameSettings, base: f32) -> Option<Volume> {
+    (settings.sound_volume > 0).then(|| Volume::Linear(base * settings.sound_volume as f32 / 100.0))
+}
+
+/// Volume of the capture sound for `pairs` pairs taken by one move
+pub fn capture_volume(pairs: usize) -> f32 {
+    (CAPTURE_VOLUME_PER_PAIR * pairs as f32).min(1.0)
+}
+
+/// A click for every stone, and a low thud on top of it when the stone captured
+pub fn play_stone_sounds(
+    mut commands: Commands,
+    mut events: EventReader<StonePlaced>,
+    settings: Res<GameSettings>,
+    mut pitches: ResMut<Assets<Pitch>>,
+) {
+    for ev in events.read() {
+        let mut sounds = vec![(CLICK_FREQUENCY, CLICK_DURATION, CLICK_VOLUME)];
+        if ev.pairs_captured > 0 {
+            sounds.push((CAPTURE_FREQUENCY, CAPTURE_DURATION, capture_volume(ev.pairs_captured)));
+        }
+
+        for (frequency, duration, base) in sounds {
+            let Some(volume) = scaled_volume(&settings, base) else {
+                continue;
+            };
+            commands.spawn((
+                AudioPlayer(pitches.add(Pitch::new(frequency, duration))),
+                PlaybackSettings {
+                    volume,
+                    ..PlaybackSettings::DESPAWN
+                },
+            ));
+        }
+    }
+}
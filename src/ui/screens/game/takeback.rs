@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::state::GameState,
+    ui::{
+        app::GameSettings,
+        screens::game::{
+            ai_turn::AiTurn,
+            board::LastMove,
+            game::{BoardRewound, MovePlayed, PonderState},
+        },
+    },
+};
+
+const TAKEBACK_BUTTON: Color = Color::srgb(0.2, 0.2, 0.2);
+const TAKEBACK_BUTTON_HOVERED: Color = Color::srgb(0.3, 0.3, 0.3);
+
+#[derive(Component)]
+pub struct TakebackButton;
+
+pub fn spawn_takeback_button(builder: &mut ChildSpawnerCommands) {
+    builder
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(120.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(TAKEBACK_BUTTON),
+            BorderRadius::all(Val::Px(6.0)),
+            TakebackButton,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Takeback"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+/// Whether the human may take back their last move against the AI: the AI has answered
+/// it and is not searching, so the human's move and the reply go together
+pub fn can_take_back(state: &GameState, settings: &GameSettings, ai_thinking: bool) -> bool {
+    settings.versus_ai
+        && !ai_thinking
+        && state.moves.len() >= 2
+        && state.moves.last().is_some_and(|&(_, _, mover)| mover != state.opener_color())
+}
+
+/// Undoes the AI's reply and the human move before it, leaving the human to move again
+pub fn takeback_button_action(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &TakebackButton), Changed<Interaction>>,
+    mut game_state: ResMut<GameState>,
+    (settings, ai_turn): (Res<GameSettings>, Res<AiTurn>),
+    (mut ponder, mut last_move): (ResMut<PonderState>, ResMut<LastMove>),
+    (mut move_played, mut board_rewound): (EventWriter<MovePlayed>, EventWriter<BoardRewound>),
+) {
+    for (interaction, mut background, _) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                if !can_take_back(&game_state, &settings, ai_turn.is_thinking()) {
+                    continue;
+                }
+                let Some(taken_back) = game_state.undo_full_turn() else {
+                    continue;
+                };
+                info!("Took back {:?}", taken_back);
+                ponder.0 = None;
+                last_move.0 = game_state.moves.last().map(|&(x, y, _)| (x, y));
+                board_rewound.write(BoardRewound);
+                move_played.write(MovePlayed);
+            }
+            Interaction::Hovered => *background = TAKEBACK_BUTTON_HOVERED.into(),
+            Interaction::None => *background = TAKEBACK_BUTTON.into(),
+        }
+    }
+}
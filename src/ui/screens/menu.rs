@@ -6,7 +6,9 @@
         prelude::*,
     };
 
-    use crate::ui::{app::{AppState, GameSettings}, screens::utils::despawn_screen};
+    use std::path::PathBuf;
+
+    use crate::{ai::difficulty::{DIFFICULTY_LEVELS, ENGINE_KINDS}, core::{rules::RulePreset, state::MAX_BOARD_SIZE}, ui::{app::{AppState, GameSettings, MAX_DEPTH_CAP}, saves::{list_saved_games, load_saved_game, saves_dir, SavedGameToResume}, screens::utils::despawn_screen}};
 
     pub fn menu_plugin(app: &mut App) {
         app
@@ -14,6 +16,14 @@
             .add_systems(OnEnter(AppState::Menu), menu_setup)
             .add_systems(OnEnter(MenuState::Main), main_menu_setup)
             .add_systems(OnExit(MenuState::Main), despawn_screen::<OnMainMenuScreen>)
+            .add_systems(OnEnter(MenuState::Settings), settings_menu_setup)
+            .add_systems(OnEnter(MenuState::Load), load_menu_setup)
+            .add_systems(Update, saved_game_button.run_if(in_state(MenuState::Load)))
+            .add_systems(OnExit(MenuState::Load), despawn_screen::<OnLoadMenuScreen>)
+            .add_systems(
+                Update,
+                (rule_preset_button, board_size_button, captures_toggle_button, double_three_toggle_button, swap2_toggle_button, difficulty_button, coordinates_toggle_button, move_numbers_toggle_button, blunder_check_toggle_button, colorblind_mode_toggle_button, animate_stones_toggle_button, sound_volume_button, clock_button, max_depth_button, engine_button).run_if(in_state(MenuState::Settings)),
+            )
             .add_systems(
                 OnExit(MenuState::Settings),
                 despawn_screen::<OnSettingsMenuScreen>,
@@ -53,6 +63,9 @@
     #[derive(Component)]
     struct OnSettingsMenuScreen;
 
+    #[derive(Component)]
+    struct OnLoadMenuScreen;
+
     #[derive(Component)]
     struct OnDisplaySettingsMenuScreen;
 
@@ -67,6 +80,71 @@
     #[derive(Component)]
     struct SelectedOption;
 
+    #[derive(Component)]
+    struct RulePresetButton(RulePreset);
+
+    #[derive(Component)]
+    struct Swap2ToggleButton;
+
+    #[derive(Component)]
+    struct CapturesToggleButton;
+
+    #[derive(Component)]
+    struct DoubleThreeToggleButton;
+
+    #[derive(Component)]
+    struct DifficultyButton;
+
+    #[derive(Component)]
+    struct ShowCoordinatesToggleButton;
+
+    #[derive(Component)]
+    struct ShowMoveNumbersToggleButton;
+
+    #[derive(Component)]
+    struct BlunderCheckToggleButton;
+
+    #[derive(Component)]
+    struct ColorblindModeToggleButton;
+
+    #[derive(Component)]
+    struct AnimateStonesToggleButton;
+
+    #[derive(Component)]
+    struct SoundVolumeButton;
+
+    #[derive(Component)]
+    struct MaxDepthButton;
+
+    #[derive(Component)]
+    struct EngineButton;
+
+    #[derive(Component)]
+    struct BoardSizeButton;
+
+    /// Depth caps offered in the settings, the last one leaves every difficulty uncapped
+    const MAX_DEPTH_STEPS: [usize; 7] = [2, 4, 6, 8, 10, 12, MAX_DEPTH_CAP];
+
+    /// Board sizes offered in the settings, up to the largest the engine supports. No line
+    /// of five fits on the smallest, only captures or a draw end a game there.
+    const BOARD_SIZE_STEPS: [usize; 6] = [4, 9, 13, 15, 19, MAX_BOARD_SIZE];
+
+    /// Entry of the resume screen, for one save file
+    #[derive(Component)]
+    struct SavedGameButton(PathBuf);
+
+    /// Line under the saves list telling why a save could not be opened
+    #[derive(Component)]
+    struct LoadStatusText;
+
+    const SOUND_VOLUME_STEPS: [usize; 3] = [0, 50, 100];
+
+    #[derive(Component)]
+    struct ClockButton;
+
+    /// Time controls offered in the settings, as minutes and increment seconds
+    const CLOCK_PRESETS: [(usize, usize); 4] = [(0, 0), (3, 2), (5, 3), (10, 5)];
+
     #[derive(Component)]
     enum MenuButtonAction {
 		Load,
@@ -110,6 +188,353 @@
         }
     }
 
+    /// Like `setting_button`, but the chosen preset is stored in `GameSettings`
+    fn rule_preset_button(
+        interaction_query: Query<(&Interaction, &RulePresetButton, Entity), Changed<Interaction>>,
+        mut selected_query: Query<(Entity, &mut BackgroundColor, &RulePresetButton), With<SelectedOption>>,
+        mut commands: Commands,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, button, entity) in &interaction_query {
+            if *interaction == Interaction::Pressed && settings.rule_preset != button.0 {
+                for (previous_button, mut previous_button_color, _) in &mut selected_query {
+                    *previous_button_color = NORMAL_BUTTON.into();
+                    commands.entity(previous_button).remove::<SelectedOption>();
+                }
+                commands.entity(entity).insert(SelectedOption);
+                settings.set_rule_preset(button.0);
+            }
+        }
+    }
+
+    /// Turns pair captures on or off; picking a rule preset resets it to the preset's rule
+    fn captures_toggle_button(
+        interaction_query: Query<(Ref<Interaction>, &Children), With<CapturesToggleButton>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children) in &interaction_query {
+            if interaction.is_changed() && *interaction == Interaction::Pressed {
+                settings.captures_enabled = !settings.captures_enabled;
+            }
+            if settings.is_changed() {
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = captures_label(settings.captures_enabled);
+                    }
+                }
+            }
+        }
+    }
+
+    fn captures_label(enabled: bool) -> String {
+        format!("Captures: {}", if enabled { "On" } else { "Off" })
+    }
+
+    /// Turns the double-three restriction on or off, reset like the capture rule by a preset
+    fn double_three_toggle_button(
+        interaction_query: Query<(Ref<Interaction>, &Children), With<DoubleThreeToggleButton>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children) in &interaction_query {
+            if interaction.is_changed() && *interaction == Interaction::Pressed {
+                settings.forbid_double_three = !settings.forbid_double_three;
+            }
+            if settings.is_changed() {
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = double_three_label(settings.forbid_double_three);
+                    }
+                }
+            }
+        }
+    }
+
+    fn double_three_label(forbidden: bool) -> String {
+        format!("Double-three: {}", if forbidden { "Forbidden" } else { "Allowed" })
+    }
+
+    fn swap2_toggle_button(
+        interaction_query: Query<(&Interaction, &Children, &Swap2ToggleButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                settings.swap2 = !settings.swap2;
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = swap2_label(settings.swap2);
+                    }
+                }
+            }
+        }
+    }
+
+    fn swap2_label(enabled: bool) -> String {
+        format!("Swap2 opening: {}", if enabled { "On" } else { "Off" })
+    }
+
+    fn coordinates_toggle_button(
+        interaction_query: Query<(&Interaction, &Children, &ShowCoordinatesToggleButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                settings.show_coordinates = !settings.show_coordinates;
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = coordinates_label(settings.show_coordinates);
+                    }
+                }
+            }
+        }
+    }
+
+    fn coordinates_label(enabled: bool) -> String {
+        format!("Coordinates: {}", if enabled { "On" } else { "Off" })
+    }
+
+    fn move_numbers_toggle_button(
+        interaction_query: Query<(&Interaction, &Children, &ShowMoveNumbersToggleButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                settings.show_move_numbers = !settings.show_move_numbers;
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = move_numbers_label(settings.show_move_numbers);
+                    }
+                }
+            }
+        }
+    }
+
+    fn move_numbers_label(enabled: bool) -> String {
+        format!("Move numbers: {}", if enabled { "On" } else { "Off" })
+    }
+
+    fn blunder_check_toggle_button(
+        interaction_query: Query<(&Interaction, &Children, &BlunderCheckToggleButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                settings.blunder_check = !settings.blunder_check;
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = blunder_check_label(settings.blunder_check);
+                    }
+                }
+            }
+        }
+    }
+
+    fn blunder_check_label(enabled: bool) -> String {
+        format!("Blunder check: {}", if enabled { "On" } else { "Off" })
+    }
+
+    fn colorblind_mode_toggle_button(
+        interaction_query: Query<(&Interaction, &Children, &ColorblindModeToggleButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                settings.colorblind_mode = !settings.colorblind_mode;
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = colorblind_mode_label(settings.colorblind_mode);
+                    }
+                }
+            }
+        }
+    }
+
+    fn colorblind_mode_label(enabled: bool) -> String {
+        format!("Colorblind stones: {}", if enabled { "On" } else { "Off" })
+    }
+
+    fn animate_stones_toggle_button(
+        interaction_query: Query<(&Interaction, &Children, &AnimateStonesToggleButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                settings.animate_stones = !settings.animate_stones;
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = animate_stones_label(settings.animate_stones);
+                    }
+                }
+            }
+        }
+    }
+
+    fn animate_stones_label(enabled: bool) -> String {
+        format!("Stone animations: {}", if enabled { "On" } else { "Off" })
+    }
+
+    /// Cycles the game sounds through off, half and full volume
+    fn sound_volume_button(
+        interaction_query: Query<(&Interaction, &Children, &SoundVolumeButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                settings.sound_volume = SOUND_VOLUME_STEPS
+                    .into_iter()
+                    .find(|&step| step > settings.sound_volume)
+                    .unwrap_or(SOUND_VOLUME_STEPS[0]);
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = sound_volume_label(settings.sound_volume);
+                    }
+                }
+            }
+        }
+    }
+
+    fn sound_volume_label(volume: usize) -> String {
+        match volume {
+            0 => "Sound: Off".to_string(),
+            volume => format!("Sound: {}%", volume),
+        }
+    }
+
+    /// Cycles through the clock presets, starting untimed
+    fn clock_button(
+        interaction_query: Query<(&Interaction, &Children, &ClockButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                let current = CLOCK_PRESETS
+                    .iter()
+                    .position(|&preset| preset == (settings.clock_minutes, settings.clock_increment_secs))
+                    .unwrap_or(0);
+                (settings.clock_minutes, settings.clock_increment_secs) = CLOCK_PRESETS[(current + 1) % CLOCK_PRESETS.len()];
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = clock_label(settings.clock_minutes, settings.clock_increment_secs);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Cycles through the depth caps, wrapping back to the shallowest
+    fn max_depth_button(
+        interaction_query: Query<(&Interaction, &Children, &MaxDepthButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                let next = MAX_DEPTH_STEPS
+                    .into_iter()
+                    .find(|&step| step > settings.max_depth)
+                    .unwrap_or(MAX_DEPTH_STEPS[0]);
+                settings.set_max_depth(next);
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = max_depth_label(settings.max_depth);
+                    }
+                }
+            }
+        }
+    }
+
+    fn max_depth_label(max_depth: usize) -> String {
+        format!("Max Depth: {}", max_depth)
+    }
+
+    /// Cycles through the board sizes, wrapping back to the smallest
+    fn board_size_button(
+        interaction_query: Query<(&Interaction, &Children, &BoardSizeButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                let next = BOARD_SIZE_STEPS
+                    .into_iter()
+                    .find(|&step| step > settings.board_size)
+                    .unwrap_or(BOARD_SIZE_STEPS[0]);
+                settings.set_board_size(next);
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = board_size_label(settings.board_size);
+                    }
+                }
+            }
+        }
+    }
+
+    fn board_size_label(board_size: usize) -> String {
+        format!("Board: {}x{}", board_size, board_size)
+    }
+
+    /// Switches the AI between the engines, wrapping back to the first
+    fn engine_button(
+        interaction_query: Query<(&Interaction, &Children, &EngineButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                let current = ENGINE_KINDS.iter().position(|&engine| engine == settings.engine).unwrap_or(0);
+                settings.engine = ENGINE_KINDS[(current + 1) % ENGINE_KINDS.len()];
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = format!("Engine: {}", settings.engine.name());
+                    }
+                }
+            }
+        }
+    }
+
+    fn clock_label(minutes: usize, increment_secs: usize) -> String {
+        match minutes {
+            0 => "Clock: Off".to_string(),
+            minutes => format!("Clock: {}+{}", minutes, increment_secs),
+        }
+    }
+
+    /// Cycles through the AI difficulty levels
+    fn difficulty_button(
+        interaction_query: Query<(&Interaction, &Children, &DifficultyButton), Changed<Interaction>>,
+        mut texts: Query<&mut Text>,
+        mut settings: ResMut<GameSettings>,
+    ) {
+        for (interaction, children, _) in &interaction_query {
+            if *interaction == Interaction::Pressed {
+                let current = DIFFICULTY_LEVELS
+                    .iter()
+                    .position(|&level| level == settings.ai_difficulty)
+                    .unwrap_or(0);
+                settings.set_ai_difficulty(DIFFICULTY_LEVELS[(current + 1) % DIFFICULTY_LEVELS.len()]);
+                for &child in children {
+                    if let Ok(mut text) = texts.get_mut(child) {
+                        text.0 = difficulty_label(settings.ai_difficulty);
+                    }
+                }
+            }
+        }
+    }
+
+    fn difficulty_label(level: &str) -> String {
+        format!("AI difficulty: {}", level)
+    }
+
     fn menu_setup(mut menu_state: ResMut<NextState<MenuState>>) {
         menu_state.set(MenuState::Main);
     }
@@ -164,6 +589,403 @@ fn main_menu_setup(mut commands: Commands) {
         });
 }
 
+fn settings_menu_setup(mut commands: Commands, settings: Res<GameSettings>) {
+    let button_node = Node {
+        width: Val::Px(300.0),
+        height: Val::Px(42.0),
+        margin: UiRect::all(Val::Px(4.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let button_text_font = TextFont {
+        font_size: 28.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            OnSettingsMenuScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Rules"),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+
+            for preset in RulePreset::ALL {
+                let mut button = parent.spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    RulePresetButton(preset),
+                ));
+                button.with_children(|parent| {
+                    parent.spawn((
+                        Text::new(preset.name()),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+                if settings.rule_preset == preset {
+                    button.insert(SelectedOption);
+                }
+            }
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    BoardSizeButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(board_size_label(settings.board_size)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    CapturesToggleButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(captures_label(settings.captures_enabled)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    DoubleThreeToggleButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(double_three_label(settings.forbid_double_three)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    Swap2ToggleButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(swap2_label(settings.swap2)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    DifficultyButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(difficulty_label(settings.ai_difficulty)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    ShowCoordinatesToggleButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(coordinates_label(settings.show_coordinates)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    ShowMoveNumbersToggleButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(move_numbers_label(settings.show_move_numbers)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    BlunderCheckToggleButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(blunder_check_label(settings.blunder_check)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    ColorblindModeToggleButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(colorblind_mode_label(settings.colorblind_mode)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    AnimateStonesToggleButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(animate_stones_label(settings.animate_stones)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    SoundVolumeButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(sound_volume_label(settings.sound_volume)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    ClockButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(clock_label(settings.clock_minutes, settings.clock_increment_secs)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    MaxDepthButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(max_depth_label(settings.max_depth)),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    EngineButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(format!("Engine: {}", settings.engine.name())),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    MenuButtonAction::BackToMainMenu,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Back"),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+        });
+}
+
+/// Saved games, newest first, with a Back button
+fn load_menu_setup(mut commands: Commands) {
+    let button_node = Node {
+        width: Val::Px(420.0),
+        height: Val::Px(42.0),
+        margin: UiRect::all(Val::Px(4.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let button_text_font = TextFont {
+        font_size: 24.0,
+        ..default()
+    };
+    let saves = list_saved_games(&saves_dir());
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            OnLoadMenuScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(if saves.is_empty() { "No saved games" } else { "Saved games" }),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+
+            for path in saves {
+                let name = path
+                    .file_stem()
+                    .map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().into_owned());
+                parent
+                    .spawn((
+                        Button,
+                        button_node.clone(),
+                        BackgroundColor(NORMAL_BUTTON),
+                        SavedGameButton(path),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new(name),
+                            button_text_font.clone(),
+                            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        ));
+                    });
+            }
+
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(CRIMSON.into()),
+                LoadStatusText,
+            ));
+
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    MenuButtonAction::BackToMainMenu,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Back"),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+        });
+}
+
+/// Opens the picked save with the settings it was played under. A file that cannot be
+/// read leaves the menu open with the reason under the list.
+fn saved_game_button(
+    interaction_query: Query<(&Interaction, &SavedGameButton), Changed<Interaction>>,
+    mut status: Query<&mut Text, With<LoadStatusText>>,
+    (mut settings, mut resume): (ResMut<GameSettings>, ResMut<SavedGameToResume>),
+    mut menu_state: ResMut<NextState<MenuState>>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, SavedGameButton(path)) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match load_saved_game(path) {
+            Ok(saved) => {
+                *settings = saved.settings;
+                resume.0 = Some(saved.state);
+                app_state.set(AppState::Game);
+                menu_state.set(MenuState::Disabled);
+            }
+            Err(e) => {
+                warn!("Could not load saved game: {}", e);
+                for mut text in &mut status {
+                    text.0 = format!("Could not open this save: {}", e);
+                }
+            }
+        }
+    }
+}
+
 fn insert_title_node(parent: &mut RelatedSpawnerCommands<'_, ChildOf>) {
     parent
         .spawn((
@@ -224,6 +1046,22 @@ fn insert_load_play_node(
                     ));
                 });
 
+            // Resume button
+            parent
+                .spawn((
+                    Button,
+                    button_node.clone(),
+                    BackgroundColor(NORMAL_BUTTON),
+                    MenuButtonAction::Load,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Resume"),
+                        button_text_font.clone(),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+
             // Settings button
             parent
                 .spawn((
@@ -0,0 +1,317 @@
+use std::path::Path;
+
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+
+use crate::{
+    core::state::GameState,
+    ui::{
+        app::{AppState, GameSettings},
+        screens::{
+            game::{
+                board::{update_last_move_marker, update_move_numbers, BoardUtils, LastMove},
+                game::{spawn_game_hud, style_stones, sync_stones, update_game_hud},
+            },
+            utils::despawn_screen,
+        },
+    },
+};
+
+const REPLAY_BUTTON: Color = Color::srgb(0.2, 0.2, 0.2);
+const SLIDER_WIDTH: f32 = 300.0;
+const SLIDER_TRACK: Color = Color::srgb(0.3, 0.3, 0.3);
+const SLIDER_FILL: Color = Color::srgb(0.85, 0.75, 0.55);
+
+/// Moves of a recorded game and how many of them are on the board
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Replay {
+    pub moves: Vec<(usize, usize)>,
+    pub ply: usize,
+}
+
+#[derive(Component)]
+pub struct OnReplayScreen;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayButton {
+    Back,
+    Forward,
+    Menu,
+}
+
+/// Track jumping to the ply under the cursor while pressed
+#[derive(Component)]
+pub struct ReplaySlider;
+
+/// Played part of the slider track
+#[derive(Component)]
+pub struct ReplaySliderFill;
+
+#[derive(Component)]
+pub struct ReplayPlyText;
+
+impl Replay {
+    /// Takes `state` back to its starting position, keeping its moves to step through
+    pub fn rewind(state: &mut GameState) -> Self {
+        let moves = state.moves.iter().map(|&(row, col, _)| (row, col)).collect();
+        while state.undo_last_move().is_some() {}
+        state.redo_history.clear();
+        Self { moves, ply: 0 }
+    }
+
+    pub fn total_plies(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Plays the next recorded move. Returns false at the end of the game.
+    pub fn step_forward(&mut self, state: &mut GameState) -> bool {
+        let Some(&mv) = self.moves.get(self.ply) else {
+            return false;
+        };
+        state.make_move(mv);
+        self.ply += 1;
+        true
+    }
+
+    /// Takes back the last shown move, captured stones included. Returns false at the start.
+    pub fn step_back(&mut self, state: &mut GameState) -> bool {
+        if self.ply == 0 {
+            return false;
+        }
+        state.undo_move(self.moves[self.ply - 1]);
+        self.ply -= 1;
+        true
+    }
+
+    /// Steps to `ply`, clamped to the length of the game
+    pub fn seek(&mut self, state: &mut GameState, ply: usize) {
+        let ply = ply.min(self.total_plies());
+        while self.ply < ply && self.step_forward(state) {}
+        while self.ply > ply && self.step_back(state) {}
+    }
+}
+
+/// Reads a saved game, as SGF for `.sgf` files and as JSON otherwise
+pub fn load_game(path: &Path) -> Result<GameState, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let is_sgf = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("sgf"));
+    if is_sgf {
+        GameState::from_sgf(&contents).map_err(|e| e.to_string())
+    } else {
+        GameState::from_json(&contents).map_err(|e| e.to_string())
+    }
+}
+
+/// Ply under a point of the slider, `fraction` being its share of the track from the left
+pub fn slider_ply(fraction: f32, total_plies: usize) -> usize {
+    (fraction.clamp(0.0, 1.0) * total_plies as f32).round() as usize
+}
+
+pub fn replay_plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppState::Replay), (setup_replay_ui, sync_stones, style_stones).chain())
+        .add_systems(
+            Update,
+            (
+                (replay_keyboard, replay_button_action, replay_slider),
+                (
+                    sync_stones,
+                    update_move_numbers,
+                    update_game_hud,
+                    update_replay_controls,
+                )
+                    .run_if(resource_changed::<GameState>),
+                style_stones,
+                update_last_move_marker.run_if(resource_changed::<LastMove>),
+            )
+                .chain()
+                .run_if(in_state(AppState::Replay)),
+        )
+        .add_systems(OnExit(AppState::Replay), despawn_screen::<OnReplayScreen>);
+}
+
+fn setup_replay_ui(mut commands: Commands, settings: Res<GameSettings>, game_state: Res<GameState>) {
+    commands
+        .spawn((
+            Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
+            OnReplayScreen,
+        ))
+        .with_children(|builder| {
+            spawn_game_hud(builder);
+            BoardUtils::spawn_board(builder, &game_state.board, &settings);
+            builder
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    spawn_replay_button(builder, ReplayButton::Back, "<");
+                    spawn_replay_slider(builder);
+                    spawn_replay_button(builder, ReplayButton::Forward, ">");
+                    builder.spawn((
+                        Text::new(""),
+                        TextFont {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        ReplayPlyText,
+                    ));
+                    spawn_replay_button(builder, ReplayButton::Menu, "Menu");
+                });
+        });
+}
+
+fn spawn_replay_button(builder: &mut ChildSpawnerCommands, action: ReplayButton, label: &str) {
+    builder
+        .spawn((
+            Button,
+            Node {
+                min_width: Val::Px(40.0),
+                height: Val::Px(40.0),
+                padding: UiRect::horizontal(Val::Px(10.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(REPLAY_BUTTON),
+            BorderRadius::all(Val::Px(6.0)),
+            action,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        });
+}
+
+fn spawn_replay_slider(builder: &mut ChildSpawnerCommands) {
+    builder
+        .spawn((
+            Node {
+                width: Val::Px(SLIDER_WIDTH),
+                height: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(SLIDER_TRACK),
+            BorderRadius::all(Val::Px(6.0)),
+            Interaction::default(),
+            RelativeCursorPosition::default(),
+            ReplaySlider,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Node {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(SLIDER_FILL),
+                BorderRadius::all(Val::Px(6.0)),
+                ReplaySliderFill,
+            ));
+        });
+}
+
+/// Left and Right step through the game, Home and End jump to its ends, Escape leaves
+fn replay_keyboard(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut replay: ResMut<Replay>,
+    mut game_state: ResMut<GameState>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    let target = if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        replay.ply.saturating_sub(1)
+    } else if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        replay.ply + 1
+    } else if keyboard_input.just_pressed(KeyCode::Home) {
+        0
+    } else if keyboard_input.just_pressed(KeyCode::End) {
+        replay.total_plies()
+    } else {
+        if keyboard_input.just_pressed(KeyCode::Escape) {
+            app_state.set(AppState::Menu);
+        }
+        return;
+    };
+    seek_if_moved(&mut replay, &mut game_state, target);
+}
+
+fn replay_button_action(
+    interaction_query: Query<(&Interaction, &ReplayButton), Changed<Interaction>>,
+    mut replay: ResMut<Replay>,
+    mut game_state: ResMut<GameState>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let target = match action {
+            ReplayButton::Back => replay.ply.saturating_sub(1),
+            ReplayButton::Forward => replay.ply + 1,
+            ReplayButton::Menu => {
+                app_state.set(AppState::Menu);
+                continue;
+            }
+        };
+        seek_if_moved(&mut replay, &mut game_state, target);
+    }
+}
+
+/// Follows the cursor for as long as the track is held down
+fn replay_slider(
+    sliders: Query<(&Interaction, &RelativeCursorPosition), With<ReplaySlider>>,
+    mut replay: ResMut<Replay>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, position) in &sliders {
+        if *interaction == Interaction::Pressed
+            && let Some(cursor) = position.normalized
+        {
+            let target = slider_ply(cursor.x, replay.total_plies());
+            seek_if_moved(&mut replay, &mut game_state, target);
+        }
+    }
+}
+
+/// Only touches the resources when the ply changes, so the board is not redrawn every frame
+fn seek_if_moved(replay: &mut ResMut<Replay>, game_state: &mut ResMut<GameState>, ply: usize) {
+    if ply.min(replay.total_plies()) != replay.ply {
+        replay.seek(game_state, ply);
+    }
+}
+
+fn update_replay_controls(
+    replay: Res<Replay>,
+    game_state: Res<GameState>,
+    mut last_move: ResMut<LastMove>,
+    mut fills: Query<&mut Node, With<ReplaySliderFill>>,
+    mut texts: Query<&mut Text, With<ReplayPlyText>>,
+) {
+    last_move.0 = game_state.moves.last().map(|&(x, y, _)| (x, y));
+
+    let progress = replay.ply as f32 / replay.total_plies().max(1) as f32;
+    for mut node in &mut fills {
+        node.width = Val::Percent(progress * 100.0);
+    }
+    for mut text in &mut texts {
+        text.0 = format!("{}/{}", replay.ply, replay.total_plies());
+    }
+}
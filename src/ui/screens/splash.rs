@@ -1,7 +1,7 @@
 
 use bevy::prelude::*;
 
-use crate::ui::{app::AppState, screens::utils::despawn_screen};
+use crate::ui::{app::AppState, screens::{replay::Replay, utils::despawn_screen}};
 
 pub fn splash_plugin(app: &mut App) {
 	app
@@ -41,8 +41,9 @@ fn countdown(
 	mut game_state: ResMut<NextState<AppState>>,
 	time: Res<Time>,
 	mut timer: ResMut<SplashTimer>,
+	replay: Option<Res<Replay>>,
 ) {
 	if timer.tick(time.delta()).finished() {
-		game_state.set(AppState::Menu);
+		game_state.set(if replay.is_some() { AppState::Replay } else { AppState::Menu });
 	}
 }
\ No newline at end of file
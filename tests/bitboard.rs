@@ -82,9 +82,9 @@ fn test_occupied_on_place() {
     let mut board = Board::new(5);
     board.place_stone(2, 2, Player::Max);
     let idx = board.index(2, 2);
-    assert!(Board::is_bit_set(&board.occupied, idx));
-    assert!(Board::is_bit_set(&board.max_bits, idx));
-    assert!(!Board::is_bit_set(&board.min_bits, idx));
+    assert!(Board::is_bit_set(board.occupied(), idx));
+    assert!(Board::is_bit_set(board.max_bits(), idx));
+    assert!(!Board::is_bit_set(board.min_bits(), idx));
 }
 
 #[test]
@@ -92,8 +92,8 @@ fn test_occupied_on_remove() {
     let mut board = create_test_board(5, vec![((2, 2), Player::Max)]);
     board.remove_stone(2, 2);
     let idx = board.index(2, 2);
-    assert!(!Board::is_bit_set(&board.occupied, idx));
-    assert!(!Board::is_bit_set(&board.max_bits, idx));
+    assert!(!Board::is_bit_set(board.occupied(), idx));
+    assert!(!Board::is_bit_set(board.max_bits(), idx));
 }
 
 // Tests for is_adjacent_to_stone
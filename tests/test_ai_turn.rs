@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use gomoku::ai::lazy_smp::{SearchConfig, SearchResult};
+use gomoku::ai::move_ordering::MoveHistory;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::ui::screens::game::ai_turn::{thinking_label, AiMove, AiTurn};
+
+/// Stand-in for the real search: answers at once with the cell right of the last stone
+fn fake_search(state: GameState, _stop: &AtomicBool) -> AiMove {
+    let &(row, col, _) = state.moves.last().unwrap();
+    AiMove {
+        chosen: Some((row, col + 1)),
+        result: SearchResult {
+            best_move: Some((row, col + 1)),
+            ..SearchResult::default()
+        },
+        config: SearchConfig::new(1, None),
+        history: MoveHistory::new(),
+    }
+}
+
+fn wait_for_move(ai_turn: &mut AiTurn, state: &GameState) -> Option<AiMove> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while ai_turn.is_thinking() && Instant::now() < deadline {
+        if let Some(ai_move) = ai_turn.poll(state) {
+            return Some(ai_move);
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    None
+}
+
+#[test]
+fn test_ai_turn_goes_from_idle_to_thinking_to_applied() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    let mut ai_turn = AiTurn::default();
+    assert!(!ai_turn.is_thinking());
+    assert!(ai_turn.poll(&state).is_none());
+
+    ai_turn.start(&state, fake_search);
+    assert!(ai_turn.is_thinking());
+
+    let ai_move = wait_for_move(&mut ai_turn, &state).expect("the search finishes");
+    assert!(!ai_turn.is_thinking());
+    assert_eq!(ai_move.chosen, Some((7, 8)));
+    state.make_move(ai_move.chosen.unwrap());
+    assert_eq!(state.board.get_player(7, 8), Some(Player::Min));
+
+    // The result is handed out once
+    assert!(ai_turn.poll(&state).is_none());
+}
+
+#[test]
+fn test_ai_turn_drops_result_for_a_left_position() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    let mut ai_turn = AiTurn::default();
+    ai_turn.start(&state, fake_search);
+
+    state.undo_last_move();
+    assert!(wait_for_move(&mut ai_turn, &state).is_none());
+    assert!(!ai_turn.is_thinking());
+}
+
+#[test]
+fn test_ai_turn_cancel_goes_back_to_idle() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    let mut ai_turn = AiTurn::default();
+    ai_turn.start(&state, fake_search);
+    ai_turn.cancel();
+    assert!(!ai_turn.is_thinking());
+    assert!(ai_turn.poll(&state).is_none());
+}
+
+#[test]
+fn test_ai_turn_cancel_stops_the_search() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    let mut ai_turn = AiTurn::default();
+    let (started, stopped) = (Arc::new(AtomicBool::new(false)), Arc::new(AtomicBool::new(false)));
+    let (search_started, search_stopped) = (Arc::clone(&started), Arc::clone(&stopped));
+    // Thinks until told to stop, or for far longer than the test waits
+    ai_turn.start(&state, move |state, stop| {
+        search_started.store(true, Ordering::Relaxed);
+        let deadline = Instant::now() + Duration::from_secs(60);
+        while !stop.load(Ordering::Relaxed) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        search_stopped.store(stop.load(Ordering::Relaxed), Ordering::Relaxed);
+        fake_search(state, stop)
+    });
+
+    // A task dropped before it starts never runs, which would prove nothing
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !started.load(Ordering::Relaxed) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    ai_turn.cancel();
+    while !stopped.load(Ordering::Relaxed) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert!(stopped.load(Ordering::Relaxed), "the search kept running after cancel");
+}
+
+#[test]
+fn test_thinking_label_spins() {
+    assert!(thinking_label(0.0).ends_with("AI is thinking..."));
+    assert_ne!(thinking_label(0.0), thinking_label(0.2));
+    assert_eq!(thinking_label(0.0), thinking_label(0.6));
+}
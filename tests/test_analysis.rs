@@ -0,0 +1,116 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use gomoku::ai::analysis::{analyze, normalize_eval, Analysis, Analyzer};
+use gomoku::ai::lazy_smp::{lazy_smp_search_with_progress, SearchConfig};
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::ui::screens::game::analysis::{eval_bar_fill, format_analysis, format_line, PV_DISPLAY_LENGTH};
+
+fn config() -> SearchConfig {
+    SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(4, Some(Duration::from_millis(500)))
+    }
+}
+
+/// Max has an open four on row 9, White to move cannot stop both ends
+fn max_winning() -> GameState {
+    let mut state = GameState::new(19, 5);
+    for col in 6..10 {
+        state.board.place_stone(9, col, Player::Max);
+    }
+    state.board.place_stone(10, 7, Player::Min);
+    state.board.place_stone(8, 8, Player::Min);
+    state.board.place_stone(11, 11, Player::Min);
+    state.current_player = Player::Min;
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+    state
+}
+
+#[test]
+fn test_winning_position_for_max_is_strongly_positive() {
+    let analysis = analyze(&max_winning(), &config(), None);
+
+    assert!(analysis.normalized > 0.9, "normalized eval {} ({})", analysis.normalized, analysis.score);
+    assert!(eval_bar_fill(analysis.normalized) > 0.95);
+    let (row, col) = analysis.best_move.expect("White still has a move to suggest");
+    assert_eq!(row, 9, "White should block the four, got {:?}", (row, col));
+}
+
+#[test]
+fn test_normalized_eval_is_symmetric_and_bounded() {
+    assert_eq!(normalize_eval(0), 0.0);
+    assert_eq!(normalize_eval(2_000), -normalize_eval(-2_000));
+    assert!(normalize_eval(1_000_000) <= 1.0 && normalize_eval(1_000_000) > 0.99);
+    assert!(normalize_eval(500) > 0.0 && normalize_eval(500) < 0.1);
+    assert_eq!(eval_bar_fill(0.0), 0.5);
+}
+
+#[test]
+fn test_background_analysis_finishes_without_blocking() {
+    let state = max_winning();
+    let mut analyzer = Analyzer::start(&state, &config()).expect("position is not over");
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let analysis = loop {
+        if let Some(analysis) = analyzer.try_finish() {
+            break analysis;
+        }
+        assert!(Instant::now() < deadline, "analysis did not finish");
+        thread::sleep(Duration::from_millis(5));
+    };
+    assert!(analysis.score > 0);
+    assert!(analyzer.try_finish().is_none(), "the result is handed out once");
+}
+
+#[test]
+fn test_dropping_the_analyzer_stops_the_search() {
+    let mut state = GameState::new(19, 5);
+    for mv in [(9, 9), (9, 10), (10, 9), (8, 8)] {
+        state.make_move(mv);
+    }
+    let unbounded = SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(30, None)
+    };
+    let analyzer = Analyzer::start(&state, &unbounded).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let dropped_at = Instant::now();
+    drop(analyzer);
+    assert!(dropped_at.elapsed() < Duration::from_millis(500), "took {:?}", dropped_at.elapsed());
+}
+
+#[test]
+fn test_readout_follows_each_completed_depth() {
+    let mut state = GameState::new(19, 5);
+    for mv in [(9, 9), (9, 10), (10, 9), (8, 8)] {
+        state.make_move(mv);
+    }
+    let fixed_depth = SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(3, None)
+    };
+    let (sender, receiver) = mpsc::channel();
+    let result = lazy_smp_search_with_progress(&mut state.clone(), &fixed_depth, None, sender);
+
+    // One report per depth, each leading with its own best move
+    let depths: Vec<_> = receiver.try_iter().map(Analysis::from_progress).collect();
+    assert_eq!(depths.iter().map(|analysis| analysis.depth).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert!(depths.iter().all(|analysis| analysis.best_move.is_some() && analysis.best_move == analysis.pv.first().copied()));
+
+    let (depth_reached, best_move) = (result.depth_reached, result.best_move);
+    let readout = Analysis::from_result(result);
+    assert_eq!(readout.depth, depth_reached);
+    assert_eq!(readout.pv.first().copied(), best_move);
+
+    let text = format_analysis(&readout, &state, true);
+    assert!(text.contains(&format!("(depth {})", depth_reached)), "{}", text);
+    let line = format_line(&readout.pv, &state, true);
+    assert_eq!(line.split(' ').count(), readout.pv.len().min(PV_DISPLAY_LENGTH));
+    if readout.pv.len() > 1 {
+        assert!(text.contains(&format!("Line: {}", line)), "{}", text);
+    }
+}
@@ -0,0 +1,17 @@
+use gomoku::ui::screens::game::animation::{appear_scale, capture_scale, ease_in_out_cubic};
+
+#[test]
+fn test_stone_animation_curves() {
+    assert_eq!(appear_scale(0.0), 0.0);
+    assert_eq!(appear_scale(0.5), 0.5);
+    assert_eq!(appear_scale(1.0), 1.0);
+    assert_eq!(capture_scale(0.0), 1.0);
+    assert_eq!(capture_scale(0.5), 0.5);
+    assert_eq!(capture_scale(1.0), 0.0);
+
+    // Eased: slow at both ends, and never past full size whatever the timer says
+    assert!(ease_in_out_cubic(0.25) < 0.25);
+    assert!(ease_in_out_cubic(0.75) > 0.75);
+    assert_eq!(ease_in_out_cubic(-1.0), 0.0);
+    assert_eq!(ease_in_out_cubic(2.0), 1.0);
+}
@@ -0,0 +1,128 @@
+use gomoku::core::board::{Board, Player};
+use gomoku::core::state::GameState;
+
+#[test]
+fn test_board_to_ascii() {
+    let mut board = Board::new_rect(3, 4);
+    board.place_stone(0, 0, Player::Max);
+    board.place_stone(1, 2, Player::Min);
+    board.place_stone(2, 3, Player::Max);
+
+    let expected = concat!(
+        "   0  1  2  3\n",
+        "0  X  .  .  .\n",
+        "1  .  .  O  .\n",
+        "2  .  .  .  X\n",
+    );
+    assert_eq!(board.to_ascii(), expected);
+}
+
+#[test]
+fn test_board_to_ascii_widens_row_labels() {
+    let board = Board::new_rect(11, 2);
+    let ascii = board.to_ascii();
+    let lines: Vec<&str> = ascii.lines().collect();
+    assert_eq!(lines.len(), 12);
+    assert_eq!(lines[0], "    0  1");
+    assert_eq!(lines[1], " 0  .  .");
+    assert_eq!(lines[11], "10  .  .");
+}
+
+#[test]
+fn test_game_state_to_ascii() {
+    let mut state = GameState::new(9, 5);
+    for mv in [(4, 4), (4, 5), (3, 3)] {
+        state.make_move(mv);
+    }
+
+    let expected = concat!(
+        "   0  1  2  3  4  5  6  7  8\n",
+        "0  .  .  .  .  .  .  .  .  .\n",
+        "1  .  .  .  .  .  .  .  .  .\n",
+        "2  .  .  .  .  .  .  .  .  .\n",
+        "3  .  .  .  X  .  .  .  .  .\n",
+        "4  .  .  .  .  X  O  .  .  .\n",
+        "5  .  .  .  .  .  .  .  .  .\n",
+        "6  .  .  .  .  .  .  .  .  .\n",
+        "7  .  .  .  .  .  .  .  .  .\n",
+        "8  .  .  .  .  .  .  .  .  .\n",
+        "Captures: X 0, O 0 (of 5)\n",
+        "O to move\n",
+    );
+    assert_eq!(state.to_ascii(), expected);
+}
+
+#[test]
+fn test_game_state_to_ascii_reports_winner() {
+    let mut state = GameState::new(9, 5);
+    for mv in [(4, 0), (5, 0), (4, 1), (5, 1), (4, 2), (5, 2), (4, 3), (5, 3), (4, 4)] {
+        state.make_move(mv);
+    }
+    assert!(state.to_ascii().ends_with("Captures: X 0, O 0 (of 5)\nX wins\n"));
+}
+
+#[test]
+fn test_board_from_ascii_round_trips() {
+    let mut board = Board::new_rect(11, 7);
+    board.place_stone(0, 0, Player::Max);
+    board.place_stone(5, 3, Player::Min);
+    board.place_stone(10, 6, Player::Max);
+    board.place_stone(4, 2, Player::Min);
+
+    assert_eq!(Board::from_ascii(&board.to_ascii()), Ok(board));
+}
+
+#[test]
+fn test_board_from_plain_diagram() {
+    let board = Board::from_ascii(
+        "
+        X . .
+        . O .
+        . . X
+        . . .
+        ",
+    )
+    .unwrap();
+
+    assert_eq!((board.rows, board.cols), (4, 3));
+    assert_eq!(board.get_player(0, 0), Some(Player::Max));
+    assert_eq!(board.get_player(1, 1), Some(Player::Min));
+    assert_eq!(board.get_player(2, 2), Some(Player::Max));
+    assert_eq!(board.count_stones(), 3);
+    assert_eq!(board.count_player_stones(Player::Max), 2);
+    // Compact rows read the same
+    assert_eq!(Board::from_ascii("X..\n.O.\n..X\n...").unwrap(), board);
+}
+
+#[test]
+fn test_board_from_ascii_errors() {
+    let ragged = Board::from_ascii("X . .\n. O\n. . X");
+    assert!(ragged.unwrap_err().contains("row 1 has 2 cells"));
+    assert!(Board::from_ascii("X . #").is_err());
+    assert!(Board::from_ascii("\n  \n").is_err());
+}
+
+#[test]
+fn test_game_state_from_ascii_sets_the_side_to_move() {
+    let state = GameState::from_ascii(
+        "
+        . . . . .
+        . X O . .
+        . . X . .
+        . . . . .
+        . . . . .
+        ",
+        5,
+    )
+    .unwrap();
+    assert_eq!(state.current_player, Player::Min);
+    assert_eq!(state.win_condition, 5);
+    assert_eq!(state.current_hash, state.zobrist_hash.compute_hash(&state));
+
+    let mut played = GameState::new(5, 5);
+    for mv in [(2, 2), (1, 2), (1, 1)] {
+        played.make_move(mv);
+    }
+    assert_eq!(state.board, played.board);
+    assert_eq!(state.hash(), played.hash());
+}
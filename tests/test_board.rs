@@ -1,4 +1,5 @@
-use gomoku::core::board::{Board, Player};
+use gomoku::core::board::{Board, BoardDiff, LineBits, Player, LINE_DIRECTIONS};
+use gomoku::core::state::GameState;
 
 #[test]
 fn test_board_creation() {
@@ -147,12 +148,12 @@ fn test_bitboard_consistency() {
         
         // Verify player-specific bitboard is set
         match player {
-            Player::Max => assert!(Board::is_bit_set(&board.max_bits, idx)),
-            Player::Min => assert!(Board::is_bit_set(&board.min_bits, idx)),
+            Player::Max => assert!(Board::is_bit_set(board.max_bits(), idx)),
+            Player::Min => assert!(Board::is_bit_set(board.min_bits(), idx)),
         }
         
         // Verify occupied bitboard is set
-        assert!(Board::is_bit_set(&board.occupied, idx));
+        assert!(Board::is_bit_set(board.occupied(), idx));
         
         // Verify get_player returns correct player
         assert_eq!(board.get_player(row, col), Some(player));
@@ -178,9 +179,9 @@ fn test_large_board_bitboard_operations() {
     assert_eq!(last_idx, 18 * 19 + 18); // 360
     
     // Verify bitboard operations work correctly for high indices
-    assert!(Board::is_bit_set(&board.max_bits, last_idx));
-    assert!(Board::is_bit_set(&board.occupied, last_idx));
-    assert!(!Board::is_bit_set(&board.min_bits, last_idx));
+    assert!(Board::is_bit_set(board.max_bits(), last_idx));
+    assert!(Board::is_bit_set(board.occupied(), last_idx));
+    assert!(!Board::is_bit_set(board.min_bits(), last_idx));
 }
 
 #[test]
@@ -230,3 +231,178 @@ fn test_player_opponent() {
     assert_eq!(Player::Min.opponent(), Player::Max);
 }
 
+
+#[test]
+fn test_clear_region() {
+    let mut board = Board::new(19);
+
+    // Fill a band of rows so the region crosses u64 word boundaries
+    for row in 2..8 {
+        for col in 0..19 {
+            let player = if (row + col) % 2 == 0 { Player::Max } else { Player::Min };
+            board.place_stone(row, col, player);
+        }
+    }
+    let before = board.clone();
+
+    board.clear_region((3, 4), (6, 15));
+
+    for row in 0..19 {
+        for col in 0..19 {
+            let inside = (3..=6).contains(&row) && (4..=15).contains(&col);
+            if inside {
+                assert!(board.is_empty_position(row, col), "({}, {}) should be cleared", row, col);
+            } else {
+                assert_eq!(board.get_player(row, col), before.get_player(row, col));
+            }
+        }
+    }
+    assert_eq!(board.count_stones(), before.count_stones() - 4 * 12);
+    assert_eq!(
+        board.count_player_stones(Player::Max) + board.count_player_stones(Player::Min),
+        board.count_stones()
+    );
+}
+
+#[test]
+fn test_clear_region_clamps_to_board() {
+    let mut board = Board::new(9);
+    board.place_stone(8, 8, Player::Max);
+    board.place_stone(0, 0, Player::Min);
+
+    board.clear_region((5, 5), (100, 100));
+
+    assert!(board.is_empty_position(8, 8));
+    assert_eq!(board.get_player(0, 0), Some(Player::Min));
+}
+
+#[test]
+fn test_clear() {
+    let mut board = Board::new(19);
+    board.place_stone(0, 0, Player::Max);
+    board.place_stone(9, 9, Player::Min);
+    board.place_stone(18, 18, Player::Max);
+
+    board.clear();
+
+    assert_eq!(board.count_stones(), 0);
+    assert!(board.is_empty());
+    assert_eq!(board, Board::new(19));
+}
+
+#[test]
+fn test_diff_of_a_capturing_move() {
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    state.board.place_stone(9, 11, Player::Min);
+    state.current_player = Player::Max;
+
+    let before = state.board.clone();
+    state.make_move((9, 12));
+    let diff = before.diff(&state.board);
+
+    assert_eq!(diff.added, vec![(9, 12, Player::Max)]);
+    assert_eq!(diff.removed, vec![(9, 10, Player::Min), (9, 11, Player::Min)]);
+    // Going back swaps the two lists
+    let back = state.board.diff(&before);
+    assert_eq!((back.added, back.removed), (diff.removed, diff.added));
+}
+
+#[test]
+fn test_diff_of_equal_boards_is_empty() {
+    let mut board = Board::new(19);
+    board.place_stone(18, 18, Player::Min);
+    assert_eq!(board.diff(&board.clone()), BoardDiff::default());
+}
+
+#[test]
+fn test_diff_reports_a_colour_change_both_ways() {
+    let mut before = Board::new_rect(9, 13);
+    before.place_stone(8, 12, Player::Max);
+    let mut after = before.clone();
+    after.remove_stone(8, 12);
+    after.place_stone(8, 12, Player::Min);
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.added, vec![(8, 12, Player::Min)]);
+    assert_eq!(diff.removed, vec![(8, 12, Player::Max)]);
+}
+
+/// Every line of `board` read cell by cell, checked against the cached line bits
+fn assert_line_bits_match(board: &Board) {
+    for (direction, &(dr, dc)) in LINE_DIRECTIONS.iter().enumerate() {
+        let mut lines = 0;
+        for row in 0..board.rows {
+            for col in 0..board.cols {
+                if board.in_bounds(row as isize - dr, col as isize - dc) {
+                    continue;
+                }
+                let mut naive = LineBits::default();
+                let (mut r, mut c, mut position) = (row as isize, col as isize, 0);
+                while board.in_bounds(r, c) {
+                    match board.get_player(r as usize, c as usize) {
+                        Some(Player::Max) => naive.max |= 1 << position,
+                        Some(Player::Min) => naive.min |= 1 << position,
+                        None => {}
+                    }
+                    (r, c, position) = (r + dr, c + dc, position + 1);
+                }
+                let (index, first) = board.line_position(row, col, direction);
+                assert_eq!(first, 0, "line {:?} of direction {} starts at {:?}", index, direction, (row, col));
+                assert_eq!(board.line_cell(direction, index, 0), (row, col));
+                assert_eq!(board.line_bits(direction, index), naive, "direction {} from {:?}", direction, (row, col));
+                lines += 1;
+            }
+        }
+        assert_eq!(lines, board.line_count(direction));
+    }
+}
+
+#[test]
+fn test_line_bits_follow_placements_and_captures() {
+    let mut state = GameState::new(19, 5);
+    // Max flanks the Min pair at (9,10)-(9,11); Min's diagonal pair moved in between Max stones
+    for mv in [(9, 9), (9, 10), (12, 12), (9, 11), (0, 18), (10, 10), (18, 0), (11, 11)] {
+        state.make_move(mv);
+        assert_line_bits_match(&state.board);
+    }
+    state.make_move((9, 12));
+    assert!(state.board.is_empty_position(9, 10) && state.board.is_empty_position(9, 11));
+    assert_line_bits_match(&state.board);
+
+    state.undo_move((9, 12));
+    assert_eq!(state.board.get_player(9, 11), Some(Player::Min));
+    assert_line_bits_match(&state.board);
+
+    state.board.remove_stone(9, 9);
+    state.board.clear_region((0, 0), (10, 10));
+    assert_line_bits_match(&state.board);
+    state.board.clear();
+    assert_line_bits_match(&state.board);
+}
+
+#[test]
+fn test_line_bits_follow_bitboard_edits() {
+    let mut board = Board::new(19);
+    board.place_stone(3, 4, Player::Max);
+    board.place_stone(9, 9, Player::Min);
+    board.edit_bits(|max, min, _| max.swap_with_slice(min));
+    assert_eq!(board.get_player(3, 4), Some(Player::Min));
+    assert_line_bits_match(&board);
+}
+
+#[test]
+fn test_line_bits_on_a_rectangular_board() {
+    let mut board = Board::new_rect(4, 7);
+    for (row, col, player) in [(0, 6, Player::Max), (3, 0, Player::Min), (1, 3, Player::Max), (2, 5, Player::Min)] {
+        board.place_stone(row, col, player);
+    }
+    assert_line_bits_match(&board);
+    assert_eq!(board.line_count(2), 10);
+
+    // Serialized boards come back with their lines
+    let json = serde_json::to_string(&board).unwrap();
+    let restored: Board = serde_json::from_str(&json).unwrap();
+    assert_line_bits_match(&restored);
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::sync::mpsc::Receiver;
+
+use gomoku::core::board::Player;
+use gomoku::core::state::{GameOutcome, GameState};
+use gomoku::interface::broadcast::{Broadcaster, GameEvent};
+
+/// What a spectator rebuilds from the events alone
+#[derive(Default)]
+struct Spectator {
+    stones: HashMap<(usize, usize), Player>,
+    outcome: Option<GameOutcome>,
+}
+
+impl Spectator {
+    fn follow(&mut self, events: &Receiver<GameEvent>) {
+        for event in events.try_iter() {
+            match event {
+                GameEvent::Move { player, pos, captures } => {
+                    self.stones.insert(pos, player);
+                    for captured in captures {
+                        self.stones.remove(&captured);
+                    }
+                }
+                GameEvent::Undo { player, pos, restored } => {
+                    self.stones.remove(&pos);
+                    for stone in restored {
+                        self.stones.insert(stone, player.opponent());
+                    }
+                    self.outcome = None;
+                }
+                GameEvent::Reset { .. } => *self = Spectator::default(),
+                GameEvent::GameOver(outcome) => self.outcome = Some(outcome),
+            }
+        }
+    }
+
+    fn assert_sees(&self, state: &GameState) {
+        for row in 0..state.board.rows {
+            for col in 0..state.board.cols {
+                assert_eq!(self.stones.get(&(row, col)).copied(), state.board.get_player(row, col), "at {:?}", (row, col));
+            }
+        }
+        assert_eq!(self.outcome, state.outcome());
+    }
+}
+
+#[test]
+fn test_capture_then_win_is_broadcast_in_order() {
+    let mut state = GameState::new(19, 5);
+    // Max has four on row 5 and flanks a Min pair on row 9 from the left
+    for (row, col) in [(5, 5), (5, 6), (5, 7), (5, 8), (9, 9)] {
+        state.board.place_stone(row, col, Player::Max);
+    }
+    for (row, col) in [(9, 10), (9, 11), (12, 3)] {
+        state.board.place_stone(row, col, Player::Min);
+    }
+    state.current_player = Player::Max;
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+
+    let mut broadcaster = Broadcaster::new();
+    let spectator = broadcaster.subscribe();
+    let hung_up = broadcaster.subscribe();
+    drop(hung_up);
+
+    broadcaster.make_move(&mut state, (9, 12));
+    broadcaster.make_move(&mut state, (0, 0));
+    broadcaster.make_move(&mut state, (5, 9));
+
+    let events: Vec<_> = spectator.try_iter().collect();
+    assert_eq!(
+        events,
+        vec![
+            GameEvent::Reset { rows: 19, cols: 19 },
+            GameEvent::Move { player: Player::Max, pos: (9, 12), captures: vec![(9, 11), (9, 10)] },
+            GameEvent::Move { player: Player::Min, pos: (0, 0), captures: Vec::new() },
+            GameEvent::Move { player: Player::Max, pos: (5, 9), captures: Vec::new() },
+            GameEvent::GameOver(GameOutcome::FiveInARow(Player::Max)),
+        ]
+    );
+    assert_eq!(broadcaster.subscriber_count(), 1, "the receiver that hung up is dropped");
+
+    // Events are plain data a transport can serialize
+    let json = serde_json::to_string(&events[1]).unwrap();
+    assert_eq!(serde_json::from_str::<GameEvent>(&json).unwrap(), events[1]);
+}
+
+#[test]
+fn test_spectator_follows_undo_redo_resignation_and_restart() {
+    let mut state = GameState::new(19, 5);
+    let mut broadcaster = Broadcaster::new();
+    let events = broadcaster.subscribe();
+    let mut spectator = Spectator::default();
+
+    // Max captures the Min pair at (9,10),(9,11) by playing (9,12)
+    for mv in [(9, 9), (9, 10), (8, 8), (9, 11), (9, 12)] {
+        broadcaster.make_move(&mut state, mv);
+    }
+    spectator.follow(&events);
+    spectator.assert_sees(&state);
+
+    // The undo puts the captured pair back
+    state.undo_last_move();
+    broadcaster.sync(&state);
+    spectator.follow(&events);
+    spectator.assert_sees(&state);
+    assert_eq!(spectator.stones.get(&(9, 10)), Some(&Player::Min));
+
+    state.redo_move();
+    state.resign(Player::Min);
+    broadcaster.sync(&state);
+    spectator.follow(&events);
+    spectator.assert_sees(&state);
+    assert_eq!(spectator.outcome, Some(GameOutcome::Resignation(Player::Max)));
+
+    state = GameState::new(15, 5);
+    broadcaster.sync(&state);
+    spectator.follow(&events);
+    spectator.assert_sees(&state);
+    assert!(spectator.stones.is_empty());
+}
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::ui::screens::game::board::LastMove;
+use gomoku::ui::screens::game::game::{place_stone, CaptureProgressEvent, MovePlayed, StonePlaced, StonePlacement};
+
+fn placement_app(state: GameState) -> App {
+    let mut app = App::new();
+    app.insert_resource(state)
+        .init_resource::<LastMove>()
+        .add_event::<StonePlacement>()
+        .add_event::<MovePlayed>()
+        .add_event::<CaptureProgressEvent>()
+        .add_event::<StonePlaced>()
+        .add_systems(Update, place_stone);
+    app
+}
+
+fn capture_events(app: &App) -> Vec<CaptureProgressEvent> {
+    let events = app.world().resource::<Events<CaptureProgressEvent>>();
+    events.get_cursor().read(events).copied().collect()
+}
+
+#[test]
+fn test_capture_fires_progress_event() {
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    state.board.place_stone(9, 11, Player::Min);
+    state.max_captures = 2;
+
+    let mut app = placement_app(state);
+    app.world_mut().send_event(StonePlacement { x: 9, y: 12 });
+    app.update();
+
+    assert_eq!(
+        capture_events(&app),
+        vec![CaptureProgressEvent { player: Player::Max, new_count: 3, needed: 5 }]
+    );
+}
+
+#[test]
+fn test_quiet_move_fires_no_progress_event() {
+    let mut app = placement_app(GameState::new(19, 5));
+    app.world_mut().send_event(StonePlacement { x: 9, y: 9 });
+    app.update();
+
+    assert!(capture_events(&app).is_empty());
+}
@@ -1,5 +1,7 @@
 use gomoku::core::board::{Board, Player};
 use gomoku::core::captures::CaptureHandler;
+use gomoku::core::moves::MoveHandler;
+use gomoku::core::state::GameState;
 
 #[test]
 fn test_horizontal_capture() {
@@ -161,3 +163,99 @@ fn test_edge_case_captures() {
     assert!(captures.contains(&(0, 1)));
     assert!(captures.contains(&(0, 2)));
 }
+
+#[test]
+fn test_allows_capture_reply() {
+    let mut board = Board::new(19);
+    board.place_stone(9, 8, Player::Min);
+    board.place_stone(9, 9, Player::Max);
+
+    // Makes the pair (9,9)-(9,10) that white takes from (9,11)
+    assert!(MoveHandler::allows_capture_reply(&board, 9, 10, Player::Max));
+    assert_eq!(MoveHandler::find_capturing_moves(&board, Player::Min), Vec::new());
+    assert!(!MoveHandler::allows_capture_reply(&board, 10, 9, Player::Max));
+    assert!(!MoveHandler::allows_capture_reply(&board, 8, 8, Player::Max));
+}
+
+#[test]
+fn test_capture_defense_blocks_the_winning_capture() {
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(5, 4, Player::Min);
+    state.board.place_stone(5, 5, Player::Max);
+    state.board.place_stone(5, 6, Player::Max);
+    state.min_captures = state.capture_win_pairs - 1;
+    state.current_player = Player::Max;
+
+    assert_eq!(MoveHandler::find_capturing_moves(&state.board, Player::Min), vec![(5, 7)]);
+    assert_eq!(state.capture_defense_moves(), Some(vec![(5, 7)]));
+
+    // One pair further from the capture win nothing is urgent
+    state.min_captures -= 1;
+    assert_eq!(state.capture_defense_moves(), None);
+}
+
+#[test]
+fn test_capture_defense_keeps_winning_moves() {
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(5, 4, Player::Min);
+    state.board.place_stone(5, 5, Player::Max);
+    state.board.place_stone(5, 6, Player::Max);
+    for col in 10..14 {
+        state.board.place_stone(12, col, Player::Max);
+    }
+    state.min_captures = state.capture_win_pairs - 1;
+    state.current_player = Player::Max;
+
+    let defense = state.capture_defense_moves().unwrap();
+    assert!(defense.contains(&(5, 7)));
+    assert!(defense.contains(&(12, 9)));
+    assert!(defense.contains(&(12, 14)));
+    assert!(!defense.contains(&(6, 6)));
+}
+
+#[test]
+fn test_captures_for_move_in_all_four_directions() {
+    let mut state = GameState::new(19, 5);
+    // White pairs running away from (9, 9) along each line, closed by black
+    for (dr, dc) in [(0isize, 1isize), (1, 0), (1, 1), (1, -1)] {
+        let at = |k: isize| ((9 + dr * k) as usize, (9 + dc * k) as usize);
+        state.board.place_stone(at(1).0, at(1).1, Player::Min);
+        state.board.place_stone(at(2).0, at(2).1, Player::Min);
+        state.board.place_stone(at(3).0, at(3).1, Player::Max);
+    }
+    let before = state.board.clone();
+
+    let mut captures = state.captures_for_move((9, 9), Player::Max);
+    captures.sort();
+    assert_eq!(
+        captures,
+        vec![(9, 10), (9, 11), (10, 8), (10, 9), (10, 10), (11, 7), (11, 9), (11, 11)]
+    );
+    assert_eq!(state.board, before, "Asking must not touch the board");
+    assert!(state.captures_for_move((9, 9), Player::Min).is_empty(), "White does not capture its own stones");
+}
+
+#[test]
+fn test_captures_for_move_rejects_bad_cells_and_edge_patterns() {
+    let mut state = GameState::new(19, 5);
+    // A white pair against the edge, and one in the open
+    state.board.place_stone(0, 0, Player::Min);
+    state.board.place_stone(0, 1, Player::Min);
+    state.board.place_stone(5, 5, Player::Min);
+    state.board.place_stone(5, 6, Player::Min);
+    state.board.place_stone(5, 7, Player::Max);
+
+    // The pattern runs off the board where the closing black stone would be
+    assert!(state.captures_for_move((0, 2), Player::Max).is_empty());
+    // Occupied and off-board cells capture nothing
+    assert!(state.captures_for_move((5, 5), Player::Max).is_empty());
+    assert!(state.captures_for_move((19, 4), Player::Max).is_empty());
+    assert!(state.captures_for_move((usize::MAX, 4), Player::Max).is_empty());
+
+    let mut captures = state.captures_for_move((5, 4), Player::Max);
+    captures.sort();
+    assert_eq!(captures, vec![(5, 5), (5, 6)]);
+
+    state.rules.captures_enabled = false;
+    assert!(state.captures_for_move((5, 4), Player::Max).is_empty());
+}
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use gomoku::core::board::Player;
+use gomoku::core::state::{GameOutcome, GameState};
+use gomoku::ui::screens::game::clock::{format_clock, tick_game_clock, GameClock};
+use gomoku::ui::screens::game::game::{GameEnded, GameStatus, PonderState};
+
+fn clock_app(state: GameState, clock: GameClock) -> App {
+    let mut app = App::new();
+    app.insert_resource(state)
+        .insert_resource(clock)
+        .init_resource::<Time<Real>>()
+        .init_resource::<GameStatus>()
+        .init_resource::<PonderState>()
+        .add_event::<GameEnded>()
+        .add_systems(Update, tick_game_clock);
+    // The first real-time update only records the starting instant
+    app.world_mut().resource_mut::<Time<Real>>().update_with_duration(Duration::ZERO);
+    app
+}
+
+fn advance(app: &mut App, elapsed: Duration) {
+    app.world_mut().resource_mut::<Time<Real>>().update_with_duration(elapsed);
+    app.update();
+}
+
+#[test]
+fn test_running_out_of_time_loses_the_game() {
+    let mut state = GameState::new(19, 5);
+    state.make_move((9, 9));
+    // White to move with two seconds left
+    let mut app = clock_app(state, GameClock::new(Duration::from_secs(2), Duration::ZERO));
+
+    advance(&mut app, Duration::from_millis(1500));
+    assert!(app.world().resource::<GameState>().outcome().is_none());
+    assert_eq!(app.world().resource::<GameClock>().remaining(Player::Min), Duration::from_millis(500));
+    assert_eq!(app.world().resource::<GameClock>().remaining(Player::Max), Duration::from_secs(2));
+
+    advance(&mut app, Duration::from_millis(800));
+    let state = app.world().resource::<GameState>();
+    assert_eq!(state.winner, Some(Player::Max));
+    assert_eq!(state.outcome(), Some(GameOutcome::Timeout(Player::Max)));
+    assert!(matches!(*app.world().resource::<GameStatus>(), GameStatus::GameOver));
+
+    let events = app.world().resource::<Events<GameEnded>>();
+    let ended: Vec<_> = events.get_cursor().read(events).map(|ev| ev.winner).collect();
+    assert_eq!(ended, vec![Some(Player::Max)]);
+}
+
+#[test]
+fn test_untimed_clock_never_flags() {
+    let mut app = clock_app(GameState::new(19, 5), GameClock::default());
+
+    advance(&mut app, Duration::from_secs(3600));
+    assert!(app.world().resource::<GameState>().outcome().is_none());
+}
+
+#[test]
+fn test_increment_and_ai_budget() {
+    let mut clock = GameClock::new(Duration::from_secs(60), Duration::from_secs(2));
+    assert!(!clock.tick(Player::Max, Duration::from_secs(10)));
+    clock.add_increment(Player::Max);
+    assert_eq!(clock.remaining(Player::Max), Duration::from_secs(52));
+
+    // A share of the clock plus the increment, and never most of what is left
+    assert_eq!(clock.move_budget(Player::Min), Duration::from_secs(5));
+    let low = GameClock::new(Duration::from_secs(2), Duration::from_secs(2));
+    assert_eq!(low.move_budget(Player::Max), Duration::from_secs(1));
+}
+
+#[test]
+fn test_clock_format() {
+    assert_eq!(format_clock(Duration::from_secs(300)), "5:00");
+    assert_eq!(format_clock(Duration::from_secs(75)), "1:15");
+    assert_eq!(format_clock(Duration::from_millis(9_350)), "0:09.3");
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use gomoku::core::board::Player;
+use gomoku::ui::app::GameSettings;
+use gomoku::ui::screens::game::board::StoneStyle;
+use gomoku::ui::screens::game::game::{stone_bundle, style_stones, Stone};
+
+fn stones_app(colorblind_mode: bool) -> App {
+    let mut app = App::new();
+    let mut settings = GameSettings::new();
+    settings.colorblind_mode = colorblind_mode;
+    app.insert_resource(settings).add_systems(Update, style_stones);
+    app.world_mut().spawn(stone_bundle(3, 3, Player::Max));
+    app.world_mut().spawn(stone_bundle(3, 4, Player::Min));
+    app.update();
+    app
+}
+
+/// Fill, border colour, border width and corner radius of the stones, Max first
+fn stone_looks(app: &mut App) -> Vec<(Color, Color, Val, Val)> {
+    let mut query = app.world_mut().query::<(&Stone, &BackgroundColor, &BorderColor, &Node, &BorderRadius)>();
+    let mut looks: Vec<_> = query
+        .iter(app.world())
+        .map(|(stone, fill, border, node, radius)| (stone.0, (fill.0, border.0, node.border.left, radius.top_left)))
+        .collect();
+    looks.sort_by_key(|&(owner, _)| owner != Player::Max);
+    looks.into_iter().map(|(_, look)| look).collect()
+}
+
+#[test]
+fn test_colorblind_toggle_changes_stone_components() {
+    let regular = stone_looks(&mut stones_app(false));
+    let colorblind = stone_looks(&mut stones_app(true));
+    assert_eq!(regular.len(), 2);
+    assert_eq!(colorblind.len(), 2);
+    assert_ne!(regular, colorblind);
+
+    // Regular stones are plain black and white discs
+    assert_eq!(regular[0], (Color::BLACK, Color::NONE, Val::Px(0.0), Val::Percent(50.0)));
+    assert_eq!(regular[1], (Color::WHITE, Color::NONE, Val::Px(0.0), Val::Percent(50.0)));
+
+    // Colorblind stones carry an outline, and the two sides differ in shape
+    let max_style = StoneStyle::for_player(Player::Max, true);
+    let min_style = StoneStyle::for_player(Player::Min, true);
+    assert_eq!(colorblind[0].1, max_style.border);
+    assert_eq!(colorblind[1].1, min_style.border);
+    assert_eq!(colorblind[0].2, Val::Px(max_style.border_width));
+    assert_ne!(colorblind[0].3, colorblind[1].3);
+}
+
+#[test]
+fn test_toggling_mid_game_restyles_existing_stones() {
+    let mut app = stones_app(false);
+    app.world_mut().resource_mut::<GameSettings>().colorblind_mode = true;
+    app.update();
+    assert_eq!(stone_looks(&mut app), stone_looks(&mut stones_app(true)));
+}
@@ -0,0 +1,47 @@
+use gomoku::ai::commentary::describe_move;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+
+#[test]
+fn test_describe_capture() {
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    state.board.place_stone(9, 11, Player::Min);
+
+    assert_eq!(describe_move(&state, (9, 12), Player::Max), "Capturing a pair");
+}
+
+#[test]
+fn test_describe_block() {
+    let mut state = GameState::new(19, 5);
+    // Min has an open three on row 5
+    state.board.place_stone(5, 5, Player::Min);
+    state.board.place_stone(5, 6, Player::Min);
+    state.board.place_stone(5, 7, Player::Min);
+    state.board.place_stone(9, 9, Player::Max);
+
+    assert_eq!(describe_move(&state, (5, 8), Player::Max), "Blocking your four");
+    assert_eq!(describe_move(&state, (5, 4), Player::Max), "Blocking your four");
+
+    state.board.remove_stone(5, 7);
+    assert_eq!(describe_move(&state, (5, 7), Player::Max), "Blocking your open three");
+}
+
+#[test]
+fn test_describe_win_fork_and_extension() {
+    let mut state = GameState::new(19, 5);
+    for col in 5..9 {
+        state.board.place_stone(9, col, Player::Max);
+    }
+    assert_eq!(describe_move(&state, (9, 9), Player::Max), "Completing five in a row");
+
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(9, 7, Player::Max);
+    state.board.place_stone(9, 8, Player::Max);
+    state.board.place_stone(7, 9, Player::Max);
+    state.board.place_stone(8, 9, Player::Max);
+    assert_eq!(describe_move(&state, (9, 9), Player::Max), "Creating a double threat");
+    assert_eq!(describe_move(&state, (9, 6), Player::Max), "Extending to a three");
+    assert_eq!(describe_move(&state, (0, 0), Player::Max), "Developing the position");
+}
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use gomoku::ui::screens::game::board::{BoardUtils, CoordinateLabel};
+
+#[test]
+fn test_label_count_matches_board_size() {
+    for size in 15..=19 {
+        let (columns, rows) = BoardUtils::coordinate_labels(size, true);
+        assert_eq!(columns.len(), size);
+        assert_eq!(rows.len(), size);
+    }
+}
+
+#[test]
+fn test_column_letters_skip_i_when_asked() {
+    let (columns, _) = BoardUtils::coordinate_labels(19, true);
+    assert_eq!(columns.first().map(String::as_str), Some("A"));
+    assert_eq!(columns.last().map(String::as_str), Some("T"));
+    assert!(!columns.contains(&"I".to_string()));
+
+    let (columns, _) = BoardUtils::coordinate_labels(19, false);
+    assert_eq!(columns[8], "I");
+    assert_eq!(columns.last().map(String::as_str), Some("S"));
+}
+
+#[test]
+fn test_rows_count_up_from_the_bottom() {
+    let (_, rows) = BoardUtils::coordinate_labels(15, true);
+    assert_eq!(rows.first().map(String::as_str), Some("15"));
+    assert_eq!(rows.last().map(String::as_str), Some("1"));
+}
+
+fn spawn_labels(mut commands: Commands) {
+    commands.spawn(Node::default()).with_children(|builder| {
+        BoardUtils::draw_coordinates(builder, 17, true);
+    });
+}
+
+#[test]
+fn test_drawn_labels_per_axis() {
+    let mut app = App::new();
+    app.add_systems(Update, spawn_labels);
+    app.update();
+
+    let mut query = app.world_mut().query::<&CoordinateLabel>();
+    let labels: Vec<_> = query.iter(app.world()).copied().collect();
+    // Each axis is labelled on both edges
+    assert_eq!(labels.iter().filter(|&&label| label == CoordinateLabel::Column).count(), 2 * 17);
+    assert_eq!(labels.iter().filter(|&&label| label == CoordinateLabel::Row).count(), 2 * 17);
+}
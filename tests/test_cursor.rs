@@ -0,0 +1,50 @@
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::ui::screens::game::cursor::{cursor_placement, BoardCursor};
+
+#[test]
+fn test_cursor_starts_in_the_middle_hidden() {
+    let cursor = BoardCursor::centered(15);
+    assert_eq!((cursor.x, cursor.y), (7, 7));
+    assert!(!cursor.shown);
+}
+
+#[test]
+fn test_cursor_movement_clamps_at_edges() {
+    let mut cursor = BoardCursor::centered(15);
+    for _ in 0..20 {
+        cursor.step(-1, -1, 15);
+    }
+    assert_eq!((cursor.x, cursor.y), (0, 0));
+    assert!(cursor.shown);
+
+    for _ in 0..20 {
+        cursor.step(1, 0, 15);
+    }
+    cursor.step(0, 1, 15);
+    assert_eq!((cursor.x, cursor.y), (14, 1));
+}
+
+#[test]
+fn test_placement_rejected_on_occupied_cell() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+
+    let mut cursor = BoardCursor::centered(15);
+    assert_eq!(cursor_placement(&state, &cursor), None);
+
+    cursor.step(1, 0, 15);
+    assert_eq!(cursor_placement(&state, &cursor), Some((8, 7)));
+}
+
+#[test]
+fn test_placement_rejected_on_double_three() {
+    let mut state = GameState::new(19, 5);
+    for cell in [(8, 8), (10, 10), (8, 10), (10, 8)] {
+        state.board.place_stone(cell.0, cell.1, Player::Max);
+    }
+    state.current_player = Player::Max;
+
+    let cursor = BoardCursor { x: 9, y: 9, shown: true };
+    assert_eq!(cursor_placement(&state, &cursor), None);
+}
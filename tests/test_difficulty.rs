@@ -0,0 +1,131 @@
+use gomoku::ai::difficulty::{pick_near_equal_move, DIFFICULTY_LEVELS, EASY_SCORE_MARGIN};
+use gomoku::ai::difficulty_params;
+use gomoku::ai::heuristic::Heuristic;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
+use std::collections::HashSet;
+use std::time::Duration;
+
+#[test]
+fn test_levels_are_distinct_and_increasing() {
+    let params: Vec<_> = DIFFICULTY_LEVELS.iter().map(|level| difficulty_params(level)).collect();
+
+    for pair in params.windows(2) {
+        let ((depth_a, time_a, _), (depth_b, time_b, _)) = (pair[0], pair[1]);
+        assert!(depth_a < depth_b, "depth should increase: {} -> {}", depth_a, depth_b);
+        assert!(time_a < time_b, "time should increase: {:?} -> {:?}", time_a, time_b);
+    }
+}
+
+#[test]
+fn test_easy_is_shallow_without_advanced_patterns() {
+    assert_eq!(difficulty_params("easy"), (2, Duration::from_millis(200), false));
+    assert!(difficulty_params("medium").2);
+    assert!(difficulty_params("hard").2);
+}
+
+#[test]
+fn test_unknown_level_plays_as_medium() {
+    assert_eq!(difficulty_params("impossible"), difficulty_params("medium"));
+    assert_eq!(difficulty_params("HARD"), difficulty_params("hard"));
+}
+
+fn one_ply_best(state: &mut GameState) -> (usize, usize) {
+    let player = state.current_player;
+    state
+        .get_possible_moves()
+        .into_iter()
+        .max_by_key(|&mv| {
+            state.make_move(mv);
+            let eval = Heuristic::evaluate(state, 0);
+            state.undo_move(mv);
+            if player == Player::Max { eval } else { -eval }
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_easy_occasionally_picks_non_optimal_move() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    state.make_move((7, 8));
+    let best = one_ply_best(&mut state);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let picks: HashSet<_> = (0..50)
+        .map(|_| pick_near_equal_move(&mut state, EASY_SCORE_MARGIN, &mut rng).unwrap())
+        .collect();
+
+    assert!(picks.len() > 1, "Easy should vary its moves, got {:?}", picks);
+    assert!(picks.iter().any(|&mv| mv != best), "Easy never strayed from {:?}", best);
+}
+
+#[test]
+fn test_easy_still_takes_a_win() {
+    let mut state = GameState::new(15, 5);
+    for col in 3..7 {
+        state.board.place_stone(7, col, Player::Max);
+    }
+    state.board.place_stone(7, 2, Player::Min);
+    state.current_player = Player::Max;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    for _ in 0..20 {
+        assert_eq!(pick_near_equal_move(&mut state, EASY_SCORE_MARGIN, &mut rng), Some((7, 7)));
+    }
+}
+
+#[test]
+fn test_max_depth_caps_the_search() {
+    use gomoku::ai::lazy_smp::{lazy_smp_search_with_config, SearchConfig};
+    use gomoku::ui::app::GameSettings;
+
+    let mut settings = GameSettings::new();
+    settings.set_ai_difficulty("hard");
+    settings.set_max_depth(3);
+    assert_eq!(settings.ai_search_depth(), 3);
+    // Clamped into range
+    settings.set_max_depth(0);
+    assert_eq!(settings.max_depth, 1);
+    settings.set_max_depth(3);
+
+    let mut state = GameState::new(15, 5);
+    for mv in [(7, 7), (7, 8), (8, 8)] {
+        state.make_move(mv);
+    }
+    let config = SearchConfig::new(settings.ai_search_depth(), Some(Duration::from_secs(10)));
+    let result = lazy_smp_search_with_config(&mut state, &config);
+    assert!(result.depth_reached <= 3, "searched to depth {}", result.depth_reached);
+    assert!(result.best_move.is_some());
+}
+
+#[test]
+fn test_both_engines_play_a_legal_move() {
+    use gomoku::ai::difficulty::{EngineKind, ENGINE_KINDS};
+    use gomoku::ai::lazy_smp::{lazy_smp_search_with_config, SearchConfig};
+
+    let mut state = GameState::new(15, 5);
+    for mv in [(7, 7), (7, 8), (8, 7), (6, 9), (9, 7)] {
+        state.make_move(mv);
+    }
+
+    let mut depths = Vec::new();
+    for engine in ENGINE_KINDS {
+        let config = engine.configure(SearchConfig {
+            num_threads: Some(1),
+            ..SearchConfig::new(6, Some(Duration::from_millis(300)))
+        });
+        let result = lazy_smp_search_with_config(&mut state.clone(), &config);
+        let mv = result.best_move.unwrap_or_else(|| panic!("{} engine found no move", engine.name()));
+        assert!(state.is_legal_move(mv).is_ok(), "{} engine played an illegal move", engine.name());
+        depths.push(result.depth_reached);
+    }
+    assert!(depths.iter().all(|&depth| depth >= 1));
+
+    let pruning = EngineKind::Pruning.configure(SearchConfig::new(4, None));
+    assert!(pruning.late_move_reductions);
+    assert_eq!(EngineKind::Advanced.configure(SearchConfig::new(4, None)).threat_space_above, None);
+    // Settings saved before the rename
+    assert_eq!(serde_json::from_str::<EngineKind>("\"LegacyPruning\"").unwrap(), EngineKind::Pruning);
+}
@@ -143,3 +143,57 @@ fn test_creates_double_three_diagonal() {
         // Should not create double-three due to board constraints
         assert!(!RuleValidator::creates_double_three(&board, 0, 1, Player::Max));
     }
+
+#[test]
+fn test_gapped_threes_are_free_threes() {
+    // _X_XX_ and _XX_X_ with the new stone on each of the three stone cells
+    for stones in [[4, 6, 7], [4, 5, 7]] {
+        for &placed in &stones {
+            let mut board = Board::new(19);
+            for &col in stones.iter().filter(|&&col| col != placed) {
+                board.place_stone(9, col, Player::Max);
+            }
+            assert_eq!(
+                RuleValidator::count_free_threes(&board, 9, placed, Player::Max),
+                1,
+                "stones {:?}, placed at {}",
+                stones,
+                placed
+            );
+        }
+    }
+}
+
+#[test]
+fn test_blocked_gapped_three_not_free() {
+    let mut board = Board::new(19);
+    board.place_stone(9, 3, Player::Min);
+    board.place_stone(9, 4, Player::Max);
+    board.place_stone(9, 6, Player::Max);
+    assert_eq!(RuleValidator::count_free_threes(&board, 9, 7, Player::Max), 0);
+
+    // Against the edge there is no room for the open four either
+    let mut board = Board::new(19);
+    board.place_stone(0, 0, Player::Max);
+    board.place_stone(0, 2, Player::Max);
+    assert_eq!(RuleValidator::count_free_threes(&board, 0, 3, Player::Max), 0);
+}
+
+#[test]
+fn test_gapped_and_solid_threes_make_double_three() {
+    let mut board = Board::new(19);
+    // Horizontal X_X? and vertical X?X through (9,9)
+    board.place_stone(9, 6, Player::Max);
+    board.place_stone(9, 8, Player::Max);
+    board.place_stone(8, 9, Player::Max);
+    board.place_stone(10, 9, Player::Max);
+    assert!(RuleValidator::creates_double_three(&board, 9, 9, Player::Max));
+
+    // Two gapped threes, horizontal and diagonal
+    let mut board = Board::new(19);
+    board.place_stone(9, 6, Player::Max);
+    board.place_stone(9, 8, Player::Max);
+    board.place_stone(7, 7, Player::Max);
+    board.place_stone(6, 6, Player::Max);
+    assert!(RuleValidator::creates_double_three(&board, 9, 9, Player::Max));
+}
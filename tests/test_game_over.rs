@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use gomoku::core::board::Player;
+use gomoku::core::state::{GameOutcome, GameState};
+use gomoku::ui::screens::game::game::GameEnded;
+use gomoku::ui::screens::game::game_over::{game_over_message, spawn_game_over_overlay, GameOverAction, GameOverOverlay};
+
+fn overlay_app(state: GameState) -> App {
+    let mut app = App::new();
+    app.insert_resource(state)
+        .add_event::<GameEnded>()
+        .add_systems(Update, spawn_game_over_overlay);
+    app
+}
+
+fn overlay_texts(app: &mut App) -> Vec<String> {
+    let mut query = app.world_mut().query::<&Text>();
+    query.iter(app.world()).map(|text| text.0.clone()).collect()
+}
+
+fn five_in_a_row_state() -> GameState {
+    let mut state = GameState::new(15, 5);
+    for i in 0..4 {
+        state.make_move((7, 3 + i));
+        state.make_move((9, 3 + i));
+    }
+    state.make_move((7, 7));
+    state
+}
+
+#[test]
+fn test_overlay_announces_winner_and_reason() {
+    let state = five_in_a_row_state();
+    assert_eq!(state.outcome(), Some(GameOutcome::FiveInARow(Player::Max)));
+
+    let mut app = overlay_app(state);
+    app.world_mut().send_event(GameEnded { winner: Some(Player::Max) });
+    app.update();
+
+    let mut overlays = app.world_mut().query_filtered::<Entity, With<GameOverOverlay>>();
+    assert_eq!(overlays.iter(app.world()).count(), 1);
+
+    let texts = overlay_texts(&mut app);
+    assert!(texts.contains(&"Black wins!".to_string()), "{:?}", texts);
+    assert!(texts.contains(&"Five in a row".to_string()), "{:?}", texts);
+    assert!(texts.contains(&"Captures - Black: 0  White: 0".to_string()), "{:?}", texts);
+
+    let mut actions = app.world_mut().query::<&GameOverAction>();
+    let mut actions: Vec<_> = actions.iter(app.world()).copied().collect();
+    actions.sort_by_key(|action| *action as u8);
+    assert_eq!(actions, vec![GameOverAction::Rematch, GameOverAction::MainMenu]);
+}
+
+#[test]
+fn test_overlay_is_spawned_once() {
+    let mut app = overlay_app(five_in_a_row_state());
+    app.world_mut().send_event(GameEnded { winner: Some(Player::Max) });
+    app.update();
+    app.world_mut().send_event(GameEnded { winner: Some(Player::Max) });
+    app.update();
+
+    let mut overlays = app.world_mut().query_filtered::<Entity, With<GameOverOverlay>>();
+    assert_eq!(overlays.iter(app.world()).count(), 1);
+}
+
+#[test]
+fn test_no_overlay_while_game_is_running() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+
+    let mut app = overlay_app(state);
+    app.world_mut().send_event(GameEnded { winner: None });
+    app.update();
+
+    let mut overlays = app.world_mut().query_filtered::<Entity, With<GameOverOverlay>>();
+    assert_eq!(overlays.iter(app.world()).count(), 0);
+}
+
+#[test]
+fn test_messages_for_each_outcome() {
+    assert_eq!(
+        game_over_message(GameOutcome::Captures(Player::Min)),
+        ("White wins!".to_string(), "By captures".to_string())
+    );
+    let (headline, detail) = game_over_message(GameOutcome::Draw);
+    assert_eq!(headline, "Draw");
+    assert_ne!(detail, game_over_message(GameOutcome::FiveInARow(Player::Max)).1);
+    assert_eq!(
+        game_over_message(GameOutcome::Resignation(Player::Max)),
+        ("Black wins!".to_string(), "White resigned".to_string())
+    );
+    assert_eq!(game_over_message(GameOutcome::DrawAgreed).0, "Draw");
+}
+
+#[test]
+fn test_draw_needs_the_opponent_to_answer_the_offer() {
+    use gomoku::ui::screens::game::resign::{offer_draw, DrawOffer};
+
+    let mut state = GameState::new(19, 5);
+    state.make_move((9, 9));
+    let mut offer = DrawOffer::default();
+
+    // An offer is not accepted by repeating it
+    assert!(!offer_draw(&mut offer, &state, Player::Min));
+    assert!(!offer_draw(&mut offer, &state, Player::Min));
+    state.make_move((9, 10));
+    assert!(offer_draw(&mut offer, &state, Player::Max));
+    assert_eq!(offer, DrawOffer::default());
+
+    // It lapses once the opponent plays on instead
+    assert!(!offer_draw(&mut offer, &state, Player::Max));
+    state.make_move((10, 10));
+    state.make_move((10, 11));
+    assert!(!offer_draw(&mut offer, &state, Player::Min));
+}
+
+#[test]
+fn test_resignation_opens_the_overlay() {
+    let mut state = GameState::new(19, 5);
+    state.make_move((9, 9));
+    state.resign(Player::Min);
+    let mut app = overlay_app(state);
+    app.world_mut().send_event(GameEnded { winner: Some(Player::Max) });
+    app.update();
+
+    let mut overlays = app.world_mut().query::<&GameOverOverlay>();
+    assert_eq!(overlays.iter(app.world()).count(), 1);
+}
+
+#[test]
+fn test_overlay_points_out_a_missed_win() {
+    use gomoku::ui::screens::game::game_over::{missed_wins, missed_wins_message};
+
+    // White has four on row 12 and plays elsewhere, Black then completes five
+    let mut state = GameState::new(15, 5);
+    for i in 0..4 {
+        state.make_move((7, 3 + i));
+        state.make_move((12, 3 + i));
+    }
+    state.make_move((0, 0));
+    state.make_move((14, 14));
+    state.make_move((7, 7));
+    assert_eq!(state.outcome(), Some(GameOutcome::FiveInARow(Player::Max)));
+
+    let (loser, mut wins) = missed_wins(&state).expect("white had a five");
+    wins.sort();
+    assert_eq!((loser, wins), (Player::Min, vec![(12, 2), (12, 7)]));
+    assert_eq!(missed_wins_message(&state, true), Some("White had a win at N13, N8".to_string()));
+
+    let mut app = overlay_app(state);
+    app.world_mut().send_event(GameEnded { winner: Some(Player::Max) });
+    app.update();
+    assert!(overlay_texts(&mut app).contains(&"White had a win at N13, N8".to_string()));
+
+    // Without a four of its own the loser missed nothing
+    let mut state = GameState::new(15, 5);
+    for (i, reply) in [(0, 0), (14, 14), (0, 14), (14, 0)].into_iter().enumerate() {
+        state.make_move((7, 3 + i));
+        state.make_move(reply);
+    }
+    state.make_move((7, 7));
+    assert_eq!(state.outcome(), Some(GameOutcome::FiveInARow(Player::Max)));
+    assert_eq!(missed_wins(&state), None);
+}
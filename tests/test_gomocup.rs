@@ -0,0 +1,134 @@
+use gomoku::ai::time_manager;
+use gomoku::core::board::Player;
+use gomoku::interface::gomocup::{run_protocol, GomocupSession, TimeControl};
+use std::io::Cursor;
+use std::time::Duration;
+
+fn run_script(script: &str) -> Vec<String> {
+    let mut output = Vec::new();
+    run_protocol(Cursor::new(script), &mut output).unwrap();
+    String::from_utf8(output).unwrap().lines().map(str::to_string).collect()
+}
+
+fn parse_reply(reply: &str) -> (usize, usize) {
+    let (x, y) = reply.split_once(',').expect("move reply should be x,y");
+    (x.parse().unwrap(), y.parse().unwrap())
+}
+
+#[test]
+fn test_scripted_session_answers_legal_moves() {
+    let replies = run_script(
+        "START 15\nINFO timeout_turn 300\nINFO timeout_match 100000\nINFO time_left 100000\nBEGIN\nTURN 1,1\nTURN 13,13\nEND\nTURN 0,0\n",
+    );
+
+    assert_eq!(replies.len(), 4, "unexpected replies: {:?}", replies);
+    assert_eq!(replies[0], "OK");
+
+    // The opponent plays far away so the engine's answers cannot collide with it
+    let mut occupied = vec![(1, 1), (13, 13)];
+    for reply in &replies[1..] {
+        let (x, y) = parse_reply(reply);
+        assert!(x < 15 && y < 15, "reply {} is off the board", reply);
+        assert!(!occupied.contains(&(x, y)), "reply {} lands on an occupied cell", reply);
+        occupied.push((x, y));
+    }
+    // First move goes to the center
+    assert_eq!(parse_reply(&replies[1]), (7, 7));
+}
+
+#[test]
+fn test_turn_maps_x_y_to_column_row() {
+    let mut session = GomocupSession::new();
+    session.handle_line("START 15");
+    session.handle_line("INFO timeout_turn 200");
+    session.handle_line("TURN 3,7");
+
+    let state = session.state.as_ref().unwrap();
+    // x is the column and y the row
+    assert_eq!(state.moves[0].0, 7);
+    assert_eq!(state.moves[0].1, 3);
+}
+
+#[test]
+fn test_board_command_rebuilds_position() {
+    let replies = run_script(
+        "START 15\nINFO timeout_turn 300\nBOARD\n7,7,1\n8,7,2\n7,8,1\n9,7,2\nDONE\nEND\n",
+    );
+
+    assert_eq!(replies.len(), 2);
+    let (x, y) = parse_reply(&replies[1]);
+    assert!(![(7, 7), (8, 7), (7, 8), (9, 7)].contains(&(x, y)));
+}
+
+#[test]
+fn test_errors_and_unknown_commands() {
+    let replies = run_script("TURN 1,1\nSTART 99\nSTART 15\nTURN 20,3\nFOO\nEND\n");
+
+    assert!(replies[0].starts_with("ERROR"));
+    assert!(replies[1].starts_with("ERROR"));
+    assert_eq!(replies[2], "OK");
+    assert!(replies[3].starts_with("ERROR"));
+    assert!(replies[4].starts_with("UNKNOWN"));
+}
+
+#[test]
+fn test_move_budget_respects_match_clock() {
+    let control = TimeControl { timeout_turn: 5000, timeout_match: 0, time_left: Some(1000) };
+    assert_eq!(control.move_budget(20, 15), Duration::from_millis(4000));
+
+    let control = TimeControl { timeout_turn: 5000, timeout_match: 60000, time_left: Some(1000) };
+    assert_eq!(control.move_budget(20, 15), time_manager::allocate(Duration::from_millis(1000), 20, 15));
+    assert!(control.move_budget(20, 15) < Duration::from_millis(100));
+}
+
+#[test]
+fn test_engine_binary_prints_nothing_but_protocol_replies() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    // No logger is set up in the engine binary, so the search stays off stdout
+    let mut engine = Command::new(env!("CARGO_BIN_EXE_gomocup"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("engine binary should start");
+    engine
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"START 15\nINFO timeout_turn 300\nBEGIN\nTURN 6,6\nTURN 9,9\nEND\n")
+        .unwrap();
+    let output = engine.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 4, "stdout should only hold the replies: {:?}", lines);
+    assert_eq!(lines[0], "OK");
+    for reply in &lines[1..] {
+        let (x, y) = parse_reply(reply);
+        assert!(x < 15 && y < 15, "reply {} is off the board", reply);
+    }
+}
+
+#[test]
+fn test_manager_moves_never_capture() {
+    let mut session = GomocupSession::new();
+    session.handle_line("START 15");
+    session.handle_line("INFO timeout_turn 300");
+    // The engine's pair at 1,0 and 2,0 is flanked by 0,0; the open three on row 10 keeps
+    // the engine busy elsewhere
+    for line in ["BOARD", "0,0,2", "1,0,1", "2,0,1", "7,10,2", "8,10,2", "9,10,2", "12,12,1"] {
+        session.handle_line(line);
+    }
+    let (replies, _) = session.handle_line("DONE");
+    assert_ne!(replies, vec!["3,0".to_string()], "the engine should answer the open three");
+
+    session.handle_line("TURN 3,0");
+    let state = session.state.as_ref().unwrap();
+    assert!(!state.rules.captures_enabled);
+    assert_eq!(state.board.get_player(0, 1), Some(Player::Min));
+    assert_eq!(state.board.get_player(0, 2), Some(Player::Min));
+    assert_eq!((state.max_captures, state.min_captures), (0, 0));
+}
@@ -1,4 +1,4 @@
-use gomoku::ai::heuristic::Heuristic;
+use gomoku::ai::heuristic::{AdvancedPatternType, Heuristic};
 use gomoku::core::board::Player;
 use gomoku::core::state::GameState;
 
@@ -85,6 +85,56 @@ fn test_heuristic_capture_advantage() {
     assert!(score >= 2000);
 }
 
+/// Max stone at (5,5) flanking the Min pair at (5,6)-(5,7), or with `threat` false the
+/// same stones with the second Min one at (6,7) instead
+fn capture_threat_state(threat: bool, captures_enabled: bool) -> GameState {
+    let mut state = GameState::new(19, 5);
+    state.rules.captures_enabled = captures_enabled;
+    state.board.place_stone(5, 5, Player::Max);
+    state.board.place_stone(5, 6, Player::Min);
+    state.board.place_stone(if threat { 5 } else { 6 }, 7, Player::Min);
+    state.board.place_stone(12, 12, Player::Min);
+    state
+}
+
+/// How much more Max gets from the threatening board than the quiet one
+fn threat_gain(captures_enabled: bool) -> i32 {
+    Heuristic::evaluate(&capture_threat_state(true, captures_enabled), 0)
+        - Heuristic::evaluate(&capture_threat_state(false, captures_enabled), 0)
+}
+
+#[test]
+fn test_capture_threat_scores_higher_for_the_threatening_side() {
+    let threat = capture_threat_state(true, true);
+    let quiet = capture_threat_state(false, true);
+    assert!(Heuristic::evaluate_capture_threats(&threat.board, Player::Max) > 0);
+    assert_eq!(Heuristic::evaluate_capture_threats(&quiet.board, Player::Max), 0);
+    assert_eq!(Heuristic::evaluate_capture_threats(&threat.board, Player::Min), 0);
+    // The stones also shape different lines, so the threat is measured against the same
+    // boards without the capture rule
+    assert_eq!(
+        threat_gain(true) - threat_gain(false),
+        Heuristic::evaluate_capture_threats(&threat.board, Player::Max)
+    );
+
+    // A pair closed off on both sides is no threat
+    let mut blocked = capture_threat_state(true, true);
+    blocked.board.place_stone(5, 8, Player::Min);
+    assert_eq!(Heuristic::evaluate_capture_threats(&blocked.board, Player::Max), 0);
+}
+
+#[test]
+fn test_capture_threat_weighs_more_near_a_capture_win() {
+    let with_captures = |max_captures| {
+        let mut state = capture_threat_state(true, true);
+        state.max_captures = max_captures;
+        Heuristic::evaluate(&state, 0) - max_captures as i32 * 1_000
+    };
+    let early = with_captures(0);
+    let late = with_captures(4);
+    assert!(late > early, "one pair short of a capture win, the threat should count for more");
+}
+
 #[test]
 fn test_heuristic_line_evaluation() {
     let mut state = GameState::new(19, 5);
@@ -396,6 +446,8 @@ fn test_heuristic_space_with_obstacles() {
     state.board.place_stone(9, 7, Player::Max);
     // positions 8,9 are empty
     state.board.place_stone(9, 10, Player::Min); // Obstacle on right
+    // Min could capture the pair at (9,8); only the line space is under test here
+    state.rules.captures_enabled = false;
 
     let score = Heuristic::evaluate(&state, 1);
     
@@ -569,3 +621,381 @@ fn test_heuristic_multiple_half_free_fours() {
     // Should get winning threat bonus for multiple half-free fours
     assert!(score >= 10000, "Multiple half-free fours should get threat bonus: {}", score);
 }
+
+#[test]
+fn test_heuristic_double_four_near_winning() {
+    let mut state = GameState::new(19, 5);
+
+    // Horizontal half-free four: O X X X X .
+    state.board.place_stone(9, 4, Player::Min);
+    for col in 5..=8 {
+        state.board.place_stone(9, col, Player::Max);
+    }
+
+    let single_four_score = Heuristic::evaluate(&state, 1);
+
+    // Vertical half-free four through (9, 8), completing a 4-4 fork
+    state.board.place_stone(5, 8, Player::Min);
+    for row in 6..=8 {
+        state.board.place_stone(row, 8, Player::Max);
+    }
+
+    let double_four_score = Heuristic::evaluate(&state, 1);
+
+    assert!(single_four_score < 20_000, "Single four should stay well below winning: {}", single_four_score);
+    assert!(double_four_score >= 500_000, "Double four should score near the winning sentinel: {}", double_four_score);
+    assert!(double_four_score < 1_000_000, "Double four is not yet a completed win: {}", double_four_score);
+}
+
+#[test]
+fn test_heuristic_four_three_combination() {
+    let mut state = GameState::new(19, 5);
+
+    // Half-free four: O X X X X .
+    state.board.place_stone(5, 3, Player::Min);
+    for col in 4..=7 {
+        state.board.place_stone(5, col, Player::Max);
+    }
+
+    // Open three elsewhere: . X X X .
+    for col in 8..=10 {
+        state.board.place_stone(9, col, Player::Max);
+    }
+
+    let score = Heuristic::evaluate(&state, 1);
+    assert!((100_000..500_000).contains(&score), "Four plus open three should score as a forcing win: {}", score);
+}
+
+#[test]
+fn test_score_to_human_bands() {
+    assert_eq!(Heuristic::score_to_human(0, 4), "roughly equal");
+    assert_eq!(Heuristic::score_to_human(199, 4), "roughly equal");
+    assert_eq!(Heuristic::score_to_human(200, 4), "Max slightly better");
+    assert_eq!(Heuristic::score_to_human(-200, 4), "Min slightly better");
+    assert_eq!(Heuristic::score_to_human(1_000, 4), "Max better");
+    assert_eq!(Heuristic::score_to_human(15_200, 4), "Max clearly better");
+    assert_eq!(Heuristic::score_to_human(-15_200, 4), "Min clearly better");
+    assert_eq!(Heuristic::score_to_human(100_000, 4), "Max winning");
+    assert_eq!(Heuristic::score_to_human(-999_999, 4), "Min winning");
+}
+
+#[test]
+fn test_score_to_human_mate() {
+    // Win found right after the root move of a depth-6 search
+    assert_eq!(Heuristic::mate_in(1_000_005, 6), Some(1));
+    assert_eq!(Heuristic::score_to_human(1_000_005, 6), "Max mate in 1");
+
+    // Win found five plies deep: three moves for the winning side
+    assert_eq!(Heuristic::mate_in(-1_000_001, 6), Some(5));
+    assert_eq!(Heuristic::score_to_human(-1_000_001, 6), "Min mate in 3");
+
+    assert_eq!(Heuristic::mate_in(15_000, 6), None);
+}
+
+#[test]
+fn test_double_four_fork_point_is_detected() {
+    use gomoku::ai::heuristic::AdvancedPattern;
+
+    let mut state = GameState::new(19, 5);
+    for (row, col) in [(9, 6), (9, 7), (9, 8), (6, 9), (7, 9), (8, 9)] {
+        state.board.place_stone(row, col, Player::Max);
+    }
+
+    assert_eq!(
+        Heuristic::detect_double_four(&state.board, Player::Max, 5),
+        vec![AdvancedPattern::DoubleFour { row: 9, col: 9 }]
+    );
+    assert!(Heuristic::detect_double_four(&state.board, Player::Min, 5).is_empty());
+}
+
+#[test]
+fn test_two_fours_on_one_line_make_a_fork_point() {
+    use gomoku::ai::heuristic::AdvancedPattern;
+
+    // X_X_X_X: (9, 6) gives X_XXX_X, a four through either gap
+    let mut state = GameState::new(19, 5);
+    for col in [3, 5, 7, 9] {
+        state.board.place_stone(9, col, Player::Max);
+    }
+
+    assert!(
+        Heuristic::detect_double_four(&state.board, Player::Max, 5).contains(&AdvancedPattern::DoubleFour { row: 9, col: 6 })
+    );
+}
+
+#[test]
+fn test_double_four_fork_scores_between_open_four_and_five() {
+    let mut fork = GameState::new(19, 5);
+    for (row, col) in [(9, 6), (9, 7), (9, 8), (6, 9), (7, 9), (8, 9)] {
+        fork.board.place_stone(row, col, Player::Max);
+    }
+
+    let mut open_four = GameState::new(19, 5);
+    for col in 5..=8 {
+        open_four.board.place_stone(9, col, Player::Max);
+    }
+
+    let mut five = open_four.clone();
+    five.current_player = Player::Max;
+    five.make_move((9, 9));
+
+    let fork_score = Heuristic::evaluate(&fork, 0);
+    assert!(fork_score > Heuristic::evaluate(&open_four, 0), "fork scored {}", fork_score);
+    assert!(fork_score < Heuristic::evaluate(&five, 0), "fork scored {}", fork_score);
+}
+
+#[test]
+fn test_single_four_is_not_a_fork() {
+    let mut state = GameState::new(19, 5);
+    for col in 5..=8 {
+        state.board.place_stone(9, col, Player::Max);
+    }
+    for (row, col) in [(3, 3), (15, 15)] {
+        state.board.place_stone(row, col, Player::Max);
+    }
+
+    assert!(Heuristic::detect_double_four(&state.board, Player::Max, 5).is_empty());
+}
+
+#[test]
+fn test_incremental_eval_matches_full_scan_through_random_play() {
+    use rand::Rng;
+    use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
+
+    let mut state = GameState::new(19, 5);
+    // Black takes two pairs before the random part starts
+    for mv in [(9, 9), (9, 10), (12, 0), (9, 11), (9, 12), (12, 1), (0, 0), (12, 2), (12, 3)] {
+        state.make_move(mv);
+        assert_eq!(state.incremental_eval(), Heuristic::line_score_full_scan(&state));
+    }
+    assert_eq!(state.max_captures, 2);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut played = Vec::new();
+    for _ in 0..30 {
+        if state.is_terminal() {
+            break;
+        }
+        let moves = state.get_possible_moves();
+        let mv = moves[rng.random_range(0..moves.len())];
+        state.make_move(mv);
+        played.push(mv);
+        assert_eq!(state.incremental_eval(), Heuristic::line_score_full_scan(&state), "after {:?}", played);
+    }
+
+    while let Some(mv) = played.pop() {
+        state.undo_move(mv);
+        assert_eq!(state.incremental_eval(), Heuristic::line_score_full_scan(&state), "undoing {:?}", mv);
+    }
+}
+
+fn assert_forks_match_full_scan(state: &GameState, context: &str) {
+    for player in [Player::Max, Player::Min] {
+        assert_eq!(
+            state.line_eval.has_fork(player),
+            !Heuristic::detect_double_four(&state.board, player, state.win_condition).is_empty(),
+            "{:?} forks {}",
+            player,
+            context
+        );
+    }
+}
+
+#[test]
+fn test_fork_points_follow_moves_and_undos() {
+    use rand::Rng;
+    use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
+
+    let mut state = GameState::new(19, 5);
+    let mut played = Vec::new();
+    // Max builds the 4-4 fork point (9, 9) and Min takes it
+    for mv in [(9, 6), (0, 0), (9, 7), (0, 2), (9, 8), (0, 4), (6, 9), (0, 6), (7, 9), (0, 8), (8, 9), (9, 9)] {
+        state.make_move(mv);
+        played.push(mv);
+        assert_forks_match_full_scan(&state, &format!("after {:?}", played));
+        if played.len() == 11 {
+            assert!(state.line_eval.has_fork(Player::Max));
+        }
+    }
+    while let Some(mv) = played.pop() {
+        state.undo_move(mv);
+        assert_forks_match_full_scan(&state, &format!("undoing {:?}", mv));
+    }
+
+    // A small board fills up fast, with forks and captures along the way
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let mut state = GameState::new(9, 5);
+    for _ in 0..50 {
+        if state.is_terminal() {
+            break;
+        }
+        let moves = state.get_possible_moves();
+        let mv = moves[rng.random_range(0..moves.len())];
+        state.make_move(mv);
+        played.push(mv);
+        assert_forks_match_full_scan(&state, &format!("after {:?}", played));
+    }
+    while let Some(mv) = played.pop() {
+        state.undo_move(mv);
+        assert_forks_match_full_scan(&state, &format!("undoing {:?}", mv));
+    }
+}
+
+#[test]
+fn test_incremental_eval_notices_board_edited_directly() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    for col in 8..10 {
+        state.board.place_stone(7, col, Player::Max);
+    }
+    assert_eq!(state.incremental_eval(), Heuristic::line_score_full_scan(&state));
+
+    state.make_move((8, 8));
+    assert_eq!(state.incremental_eval(), Heuristic::line_score_full_scan(&state));
+}
+
+/// Position after `count` random legal moves from a seeded stream
+#[cfg(debug_assertions)]
+fn random_position(seed: u64, count: usize, captures_enabled: bool) -> GameState {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut state = GameState::new(15, 5);
+    state.rules.captures_enabled = captures_enabled;
+    for _ in 0..count {
+        let moves = state.get_possible_moves();
+        if state.is_terminal() || moves.is_empty() {
+            break;
+        }
+        state.make_move(moves[rng.random_range(0..moves.len())]);
+    }
+    state
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn test_evaluation_is_antisymmetric_on_random_positions() {
+    for seed in 0..20 {
+        for count in [3, 10, 25] {
+            Heuristic::assert_antisymmetric(&random_position(seed, count, false));
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn test_evaluation_is_antisymmetric_with_captures() {
+    // Both colours can take a pair, with unequal capture counts
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(5, 5, Player::Max);
+    state.board.place_stone(5, 6, Player::Min);
+    state.board.place_stone(5, 7, Player::Min);
+    state.board.place_stone(12, 12, Player::Min);
+    state.board.place_stone(12, 13, Player::Max);
+    state.board.place_stone(12, 14, Player::Max);
+    state.max_captures = 3;
+    state.min_captures = 1;
+    Heuristic::assert_antisymmetric(&state);
+
+    for seed in 0..10 {
+        Heuristic::assert_antisymmetric(&random_position(seed, 30, true));
+    }
+}
+
+#[test]
+fn test_threats_at_fork_point_reports_several_kinds() {
+    let mut state = GameState::new(19, 5);
+    // Black row 9 XXX_ and column 12 X_X: (9, 12) makes a four and an open three
+    for (row, col) in [(9, 9), (9, 10), (9, 11), (7, 12), (8, 12)] {
+        state.board.place_stone(row, col, Player::Max);
+    }
+    // The White pair on the diagonal below is taken by the same stone
+    state.board.place_stone(10, 13, Player::Min);
+    state.board.place_stone(11, 14, Player::Min);
+    state.board.place_stone(12, 15, Player::Max);
+
+    let threats = Heuristic::threats_at(&state.board, 9, 12, Player::Max, state.win_condition);
+    assert!(threats.contains(&AdvancedPatternType::Fork), "{:?}", threats);
+    assert!(threats.contains(&AdvancedPatternType::Four), "{:?}", threats);
+    assert!(threats.contains(&AdvancedPatternType::OpenThree), "{:?}", threats);
+    assert!(threats.contains(&AdvancedPatternType::Capture), "{:?}", threats);
+    let mut kinds = threats.clone();
+    kinds.dedup();
+    assert_eq!(kinds, threats);
+
+    // Filling the gap of XX_XX is a split four, not a solid one
+    let mut split = GameState::new(19, 5);
+    for col in [3, 4, 7] {
+        split.board.place_stone(4, col, Player::Min);
+    }
+    let threats = Heuristic::threats_at(&split.board, 4, 6, Player::Min, split.win_condition);
+    assert_eq!(threats, vec![AdvancedPatternType::SplitFour]);
+}
+
+#[test]
+fn test_threats_at_dead_cell_reports_nothing() {
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    assert!(Heuristic::threats_at(&state.board, 0, 0, Player::Max, state.win_condition).is_empty());
+    assert!(Heuristic::threats_at(&state.board, 9, 11, Player::Max, state.win_condition).is_empty());
+    // An occupied cell makes nothing either
+    assert!(Heuristic::threats_at(&state.board, 9, 9, Player::Max, state.win_condition).is_empty());
+}
+
+#[test]
+fn test_threats_at_follows_the_win_condition() {
+    let mut state = GameState::new(8, 4);
+    state.board.place_stone(3, 3, Player::Max);
+    state.board.place_stone(3, 4, Player::Max);
+
+    let threats = Heuristic::threats_at(&state.board, 3, 5, Player::Max, state.win_condition);
+    assert!(threats.contains(&AdvancedPatternType::Four), "{:?}", threats);
+    assert!(!Heuristic::threats_at(&state.board, 3, 5, Player::Max, 5).contains(&AdvancedPatternType::Four));
+}
+
+#[test]
+fn test_terminal_score_prefers_the_quicker_win() {
+    // A win one ply below the root of a depth-4 search against one three plies down
+    assert!(Heuristic::terminal_score(Player::Max, 3) > Heuristic::terminal_score(Player::Max, 1));
+    assert!(Heuristic::terminal_score(Player::Min, 3) < Heuristic::terminal_score(Player::Min, 1));
+    // Below the horizon, as quiescence scores it
+    assert!(Heuristic::terminal_score(Player::Max, 0) > Heuristic::terminal_score(Player::Max, -2));
+
+    let mut state = GameState::new(19, 5);
+    state.max_captures = 5;
+    assert_eq!(Heuristic::evaluate(&state, 3), Heuristic::terminal_score(Player::Max, 3));
+}
+
+#[test]
+fn test_capture_mate_in_one_outscores_mate_in_three() {
+    use gomoku::ai::minimax::mtdf;
+    use gomoku::ai::transposition::TranspositionTable;
+    use gomoku::core::rules::RulePreset;
+    use std::time::Instant;
+
+    let search = |state: &mut GameState| {
+        let mut tt = TranspositionTable::default();
+        mtdf(state, 0, 4, &mut tt, &Instant::now(), None).0
+    };
+
+    // Max takes the fifth pair at (9,12)
+    let mut capture_win = GameState::new(19, 5);
+    capture_win.max_captures = 4;
+    for (row, col, player) in [(9, 9, Player::Max), (9, 10, Player::Min), (9, 11, Player::Min), (3, 3, Player::Min)] {
+        capture_win.board.place_stone(row, col, player);
+    }
+    let mate_in_one = search(&mut capture_win);
+
+    // An open three: an open four next, then five whichever end Min blocks
+    let mut line_win = GameState::with_preset(19, RulePreset::ClassicGomoku);
+    for (row, col, player) in [(9, 7, Player::Max), (9, 8, Player::Max), (9, 9, Player::Max), (2, 2, Player::Min), (2, 16, Player::Min), (16, 2, Player::Min)] {
+        line_win.board.place_stone(row, col, player);
+    }
+    let mate_in_three = search(&mut line_win);
+
+    assert_eq!(Heuristic::mate_in(mate_in_one, 4), Some(1));
+    assert_eq!(Heuristic::mate_in(mate_in_three, 4), Some(3));
+    assert!(mate_in_one > mate_in_three);
+}
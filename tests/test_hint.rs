@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::ui::screens::game::board::BoardUtils;
+use gomoku::ui::screens::game::game::MovePlayed;
+use gomoku::ui::screens::game::hint::{clear_hint, suggest_move, update_hint_marker, Hint, HintMarker};
+
+#[test]
+fn test_hint_finds_the_winning_move() {
+    let mut state = GameState::new(19, 5);
+    for col in 5..=8 {
+        state.board.place_stone(9, col, Player::Max);
+    }
+    state.board.place_stone(9, 4, Player::Min);
+    state.board.place_stone(10, 10, Player::Min);
+    state.current_player = Player::Max;
+
+    assert_eq!(suggest_move(&state), Some((9, 9)));
+}
+
+#[test]
+fn test_hint_is_always_a_legal_move() {
+    let mut state = GameState::new(19, 5);
+    for mv in [(9, 9), (10, 10), (9, 7), (11, 11), (7, 9)] {
+        state.make_move(mv);
+    }
+
+    let hint = suggest_move(&state).expect("a hint on a non-empty board");
+    assert!(state.get_possible_moves().contains(&hint), "{:?} is not legal", hint);
+}
+
+fn marker(app: &mut App) -> (Val, Val, Visibility) {
+    let mut query = app.world_mut().query_filtered::<(&Node, &Visibility), With<HintMarker>>();
+    let (node, visibility) = query.single(app.world()).unwrap();
+    (node.left, node.top, *visibility)
+}
+
+#[test]
+fn test_hint_marker_shows_and_clears_on_move() {
+    let mut app = App::new();
+    app.init_resource::<Hint>()
+        .add_event::<MovePlayed>()
+        .add_systems(Update, (clear_hint.run_if(on_event::<MovePlayed>), update_hint_marker).chain());
+    app.world_mut().spawn((Node::default(), Visibility::Hidden, HintMarker));
+
+    app.world_mut().resource_mut::<Hint>().0 = Some((3, 4));
+    app.update();
+    let expected = BoardUtils::stone_node(3, 4, BoardUtils::PREVIEW_SIZE);
+    assert_eq!(marker(&mut app), (expected.left, expected.top, Visibility::Visible));
+
+    app.world_mut().send_event(MovePlayed);
+    app.update();
+    assert_eq!(app.world().resource::<Hint>().0, None);
+    assert_eq!(marker(&mut app).2, Visibility::Hidden);
+}
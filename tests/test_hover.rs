@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::ui::app::GameSettings;
+use gomoku::ui::screens::game::board::{BoardUtils, StoneStyle};
+use gomoku::ui::screens::game::game::{GameStatus, GridCell};
+use gomoku::ui::screens::game::hover::{
+    hover_capture_preview, hover_preview_style, spawn_hover_preview, update_hover_preview, HoverPreview,
+};
+
+/// Black to move with (6,8) a forbidden double-three: two open twos cross there
+fn double_three_state() -> GameState {
+    let mut state = GameState::new(19, 5);
+    for (row, col) in [(5, 7), (5, 9), (6, 6), (6, 10), (7, 7), (7, 9)] {
+        state.board.place_stone(row, col, Player::Max);
+    }
+    state.board.place_stone(0, 0, Player::Min);
+    state.current_player = Player::Max;
+    state
+}
+
+#[test]
+fn test_hover_preview_style_per_cell() {
+    let state = double_three_state();
+
+    // Occupied: no preview at all
+    assert_eq!(hover_preview_style(&state, (5, 7), false), None);
+
+    // Legal: the side to move's stone, see-through
+    let legal = state.get_possible_moves()[0];
+    let style = hover_preview_style(&state, legal, false).expect("a preview");
+    assert_eq!(style.fill, Color::BLACK.with_alpha(0.5));
+    assert_eq!(style.corner_radius, StoneStyle::for_player(Player::Max, false).corner_radius);
+
+    // Forbidden double-three: red
+    assert!(!state.get_possible_moves().contains(&(6, 8)));
+    let red = hover_preview_style(&state, (6, 8), false).expect("a red preview");
+    assert_ne!(red.fill, style.fill);
+    assert!(red.fill.to_srgba().red > 0.8 && red.fill.to_srgba().alpha < 1.0);
+
+    // White sees a white stone, in the colorblind shape when asked
+    let mut white = state.clone();
+    white.current_player = Player::Min;
+    let style = hover_preview_style(&white, (6, 8), true).expect("legal for white");
+    assert_eq!(style.fill, StoneStyle::for_player(Player::Min, true).fill.with_alpha(0.5));
+    assert_eq!(style.corner_radius, StoneStyle::for_player(Player::Min, true).corner_radius);
+}
+
+#[test]
+fn test_no_hover_preview_once_the_game_is_over() {
+    let mut state = GameState::new(19, 5);
+    for col in 0..5 {
+        state.make_move((9, 5 + col));
+        if col < 4 {
+            state.make_move((11, 5 + col));
+        }
+    }
+    assert!(state.is_terminal());
+    assert_eq!(hover_preview_style(&state, (9, 4), false), None);
+}
+
+#[test]
+fn test_hover_capture_preview_lists_the_captured_pairs() {
+    // Black at (9,9) would flank a white pair to the right and another one below
+    let mut state = GameState::new(19, 5);
+    for (row, col) in [(9, 12), (12, 9), (3, 3)] {
+        state.board.place_stone(row, col, Player::Max);
+    }
+    for (row, col) in [(9, 10), (9, 11), (10, 9), (11, 9), (10, 10)] {
+        state.board.place_stone(row, col, Player::Min);
+    }
+    state.current_player = Player::Max;
+
+    let mut captured = hover_capture_preview(&state, (9, 9));
+    captured.sort();
+    assert_eq!(captured, vec![(9, 10), (9, 11), (10, 9), (11, 9)]);
+
+    // A quiet move, an occupied cell, or White to move: nothing to highlight
+    assert!(hover_capture_preview(&state, (0, 0)).is_empty());
+    assert!(hover_capture_preview(&state, (9, 10)).is_empty());
+    state.current_player = Player::Min;
+    assert!(hover_capture_preview(&state, (9, 9)).is_empty());
+}
+
+fn hover_app(status: GameStatus) -> App {
+    let mut settings = GameSettings::new();
+    settings.versus_ai = false;
+    let mut app = App::new();
+    app.insert_resource(double_three_state())
+        .insert_resource(settings)
+        .insert_resource(status)
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn(Node::default()).with_children(spawn_hover_preview);
+        })
+        .add_systems(Update, update_hover_preview);
+    app.world_mut().spawn((Interaction::None, GridCell { x: 6, y: 8 }));
+    app.world_mut().spawn((Interaction::None, GridCell { x: 5, y: 7 }));
+    app
+}
+
+fn hover(app: &mut App, cell: (usize, usize)) {
+    let mut cells = app.world_mut().query::<(&mut Interaction, &GridCell)>();
+    for (mut interaction, grid) in cells.iter_mut(app.world_mut()) {
+        *interaction = if (grid.x, grid.y) == cell { Interaction::Hovered } else { Interaction::None };
+    }
+    app.update();
+}
+
+fn preview(app: &mut App) -> (Visibility, Color, Val) {
+    let mut query = app.world_mut().query_filtered::<(&Visibility, &BackgroundColor, &Node), With<HoverPreview>>();
+    let (visibility, fill, node) = query.single(app.world()).expect("one preview");
+    (*visibility, fill.0, node.left)
+}
+
+#[test]
+fn test_hover_moves_and_hides_the_preview() {
+    let mut app = hover_app(GameStatus::AwaitingUserInput);
+    app.update();
+    assert_eq!(preview(&mut app).0, Visibility::Hidden);
+
+    hover(&mut app, (6, 8));
+    let (visibility, fill, left) = preview(&mut app);
+    assert_eq!(visibility, Visibility::Visible);
+    assert_eq!(Some(fill), hover_preview_style(&double_three_state(), (6, 8), false).map(|style| style.fill));
+    assert_eq!(left, BoardUtils::stone_node(6, 8, BoardUtils::STONE_SIZE).left);
+
+    // Over a stone, or with the pointer off the board, nothing shows
+    hover(&mut app, (5, 7));
+    assert_eq!(preview(&mut app).0, Visibility::Hidden);
+    hover(&mut app, (6, 8));
+    hover(&mut app, (99, 99));
+    assert_eq!(preview(&mut app).0, Visibility::Hidden);
+
+    // Nor while the AI is thinking
+    let mut app = hover_app(GameStatus::AiThinking);
+    hover(&mut app, (6, 8));
+    assert_eq!(preview(&mut app).0, Visibility::Hidden);
+}
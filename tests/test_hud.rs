@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::ui::screens::game::game::{spawn_game_hud, update_game_hud, HudText};
+
+fn hud_app(state: GameState) -> App {
+    let mut app = App::new();
+    app.insert_resource(state)
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn(Node::default()).with_children(spawn_game_hud);
+        })
+        .add_systems(Update, update_game_hud);
+    app
+}
+
+fn hud(app: &mut App, wanted: HudText) -> (String, Color) {
+    let mut query = app.world_mut().query::<(&Text, &TextColor, &HudText)>();
+    query
+        .iter(app.world())
+        .find(|(_, _, hud)| **hud == wanted)
+        .map(|(text, color, _)| (text.0.clone(), color.0))
+        .unwrap()
+}
+
+/// Black plays (9, 12) and (12, 3) to take the pairs flanked by its stones
+fn capturing_game() -> GameState {
+    let mut state = GameState::new(19, 5);
+    for mv in [(9, 9), (9, 10), (12, 0), (9, 11), (9, 12), (12, 1), (0, 0), (12, 2), (12, 3)] {
+        state.make_move(mv);
+    }
+    state
+}
+
+#[test]
+fn test_hud_counts_match_state_after_captures() {
+    let state = capturing_game();
+    assert_eq!((state.max_captures, state.min_captures), (2, 0));
+
+    let mut app = hud_app(state);
+    app.update();
+
+    assert_eq!(hud(&mut app, HudText::Captures(Player::Max)).0, "Black captures: 2/5");
+    assert_eq!(hud(&mut app, HudText::Captures(Player::Min)).0, "White captures: 0/5");
+    assert_eq!(hud(&mut app, HudText::Turn).0, "White to move");
+    assert_eq!(hud(&mut app, HudText::Rules).0, "5 in a row, 19x19");
+    assert_eq!(hud(&mut app, HudText::Stones).0, "Move 10, stones 5-0");
+}
+
+#[test]
+fn test_hud_follows_state_changes() {
+    let mut app = hud_app(GameState::new(15, 5));
+    app.update();
+    assert_eq!(hud(&mut app, HudText::Turn).0, "Black to move");
+
+    app.world_mut().resource_mut::<GameState>().make_move((7, 7));
+    app.update();
+    assert_eq!(hud(&mut app, HudText::Turn).0, "White to move");
+}
+
+#[test]
+fn test_hud_warns_one_pair_from_capture_win() {
+    let mut state = GameState::new(19, 5);
+    let (_, normal) = {
+        let mut app = hud_app(state.clone());
+        app.update();
+        hud(&mut app, HudText::Captures(Player::Max))
+    };
+
+    state.max_captures = 4;
+    assert!(state.is_about_to_lose_by_capture(Player::Min));
+    let mut app = hud_app(state);
+    app.update();
+
+    let (text, color) = hud(&mut app, HudText::Captures(Player::Max));
+    assert_eq!(text, "Black captures: 4/5");
+    assert_ne!(color, normal);
+    assert_eq!(hud(&mut app, HudText::Captures(Player::Min)).1, normal);
+}
@@ -552,3 +552,20 @@ fn test_game_like_conditions() {
     println!("Game-like conditions test result: {:?}", result);
     println!("Position has {} valid moves", valid_moves.len());
 }
+
+#[test]
+fn test_depth_times_and_nps() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    state.make_move((7, 8));
+    state.make_move((8, 6));
+
+    let result = lazy_smp_search(&mut state, 4, None, Some(1));
+
+    let depths: Vec<i32> = result.depth_times.iter().map(|&(depth, _, _)| depth).collect();
+    assert_eq!(depths, (1..=result.depth_reached).collect::<Vec<_>>());
+    assert!(result.depth_times.windows(2).all(|pair| pair[0].2 <= pair[1].2));
+    assert!(result.depth_times.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    assert!(result.depth_times.last().unwrap().2 <= result.nodes_searched);
+    assert!(result.nps > 0);
+}
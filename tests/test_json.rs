@@ -0,0 +1,82 @@
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+
+fn mid_game_with_captures() -> GameState {
+    let mut state = GameState::new(19, 5);
+    // Black (Max) takes the white pair at (9,10)-(9,11), then both sides keep playing
+    for mv in [(9, 9), (9, 10), (0, 0), (9, 11), (9, 12), (10, 9), (5, 5), (11, 9), (6, 6), (12, 9)] {
+        state.make_move(mv);
+    }
+    state
+}
+
+#[test]
+fn test_round_trip_preserves_board_and_hash() {
+    let state = mid_game_with_captures();
+    assert!(state.max_captures > 0, "fixture should contain a capture");
+
+    let json = state.to_json();
+    let loaded = GameState::from_json(&json).expect("round trip");
+
+    assert_eq!(loaded.board, state.board);
+    assert_eq!(loaded.current_player, state.current_player);
+    assert_eq!((loaded.max_captures, loaded.min_captures), (state.max_captures, state.min_captures));
+    assert_eq!(loaded.capture_history, state.capture_history);
+    assert_eq!(loaded.moves, state.moves);
+    assert_eq!(loaded.current_hash, state.current_hash);
+    assert_eq!(loaded.current_hash, loaded.zobrist_hash.compute_hash(&loaded));
+}
+
+#[test]
+fn test_loaded_game_keeps_playing() {
+    let state = mid_game_with_captures();
+    let mut loaded = GameState::from_json(&state.to_json()).unwrap();
+    let mut original = state.clone();
+
+    loaded.undo_last_move();
+    original.undo_last_move();
+    assert_eq!(loaded.board, original.board);
+    assert_eq!(loaded.current_hash, original.current_hash);
+}
+
+#[test]
+fn test_rejects_overlapping_stones() {
+    let mut value: serde_json::Value = serde_json::from_str(&GameState::new(15, 5).to_json()).unwrap();
+    value["board"]["max_bits"][0] = 1.into();
+    value["board"]["min_bits"][0] = 1.into();
+    value["board"]["occupied"][0] = 1.into();
+
+    assert!(GameState::from_json(&value.to_string()).is_err());
+}
+
+#[test]
+fn test_rejects_inconsistent_occupied_bits() {
+    let mut state = GameState::new(15, 5);
+    state.board.place_stone(7, 7, Player::Max);
+    let mut value: serde_json::Value = serde_json::from_str(&state.to_json()).unwrap();
+    value["board"]["occupied"][0] = 0.into();
+    value["board"]["occupied"][1] = 0.into();
+    value["board"]["occupied"][2] = 0.into();
+    value["board"]["occupied"][3] = 0.into();
+
+    assert!(GameState::from_json(&value.to_string()).is_err());
+}
+
+#[test]
+fn test_rejects_wrong_word_count() {
+    let mut value: serde_json::Value = serde_json::from_str(&GameState::new(15, 5).to_json()).unwrap();
+    value["board"]["size"] = 19.into();
+
+    assert!(GameState::from_json(&value.to_string()).is_err());
+}
+
+#[test]
+fn test_rejects_a_win_the_board_does_not_show() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    let mut value: serde_json::Value = serde_json::from_str(&state.to_json()).unwrap();
+    value["winner"] = "Min".into();
+
+    let err = GameState::from_json(&value.to_string()).unwrap_err();
+    assert!(err.to_string().contains("winner"), "{}", err);
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::ui::screens::game::board::{update_last_move_marker, BoardUtils, LastMove, LastMoveMarker};
+use gomoku::ui::screens::game::game::{place_stone, CaptureProgressEvent, MovePlayed, StonePlaced, StonePlacement};
+
+fn marker_app(state: GameState) -> App {
+    let mut app = App::new();
+    app.insert_resource(state)
+        .init_resource::<LastMove>()
+        .add_event::<StonePlacement>()
+        .add_event::<MovePlayed>()
+        .add_event::<CaptureProgressEvent>()
+        .add_event::<StonePlaced>()
+        .add_systems(Update, (place_stone, update_last_move_marker).chain());
+    app.world_mut().spawn((Node::default(), Visibility::Hidden, LastMoveMarker));
+    app
+}
+
+fn play(app: &mut App, x: usize, y: usize) {
+    app.world_mut().send_event(StonePlacement { x, y });
+    app.update();
+}
+
+fn marker(app: &mut App) -> (Val, Val, Visibility) {
+    let mut query = app.world_mut().query_filtered::<(&Node, &Visibility), With<LastMoveMarker>>();
+    let (node, visibility) = query.single(app.world()).unwrap();
+    (node.left, node.top, *visibility)
+}
+
+#[test]
+fn test_marker_follows_latest_move() {
+    let mut app = marker_app(GameState::new(19, 5));
+    app.update();
+    assert_eq!(marker(&mut app).2, Visibility::Hidden);
+
+    for (x, y) in [(9, 9), (9, 10), (3, 15)] {
+        play(&mut app, x, y);
+        assert_eq!(app.world().resource::<LastMove>().0, Some((x, y)));
+
+        let expected = BoardUtils::stone_node(x, y, BoardUtils::MARKER_SIZE);
+        assert_eq!(marker(&mut app), (expected.left, expected.top, Visibility::Visible));
+    }
+}
+
+#[test]
+fn test_marker_reflects_move_stack_after_undo() {
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    state.board.place_stone(9, 11, Player::Min);
+    let mut app = marker_app(state);
+
+    // Capturing move, then take it back and replay elsewhere
+    play(&mut app, 9, 12);
+    let mut game_state = app.world_mut().resource_mut::<GameState>();
+    game_state.undo_move((9, 12));
+    assert_eq!(game_state.board.get_player(9, 10), Some(Player::Min));
+
+    play(&mut app, 0, 0);
+    assert_eq!(app.world().resource::<LastMove>().0, Some((0, 0)));
+    let expected = BoardUtils::stone_node(0, 0, BoardUtils::MARKER_SIZE);
+    assert_eq!(marker(&mut app).0, expected.left);
+}
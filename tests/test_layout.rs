@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+use gomoku::ui::screens::game::layout::{board_fit_scale, board_footprint, HUD_HEIGHT, MIN_UI_SCALE, SIDE_PANELS_WIDTH};
+
+#[test]
+fn test_small_window_shrinks_board_within_bounds() {
+    let window = Vec2::new(800.0, 600.0);
+    let scale = board_fit_scale(window, 19, 19);
+    assert!(scale < 1.0);
+
+    let shown = (board_footprint(19, 19) + Vec2::new(SIDE_PANELS_WIDTH, HUD_HEIGHT)) * scale;
+    assert!(shown.x <= window.x && shown.y <= window.y, "{:?} overflows {:?}", shown, window);
+}
+
+#[test]
+fn test_large_window_keeps_scale_one() {
+    assert_eq!(board_fit_scale(Vec2::new(2560.0, 1440.0), 19, 19), 1.0);
+    assert_eq!(board_fit_scale(Vec2::new(1240.0, 720.0), 9, 9), 1.0);
+}
+
+#[test]
+fn test_scale_is_clamped() {
+    assert_eq!(board_fit_scale(Vec2::new(10.0, 10.0), 19, 19), MIN_UI_SCALE);
+    // Minimised windows report a zero size
+    assert_eq!(board_fit_scale(Vec2::ZERO, 19, 19), MIN_UI_SCALE);
+
+    // A 19x19 board still fits a small window at the clamp
+    let shown = (board_footprint(19, 19) + Vec2::new(SIDE_PANELS_WIDTH, HUD_HEIGHT)) * MIN_UI_SCALE;
+    assert!(shown.x <= 320.0 && shown.y <= 240.0);
+}
+
+#[test]
+fn test_rectangular_board_fits_its_longer_side() {
+    let window = Vec2::new(1240.0, 720.0);
+    let wide = board_fit_scale(window, 19, 9);
+    let tall = board_fit_scale(window, 9, 19);
+    assert!(tall < wide);
+}
@@ -1,4 +1,4 @@
-use gomoku::ai::lazy_smp::lazy_smp_search;
+use gomoku::ai::lazy_smp::{lazy_smp_search, lazy_smp_search_with_config, SearchConfig};
 use gomoku::core::board::{Board, Player};
 use gomoku::core::state::GameState;
 use std::time::Duration;
@@ -144,4 +144,464 @@ fn test_parallel_search_performance() {
     } else {
         assert!(result.nodes_searched > 100, "Should search at least 100 nodes for non-terminal positions, got {}", result.nodes_searched);
     }
-}
\ No newline at end of file
+}
+
+fn play_seeded_session(seed: u64) -> Vec<(usize, usize)> {
+    let mut state = GameState::new(15, 5);
+    let config = SearchConfig {
+        num_threads: Some(1),
+        seed: Some(seed),
+        ..SearchConfig::new(2, None)
+    };
+    let mut ai_moves = Vec::new();
+
+    for _ in 0..4 {
+        // Scripted human: always the lowest legal cell
+        let mut human_moves = state.get_possible_moves();
+        human_moves.sort();
+        state.make_move(human_moves[0]);
+        if state.is_terminal() {
+            break;
+        }
+
+        let result = lazy_smp_search_with_config(&mut state, &config);
+        let ai_move = result.best_move.expect("AI should find a move");
+        ai_moves.push(ai_move);
+        state.make_move(ai_move);
+        if state.is_terminal() {
+            break;
+        }
+    }
+
+    ai_moves
+}
+
+#[test]
+fn test_same_seed_reproduces_game() {
+    let first = play_seeded_session(0xC0FFEE);
+    let second = play_seeded_session(0xC0FFEE);
+
+    assert!(!first.is_empty());
+    assert_eq!(first, second, "Same seed and inputs should reproduce the AI moves");
+}
+
+#[test]
+fn test_different_seeds_break_ties_differently() {
+    // Every cell around the lone center stone is worth the same
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+
+    let moves: std::collections::HashSet<_> = (0..8)
+        .map(|seed| {
+            let config = SearchConfig {
+                num_threads: Some(1),
+                seed: Some(seed),
+                ..SearchConfig::new(1, None)
+            };
+            lazy_smp_search_with_config(&mut state.clone(), &config).best_move
+        })
+        .collect();
+
+    assert!(moves.len() > 1, "every seed picked {:?}", moves);
+}
+
+#[test]
+fn test_stop_flag_returns_promptly_with_completed_depth() {
+    use gomoku::ai::lazy_smp::lazy_smp_search_with_stop;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    let mut state = GameState::new(15, 5);
+    for mv in [(7, 7), (7, 8), (8, 6), (6, 8), (9, 9), (8, 9)] {
+        state.make_move(mv);
+    }
+    let legal = state.get_possible_moves();
+
+    // Deep enough that only the flag can end the search
+    let config = SearchConfig {
+        num_threads: Some(2),
+        ..SearchConfig::new(30, None)
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = Arc::clone(&stop);
+    let handle = std::thread::spawn(move || lazy_smp_search_with_stop(&mut state, &config, Some(&thread_stop)));
+
+    std::thread::sleep(Duration::from_millis(100));
+    let stopped_at = std::time::Instant::now();
+    stop.store(true, Ordering::Relaxed);
+    let result = handle.join().unwrap();
+
+    assert!(stopped_at.elapsed() < Duration::from_millis(500), "took {:?} to stop", stopped_at.elapsed());
+    let best_move = result.best_move.expect("a move from a completed iteration");
+    assert!(legal.contains(&best_move), "{:?} is not legal", best_move);
+    assert!(result.depth_reached >= 1);
+}
+
+#[test]
+fn test_shared_table_search_finds_the_same_win() {
+    use gomoku::ai::lazy_smp::find_best_move_parallel;
+
+    // The same four for either side, so Min to move must not pick Max's best move
+    for player in [Player::Max, Player::Min] {
+        let mut state = GameState::new(15, 5);
+        for col in 7..=10 {
+            state.board.place_stone(7, col, player);
+        }
+        state.board.place_stone(7, 6, player.opponent());
+        state.board.place_stone(8, 8, player.opponent());
+        state.current_player = player;
+
+        let single = lazy_smp_search(&mut state.clone(), 6, Some(Duration::from_millis(500)), Some(1));
+        let parallel = find_best_move_parallel(&mut state, 6, Some(Duration::from_millis(500)), 4);
+
+        let best_move = parallel.best_move.expect("parallel search should find a move");
+        let winning = match player {
+            Player::Max => parallel.score >= 900_000,
+            Player::Min => parallel.score <= -900_000,
+        };
+        assert!(
+            best_move == single.best_move.unwrap() || winning,
+            "{:?}: {:?} differs from {:?} and is not winning ({})",
+            player,
+            best_move,
+            single.best_move,
+            parallel.score
+        );
+        state.make_move(best_move);
+        assert_eq!(state.winner, Some(player), "{:?} should complete the five", best_move);
+    }
+}
+
+#[test]
+fn test_shared_best_keeps_the_side_to_move_best() {
+    use gomoku::ai::lazy_smp::SharedSearchState;
+
+    let min_to_move = SharedSearchState::new(false);
+    assert_eq!(min_to_move.first_guess(), 0);
+    assert!(min_to_move.update_best(-300, Some((7, 7)), 2));
+    assert!(!min_to_move.update_best(500, Some((3, 3)), 2), "a higher score is worse for Min");
+    assert_eq!(*min_to_move.best_move.lock().unwrap(), Some((7, 7)));
+    assert_eq!(min_to_move.first_guess(), -300);
+
+    let max_to_move = SharedSearchState::new(true);
+    assert!(max_to_move.update_best(-300, Some((7, 7)), 2), "any score beats none");
+    assert!(max_to_move.update_best(500, Some((3, 3)), 2));
+    assert_eq!(*max_to_move.best_move.lock().unwrap(), Some((3, 3)));
+
+    // A deeper search replaces a shallower one whatever their scores
+    assert!(max_to_move.update_best(-999_999, Some((5, 5)), 3));
+    assert!(!max_to_move.update_best(500, Some((3, 3)), 2));
+    assert_eq!(max_to_move.first_guess(), -999_999);
+}
+
+#[test]
+fn test_shared_table_threads_search_more_nodes() {
+    use gomoku::ai::lazy_smp::find_best_move_parallel;
+
+    let mut state = GameState::new(15, 5);
+    for mv in [(7, 7), (7, 8), (8, 6), (6, 8), (9, 9), (8, 9)] {
+        state.make_move(mv);
+    }
+
+    // Fixed depth, so the comparison does not depend on how many cores the machine has
+    let single = find_best_move_parallel(&mut state.clone(), 3, None, 1);
+    let parallel = find_best_move_parallel(&mut state, 3, None, 4);
+
+    assert!(parallel.best_move.is_some());
+    assert!(
+        parallel.nodes_searched > single.nodes_searched,
+        "4 threads searched {} nodes, 1 thread {}",
+        parallel.nodes_searched,
+        single.nodes_searched
+    );
+}
+
+#[test]
+fn test_threat_mode_searches_fewer_nodes_and_keeps_the_attack() {
+    use gomoku::core::moves::ThreatLevel;
+
+    let mut state = GameState::new(15, 5);
+    for col in 5..8 {
+        state.board.place_stone(7, col, Player::Max);
+    }
+    state.board.place_stone(9, 6, Player::Max);
+    state.board.place_stone(8, 6, Player::Min);
+    state.board.place_stone(6, 7, Player::Min);
+    state.board.place_stone(5, 11, Player::Min);
+    state.current_player = Player::Max;
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+
+    let zone_config = SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(3, None)
+    };
+    let threat_config = SearchConfig {
+        threat_space_above: Some(ThreatLevel::Three),
+        ..zone_config
+    };
+    let zone = lazy_smp_search_with_config(&mut state.clone(), &zone_config);
+    let threat = lazy_smp_search_with_config(&mut state, &threat_config);
+
+    let best_move = threat.best_move.expect("threat mode should find a move");
+    assert!(best_move == (7, 8) || best_move == (7, 4), "expected an open four, got {:?}", best_move);
+    assert!(
+        threat.nodes_searched < zone.nodes_searched,
+        "threat mode searched {} nodes, zone moves {}",
+        threat.nodes_searched,
+        zone.nodes_searched
+    );
+}
+
+#[test]
+fn test_search_on_largest_board_plays_past_row_eighteen() {
+    use gomoku::core::state::MAX_BOARD_SIZE;
+
+    let mut state = GameState::new(MAX_BOARD_SIZE, 5);
+    // Max threatens five in the far corner, beyond the cells of a 19x19 board
+    for col in 19..23 {
+        state.board.place_stone(22, col, Player::Max);
+    }
+    state.board.place_stone(22, 18, Player::Min);
+    state.board.place_stone(21, 21, Player::Min);
+    state.current_player = Player::Max;
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+
+    let config = SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(3, None)
+    };
+    let result = lazy_smp_search_with_config(&mut state, &config);
+
+    assert_eq!(result.best_move, Some((22, 23)));
+    assert!(result.score >= 900_000);
+}
+
+/// Early middlegames with either side to move, one with an open three on the board
+fn aspiration_positions() -> Vec<GameState> {
+    let stones: [&[((usize, usize), Player)]; 4] = [
+        &[((7, 7), Player::Max), ((7, 8), Player::Min), ((8, 7), Player::Max), ((8, 8), Player::Min)],
+        &[((7, 7), Player::Max), ((8, 8), Player::Min), ((6, 8), Player::Max), ((6, 6), Player::Min), ((8, 6), Player::Max)],
+        &[((7, 5), Player::Max), ((7, 6), Player::Max), ((7, 7), Player::Max), ((8, 6), Player::Min), ((6, 7), Player::Min), ((9, 9), Player::Min)],
+        &[((7, 7), Player::Max), ((7, 8), Player::Min), ((8, 8), Player::Max), ((6, 6), Player::Min), ((9, 9), Player::Max), ((10, 10), Player::Min), ((8, 6), Player::Max)],
+    ];
+    stones
+        .iter()
+        .map(|stones| {
+            let mut state = GameState::new(15, 5);
+            for &((row, col), player) in stones.iter() {
+                state.board.place_stone(row, col, player);
+            }
+            state.current_player = if stones.len() % 2 == 0 { Player::Max } else { Player::Min };
+            state.current_hash = state.zobrist_hash.compute_hash(&state);
+            state
+        })
+        .collect()
+}
+
+#[test]
+fn test_aspiration_windows_match_mtdf() {
+    for state in aspiration_positions() {
+        for depth in [3, 4] {
+            let mtdf_config = SearchConfig {
+                num_threads: Some(1),
+                ..SearchConfig::new(depth, None)
+            };
+            let aspiration_config = SearchConfig {
+                aspiration_windows: true,
+                ..mtdf_config
+            };
+            let mtdf = lazy_smp_search_with_config(&mut state.clone(), &mtdf_config);
+            let aspiration = lazy_smp_search_with_config(&mut state.clone(), &aspiration_config);
+
+            assert_eq!(aspiration.best_move, mtdf.best_move, "depth {}", depth);
+            assert_eq!(aspiration.score, mtdf.score, "depth {}", depth);
+        }
+    }
+}
+
+#[test]
+fn test_node_limit_stops_search_deterministically() {
+    let mut state = GameState::new(15, 5);
+    for mv in [(7, 7), (7, 8), (8, 6), (6, 8), (9, 9), (8, 9)] {
+        state.make_move(mv);
+    }
+    let legal = state.get_possible_moves();
+    const NODE_LIMIT: u64 = 5_000;
+
+    // Too deep to finish, and no time limit: only the budget ends the search
+    let config = SearchConfig {
+        num_threads: Some(1),
+        node_limit: Some(NODE_LIMIT),
+        ..SearchConfig::new(30, None)
+    };
+    let result = lazy_smp_search_with_config(&mut state.clone(), &config);
+
+    assert!(result.nodes_searched >= NODE_LIMIT, "stopped early at {} nodes", result.nodes_searched);
+    // At most one quiescence search past the budget
+    assert!(result.nodes_searched < NODE_LIMIT + 500, "searched {} nodes", result.nodes_searched);
+    let best_move = result.best_move.expect("a move within the budget");
+    assert!(legal.contains(&best_move), "{:?} is not legal", best_move);
+
+    let again = lazy_smp_search_with_config(&mut state, &config);
+    assert_eq!(again.nodes_searched, result.nodes_searched);
+    assert_eq!(again.best_move, result.best_move);
+}
+
+#[test]
+fn test_temperature_varies_quiet_moves_and_zero_keeps_the_best() {
+    let mut state = GameState::new(15, 5);
+    for mv in [(7, 7), (7, 8), (8, 6), (6, 8)] {
+        state.make_move(mv);
+    }
+    let legal = state.get_possible_moves();
+    let base = SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(2, None)
+    };
+    let best = lazy_smp_search_with_config(&mut state.clone(), &SearchConfig { seed: Some(1), ..base })
+        .best_move
+        .expect("a best move");
+
+    // Temperature 0 is the plain search, whatever the seed
+    for seed in 0..4 {
+        let plain = SearchConfig { seed: Some(seed), ..base };
+        let cold = SearchConfig { temperature: 0.0, ..plain };
+        assert_eq!(
+            lazy_smp_search_with_config(&mut state.clone(), &cold).best_move,
+            lazy_smp_search_with_config(&mut state.clone(), &plain).best_move,
+        );
+    }
+
+    let hot = |seed| SearchConfig { seed: Some(seed), temperature: 5.0, ..base };
+    let sampled: Vec<_> = (0..20)
+        .map(|seed| lazy_smp_search_with_config(&mut state.clone(), &hot(seed)).best_move.expect("a move"))
+        .collect();
+    assert!(sampled.iter().all(|mv| legal.contains(mv)));
+    assert!(sampled.iter().any(|&mv| mv != best), "20 hot searches all played {:?}", best);
+
+    // Seeded sampling is reproducible
+    let again = lazy_smp_search_with_config(&mut state.clone(), &hot(3)).best_move.expect("a move");
+    assert_eq!(again, sampled[3]);
+}
+
+#[test]
+fn test_temperature_never_passes_up_a_win() {
+    let mut board = Board::new(15);
+    for col in 3..7 {
+        board.place_stone(7, col, Player::Max);
+    }
+    let mut state = GameState::new(15, 5);
+    state.board = board;
+    state.current_player = Player::Max;
+
+    for seed in 0..5 {
+        let config = SearchConfig {
+            num_threads: Some(1),
+            seed: Some(seed),
+            temperature: 10.0,
+            ..SearchConfig::new(2, None)
+        };
+        let mv = lazy_smp_search_with_config(&mut state.clone(), &config).best_move.expect("a move");
+        assert!(mv == (7, 2) || mv == (7, 7), "seed {} played {:?}", seed, mv);
+    }
+}
+
+#[test]
+fn test_late_move_reductions_keep_tactical_answers_with_fewer_nodes() {
+    use gomoku::core::positions;
+
+    // White to stop an open three, Black to turn it into five, White to block a closed four
+    let mut ignored_three = positions::open_three();
+    ignored_three.make_move((12, 12));
+    let closed_four = positions::from_moves(&[(9, 9), (10, 10), (9, 10), (10, 11), (9, 11), (9, 8), (9, 12)]);
+    let cases = [
+        (positions::open_three(), vec![(9, 8), (9, 12), (9, 7), (9, 13)]),
+        (ignored_three, vec![(9, 8), (9, 12)]),
+        (closed_four, vec![(9, 13)]),
+    ];
+
+    let mut plain_nodes = 0;
+    let mut reduced_nodes = 0;
+    for (state, answers) in cases {
+        let plain = SearchConfig {
+            num_threads: Some(1),
+            ..SearchConfig::new(4, None)
+        };
+        let reduced = SearchConfig { late_move_reductions: true, ..plain };
+        let without = lazy_smp_search_with_config(&mut state.clone(), &plain);
+        let with = lazy_smp_search_with_config(&mut state.clone(), &reduced);
+
+        assert_eq!(with.best_move, without.best_move);
+        assert_eq!(with.score, without.score);
+        let best_move = with.best_move.expect("a move");
+        assert!(answers.contains(&best_move), "{:?} is not one of {:?}", best_move, answers);
+        plain_nodes += without.nodes_searched;
+        reduced_nodes += with.nodes_searched;
+    }
+    assert!(reduced_nodes < plain_nodes, "{} nodes with reductions, {} without", reduced_nodes, plain_nodes);
+}
+
+#[test]
+fn test_forcing_extensions_find_a_win_past_the_nominal_depth() {
+    // Black wins by force: four on row 9, then the double three at (10, 9) once (9, 8)
+    // lines up the diagonal. White's stones away from the centre are fillers.
+    let mut state = GameState::new(19, 5);
+    state.rules.forbid_double_three = false;
+    state.rules.captures_enabled = false;
+    for mv in [
+        (9, 5), (9, 4), (9, 6), (0, 18), (9, 7), (18, 0), (11, 10), (0, 9), (10, 10), (18, 9), (10, 11), (3, 3),
+    ] {
+        state.make_move(mv);
+    }
+
+    let plain = SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(2, None)
+    };
+    let extended = SearchConfig { forcing_extensions: 4, ..plain };
+    let without = lazy_smp_search_with_config(&mut state.clone(), &plain);
+    let with = lazy_smp_search_with_config(&mut state.clone(), &extended);
+
+    assert!(without.score < 900_000, "plain depth 2 already sees the win: {}", without.score);
+    assert!(with.score > 900_000, "extended search missed the win: {}", with.score);
+    assert_eq!(with.best_move, Some((9, 8)));
+}
+
+#[test]
+fn test_warm_history_reaches_the_same_depth_in_fewer_nodes() {
+    use gomoku::ai::lazy_smp::lazy_smp_search_with_history;
+    use gomoku::ai::move_ordering::MoveHistory;
+
+    let config = SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(4, None)
+    };
+    let mut state = GameState::new(15, 5);
+    let mut history = MoveHistory::new();
+    let (mut warm_nodes, mut cold_nodes) = (0, 0);
+
+    for turn in 0..4 {
+        // Scripted human: the lowest legal cell around the center
+        let mut human_moves = state.get_possible_moves();
+        human_moves.sort_by_key(|&(row, col)| (row.abs_diff(7) + col.abs_diff(7), row, col));
+        state.make_move(human_moves[0]);
+
+        let cold = lazy_smp_search_with_config(&mut state.clone(), &config);
+        let warm = lazy_smp_search_with_history(&mut state, &config, None, &mut history);
+        assert_eq!(warm.depth_reached, cold.depth_reached);
+        assert!(!history.is_empty(), "The search should leave its cutoffs behind");
+        // The first search starts cold too
+        if turn > 0 {
+            warm_nodes += warm.nodes_searched;
+            cold_nodes += cold.nodes_searched;
+        }
+        state.make_move(warm.best_move.expect("AI should find a move"));
+    }
+
+    assert!(
+        warm_nodes < cold_nodes,
+        "Warm history should save nodes: {} warm against {} cold",
+        warm_nodes,
+        cold_nodes
+    );
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use gomoku::core::state::GameState;
+use gomoku::ui::screens::game::board::move_numbers;
+
+/// Cell -> ply map built by replaying `history` on a fresh board
+fn replayed(history: &[(usize, usize)]) -> HashMap<(usize, usize), usize> {
+    let mut state = GameState::new(19, 5);
+    let mut numbers = HashMap::new();
+    for (ply, &mv) in history.iter().enumerate() {
+        state.make_move(mv);
+        numbers.insert(mv, ply + 1);
+        numbers.retain(|&(row, col), _| !state.board.is_empty_position(row, col));
+    }
+    numbers
+}
+
+// Black captures the white pair (9,10)-(9,11) with its third stone, then white plays
+// back into the captured cell
+const GAME: [(usize, usize); 6] = [(9, 9), (9, 10), (0, 0), (9, 11), (9, 12), (9, 10)];
+
+#[test]
+fn test_numbers_match_replayed_history() {
+    let mut state = GameState::new(19, 5);
+    for mv in GAME {
+        state.make_move(mv);
+    }
+
+    let numbers = move_numbers(&state);
+    assert_eq!(numbers, replayed(&GAME));
+    assert_eq!(numbers.get(&(9, 10)), Some(&6));
+    assert_eq!(numbers.get(&(9, 11)), None);
+    assert_eq!(numbers.len(), 4);
+}
+
+#[test]
+fn test_numbers_follow_undo_and_redo() {
+    let mut state = GameState::new(19, 5);
+    for mv in GAME {
+        state.make_move(mv);
+    }
+
+    // Back to before the capture: the white pair is restored with its old numbers
+    state.undo_last_move();
+    state.undo_last_move();
+    assert_eq!(move_numbers(&state), replayed(&GAME[..4]));
+    assert_eq!(move_numbers(&state).get(&(9, 11)), Some(&4));
+
+    state.redo_move();
+    assert_eq!(move_numbers(&state), replayed(&GAME[..5]));
+}
@@ -1,6 +1,7 @@
-use gomoku::ai::move_ordering::MoveOrdering;
+use gomoku::ai::move_ordering::{MoveHistory, MoveOrdering};
 use gomoku::core::board::Player;
 use gomoku::core::state::GameState;
+use gomoku::core::moves::{MoveHandler, ThreatOrdering};
 
 #[test]
 fn test_move_ordering_prioritizes_center() {
@@ -122,3 +123,82 @@ fn test_move_ordering_performance() {
     assert!(elapsed.as_millis() < 10, "Move ordering should be fast: {:?}", elapsed);
     assert!(!moves.is_empty(), "Should have moves to order");
 }
+
+#[test]
+fn test_move_ordering_puts_captures_ahead_of_center() {
+    let mut state = GameState::new(15, 5);
+    // Max at (3,3) can take the white pair at (3,4),(3,5) by playing (3,6)
+    state.board.place_stone(7, 7, Player::Max);
+    state.board.place_stone(3, 3, Player::Max);
+    state.board.place_stone(3, 4, Player::Min);
+    state.board.place_stone(3, 5, Player::Min);
+    state.current_player = Player::Max;
+
+    let mut moves = state.get_possible_moves();
+    assert!(moves.contains(&(3, 6)) && moves.contains(&(7, 8)));
+    MoveOrdering::order_moves(&state, &mut moves);
+
+    let position = |moves: &[(usize, usize)], mv| moves.iter().position(|&m| m == mv).unwrap();
+    assert_eq!(moves[0], (3, 6));
+    assert!(position(&moves, (3, 6)) < position(&moves, (7, 8)));
+
+    // Without captures in the rules the same move falls behind the center
+    state.rules.captures_enabled = false;
+    MoveOrdering::order_moves(&state, &mut moves);
+    assert!(position(&moves, (3, 6)) > position(&moves, (7, 8)));
+}
+
+#[test]
+fn test_move_history_ages_instead_of_forgetting() {
+    let mut history = MoveHistory::new();
+    history.record_cutoff((7, 8), 4);
+    history.record_cutoff((7, 8), 2);
+    history.record_cutoff((3, 3), 1);
+
+    assert_eq!(history.score((7, 8)), 20);
+    assert!(history.is_killer((7, 8), 4));
+    assert!(!history.is_killer((7, 8), 3));
+    assert!(history.bonus((7, 8), 4) > history.bonus((3, 3), 4));
+
+    history.age();
+    assert_eq!(history.score((7, 8)), 10);
+    assert_eq!(history.score((3, 3)), 0, "A score halved to nothing is dropped");
+    assert!(history.is_killer((7, 8), 4), "Killers survive until replaced");
+
+    history.clear();
+    assert!(history.is_empty());
+}
+
+#[test]
+fn test_history_raises_a_quiet_move_among_equals() {
+    let mut state = GameState::new(15, 5);
+    state.board.place_stone(7, 7, Player::Max);
+    let mut moves = state.get_possible_moves();
+    MoveOrdering::order_moves_with_history(&state, &mut moves, None, &MoveHistory::new(), 3);
+    let last = *moves.last().unwrap();
+
+    let mut history = MoveHistory::new();
+    history.record_cutoff(last, 3);
+    MoveOrdering::order_moves_with_history(&state, &mut moves, None, &history, 3);
+    assert_eq!(moves[0], last, "The move that cut off before should be tried first");
+}
+
+#[test]
+fn test_threat_moves_lead_the_search_ordering() {
+    let mut state = GameState::new(15, 5);
+    state.board.place_stone(7, 5, Player::Max);
+    state.board.place_stone(7, 6, Player::Max);
+    state.board.place_stone(7, 7, Player::Max);
+    state.board.place_stone(3, 3, Player::Min);
+
+    let mut moves = state.get_possible_moves();
+    let threats =
+        MoveHandler::find_threat_moves_among(&state.board, Player::Max, &moves, 5, &ThreatOrdering::default()).unwrap();
+
+    // A quiet move raised by history does not get ahead of the threats
+    let mut history = MoveHistory::new();
+    history.record_cutoff((3, 4), 3);
+    MoveOrdering::order_moves_with_history(&state, &mut moves, None, &history, 3);
+    assert!(!threats.contains(&(3, 4)));
+    assert!(threats.iter().all(|threat| moves[..threats.len()].contains(threat)));
+}
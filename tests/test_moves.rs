@@ -1,5 +1,7 @@
 use gomoku::core::board::{Board, Player};
-use gomoku::core::moves::MoveHandler;
+use gomoku::core::moves::{MoveHandler, ThreatLevel, ThreatOrdering};
+use gomoku::core::rules::{RuleFlags, RulePreset};
+use gomoku::core::state::GameState;
 
 #[test]
 fn test_first_move_center_only() {
@@ -176,3 +178,235 @@ fn test_double_three_moves_excluded() {
         }
     }
 }
+
+#[test]
+fn test_threat_moves_four_before_three() {
+    let mut board = Board::new(19);
+
+    // Max: X X X on row 9 (extending makes a four)
+    board.place_stone(9, 5, Player::Max);
+    board.place_stone(9, 6, Player::Max);
+    board.place_stone(9, 7, Player::Max);
+
+    // Max: X X on row 3 (extending makes only a three)
+    board.place_stone(3, 10, Player::Max);
+    board.place_stone(3, 11, Player::Max);
+
+    let threats = MoveHandler::find_threat_moves(&board, Player::Max, &RuleFlags::default(), 5, &ThreatOrdering::default())
+        .expect("Position has threat moves");
+
+    let four_pos = threats.iter().position(|&mv| mv == (9, 8)).expect("Four-making move listed");
+    let three_pos = threats.iter().position(|&mv| mv == (3, 12)).expect("Three-making move listed");
+    assert!(four_pos < three_pos, "Four-making move should come before three-making move: {:?}", threats);
+    assert_eq!(MoveHandler::threat_level(&board, 9, 8, Player::Max, 5), Some(ThreatLevel::Four));
+    assert_eq!(MoveHandler::threat_level(&board, 3, 12, Player::Max, 5), Some(ThreatLevel::Three));
+}
+
+#[test]
+fn test_threat_moves_offense_preference() {
+    let mut board = Board::new(19);
+
+    // Max threatens a four at (9, 8), Min threatens a four at (5, 8)
+    for col in 5..8 {
+        board.place_stone(9, col, Player::Max);
+        board.place_stone(5, col, Player::Min);
+    }
+
+    let offensive = ThreatOrdering { prefer_offense: true, ..ThreatOrdering::default() };
+    let defensive = ThreatOrdering { prefer_offense: false, ..ThreatOrdering::default() };
+
+    let threats = MoveHandler::find_threat_moves(&board, Player::Max, &RuleFlags::default(), 5, &offensive).unwrap();
+    assert!(threats[0] == (9, 8) || threats[0] == (9, 4), "Offense first: {:?}", threats);
+
+    let threats = MoveHandler::find_threat_moves(&board, Player::Max, &RuleFlags::default(), 5, &defensive).unwrap();
+    assert!(threats[0] == (5, 8) || threats[0] == (5, 4), "Defense first: {:?}", threats);
+}
+
+#[test]
+fn test_threat_moves_cap() {
+    let mut board = Board::new(19);
+    board.place_stone(9, 5, Player::Max);
+    board.place_stone(9, 6, Player::Max);
+
+    let uncapped = MoveHandler::find_threat_moves(&board, Player::Max, &RuleFlags::default(), 5, &ThreatOrdering::default()).unwrap();
+    let capped = ThreatOrdering { max_moves: uncapped.len() - 1, ..ThreatOrdering::default() };
+
+    assert!(MoveHandler::find_threat_moves(&board, Player::Max, &RuleFlags::default(), 5, &capped).is_none());
+    assert!(MoveHandler::find_threat_moves(&Board::new(19), Player::Max, &RuleFlags::default(), 5, &ThreatOrdering::default()).is_none());
+}
+
+fn open_three_position() -> Board {
+    let mut board = Board::new(19);
+    // Max has an open three on row 9, both sides have a few loose stones
+    for col in 5..8 {
+        board.place_stone(9, col, Player::Max);
+    }
+    board.place_stone(11, 6, Player::Max);
+    board.place_stone(6, 12, Player::Max);
+    board.place_stone(10, 6, Player::Min);
+    board.place_stone(8, 7, Player::Min);
+    board.place_stone(7, 11, Player::Min);
+    board.place_stone(12, 9, Player::Min);
+    board
+}
+
+#[test]
+fn test_threat_space_moves_are_a_narrow_subset() {
+    let board = open_three_position();
+
+    let zone = MoveHandler::get_possible_moves(&board, Player::Max);
+    let threat_space =
+        MoveHandler::get_threat_space_moves(&board, Player::Max, &RuleFlags::default(), 5).expect("Max has a three to extend");
+
+    assert!(threat_space.len() < zone.len(), "{} threat moves vs {} zone moves", threat_space.len(), zone.len());
+    assert!(threat_space.iter().all(|mv| zone.contains(mv)), "{:?} is not a subset", threat_space);
+    assert!(threat_space.contains(&(9, 8)) && threat_space.contains(&(9, 4)), "Open four moves missing: {:?}", threat_space);
+}
+
+#[test]
+fn test_threat_space_includes_blocks_of_opponent_four() {
+    let mut board = open_three_position();
+    // Min now threatens five on column 14
+    for row in 3..7 {
+        board.place_stone(row, 14, Player::Min);
+    }
+
+    let threat_space = MoveHandler::get_threat_space_moves(&board, Player::Max, &RuleFlags::default(), 5).unwrap();
+
+    assert!(
+        threat_space[0] == (7, 14) || threat_space[0] == (2, 14),
+        "Blocking the five comes first: {:?}",
+        threat_space
+    );
+    assert!(threat_space.contains(&(9, 8)));
+}
+
+#[test]
+fn test_threat_space_needs_an_attacker_threat() {
+    let mut board = Board::new(19);
+    board.place_stone(9, 9, Player::Max);
+    board.place_stone(9, 10, Player::Max);
+    for col in 3..6 {
+        board.place_stone(4, col, Player::Min);
+    }
+
+    assert!(MoveHandler::get_threat_space_moves(&board, Player::Max, &RuleFlags::default(), 5).is_none());
+    assert!(MoveHandler::get_threat_space_moves(&board, Player::Min, &RuleFlags::default(), 5).is_some());
+}
+
+fn double_three_block_position() -> Board {
+    let mut board = Board::new(19);
+    // Min threatens five on row 3; the block at (3, 7) also makes two open threes for Max
+    for col in 3..7 {
+        board.place_stone(3, col, Player::Min);
+    }
+    for (row, col) in [(4, 7), (5, 7), (4, 6), (5, 5)] {
+        board.place_stone(row, col, Player::Max);
+    }
+    for col in 8..11 {
+        board.place_stone(12, col, Player::Max);
+    }
+    board
+}
+
+#[test]
+fn test_threat_space_follows_the_double_three_rule() {
+    let board = double_three_block_position();
+
+    let standard = MoveHandler::get_threat_space_moves(&board, Player::Max, &RuleFlags::default(), 5).unwrap();
+    let classic = RulePreset::ClassicGomoku.flags();
+    let free = MoveHandler::get_threat_space_moves(&board, Player::Max, &classic, 5).unwrap();
+
+    assert!(!standard.contains(&(3, 7)), "Double-three block kept: {:?}", standard);
+    assert!(free.contains(&(3, 7)), "Double-three block missing without the rule: {:?}", free);
+}
+
+#[test]
+fn test_threat_moves_follow_the_double_three_rule() {
+    let board = double_three_block_position();
+    let ordering = ThreatOrdering::default();
+
+    let standard = MoveHandler::find_threat_moves(&board, Player::Max, &RuleFlags::default(), 5, &ordering).unwrap();
+    let classic = RulePreset::ClassicGomoku.flags();
+    let free = MoveHandler::find_threat_moves(&board, Player::Max, &classic, 5, &ordering).unwrap();
+
+    assert!(!standard.contains(&(3, 7)), "Double-three block kept: {:?}", standard);
+    assert!(free.contains(&(3, 7)), "Double-three block missing without the rule: {:?}", free);
+}
+
+#[test]
+fn test_gapped_four_gap_is_forced() {
+    let mut board = Board::new(19);
+    // Min: X X X _ X on row 4, the gap at (4, 6) completes five
+    for col in [3, 4, 5, 7] {
+        board.place_stone(4, col, Player::Min);
+    }
+    board.place_stone(9, 9, Player::Max);
+
+    assert_eq!(MoveHandler::threat_level(&board, 4, 6, Player::Min, 5), Some(ThreatLevel::Five));
+    let threats = MoveHandler::find_threat_moves(&board, Player::Max, &RuleFlags::default(), 5, &ThreatOrdering::default()).unwrap();
+    assert_eq!(threats[0], (4, 6), "Blocking the gap comes first: {:?}", threats);
+    assert_eq!(MoveHandler::find_winning_moves(&board, Player::Min, 5, false), vec![(4, 6)]);
+
+    let mut state = GameState::new(19, 5);
+    state.board = board.clone();
+    state.current_player = Player::Max;
+    assert_eq!(state.find_all_winning_moves(Player::Min), vec![(4, 6)]);
+}
+
+#[test]
+fn test_making_a_gapped_four_is_a_four_threat() {
+    let mut board = open_three_position();
+    // Min: X X _ X on column 14, a stone at (7, 14) makes the broken four X X _ X X
+    for row in [3, 4, 6] {
+        board.place_stone(row, 14, Player::Min);
+    }
+
+    assert_eq!(MoveHandler::line_lengths_through(&board, 7, 14, Player::Min)[1], 2);
+    assert_eq!(MoveHandler::threat_level(&board, 7, 14, Player::Min, 5), Some(ThreatLevel::Four));
+    assert_eq!(MoveHandler::threat_level(&board, 2, 14, Player::Min, 5), Some(ThreatLevel::Four));
+
+    let threat_space = MoveHandler::get_threat_space_moves(&board, Player::Max, &RuleFlags::default(), 5).unwrap();
+    for block in [(5, 14), (7, 14), (2, 14)] {
+        assert!(threat_space.contains(&block), "{:?} missing from {:?}", block, threat_space);
+    }
+}
+
+#[test]
+fn test_threat_levels_follow_the_win_condition() {
+    let mut board = Board::new(8);
+    // Max: X X on row 3, already an open three's worth when four in a row wins
+    board.place_stone(3, 3, Player::Max);
+    board.place_stone(3, 4, Player::Max);
+
+    assert_eq!(MoveHandler::level_for_length(3, 4), Some(ThreatLevel::Four));
+    assert_eq!(MoveHandler::level_for_length(2, 4), Some(ThreatLevel::Three));
+    assert_eq!(MoveHandler::threat_level(&board, 3, 5, Player::Max, 4), Some(ThreatLevel::Four));
+    assert_eq!(MoveHandler::threat_level(&board, 3, 5, Player::Max, 5), Some(ThreatLevel::Three));
+
+    assert!(MoveHandler::get_threat_space_moves(&board, Player::Max, &RuleFlags::default(), 5).is_none());
+    let threat_space = MoveHandler::get_threat_space_moves(&board, Player::Max, &RuleFlags::default(), 4).unwrap();
+    assert!(threat_space.contains(&(3, 5)) && threat_space.contains(&(3, 2)), "{:?}", threat_space);
+}
+
+#[test]
+fn test_threat_moves_skip_lines_without_room_for_five() {
+    // 7x7 board, five to win. Row 0 is shut in by the edge and a white stone at
+    // (0, 4), so black's pair there can never become five. Row 4 has room.
+    let mut board = Board::new(7);
+    board.place_stone(0, 1, Player::Max);
+    board.place_stone(0, 2, Player::Max);
+    board.place_stone(0, 4, Player::Min);
+    board.place_stone(4, 2, Player::Max);
+    board.place_stone(4, 3, Player::Max);
+
+    assert!(!MoveHandler::has_room_for_win(&board, 0, 3, Player::Max, (0, 1), 5));
+    assert!(MoveHandler::has_room_for_win(&board, 4, 4, Player::Max, (0, 1), 5));
+
+    let threats = MoveHandler::find_threat_moves(&board, Player::Max, &RuleFlags::default(), 5, &ThreatOrdering::default())
+        .expect("the open pair on row 4 still makes threes");
+    assert!(threats.contains(&(4, 1)) && threats.contains(&(4, 4)));
+    for dead_end in [(0, 0), (0, 3)] {
+        assert!(!threats.contains(&dead_end), "{:?} makes a three that can never be five", dead_end);
+    }
+}
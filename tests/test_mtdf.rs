@@ -192,15 +192,19 @@ fn test_mtdf_defensive_move() {
     let mut state = GameState::new(19, 5);
     let mut tt = TranspositionTable::default();
     
-    // Set up a position where Min needs to defend
+    // Set up a position where Min needs to defend two open threes at once
     state.board.place_stone(9, 9, Player::Max);
     state.board.place_stone(9, 10, Player::Max);
     state.board.place_stone(9, 11, Player::Max);
+    state.board.place_stone(13, 3, Player::Max);
+    state.board.place_stone(13, 4, Player::Max);
+    state.board.place_stone(13, 5, Player::Max);
     state.current_player = Player::Min;
 
     let (score, _, _) = test_mtdf(&mut state, 2, 0, &mut tt);
 
-    // Max has 3 in a row, so score should be positive from Max's perspective
+    // Min blocks one three and Max opens a four with the other, which quiescence
+    // follows through, so the score stays well in Max's favour
     println!("Defensive move score: {}", score);
     assert!(score > 1_000); // Positive score - Max has advantage
 }
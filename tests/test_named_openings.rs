@@ -0,0 +1,66 @@
+use gomoku::core::board::Player;
+use gomoku::core::rules::{OpeningRestriction, NAMED_OPENINGS};
+use gomoku::core::state::GameState;
+
+#[test]
+fn test_named_opening_places_its_stones() {
+    let mut state = GameState::new(15, 5);
+    state.apply_named_opening("Kagetsu").unwrap();
+
+    assert_eq!(state.moves, vec![(7, 7, Player::Max), (7, 6, Player::Min), (8, 6, Player::Max)]);
+    assert_eq!(state.board.get_player(7, 7), Some(Player::Max));
+    assert_eq!(state.board.get_player(7, 6), Some(Player::Min));
+    assert_eq!(state.board.get_player(8, 6), Some(Player::Max));
+    assert_eq!(state.board.count_stones(), 3);
+    assert_eq!(state.current_player, Player::Min);
+}
+
+#[test]
+fn test_named_opening_ignores_case() {
+    let mut state = GameState::new(19, 5);
+    state.apply_named_opening("ugetsu").unwrap();
+    assert_eq!(state.moves.last(), Some(&(10, 9, Player::Max)));
+}
+
+#[test]
+fn test_unknown_opening_errors() {
+    let mut state = GameState::new(15, 5);
+    assert!(state.apply_named_opening("Soosorv").is_err());
+    assert!(state.moves.is_empty());
+}
+
+#[test]
+fn test_opening_needs_an_empty_board() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    assert!(state.apply_named_opening("Kansei").is_err());
+    assert_eq!(state.moves.len(), 1);
+}
+
+#[test]
+fn test_openings_follow_the_restriction() {
+    let mut state = GameState::new(15, 5);
+    assert_eq!(state.available_openings(), vec!["Kansei", "Keigetsu", "Kagetsu", "Ugetsu", "Shogetsu"]);
+    // A refused opening leaves the board untouched
+    assert!(state.apply_named_opening("Pro").is_err());
+    assert!(state.moves.is_empty());
+
+    state.opening_restriction = OpeningRestriction::Pro;
+    assert_eq!(state.available_openings(), vec!["Pro", "Long pro"]);
+    state.opening_restriction = OpeningRestriction::LongPro;
+    assert_eq!(state.available_openings(), vec!["Long pro"]);
+}
+
+#[test]
+fn test_every_opening_fits_its_restriction() {
+    for opening in NAMED_OPENINGS {
+        let fits = [OpeningRestriction::Center, OpeningRestriction::Pro, OpeningRestriction::Free]
+            .into_iter()
+            .any(|restriction| {
+                let mut state = GameState::new(19, 5);
+                state.opening_restriction = restriction;
+                state.apply_named_opening(opening.name).is_ok()
+            });
+        assert!(fits, "{} is legal under no restriction", opening.name);
+    }
+}
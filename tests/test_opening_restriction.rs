@@ -0,0 +1,80 @@
+use gomoku::core::moves::IllegalMove;
+use gomoku::core::rules::OpeningRestriction;
+use gomoku::core::state::GameState;
+
+fn chebyshev_from_center((row, col): (usize, usize)) -> usize {
+    row.abs_diff(7).max(col.abs_diff(7))
+}
+
+/// 15x15 game with the center and a white reply next to it on the board
+fn after_two_moves(restriction: OpeningRestriction) -> GameState {
+    let mut state = GameState::new(15, 5);
+    state.opening_restriction = restriction;
+    assert_eq!(state.get_possible_moves(), vec![(7, 7)]);
+    state.make_move((7, 7));
+    state.make_move((7, 8));
+    state
+}
+
+#[test]
+fn test_pro_keeps_black_second_stone_three_away() {
+    let state = after_two_moves(OpeningRestriction::Pro);
+    let moves = state.get_possible_moves();
+
+    // Everything outside the central 5x5 square, adjacent to a stone or not
+    assert_eq!(moves.len(), 225 - 25);
+    assert!(moves.iter().all(|&mv| chebyshev_from_center(mv) >= 3));
+    assert!(moves.contains(&(0, 0)));
+    assert!(moves.contains(&(7, 10)));
+    assert!(!moves.contains(&(7, 9)));
+    assert_eq!(state.is_legal_move((9, 9)), Err(IllegalMove::TooCloseToCenter));
+    assert_eq!(state.is_legal_move((4, 7)), Ok(()));
+}
+
+#[test]
+fn test_long_pro_keeps_black_second_stone_four_away() {
+    let state = after_two_moves(OpeningRestriction::LongPro);
+    let moves = state.get_possible_moves();
+
+    assert_eq!(moves.len(), 225 - 49);
+    assert!(moves.iter().all(|&mv| chebyshev_from_center(mv) >= 4));
+    assert!(!moves.contains(&(7, 10)));
+    assert!(moves.contains(&(7, 11)));
+    assert_eq!(state.is_legal_move((4, 7)), Err(IllegalMove::TooCloseToCenter));
+}
+
+#[test]
+fn test_restriction_ends_after_black_second_stone() {
+    let mut state = after_two_moves(OpeningRestriction::Pro);
+    state.make_move((3, 3));
+    // White is back to the usual rule: next to a stone
+    let moves = state.get_possible_moves();
+    assert!(moves.contains(&(6, 6)));
+    assert!(moves.contains(&(2, 2)));
+    assert!(!moves.contains(&(0, 14)));
+}
+
+#[test]
+fn test_center_restriction_allows_adjacent_second_stone() {
+    let state = after_two_moves(OpeningRestriction::Center);
+    assert_eq!(state.is_legal_move((7, 9)), Ok(()));
+    assert!(!state.get_possible_moves().contains(&(0, 0)));
+}
+
+#[test]
+fn test_free_opening_allows_any_first_stone() {
+    let mut state = GameState::new(15, 5);
+    state.opening_restriction = OpeningRestriction::Free;
+    assert_eq!(state.get_possible_moves().len(), 225);
+    assert_eq!(state.is_legal_move((0, 14)), Ok(()));
+
+    state.make_move((0, 14));
+    assert!(!state.get_possible_moves().contains(&(7, 7)));
+}
+
+#[test]
+fn test_opening_restriction_survives_json() {
+    let state = after_two_moves(OpeningRestriction::LongPro);
+    let loaded = GameState::from_json(&state.to_json()).unwrap();
+    assert_eq!(loaded.opening_restriction, OpeningRestriction::LongPro);
+}
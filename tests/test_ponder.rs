@@ -0,0 +1,85 @@
+use gomoku::ai::lazy_smp::{lazy_smp_search_with_config, SearchConfig};
+use gomoku::ai::ponder::Ponderer;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn midgame() -> GameState {
+    let mut state = GameState::new(15, 5);
+    for &(row, col, player) in &[
+        (7, 7, Player::Max),
+        (7, 8, Player::Min),
+        (8, 6, Player::Max),
+        (6, 8, Player::Min),
+        (9, 9, Player::Max),
+        (8, 9, Player::Min),
+    ] {
+        state.board.place_stone(row, col, player);
+    }
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+    state
+}
+
+fn config(time_limit: Option<Duration>) -> SearchConfig {
+    SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(20, time_limit)
+    }
+}
+
+#[test]
+fn test_ponder_hit_searches_deeper_than_cold_search() {
+    let budget = Duration::from_millis(50);
+    let state = midgame();
+    let predicted = (6, 6);
+
+    let mut after_reply = state.clone();
+    after_reply.make_move(predicted);
+    let cold = lazy_smp_search_with_config(&mut after_reply, &config(Some(budget)));
+
+    let ponderer = Ponderer::start(&state, predicted, &config(Some(budget))).expect("pondering should start");
+    // The human thinks for a while
+    thread::sleep(Duration::from_millis(1000));
+    let hit = ponderer.finish(predicted, Some(budget)).expect("matching move is a ponder hit");
+
+    assert!(hit.best_move.is_some());
+    assert!(
+        hit.time_elapsed < Duration::from_millis(500),
+        "the human's thinking time counts as the engine's: {:?}",
+        hit.time_elapsed
+    );
+    assert!(
+        hit.depth_reached > cold.depth_reached,
+        "ponder depth {} should exceed cold depth {}",
+        hit.depth_reached,
+        cold.depth_reached
+    );
+}
+
+#[test]
+fn test_ponder_miss_cancels_search() {
+    let state = midgame();
+    let ponderer = Ponderer::start(&state, (6, 6), &config(None)).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let start = Instant::now();
+    assert!(ponderer.finish((10, 10), None).is_none());
+    assert!(start.elapsed() < Duration::from_millis(500), "cancelling took {:?}", start.elapsed());
+}
+
+#[test]
+fn test_ponder_rejects_illegal_prediction() {
+    let state = midgame();
+    assert!(Ponderer::start(&state, (7, 7), &config(None)).is_none());
+}
+
+#[test]
+fn test_search_reports_ponder_move() {
+    let mut state = midgame();
+    let result = lazy_smp_search_with_config(&mut state, &SearchConfig { max_depth: 4, ..config(None) });
+    let (best, reply) = (result.best_move.unwrap(), result.ponder_move.expect("PV should have a reply"));
+
+    assert_ne!(best, reply);
+    assert!(state.board.is_empty_position(reply.0, reply.1));
+}
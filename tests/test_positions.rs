@@ -0,0 +1,55 @@
+use gomoku::ai::heuristic::Heuristic;
+use gomoku::ai::lazy_smp::{lazy_smp_search_with_config, SearchConfig};
+use gomoku::core::board::Player;
+use gomoku::core::positions;
+
+fn stone_count(state: &gomoku::core::state::GameState) -> usize {
+    state.board.occupied().iter().map(|word| word.count_ones() as usize).sum()
+}
+
+#[test]
+fn test_fixtures_are_quiet_positions_from_real_games() {
+    for (name, state) in positions::canonical() {
+        assert!(!state.is_terminal(), "{} is over", name);
+        assert_eq!(stone_count(&state), state.moves.len(), "{} lost stones to captures", name);
+        let to_move = if state.moves.len() % 2 == 0 { Player::Max } else { Player::Min };
+        assert_eq!(state.current_player, to_move, "{}", name);
+        assert!(!state.get_possible_moves().is_empty(), "{}", name);
+    }
+    assert_eq!(positions::crowded().moves.len(), 60);
+    assert!(positions::empty().moves.is_empty());
+}
+
+#[test]
+fn test_fixtures_are_reproducible() {
+    for ((name, first), (_, second)) in positions::canonical().into_iter().zip(positions::canonical()) {
+        assert_eq!(first.hash(), second.hash(), "{}", name);
+        assert_eq!(Heuristic::evaluate(&first, 0), Heuristic::evaluate(&second, 0), "{}", name);
+    }
+
+    // The benchmark setup: one thread, fixed seed and depth, no time limit
+    let config = SearchConfig {
+        num_threads: Some(1),
+        seed: Some(7),
+        ..SearchConfig::new(2, None)
+    };
+    let first = lazy_smp_search_with_config(&mut positions::opening(), &config);
+    let second = lazy_smp_search_with_config(&mut positions::opening(), &config);
+    assert_eq!(first.best_move, second.best_move);
+    assert_eq!(first.nodes_searched, second.nodes_searched);
+}
+
+#[test]
+fn test_open_three_fixture_needs_an_answer() {
+    let state = positions::open_three();
+    assert_eq!(state.current_player, Player::Min);
+    let result = lazy_smp_search_with_config(
+        &mut state.clone(),
+        &SearchConfig {
+            num_threads: Some(1),
+            ..SearchConfig::new(2, None)
+        },
+    );
+    let answer = result.best_move.expect("a move");
+    assert!([(9, 8), (9, 12), (9, 7), (9, 13)].contains(&answer), "{:?} leaves the three open", answer);
+}
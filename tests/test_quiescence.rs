@@ -0,0 +1,88 @@
+use gomoku::ai::heuristic::Heuristic;
+use gomoku::ai::minimax::{mtdf, quiescence};
+use gomoku::ai::transposition::TranspositionTable;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use std::time::Instant;
+
+const WIN_THRESHOLD: i32 = 900_000;
+
+fn place(state: &mut GameState, stones: &[(usize, usize)], player: Player) {
+    for &(row, col) in stones {
+        state.board.place_stone(row, col, player);
+    }
+}
+
+#[test]
+fn test_open_four_for_opponent_is_lost_after_quiescence() {
+    let mut state = GameState::new(15, 5);
+    place(&mut state, &[(7, 5), (7, 6), (7, 7), (7, 8)], Player::Max);
+    place(&mut state, &[(9, 9), (10, 10), (3, 3)], Player::Min);
+    state.current_player = Player::Min;
+
+    let static_eval = Heuristic::evaluate(&state, 0);
+    assert!(static_eval < WIN_THRESHOLD, "a single open four is not scored as a win statically");
+
+    // Min can only block one end, Max completes the other
+    let score = quiescence(&mut state, i32::MIN + 1, i32::MAX - 1, false);
+    assert!(score >= WIN_THRESHOLD, "open four should resolve to a Max win, got {}", score);
+}
+
+#[test]
+fn test_dangling_four_is_converted_by_side_to_move() {
+    let mut state = GameState::new(15, 5);
+    place(&mut state, &[(7, 7), (7, 8), (7, 9), (7, 10)], Player::Max);
+    place(&mut state, &[(7, 6), (9, 9), (3, 3)], Player::Min);
+    state.current_player = Player::Max;
+
+    let static_eval = Heuristic::evaluate(&state, 0);
+    assert!(static_eval < WIN_THRESHOLD);
+
+    let score = quiescence(&mut state, i32::MIN + 1, i32::MAX - 1, true);
+    assert!(score >= WIN_THRESHOLD, "Max should complete the four, got {}", score);
+}
+
+#[test]
+fn test_dangling_four_must_be_blocked() {
+    let mut state = GameState::new(15, 5);
+    place(&mut state, &[(7, 7), (7, 8), (7, 9), (7, 10)], Player::Max);
+    place(&mut state, &[(7, 6), (9, 9), (3, 3)], Player::Min);
+    state.current_player = Player::Min;
+
+    // Min blocks at (7, 11) and the position settles well short of a win
+    let score = quiescence(&mut state, i32::MIN + 1, i32::MAX - 1, false);
+    assert!(score < WIN_THRESHOLD, "blocked four should not be a win, got {}", score);
+    assert_eq!(state.board.get_player(7, 11), None, "quiescence must restore the board");
+}
+
+#[test]
+fn test_quiet_position_returns_static_eval() {
+    let mut state = GameState::new(15, 5);
+    place(&mut state, &[(7, 7), (8, 8)], Player::Max);
+    place(&mut state, &[(7, 8), (6, 6)], Player::Min);
+    state.current_player = Player::Max;
+
+    let static_eval = Heuristic::evaluate(&state, 0);
+    let score = quiescence(&mut state, i32::MIN + 1, i32::MAX - 1, true);
+    assert_eq!(score, static_eval);
+}
+
+#[test]
+fn test_shallow_search_sees_through_leaf_four() {
+    // At depth 1 Max's only move lands on a leaf where Min still faces the open four
+    let mut state = GameState::new(15, 5);
+    place(&mut state, &[(7, 6), (7, 7), (7, 8)], Player::Max);
+    place(&mut state, &[(9, 9), (10, 10)], Player::Min);
+    state.current_player = Player::Max;
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+
+    let mut tt = TranspositionTable::default();
+    let (score, _, best_move) = mtdf(&mut state, 0, 1, &mut tt, &Instant::now(), None);
+
+    assert!(score >= WIN_THRESHOLD, "extending an open three to an open four wins, got {}", score);
+    assert!(
+        matches!(best_move, Some((7, 5)) | Some((7, 9))),
+        "expected an open four, got {:?}",
+        best_move
+    );
+}
@@ -0,0 +1,114 @@
+use gomoku::ai::heuristic::Heuristic;
+use gomoku::ai::lazy_smp::lazy_smp_search;
+use gomoku::core::board::{Board, Player};
+use gomoku::core::rules::WinChecker;
+use gomoku::core::state::GameState;
+use rand::Rng;
+use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
+use std::time::Duration;
+
+const ROWS: usize = 10;
+const COLS: usize = 20;
+
+#[test]
+fn test_rect_board_dimensions_and_bounds() {
+    let mut board = Board::new_rect(ROWS, COLS);
+    assert_eq!((board.rows, board.cols), (ROWS, COLS));
+    assert_eq!(board.total_cells, 200);
+    assert_eq!(board.get_empty_positions().len(), 200);
+    assert_eq!(board.center(), (5, 10));
+
+    board.place_stone(9, 19, Player::Max);
+    board.place_stone(0, 19, Player::Min);
+    assert_eq!(board.get_player(9, 19), Some(Player::Max));
+    assert_eq!(board.get_player(0, 19), Some(Player::Min));
+    assert_eq!(board.count_stones(), 2);
+
+    // Past the last row is off the board even though the board is 20 wide
+    board.place_stone(10, 0, Player::Max);
+    assert_eq!(board.count_stones(), 2);
+    assert!(!board.is_empty_position(10, 0));
+    assert!(!board.in_bounds(10, 0));
+    assert!(board.in_bounds(9, 19));
+}
+
+#[test]
+fn test_rect_board_wins_along_the_long_edge() {
+    let lines: [[(usize, usize); 5]; 4] = [
+        [(9, 15), (9, 16), (9, 17), (9, 18), (9, 19)],
+        [(5, 19), (6, 19), (7, 19), (8, 19), (9, 19)],
+        [(5, 15), (6, 16), (7, 17), (8, 18), (9, 19)],
+        [(5, 19), (6, 18), (7, 17), (8, 16), (9, 15)],
+    ];
+
+    for line in lines {
+        let mut state = GameState::new_rect(ROWS, COLS, 5);
+        for &(row, col) in &line[..4] {
+            state.board.place_stone(row, col, Player::Max);
+        }
+        let last = line[4];
+        assert!(!WinChecker::check_win_around(&state.board, line[0].0, line[0].1, 5));
+        state.make_move(last);
+        assert_eq!(state.winner, Some(Player::Max), "line {:?}", line);
+        assert!(WinChecker::check_exact_win_around(&state.board, last.0, last.1, 5));
+    }
+}
+
+#[test]
+fn test_rect_board_lines_do_not_wrap_between_rows() {
+    let mut board = Board::new_rect(ROWS, COLS);
+    // End of row 0 and start of row 1 are next to each other in the bitboard only
+    for (row, col) in [(0, 17), (0, 18), (0, 19), (1, 0), (1, 1)] {
+        board.place_stone(row, col, Player::Min);
+    }
+    assert!(!WinChecker::check_win_around(&board, 0, 19, 5));
+    assert!(!WinChecker::check_win_around(&board, 1, 0, 5));
+}
+
+#[test]
+fn test_rect_board_moves_stay_in_bounds() {
+    let mut state = GameState::new_rect(ROWS, COLS, 5);
+    assert_eq!(state.get_possible_moves(), vec![(5, 10)]);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    for _ in 0..60 {
+        if state.is_terminal() {
+            break;
+        }
+        let moves = state.get_possible_moves();
+        assert!(moves.iter().all(|&(row, col)| row < ROWS && col < COLS));
+        let mv = moves[rng.random_range(0..moves.len())];
+        state.make_move(mv);
+        assert_eq!(state.incremental_eval(), Heuristic::line_score_full_scan(&state));
+    }
+}
+
+#[test]
+fn test_rect_board_search_returns_move_on_board() {
+    let mut state = GameState::new_rect(ROWS, COLS, 5);
+    for mv in [(5, 10), (5, 11), (6, 10), (4, 12)] {
+        state.make_move(mv);
+    }
+    let result = lazy_smp_search(&mut state, 2, Some(Duration::from_millis(500)), Some(1));
+    let (row, col) = result.best_move.expect("a move");
+    assert!(row < ROWS && col < COLS);
+}
+
+#[test]
+fn test_rect_board_round_trips_through_sgf_and_json() {
+    let mut state = GameState::new_rect(ROWS, COLS, 5);
+    for mv in [(5, 10), (5, 11), (6, 10), (9, 12)] {
+        if state.get_possible_moves().contains(&mv) {
+            state.make_move(mv);
+        }
+    }
+
+    let sgf = state.to_sgf();
+    assert!(sgf.contains("SZ[20:10]"), "{}", sgf);
+    let from_sgf = GameState::from_sgf(&sgf).unwrap();
+    assert_eq!(from_sgf.board, state.board);
+
+    let from_json = GameState::from_json(&state.to_json()).unwrap();
+    assert_eq!(from_json.board, state.board);
+    assert_eq!(from_json.current_hash, state.current_hash);
+}
@@ -0,0 +1,85 @@
+use gomoku::core::board::Player;
+use gomoku::core::moves::{ForbiddenReason, RuleValidator};
+use gomoku::core::rules::RulePreset;
+use gomoku::core::state::GameState;
+
+fn renju_with(stones: &[(usize, usize)]) -> GameState {
+    let mut state = GameState::with_preset(19, RulePreset::Renju);
+    for &(row, col) in stones {
+        state.board.place_stone(row, col, Player::Max);
+    }
+    state
+}
+
+fn assert_forbidden_only_for_black(mut state: GameState, mv: (usize, usize), reason: ForbiddenReason) {
+    assert_eq!(RuleValidator::is_forbidden_for_black(&state.board, mv.0, mv.1), Some(reason));
+    assert!(!state.get_possible_moves().contains(&mv), "{:?} should be forbidden for black", mv);
+
+    state.current_player = Player::Min;
+    assert!(state.get_possible_moves().contains(&mv), "{:?} should be legal for white", mv);
+}
+
+#[test]
+fn test_four_four_fork_is_forbidden() {
+    let state = renju_with(&[(9, 6), (9, 7), (9, 8), (6, 9), (7, 9), (8, 9)]);
+    assert_forbidden_only_for_black(state, (9, 9), ForbiddenReason::DoubleFour);
+}
+
+#[test]
+fn test_two_fours_on_one_line_are_forbidden() {
+    // X_XXX_X: either gap makes five, each with a different four stones
+    let state = renju_with(&[(9, 3), (9, 5), (9, 7), (9, 9)]);
+    assert_eq!(RuleValidator::count_fours(&state.board, 9, 6, Player::Max, 5), 2);
+    assert_forbidden_only_for_black(state, (9, 6), ForbiddenReason::DoubleFour);
+
+    // XX_XX_XX on a diagonal
+    let state = renju_with(&[(3, 3), (4, 4), (7, 7), (9, 9), (10, 10)]);
+    assert_forbidden_only_for_black(state, (6, 6), ForbiddenReason::DoubleFour);
+}
+
+#[test]
+fn test_three_three_fork_is_forbidden() {
+    let state = renju_with(&[(9, 7), (9, 8), (7, 9), (8, 9)]);
+    assert_forbidden_only_for_black(state, (9, 9), ForbiddenReason::DoubleThree);
+}
+
+#[test]
+fn test_renju_without_the_double_three_ban_keeps_the_others() {
+    let mut state = renju_with(&[(9, 7), (9, 8), (7, 9), (8, 9)]);
+    state.rules.forbid_double_three = false;
+    assert!(state.get_possible_moves().contains(&(9, 9)));
+
+    let mut state = renju_with(&[(9, 6), (9, 7), (9, 8), (6, 9), (7, 9), (8, 9)]);
+    state.rules.forbid_double_three = false;
+    assert!(!state.get_possible_moves().contains(&(9, 9)), "the double-four stays forbidden");
+}
+
+#[test]
+fn test_overline_is_forbidden() {
+    let state = renju_with(&[(9, 4), (9, 5), (9, 6), (9, 8), (9, 9)]);
+    assert_forbidden_only_for_black(state, (9, 7), ForbiddenReason::Overline);
+}
+
+#[test]
+fn test_exact_five_beats_a_fork() {
+    // (9,9) makes five on the row and a four on the column at the same time
+    let state = renju_with(&[(9, 5), (9, 6), (9, 7), (9, 8), (6, 9), (7, 9), (8, 9)]);
+    assert_eq!(RuleValidator::is_forbidden_for_black(&state.board, 9, 9), None);
+    assert!(state.get_possible_moves().contains(&(9, 9)));
+}
+
+#[test]
+fn test_single_four_is_allowed() {
+    let state = renju_with(&[(9, 6), (9, 7), (9, 8), (3, 3)]);
+    assert_eq!(RuleValidator::is_forbidden_for_black(&state.board, 9, 9), None);
+    assert!(state.get_possible_moves().contains(&(9, 9)));
+}
+
+#[test]
+fn test_fours_are_allowed_outside_renju() {
+    let mut state = GameState::with_preset(19, RulePreset::Standard);
+    for (row, col) in [(9, 6), (9, 7), (9, 8), (6, 9), (7, 9), (8, 9)] {
+        state.board.place_stone(row, col, Player::Max);
+    }
+    assert!(state.get_possible_moves().contains(&(9, 9)));
+}
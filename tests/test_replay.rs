@@ -0,0 +1,69 @@
+use gomoku::core::state::GameState;
+use gomoku::ui::screens::replay::{slider_ply, Replay};
+
+/// Black captures two pairs on the way
+fn recorded_game() -> GameState {
+    let mut state = GameState::new(19, 5);
+    for mv in [(9, 9), (9, 10), (12, 0), (9, 11), (9, 12), (12, 1), (0, 0), (12, 2), (12, 3), (5, 5)] {
+        state.make_move(mv);
+    }
+    assert_eq!(state.max_captures, 2);
+    state
+}
+
+#[test]
+fn test_replay_forward_then_back_restores_starting_board() {
+    let mut state = recorded_game();
+    let mut replay = Replay::rewind(&mut state);
+    let start = GameState::new(19, 5);
+    assert_eq!(replay.total_plies(), 10);
+    assert_eq!(state.board, start.board);
+
+    replay.seek(&mut state, 9);
+    assert_eq!(replay.ply, 9);
+    assert_eq!(state.max_captures, 2);
+
+    replay.seek(&mut state, 0);
+    assert_eq!(replay.ply, 0);
+    assert_eq!(state.board, start.board);
+    assert_eq!(state.current_player, start.current_player);
+    assert_eq!((state.max_captures, state.min_captures), (0, 0));
+    assert_eq!(state.current_hash, start.current_hash);
+}
+
+#[test]
+fn test_replay_steps_reproduce_the_recorded_positions() {
+    let game = recorded_game();
+    let mut state = game.clone();
+    let mut replay = Replay::rewind(&mut state);
+
+    while replay.step_forward(&mut state) {}
+    assert_eq!(replay.ply, replay.total_plies());
+    assert_eq!(state.board, game.board);
+    assert_eq!(state.max_captures, game.max_captures);
+
+    // Stepping back over the capturing moves puts the captured stones back
+    assert!(replay.step_back(&mut state));
+    assert!(replay.step_back(&mut state));
+    assert_eq!(state.max_captures, 1);
+    assert!(!state.board.is_empty_position(12, 1));
+    assert!(!state.board.is_empty_position(12, 2));
+}
+
+#[test]
+fn test_replay_seek_clamps_to_game_length() {
+    let mut state = recorded_game();
+    let mut replay = Replay::rewind(&mut state);
+    replay.seek(&mut state, 100);
+    assert_eq!(replay.ply, 10);
+    assert!(!replay.step_forward(&mut state));
+}
+
+#[test]
+fn test_slider_maps_track_position_to_ply() {
+    assert_eq!(slider_ply(0.0, 40), 0);
+    assert_eq!(slider_ply(0.5, 40), 20);
+    assert_eq!(slider_ply(1.0, 40), 40);
+    assert_eq!(slider_ply(-0.2, 40), 0);
+    assert_eq!(slider_ply(1.3, 40), 40);
+}
@@ -0,0 +1,63 @@
+use bevy::asset::AssetPlugin;
+use bevy::audio::Pitch;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use gomoku::ai::transposition::TranspositionTable;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::ui::app::{AppState, GameSettings};
+use gomoku::ui::screens::game::game::{game_plugin, Stone, StonePlacement};
+use gomoku::ui::screens::game::restart::RestartButton;
+
+/// Two-player game screen, headless
+fn game_app() -> App {
+    let mut app = App::new();
+    let mut settings = GameSettings::new();
+    settings.versus_ai = false;
+    app.add_plugins((MinimalPlugins, StatesPlugin, InputPlugin, AssetPlugin::default()))
+        .init_asset::<Pitch>()
+        .insert_resource(settings.new_game_state().unwrap())
+        .insert_resource(settings)
+        .init_resource::<TranspositionTable>()
+        .init_state::<AppState>()
+        .add_plugins(game_plugin);
+    app.world_mut().resource_mut::<NextState<AppState>>().set(AppState::Game);
+    app.update();
+    app
+}
+
+fn stone_count(app: &mut App) -> usize {
+    let mut stones = app.world_mut().query::<&Stone>();
+    stones.iter(app.world()).count()
+}
+
+#[test]
+fn test_restart_clears_the_board_and_the_stones() {
+    let mut app = game_app();
+    // Black captures the white pair at (9,10) and (9,11) on the last move
+    for (x, y) in [(9, 9), (9, 10), (0, 0), (9, 11), (9, 12)] {
+        app.world_mut().send_event(StonePlacement { x, y });
+        app.update();
+    }
+    let state = app.world().resource::<GameState>();
+    assert_eq!(state.max_captures, 1);
+    assert_eq!(state.current_player, Player::Min);
+    assert_eq!(stone_count(&mut app), 3);
+
+    let mut buttons = app.world_mut().query_filtered::<Entity, With<RestartButton>>();
+    let button = buttons.single(app.world()).expect("one restart button");
+    app.world_mut().entity_mut(button).insert(Interaction::Pressed);
+    app.update();
+    app.update();
+
+    let state = app.world().resource::<GameState>();
+    assert!(state.moves.is_empty());
+    assert_eq!(state.board.occupied().iter().map(|word| word.count_ones()).sum::<u32>(), 0);
+    assert_eq!(state.current_player, Player::Max);
+    assert_eq!((state.max_captures, state.min_captures), (0, 0));
+    assert_eq!(stone_count(&mut app), 0);
+
+    let mut buttons = app.world_mut().query_filtered::<Entity, With<RestartButton>>();
+    assert_eq!(buttons.iter(app.world()).count(), 1, "the screen is rebuilt once");
+}
@@ -1,5 +1,9 @@
+use gomoku::ai::heuristic::Heuristic;
 use gomoku::core::board::{Board, Player};
-use gomoku::core::rules::WinChecker;
+use gomoku::core::moves::MoveHandler;
+use gomoku::core::rules::{RuleFlags, RulePreset, WinChecker};
+use gomoku::core::state::GameState;
+use gomoku::ui::app::GameSettings;
 
 #[test]
 fn test_horizontal_win() {
@@ -158,3 +162,199 @@ fn test_edge_case_wins() {
     assert!(WinChecker::check_win_around(&board, 0, 0, 5));
     assert!(WinChecker::check_win_around(&board, 4, 0, 5));
 }
+
+#[test]
+fn test_presets_set_rule_flags() {
+    let expected = [
+        (RulePreset::Standard, RuleFlags { captures_enabled: true, forbid_double_three: true, allow_overline: true, renju: false }),
+        (RulePreset::ClassicGomoku, RuleFlags { captures_enabled: false, forbid_double_three: false, allow_overline: true, renju: false }),
+        (RulePreset::Ninuki, RuleFlags { captures_enabled: true, forbid_double_three: false, allow_overline: true, renju: false }),
+        (RulePreset::Renju, RuleFlags { captures_enabled: false, forbid_double_three: true, allow_overline: false, renju: true }),
+    ];
+
+    for (preset, flags) in expected {
+        let mut settings = GameSettings::new();
+        settings.minimum_chain_to_win = 4;
+        settings.set_rule_preset(preset);
+        let state = settings.new_game_state().unwrap();
+
+        assert_eq!(state.rules, flags, "{:?}", preset);
+        assert_eq!(state.win_condition, 5, "{:?}", preset);
+        assert_eq!(GameState::with_preset(19, preset).rules, flags, "{:?}", preset);
+    }
+}
+
+#[test]
+fn test_classic_gomoku_has_no_captures() {
+    let mut state = GameState::with_preset(19, RulePreset::ClassicGomoku);
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    state.board.place_stone(9, 11, Player::Min);
+
+    state.make_move((9, 12));
+    assert_eq!(state.board.get_player(9, 10), Some(Player::Min));
+    assert_eq!(state.max_captures, 0);
+}
+
+#[test]
+fn test_double_three_allowed_without_the_rule() {
+    let mut board = Board::new(19);
+    board.place_stone(9, 7, Player::Max);
+    board.place_stone(9, 8, Player::Max);
+    board.place_stone(7, 9, Player::Max);
+    board.place_stone(8, 9, Player::Max);
+    board.place_stone(0, 18, Player::Min);
+
+    let mut standard = GameState::with_preset(19, RulePreset::Standard);
+    standard.board = board.clone();
+    assert!(!standard.get_possible_moves().contains(&(9, 9)));
+
+    let mut classic = GameState::with_preset(19, RulePreset::ClassicGomoku);
+    classic.board = board.clone();
+    assert!(classic.get_possible_moves().contains(&(9, 9)));
+
+    // Renju only forbids black
+    let mut renju = GameState::with_preset(19, RulePreset::Renju);
+    renju.board = board;
+    assert!(!renju.get_possible_moves().contains(&(9, 9)));
+    renju.current_player = Player::Min;
+    assert!(renju.get_possible_moves().contains(&(9, 9)));
+}
+
+#[test]
+fn test_renju_overline_does_not_win_for_black() {
+    let setup = |state: &mut GameState| {
+        for col in [4, 5, 6, 8, 9] {
+            state.board.place_stone(9, col, Player::Max);
+        }
+    };
+
+    let mut renju = GameState::with_preset(19, RulePreset::Renju);
+    setup(&mut renju);
+    renju.make_move((9, 7));
+    assert_eq!(renju.winner, None);
+
+    let mut classic = GameState::with_preset(19, RulePreset::ClassicGomoku);
+    setup(&mut classic);
+    classic.make_move((9, 7));
+    assert_eq!(classic.winner, Some(Player::Max));
+}
+
+fn without_overline() -> GameState {
+    let mut state = GameState::with_preset(19, RulePreset::ClassicGomoku);
+    state.rules.allow_overline = false;
+    state
+}
+
+#[test]
+fn test_overline_is_not_a_win_when_disabled() {
+    let mut state = without_overline();
+    for col in [4, 5, 6, 8, 9] {
+        state.board.place_stone(9, col, Player::Max);
+    }
+    assert!(!MoveHandler::completes_line(&state.board, 9, 7, Player::Max, 5, false));
+
+    state.make_move((9, 7));
+    assert_eq!(state.winner, None);
+    // The six is a dead line for the evaluation too
+    assert!(Heuristic::evaluate(&state, 0) < 900_000);
+}
+
+#[test]
+fn test_exact_five_wins_when_overline_disabled() {
+    let mut state = without_overline();
+    for col in [5, 6, 8, 9] {
+        state.board.place_stone(9, col, Player::Max);
+    }
+    assert!(MoveHandler::completes_line(&state.board, 9, 7, Player::Max, 5, false));
+
+    state.make_move((9, 7));
+    assert_eq!(state.winner, Some(Player::Max));
+}
+
+#[test]
+fn test_overline_wins_when_allowed() {
+    let mut state = GameState::with_preset(19, RulePreset::ClassicGomoku);
+    assert!(state.rules.allow_overline);
+    for col in [4, 5, 6, 8, 9] {
+        state.board.place_stone(9, col, Player::Max);
+    }
+    assert!(MoveHandler::completes_line(&state.board, 9, 7, Player::Max, 5, true));
+
+    state.make_move((9, 7));
+    assert_eq!(state.winner, Some(Player::Max));
+}
+
+#[test]
+fn test_captures_off_leaves_pairs_and_keeps_undo_symmetric() {
+    let mut state = GameState::new(19, 5);
+    state.rules.captures_enabled = false;
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    state.board.place_stone(9, 11, Player::Min);
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+    let before = state.clone();
+
+    state.make_move((9, 12));
+    assert_eq!(state.board.get_player(9, 10), Some(Player::Min));
+    assert_eq!(state.board.get_player(9, 11), Some(Player::Min));
+    assert_eq!((state.max_captures, state.min_captures), (0, 0));
+    assert_eq!(state.capture_history, vec![Vec::new()]);
+
+    state.undo_move((9, 12));
+    assert_eq!(state, before);
+}
+
+#[test]
+fn test_captures_off_ignores_capture_counts() {
+    let mut state = GameState::new(19, 5);
+    state.max_captures = state.capture_win_pairs;
+    let with_captures = Heuristic::evaluate(&state, 0);
+    assert_eq!(state.check_capture_win(), Some(Player::Max));
+
+    state.rules.captures_enabled = false;
+    assert_eq!(state.check_capture_win(), None);
+    assert!(Heuristic::evaluate(&state, 0) < with_captures);
+    assert_eq!(Heuristic::evaluate(&state, 0), 0);
+}
+
+#[test]
+fn test_captures_setting_follows_preset_until_toggled() {
+    let mut settings = GameSettings::new();
+    assert!(settings.new_game_state().unwrap().rules.captures_enabled);
+
+    settings.captures_enabled = false;
+    let state = settings.new_game_state().unwrap();
+    assert!(!state.rules.captures_enabled);
+    assert!(state.rules.forbid_double_three, "the rest of the Standard rules stay");
+
+    settings.set_rule_preset(RulePreset::Ninuki);
+    assert!(settings.captures_enabled);
+    settings.set_rule_preset(RulePreset::ClassicGomoku);
+    assert!(!settings.captures_enabled);
+}
+
+#[test]
+fn test_double_three_setting_follows_preset_until_toggled() {
+    let mut settings = GameSettings::new();
+    let mut forbidden = settings.new_game_state().unwrap();
+    for (row, col) in [(9, 7), (9, 8), (7, 9), (8, 9)] {
+        forbidden.board.place_stone(row, col, Player::Max);
+    }
+    forbidden.board.place_stone(0, 18, Player::Min);
+    assert!(!forbidden.get_possible_moves().contains(&(9, 9)));
+    assert!(forbidden.is_legal_move((9, 9)).is_err());
+
+    settings.forbid_double_three = false;
+    let mut allowed = settings.new_game_state().unwrap();
+    allowed.board = forbidden.board.clone();
+    assert!(!allowed.rules.forbid_double_three);
+    assert!(allowed.rules.captures_enabled, "the rest of the Standard rules stay");
+    assert!(allowed.get_possible_moves().contains(&(9, 9)));
+    assert!(allowed.is_legal_move((9, 9)).is_ok());
+
+    settings.set_rule_preset(RulePreset::Renju);
+    assert!(settings.forbid_double_three);
+    settings.set_rule_preset(RulePreset::Ninuki);
+    assert!(!settings.forbid_double_three);
+}
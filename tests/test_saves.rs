@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+
+use gomoku::core::state::GameState;
+use gomoku::ui::app::GameSettings;
+use gomoku::ui::saves::{list_saved_games, load_saved_game, save_game};
+
+/// Fresh directory under the system temp dir, unique to the test
+fn temp_saves_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gomoku-saves-{}-{}", std::process::id(), test));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_save_then_load_reproduces_the_game() {
+    let dir = temp_saves_dir("roundtrip");
+    let mut settings = GameSettings::new();
+    settings.set_ai_difficulty("hard");
+    let mut state = settings.new_game_state().unwrap();
+    // Max captures the pair at (9,10) and (9,11) on the last move
+    for mv in [(9, 9), (9, 10), (0, 0), (9, 11), (9, 12)] {
+        state.make_move(mv);
+    }
+    assert_eq!(state.max_captures, 1);
+
+    let path = save_game(&dir, &state, &settings).unwrap();
+    let loaded = load_saved_game(&path).unwrap();
+    assert_eq!(loaded.state, state);
+    assert_eq!(loaded.state.position_history, state.position_history);
+    assert_eq!(loaded.state.pattern_analyzer, state.pattern_analyzer);
+    assert_eq!(loaded.state.hash(), state.hash());
+    assert_eq!(loaded.settings, settings);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_list_saved_games_in_missing_dir_is_empty() {
+    let dir = temp_saves_dir("missing");
+    assert!(list_saved_games(&dir).is_empty());
+}
+
+#[test]
+fn test_saves_are_listed_newest_first() {
+    let dir = temp_saves_dir("listing");
+    let settings = GameSettings::new();
+    let first = save_game(&dir, &GameState::new(15, 5), &settings).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    let second = save_game(&dir, &GameState::new(15, 5), &settings).unwrap();
+    assert_ne!(first, second);
+    assert_eq!(list_saved_games(&dir), vec![second, first]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_corrupt_or_missing_save_is_an_error() {
+    let dir = temp_saves_dir("corrupt");
+    std::fs::create_dir_all(&dir).unwrap();
+    let corrupt = dir.join("game-1.json");
+    std::fs::write(&corrupt, "{\"state\": 42").unwrap();
+    assert!(load_saved_game(&corrupt).is_err());
+    assert!(load_saved_game(&dir.join("game-2.json")).is_err());
+
+    let settings = GameSettings::new();
+    let path = save_game(&dir, &GameState::new(15, 5), &settings).unwrap();
+    let json = std::fs::read_to_string(&path).unwrap().replace("\"medium\"", "\"impossible\"");
+    std::fs::write(&path, json).unwrap();
+    assert!(load_saved_game(&path).is_err());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_max_depth_survives_save_and_load() {
+    let dir = temp_saves_dir("max-depth");
+    let mut settings = GameSettings::new();
+    settings.set_ai_difficulty("hard");
+    settings.set_max_depth(6);
+    assert_eq!(settings.ai_search_depth(), 6);
+
+    let path = save_game(&dir, &GameState::new(15, 5), &settings).unwrap();
+    let loaded = load_saved_game(&path).unwrap();
+    assert_eq!(loaded.settings.max_depth, 6);
+    assert_eq!(loaded.settings.ai_search_depth(), 6);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_max_depth_is_checked_on_load() {
+    let mut json = serde_json::to_value(GameSettings::new()).unwrap();
+    json["max_depth"] = 40.into();
+    assert!(serde_json::from_value::<GameSettings>(json.clone()).is_err());
+    json["max_depth"] = 0.into();
+    assert!(serde_json::from_value::<GameSettings>(json.clone()).is_err());
+
+    // Saves from before the setting leave the AI uncapped
+    json.as_object_mut().unwrap().remove("max_depth");
+    let settings: GameSettings = serde_json::from_value(json).unwrap();
+    assert_eq!(settings.max_depth, 16);
+}
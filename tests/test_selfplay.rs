@@ -0,0 +1,37 @@
+use gomoku::ai::lazy_smp::SearchConfig;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::interface::selfplay::run_match;
+
+fn fast_config(depth: i32) -> SearchConfig {
+    SearchConfig {
+        num_threads: Some(1),
+        seed: Some(7),
+        ..SearchConfig::new(depth, None)
+    }
+}
+
+#[test]
+fn test_selfplay_games_end_without_illegal_moves() {
+    let result = run_match(&fast_config(2), &fast_config(1), 4, 9, 5);
+
+    assert_eq!(result.games.len(), 4);
+    assert_eq!(result.a_wins + result.b_wins + result.draws, 4);
+    for (game, record) in result.games.iter().enumerate() {
+        let a_color = if game % 2 == 0 { Player::Max } else { Player::Min };
+        assert_eq!(record.a_color, a_color);
+        assert_eq!(record.illegal_move, None, "game {} had an illegal move", game);
+
+        // Replaying the game checks each move against the rules and ends on the same result
+        let mut state = GameState::new(9, 5);
+        for &mv in &record.moves {
+            assert!(state.get_possible_moves().contains(&mv), "game {}: {:?} is not legal", game, mv);
+            state.make_move(mv);
+        }
+        assert_eq!(state.outcome(), Some(record.outcome), "game {} did not finish", game);
+    }
+
+    let total_moves: usize = result.games.iter().map(|record| record.moves.len()).sum();
+    assert_eq!(result.a_stats.moves + result.b_stats.moves, total_moves);
+    assert!(result.a_stats.total_nodes > 0 && result.b_stats.total_nodes > 0);
+}
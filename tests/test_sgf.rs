@@ -0,0 +1,116 @@
+use gomoku::core::board::Player;
+use gomoku::core::sgf::SgfError;
+use gomoku::core::state::GameState;
+
+fn play(state: &mut GameState, moves: &[(usize, usize)]) {
+    for &mv in moves {
+        state.make_move(mv);
+    }
+}
+
+fn assert_same_board(a: &GameState, b: &GameState) {
+    assert_eq!(a.board.size, b.board.size);
+    for row in 0..a.board.size {
+        for col in 0..a.board.size {
+            assert_eq!(
+                a.board.get_player(row, col),
+                b.board.get_player(row, col),
+                "Mismatch at ({}, {})",
+                row,
+                col
+            );
+        }
+    }
+}
+
+#[test]
+fn test_sgf_round_trip_in_progress() {
+    let mut state = GameState::new(15, 5);
+    play(&mut state, &[(7, 7), (7, 8), (8, 8), (6, 6), (8, 6), (9, 9)]);
+
+    let sgf = state.to_sgf();
+    assert!(sgf.starts_with("(;FF[4]GM[4]SZ[15]"));
+    assert!(sgf.contains(";B[hh]"));
+    assert!(sgf.contains(";W[ih]"));
+
+    let restored = GameState::from_sgf(&sgf).unwrap();
+    assert_same_board(&state, &restored);
+    assert_eq!(restored.moves, state.moves);
+    assert_eq!(restored.current_player, state.current_player);
+    assert_eq!(restored.win_condition, 5);
+    assert_eq!(restored.hash(), state.hash());
+}
+
+#[test]
+fn test_sgf_round_trip_with_capture() {
+    let mut state = GameState::new(19, 5);
+    // Max captures the Min pair at (9,10),(9,11) by playing (9,12)
+    play(&mut state, &[(9, 9), (9, 10), (8, 8), (9, 11), (9, 12)]);
+    assert_eq!(state.max_captures, 1);
+
+    let restored = GameState::from_sgf(&state.to_sgf()).unwrap();
+    assert_same_board(&state, &restored);
+    assert_eq!(restored.max_captures, 1);
+    assert_eq!(restored.min_captures, 0);
+    assert!(restored.board.is_empty_position(9, 10));
+    assert!(restored.board.is_empty_position(9, 11));
+}
+
+#[test]
+fn test_sgf_round_trip_completed_game() {
+    let mut state = GameState::new(15, 5);
+    play(
+        &mut state,
+        &[(7, 7), (8, 7), (7, 8), (8, 8), (7, 9), (8, 9), (7, 10), (8, 10), (7, 11)],
+    );
+    assert_eq!(state.winner, Some(Player::Max));
+
+    let restored = GameState::from_sgf(&state.to_sgf()).unwrap();
+    assert_same_board(&state, &restored);
+    assert_eq!(restored.winner, Some(Player::Max));
+}
+
+#[test]
+fn test_sgf_rejects_occupied_cell() {
+    let err = GameState::from_sgf("(;FF[4]GM[4]SZ[15];B[hh];W[hh])").unwrap_err();
+    assert!(matches!(err, SgfError::IllegalMove { move_number: 2, .. }), "{}", err);
+}
+
+#[test]
+fn test_sgf_rejects_out_of_bounds() {
+    let err = GameState::from_sgf("(;FF[4]GM[4]SZ[15];B[hh];W[zz])").unwrap_err();
+    assert!(matches!(err, SgfError::IllegalMove { move_number: 2, .. }), "{}", err);
+}
+
+#[test]
+fn test_sgf_rejects_malformed_and_other_games() {
+    assert!(matches!(GameState::from_sgf(";B[aa]"), Err(SgfError::Malformed(_))));
+    assert!(matches!(GameState::from_sgf("(;GM[1]SZ[19])"), Err(SgfError::UnsupportedGame(_))));
+    assert!(matches!(
+        GameState::from_sgf("(;GM[4]SZ[abc])"),
+        Err(SgfError::InvalidProperty { .. })
+    ));
+}
+
+#[test]
+fn test_sgf_round_trip_keeps_the_rules() {
+    use gomoku::core::rules::RulePreset;
+
+    let mut state = GameState::with_preset(19, RulePreset::Renju);
+    state.rules.forbid_double_three = false;
+    let restored = GameState::from_sgf(&state.to_sgf()).unwrap();
+    assert_eq!(restored.rules, state.rules);
+
+    // Without the capture rule the flanking move leaves the pair on the board
+    let mut state = GameState::with_preset(19, RulePreset::ClassicGomoku);
+    play(&mut state, &[(9, 9), (9, 10), (8, 8), (9, 11), (9, 12)]);
+    let restored = GameState::from_sgf(&state.to_sgf()).unwrap();
+    assert_eq!(restored.rules, state.rules);
+    assert_eq!(restored.max_captures, 0);
+    assert_eq!(restored.board.get_player(9, 10), Some(Player::Min));
+
+    assert!(matches!(
+        GameState::from_sgf("(;GM[4]SZ[15]CE[2])"),
+        Err(SgfError::InvalidProperty { .. })
+    ));
+}
@@ -0,0 +1,91 @@
+use std::io::Cursor;
+use std::time::Duration;
+
+use gomoku::ai::commentary::describe_move;
+use gomoku::ai::lazy_smp::SearchConfig;
+use gomoku::core::board::{Board, Player};
+use gomoku::core::state::{GameOutcome, GameState};
+use gomoku::interface::shell_game::{format_move, new_game, parse_move, ShellGame};
+
+fn quick_config() -> SearchConfig {
+    SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(2, Some(Duration::from_millis(100)))
+    }
+}
+
+#[test]
+fn test_parse_move_reads_both_notations() {
+    let board = Board::new(15);
+    assert_eq!(parse_move("h8", &board), Some((7, 7)));
+    assert_eq!(parse_move("H8", &board), Some((7, 7)));
+    assert_eq!(parse_move("a1", &board), Some((0, 0)));
+    assert_eq!(parse_move("7,7", &board), Some((7, 7)));
+    assert_eq!(parse_move(" 3 , 12 ", &board), Some((3, 12)));
+    assert_eq!(parse_move("a0", &board), None);
+    assert_eq!(parse_move("p1", &board), None);
+    assert_eq!(parse_move("15,0", &board), None);
+    assert_eq!(parse_move("hello", &board), None);
+    assert_eq!(format_move((7, 7)), "h8 (7,7)");
+}
+
+#[test]
+fn test_scripted_game_gets_legal_replies_and_ends() {
+    let script = "h8\nboard\ng7\nzz\nundo\ng7\nf6\nresign\nh9\nquit\n";
+    let mut output = Vec::new();
+    let state = new_game(Cursor::new(script), &mut output, 15, quick_config()).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("AI plays"), "{}", output);
+    assert!(output.contains("Cannot read move 'zz'"), "{}", output);
+    assert!(output.contains("The AI wins by resignation."), "{}", output);
+    assert!(output.contains("The game is over"), "{}", output);
+    assert_eq!(state.outcome(), Some(GameOutcome::Resignation(Player::Min)));
+
+    // Every move of the game, the AI's included, replays legally from the start
+    let mut replay = GameState::new(15, 5);
+    for &(row, col, player) in &state.moves {
+        assert_eq!(replay.current_player, player);
+        assert_eq!(replay.is_legal_move((row, col)), Ok(()), "move {:?}", (row, col));
+        replay.make_move((row, col));
+    }
+    assert!(state.moves.len() >= 4);
+}
+
+#[test]
+fn test_undo_gives_the_turn_back_to_the_human() {
+    let mut game = ShellGame::new(15, quick_config());
+    game.handle_line("h8");
+    assert_eq!(game.state.moves.len(), 2);
+    assert_eq!(game.state.current_player, Player::Max);
+
+    game.handle_line("undo");
+    assert!(game.state.moves.is_empty());
+    assert_eq!(game.state.current_player, Player::Max);
+    let (replies, done) = game.handle_line("undo");
+    assert_eq!(replies, vec!["Nothing to undo.".to_string()]);
+    assert!(!done);
+}
+
+#[test]
+fn test_ai_reply_comes_with_its_commentary() {
+    let mut game = ShellGame::new(15, quick_config());
+    game.handle_line("h8");
+    let (replies, _) = game.handle_line("i8");
+
+    let &(row, col, ai) = game.state.moves.last().unwrap();
+    let mut before = game.state.clone();
+    before.undo_last_move();
+    let expected = format!("AI plays {} ", format_move((row, col)));
+    assert!(replies[0].starts_with(&expected), "{:?}", replies);
+    assert!(replies[0].ends_with(&describe_move(&before, (row, col), ai)), "{:?}", replies);
+}
+
+#[test]
+fn test_illegal_move_is_refused() {
+    let mut game = ShellGame::new(15, quick_config());
+    let (replies, _) = game.handle_line("a1");
+    assert!(replies[0].starts_with("Illegal move a1 (0,0)"), "{:?}", replies);
+    assert!(game.state.moves.is_empty());
+    assert!(game.handle_line("quit").1);
+}
@@ -0,0 +1,62 @@
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+use gomoku::ui::app::GameSettings;
+use gomoku::ui::screens::game::board::LastMove;
+use gomoku::ui::screens::game::game::{place_stone, CaptureProgressEvent, MovePlayed, StonePlaced, StonePlacement};
+use gomoku::ui::screens::game::sound::{capture_volume, scaled_volume};
+
+fn placed_events(state: GameState, x: usize, y: usize) -> Vec<StonePlaced> {
+    let mut app = App::new();
+    app.insert_resource(state)
+        .init_resource::<LastMove>()
+        .add_event::<StonePlacement>()
+        .add_event::<MovePlayed>()
+        .add_event::<CaptureProgressEvent>()
+        .add_event::<StonePlaced>()
+        .add_systems(Update, place_stone);
+    app.world_mut().send_event(StonePlacement { x, y });
+    app.update();
+
+    let events = app.world().resource::<Events<StonePlaced>>();
+    events.get_cursor().read(events).copied().collect()
+}
+
+#[test]
+fn test_capture_reports_pairs_to_the_sound_system() {
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    state.board.place_stone(9, 11, Player::Min);
+    // Second pair on the column, taken by the same stone
+    state.board.place_stone(6, 12, Player::Max);
+    state.board.place_stone(7, 12, Player::Min);
+    state.board.place_stone(8, 12, Player::Min);
+
+    assert_eq!(
+        placed_events(state, 9, 12),
+        vec![StonePlaced { player: Player::Max, pairs_captured: 2 }]
+    );
+}
+
+#[test]
+fn test_quiet_move_is_a_plain_placement() {
+    assert_eq!(
+        placed_events(GameState::new(19, 5), 9, 9),
+        vec![StonePlaced { player: Player::Max, pairs_captured: 0 }]
+    );
+}
+
+#[test]
+fn test_volume_follows_settings_and_capture_size() {
+    let mut settings = GameSettings::new();
+    settings.sound_volume = 50;
+    assert_eq!(scaled_volume(&settings, 0.8), Some(Volume::Linear(0.4)));
+
+    settings.sound_volume = 0;
+    assert_eq!(scaled_volume(&settings, 0.8), None);
+
+    assert!(capture_volume(2) > capture_volume(1));
+    assert_eq!(capture_volume(10), 1.0);
+}
@@ -1,5 +1,12 @@
+use gomoku::ai::heuristic::Heuristic;
 use gomoku::core::board::Player;
-use gomoku::core::state::GameState;
+use gomoku::core::moves::IllegalMove;
+use gomoku::core::rules::WinChecker;
+use gomoku::ai::lazy_smp::lazy_smp_search;
+use gomoku::core::board::Board;
+use gomoku::core::state::{BoardStats, GameOutcome, GameState, IntegrityError, WinReason, MAX_BOARD_SIZE};
+use gomoku::ui::app::GameSettings;
+use std::time::Duration;
 
 #[test]
 fn test_game_state_creation() {
@@ -313,3 +320,670 @@ fn test_capture_history_tracking() {
     assert_eq!(state.capture_history.len(), 3);
     assert!(!state.capture_history[2].is_empty());
 }
+
+#[test]
+fn test_new_with_opening_matches_manual_play() {
+    let opening = [(9, 9), (9, 10), (10, 9), (8, 10)];
+    let state = GameState::new_with_opening(19, 5, 5, &opening).unwrap();
+
+    let mut manual = GameState::new(19, 5);
+    for mv in opening {
+        manual.make_move(mv);
+    }
+
+    assert_eq!(state, manual);
+    assert_eq!(state.hash(), manual.hash());
+    assert_eq!(state.current_player, Player::Max);
+}
+
+#[test]
+fn test_new_with_opening_rejects_illegal_moves() {
+    // First stone must be in the center
+    assert!(GameState::new_with_opening(19, 5, 5, &[(0, 0)]).is_err());
+
+    // Occupied cell
+    assert!(GameState::new_with_opening(19, 5, 5, &[(9, 9), (9, 9)]).is_err());
+
+    // Not adjacent to any stone
+    assert!(GameState::new_with_opening(19, 5, 5, &[(9, 9), (2, 2)]).is_err());
+}
+
+#[test]
+fn test_new_with_opening_capture_pairs() {
+    let state = GameState::new_with_opening(19, 5, 3, &[(9, 9)]).unwrap();
+    assert_eq!(state.capture_win_pairs, 3);
+}
+
+#[test]
+fn test_try_new_rejects_oversized_board() {
+    assert!(GameState::try_new(MAX_BOARD_SIZE + 1, 5).is_err());
+    assert!(GameState::try_new(50, 5).is_err());
+    assert!(GameState::try_new(0, 5).is_err());
+    assert!(GameState::new_with_opening(50, 5, 5, &[]).is_err());
+
+    let mut settings = GameSettings::new();
+    settings.set_board_size(50);
+    assert_eq!(settings.board_size, MAX_BOARD_SIZE);
+    assert_eq!(settings.new_game_state().unwrap().board.size, MAX_BOARD_SIZE);
+
+    // Settings set out of range by hand, or read from an edited save, make no game
+    settings.board_size = 50;
+    assert!(settings.new_game_state().is_err());
+    let mut json = serde_json::to_value(GameSettings::new()).unwrap();
+    json["board_size"] = 50.into();
+    assert!(serde_json::from_value::<GameSettings>(json).is_err());
+}
+
+#[test]
+fn test_largest_board_plays_end_to_end() {
+    let mut state = GameState::try_new(MAX_BOARD_SIZE, 5).unwrap();
+
+    for _ in 0..3 {
+        let result = lazy_smp_search(&mut state, 2, Some(Duration::from_millis(500)), Some(1));
+        let mv = result.best_move.expect("search should find a move on the largest board");
+        assert!(mv.0 < MAX_BOARD_SIZE && mv.1 < MAX_BOARD_SIZE);
+        state.make_move(mv);
+    }
+    assert_eq!(state.moves.len(), 3);
+}
+
+fn old_is_terminal(state: &GameState) -> bool {
+    state.winner.is_some() || state.get_possible_moves().is_empty()
+}
+
+#[test]
+fn test_is_terminal_matches_move_generation() {
+    let mut positions = vec![GameState::new(19, 5)];
+
+    let mut single = GameState::new(19, 5);
+    single.board.place_stone(0, 18, Player::Min);
+    positions.push(single);
+
+    // Full board, filled in a pattern without five in a row
+    let mut full = GameState::new(15, 5);
+    for row in 0..15 {
+        for col in 0..15 {
+            let player = if (col + 2 * (row % 2) + row / 2) % 4 < 2 { Player::Max } else { Player::Min };
+            full.board.place_stone(row, col, player);
+        }
+    }
+    assert!(full.board.is_full());
+    positions.push(full.clone());
+
+    // Near-full board: a single hole in the corner
+    let mut near_full = full;
+    near_full.board.remove_stone(14, 14);
+    positions.push(near_full);
+
+    // Positions along a game
+    let mut game = GameState::new(19, 5);
+    for _ in 0..40 {
+        let moves = game.get_possible_moves();
+        if moves.is_empty() || game.winner.is_some() {
+            break;
+        }
+        game.make_move(moves[moves.len() / 3]);
+        positions.push(game.clone());
+    }
+
+    for mut state in positions {
+        for player in [Player::Max, Player::Min] {
+            state.current_player = player;
+            assert_eq!(state.is_terminal(), old_is_terminal(&state), "{:?}", state.board.get_occupied_positions());
+        }
+    }
+}
+
+#[test]
+fn test_outcome_distinguishes_captures_from_lines() {
+    let mut state = GameState::new(19, 5);
+    assert_eq!(state.outcome(), None);
+
+    // Max takes the fifth pair with this move
+    state.max_captures = 4;
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    state.board.place_stone(9, 11, Player::Min);
+    state.current_player = Player::Max;
+    state.make_move((9, 12));
+    assert_eq!(state.outcome(), Some(GameOutcome::Captures(Player::Max)));
+
+    let mut state = GameState::new(19, 5);
+    for i in 0..4 {
+        state.make_move((5, i));
+        state.make_move((7, i));
+    }
+    state.make_move((5, 4));
+    assert_eq!(state.outcome(), Some(GameOutcome::FiveInARow(Player::Max)));
+}
+
+#[test]
+fn test_outcome_is_draw_without_legal_moves() {
+    let mut state = GameState::new(3, 3);
+    // X O X / X O O / O X X: full board, no three in a row
+    let cells = [
+        ((0, 0), Player::Max), ((0, 1), Player::Min), ((0, 2), Player::Max),
+        ((1, 0), Player::Max), ((1, 1), Player::Min), ((1, 2), Player::Min),
+        ((2, 0), Player::Min), ((2, 1), Player::Max), ((2, 2), Player::Max),
+    ];
+    for ((row, col), player) in cells {
+        state.board.place_stone(row, col, player);
+    }
+
+    assert_eq!(state.outcome(), Some(GameOutcome::Draw));
+}
+
+/// Max one capture short of `threshold`, with the capturing move (9, 12) available
+fn one_capture_from_win(threshold: usize) -> GameState {
+    let mut state = GameState::new(19, 5);
+    state.capture_win_pairs = threshold;
+    state.max_captures = threshold - 1;
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    state.board.place_stone(9, 11, Player::Min);
+    state.current_player = Player::Max;
+    state
+}
+
+#[test]
+fn test_capture_win_follows_the_threshold() {
+    use gomoku::ai::heuristic::Heuristic;
+
+    for threshold in [3, 5] {
+        let mut state = one_capture_from_win(threshold);
+        assert_eq!(state.check_capture_win(), None, "threshold {}", threshold);
+        assert!(state.is_about_to_lose_by_capture(Player::Min), "threshold {}", threshold);
+        assert!(!state.is_about_to_lose_by_capture(Player::Max), "threshold {}", threshold);
+
+        state.make_move((9, 12));
+        assert_eq!(state.max_captures, threshold);
+        assert_eq!(state.winner, Some(Player::Max), "threshold {}", threshold);
+        assert!(Heuristic::evaluate(&state, 0) >= 1_000_000);
+    }
+}
+
+#[test]
+fn test_capture_count_below_threshold_is_not_a_win() {
+    use gomoku::ai::heuristic::Heuristic;
+
+    let mut state = GameState::new(19, 5);
+    state.capture_win_pairs = 8;
+    state.max_captures = 5;
+    state.board.place_stone(9, 9, Player::Max);
+
+    assert_eq!(state.check_capture_win(), None);
+    assert!(!state.is_about_to_lose_by_capture(Player::Min));
+    assert!(Heuristic::evaluate(&state, 0) < 1_000_000);
+}
+
+#[test]
+fn test_settings_capture_stones_become_pairs() {
+    let mut settings = GameSettings::new();
+    settings.total_capture_to_win = 6;
+    assert_eq!(settings.new_game_state().unwrap().capture_win_pairs, 3);
+}
+
+/// Row 9 reads `X O O . . O` with Max to move: Max captures, Min rebuilds, Max extends
+/// and Min recaptures, which brings back the starting board with Max to move.
+fn capture_cycle_state() -> GameState {
+    let mut state = GameState::new(19, 5);
+    state.board.place_stone(9, 5, Player::Max);
+    state.board.place_stone(9, 6, Player::Min);
+    state.board.place_stone(9, 7, Player::Min);
+    state.board.place_stone(9, 10, Player::Min);
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+    state
+}
+
+const CAPTURE_CYCLE: [(usize, usize); 4] = [(9, 8), (9, 6), (9, 9), (9, 7)];
+
+#[test]
+fn test_capture_recapture_cycle_is_not_a_repetition() {
+    let mut state = capture_cycle_state();
+    let start = state.board.clone();
+
+    for &mv in &CAPTURE_CYCLE {
+        assert!(!state.is_repetition());
+        state.make_move(mv);
+    }
+
+    // Same stones, but each side is one pair closer to a capture win
+    assert_eq!(state.board, start);
+    assert_eq!((state.max_captures, state.min_captures), (1, 1));
+    assert!(!state.is_repetition());
+
+    for &mv in CAPTURE_CYCLE.iter().rev() {
+        state.undo_move(mv);
+    }
+    assert!(state.position_history.is_empty());
+}
+
+#[test]
+fn test_same_stones_and_capture_totals_is_a_repetition() {
+    let mut state = capture_cycle_state();
+    for &mv in &CAPTURE_CYCLE {
+        state.make_move(mv);
+    }
+
+    // Every move adds a stone, so only captures bring stones back in play; taking the
+    // totals back by hand leaves a quiet return to the starting position
+    state.max_captures = 0;
+    state.min_captures = 0;
+    assert!(state.is_repetition());
+}
+
+#[test]
+fn test_search_through_capture_cycles_terminates() {
+    use gomoku::ai::lazy_smp::{lazy_smp_search_with_config, SearchConfig};
+
+    let mut state = capture_cycle_state();
+    // Play the cycle once so the search can go round it again
+    for &mv in &CAPTURE_CYCLE {
+        state.make_move(mv);
+    }
+    let legal = state.get_possible_moves();
+
+    let config = SearchConfig {
+        num_threads: Some(1),
+        ..SearchConfig::new(4, None)
+    };
+    let result = lazy_smp_search_with_config(&mut state, &config);
+
+    let best_move = result.best_move.expect("search should return a move");
+    assert!(legal.contains(&best_move), "{:?} is not legal", best_move);
+    assert_eq!(state.position_history.len(), CAPTURE_CYCLE.len());
+}
+
+#[test]
+fn test_resigning_ends_the_game_and_rejects_moves() {
+    let mut state = GameState::new(19, 5);
+    state.make_move((9, 9));
+    state.make_move((9, 10));
+
+    assert!(state.resign(Player::Max));
+    assert_eq!(state.winner, Some(Player::Min));
+    assert_eq!(state.outcome(), Some(GameOutcome::Resignation(Player::Min)));
+    assert!(state.is_terminal());
+
+    let board = state.board.clone();
+    state.make_move((10, 10));
+    assert_eq!(state.board, board, "no move after a resignation");
+    assert_eq!(state.moves.len(), 2);
+    assert!(!state.resign(Player::Min), "the game is already over");
+}
+
+#[test]
+fn test_agreed_draw_has_no_winner_and_undo_reopens_the_game() {
+    let mut state = GameState::new(19, 5);
+    state.make_move((9, 9));
+
+    assert!(state.agree_draw());
+    assert_eq!(state.winner, None);
+    assert_eq!(state.outcome(), Some(GameOutcome::DrawAgreed));
+    assert!(state.is_terminal());
+
+    state.undo_last_move();
+    assert_eq!(state.outcome(), None);
+    state.make_move((9, 9));
+    assert_eq!(state.moves.len(), 1);
+}
+
+#[test]
+fn test_stats_count_stones_and_captures() {
+    let mut state = GameState::new(19, 5);
+    // Black takes (9, 10)-(9, 11) and (12, 1)-(12, 2), leaving white with no stones
+    for mv in [(9, 9), (9, 10), (12, 0), (9, 11), (9, 12), (12, 1), (0, 0), (12, 2), (12, 3)] {
+        state.make_move(mv);
+    }
+    state.make_move((5, 5));
+
+    assert_eq!(
+        state.stats(),
+        BoardStats {
+            max_stones: 5,
+            min_stones: 1,
+            empty: 19 * 19 - 6,
+            max_captures: 2,
+            min_captures: 0,
+            move_number: 11,
+        }
+    );
+}
+
+#[test]
+fn test_is_legal_move_reasons() {
+    let mut state = GameState::new(15, 5);
+    assert_eq!(state.is_legal_move((0, 0)), Err(IllegalMove::OffCenterOpening));
+    assert_eq!(state.is_legal_move((7, 7)), Ok(()));
+    state.make_move((7, 7));
+
+    assert_eq!(state.is_legal_move((15, 3)), Err(IllegalMove::OutOfBounds));
+    assert_eq!(state.is_legal_move((7, 7)), Err(IllegalMove::Occupied));
+    assert_eq!(state.is_legal_move((0, 0)), Err(IllegalMove::NotAdjacentOnNonEmptyBoard));
+    assert_eq!(state.is_legal_move((8, 8)), Ok(()));
+
+    state.resign(Player::Min);
+    assert_eq!(state.is_legal_move((8, 8)), Err(IllegalMove::GameOver));
+}
+
+#[test]
+fn test_is_legal_move_refuses_double_three() {
+    let mut state = GameState::new(19, 5);
+    for cell in [(8, 8), (10, 10), (8, 10), (10, 8)] {
+        state.board.place_stone(cell.0, cell.1, Player::Max);
+    }
+    state.current_player = Player::Max;
+
+    assert_eq!(state.is_legal_move((9, 9)), Err(IllegalMove::DoubleThree));
+    // White is not bound by the double-three rule
+    state.current_player = Player::Min;
+    assert_eq!(state.is_legal_move((9, 9)), Ok(()));
+}
+
+#[test]
+fn test_is_legal_move_agrees_with_possible_moves() {
+    let mut state = GameState::new(15, 5);
+    for mv in [(7, 7), (7, 8), (8, 7), (6, 6), (9, 7), (6, 8)] {
+        state.make_move(mv);
+    }
+    let possible = state.get_possible_moves();
+    for row in 0..15 {
+        for col in 0..15 {
+            assert_eq!(state.is_legal_move((row, col)).is_ok(), possible.contains(&(row, col)), "({}, {})", row, col);
+        }
+    }
+}
+
+#[test]
+fn test_move_leaving_a_four_allows_opponent_win() {
+    let mut state = GameState::new(19, 5);
+    // White gets four in a row on row 8, blocked by black on the left only
+    for mv in [(9, 9), (8, 8), (8, 7), (8, 9), (10, 10), (8, 10), (10, 8), (8, 11)] {
+        state.make_move(mv);
+    }
+    assert_eq!(state.current_player, Player::Max);
+
+    assert!(state.move_allows_opponent_win((11, 9)));
+    assert!(!state.move_allows_opponent_win((8, 12)));
+}
+
+#[test]
+fn test_move_offering_the_last_capture_allows_opponent_win() {
+    let mut state = GameState::new(19, 5);
+    for mv in [(9, 9), (9, 8), (10, 9), (11, 11)] {
+        state.make_move(mv);
+    }
+    state.min_captures = state.capture_win_pairs - 1;
+
+    // Makes a pair White can take with (9, 11)
+    assert!(state.move_allows_opponent_win((9, 10)));
+    assert!(!state.move_allows_opponent_win((8, 10)));
+}
+
+#[test]
+fn test_played_out_board_is_a_draw_scored_zero() {
+    let mut state = GameState::new(3, 3);
+    state.win_condition = 3;
+    // Ends on X O X / X O O / O X X, with no three in a row along the way
+    for mv in [(0, 0), (0, 1), (0, 2), (1, 1), (1, 0), (1, 2), (2, 1), (2, 0)] {
+        state.make_move(mv);
+        assert!(!state.is_draw(), "drawn before the board was full, at {:?}", mv);
+    }
+    state.make_move((2, 2));
+
+    assert!(state.board.is_full());
+    assert!(state.is_draw());
+    assert_eq!(state.outcome(), Some(GameOutcome::Draw));
+    assert_eq!(Heuristic::evaluate(&state, 0), 0);
+}
+
+#[test]
+fn test_wins_on_a_full_board_are_not_draws() {
+    let mut state = GameState::new(3, 3);
+    state.win_condition = 3;
+    // X X X / O O X / X O O: Max completes the top row with the last stone
+    for mv in [(0, 0), (1, 0), (0, 1), (1, 1), (1, 2), (2, 1), (2, 0), (2, 2), (0, 2)] {
+        state.make_move(mv);
+    }
+    assert!(state.board.is_full());
+    assert_eq!(state.check_winner(), Some(Player::Max));
+    assert!(!state.is_draw());
+    assert!(Heuristic::evaluate(&state, 0) > 0);
+
+    // A capture win on a full board is not a draw either
+    let mut captured = GameState::new(3, 3);
+    captured.win_condition = 3;
+    for mv in [(0, 0), (0, 1), (0, 2), (1, 1), (1, 0), (1, 2), (2, 1), (2, 0), (2, 2)] {
+        captured.make_move(mv);
+    }
+    captured.min_captures = captured.capture_win_pairs;
+    assert!(!captured.is_draw());
+    assert!(Heuristic::evaluate(&captured, 0) < 0);
+}
+
+#[test]
+fn test_find_all_winning_moves_on_a_double_four() {
+    let mut state = GameState::new(19, 5);
+    // A four along row 5, closed on the left, and one down column 12, closed at the bottom
+    for col in 3..7 {
+        state.board.place_stone(5, col, Player::Max);
+    }
+    state.board.place_stone(5, 2, Player::Min);
+    for row in 10..14 {
+        state.board.place_stone(row, 12, Player::Max);
+    }
+    state.board.place_stone(14, 12, Player::Min);
+    state.current_player = Player::Max;
+
+    let mut wins = state.find_all_winning_moves(Player::Max);
+    wins.sort();
+    assert_eq!(wins, vec![(5, 7), (9, 12)]);
+    // Asked for the side not to move, and for a side without fours
+    assert_eq!(state.find_all_winning_moves(Player::Min), Vec::new());
+    state.current_player = Player::Min;
+    assert_eq!(state.find_all_winning_moves(Player::Max).len(), 2);
+}
+
+#[test]
+fn test_find_all_winning_moves_counts_capture_wins() {
+    let state = one_capture_from_win(5);
+    assert_eq!(state.find_all_winning_moves(Player::Max), vec![(9, 12)]);
+    // One pair short of the target is not a win
+    let state = one_capture_from_win(6);
+    let mut short = state.clone();
+    short.max_captures = 4;
+    assert!(short.find_all_winning_moves(Player::Max).is_empty());
+}
+
+#[test]
+fn test_find_all_winning_moves_is_empty_in_a_quiet_position() {
+    let mut state = GameState::new(19, 5);
+    for mv in [(9, 9), (9, 10), (10, 9), (11, 11), (8, 8), (7, 7)] {
+        state.make_move(mv);
+    }
+    assert!(state.find_all_winning_moves(Player::Max).is_empty());
+    assert!(state.find_all_winning_moves(Player::Min).is_empty());
+}
+
+/// Black five on row 9, columns 5 to 9, set up on the board
+fn five_on_row_nine() -> GameState {
+    let mut state = GameState::new(19, 5);
+    for col in 5..10 {
+        state.board.place_stone(9, col, Player::Max);
+    }
+    state.current_player = Player::Min;
+    state
+}
+
+#[test]
+fn test_five_breaking_capture_is_found() {
+    let mut state = five_on_row_nine();
+    // (9,7) and (10,7) form a pair under White's (8,7): White at (11,7) takes it
+    state.board.place_stone(10, 7, Player::Max);
+    state.board.place_stone(8, 7, Player::Min);
+    // A capturable pair outside the five does not count
+    state.board.place_stone(3, 3, Player::Max);
+    state.board.place_stone(3, 4, Player::Max);
+    state.board.place_stone(3, 2, Player::Min);
+
+    assert_eq!(state.find_five_breaking_captures(Player::Max), vec![(11, 7)]);
+    assert!(state.find_five_breaking_captures(Player::Min).is_empty());
+
+    state.rules.captures_enabled = false;
+    assert!(state.find_five_breaking_captures(Player::Max).is_empty());
+}
+
+#[test]
+fn test_unbreakable_five_has_no_breaking_capture() {
+    let mut state = five_on_row_nine();
+    // A pair through the five, but closed by White's stones on both sides
+    state.board.place_stone(10, 7, Player::Max);
+    state.board.place_stone(8, 7, Player::Min);
+    state.board.place_stone(11, 7, Player::Min);
+    assert!(state.find_five_breaking_captures(Player::Max).is_empty());
+    assert!(five_on_row_nine().find_five_breaking_captures(Player::Max).is_empty());
+}
+
+#[test]
+fn test_capture_win_takes_precedence_over_five() {
+    let mut state = GameState::new(19, 5);
+    state.max_captures = 4;
+    // (9,12) takes the fifth pair and completes the column (9..=13, 12)
+    state.board.place_stone(9, 9, Player::Max);
+    state.board.place_stone(9, 10, Player::Min);
+    state.board.place_stone(9, 11, Player::Min);
+    for row in 10..=13 {
+        state.board.place_stone(row, 12, Player::Max);
+    }
+    state.current_player = Player::Max;
+    state.make_move((9, 12));
+
+    assert_eq!(state.max_captures, 5);
+    assert!(WinChecker::check_win_around(&state.board, 9, 12, 5));
+    assert_eq!(state.winner, Some(Player::Max));
+    assert_eq!(state.win_reason, Some(WinReason::Captures));
+    assert_eq!(state.outcome(), Some(GameOutcome::Captures(Player::Max)));
+
+    // Undone and replayed, the same reason comes back
+    state.undo_last_move();
+    assert_eq!((state.winner, state.win_reason), (None, None));
+    state.redo_move();
+    assert_eq!(state.win_reason, Some(WinReason::Captures));
+
+    let loaded = GameState::from_json(&state.to_json()).unwrap();
+    assert_eq!(loaded.win_reason, Some(WinReason::Captures));
+}
+
+#[test]
+fn test_five_records_its_reason() {
+    let mut state = GameState::new(19, 5);
+    for i in 0..4 {
+        state.make_move((5, i));
+        state.make_move((7, i));
+    }
+    assert_eq!(state.win_reason, None);
+    state.make_move((5, 4));
+    assert_eq!(state.win_reason, Some(WinReason::FiveInARow));
+}
+
+#[test]
+fn test_validate_integrity_accepts_played_games() {
+    let mut state = GameState::new(15, 5);
+    assert_eq!(state.validate_integrity(), Ok(()));
+    for mv in [(7, 7), (8, 7), (7, 8), (8, 8), (7, 9), (8, 9), (7, 10), (8, 10), (7, 11)] {
+        state.make_move(mv);
+    }
+    assert_eq!(state.winner, Some(Player::Max));
+    assert_eq!(state.validate_integrity(), Ok(()));
+
+    // A resignation needs no line on the board
+    let mut resigned = GameState::new(15, 5);
+    resigned.make_move((7, 7));
+    resigned.resign(Player::Min);
+    assert_eq!(resigned.validate_integrity(), Ok(()));
+}
+
+#[test]
+fn test_validate_integrity_reports_bad_bitboards() {
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    let index = state.board.index(7, 7);
+
+    let mut overlapping = state.clone();
+    overlapping.board.edit_bits(|_, min, _| Board::set_bit(min, index));
+    assert_eq!(overlapping.validate_integrity(), Err(IntegrityError::OverlappingStones));
+
+    let mut unoccupied = state.clone();
+    unoccupied.board.edit_bits(|_, _, occupied| Board::clear_bit(occupied, index));
+    assert_eq!(unoccupied.validate_integrity(), Err(IntegrityError::OccupiedMismatch));
+}
+
+#[test]
+fn test_validate_integrity_reports_impossible_wins() {
+    let five = |state: &mut GameState, row: usize, player: Player| {
+        for col in 3..8 {
+            state.board.place_stone(row, col, player);
+        }
+    };
+
+    let mut both = GameState::new(15, 5);
+    five(&mut both, 2, Player::Max);
+    five(&mut both, 9, Player::Min);
+    assert_eq!(both.validate_integrity(), Err(IntegrityError::BothPlayersHaveFive));
+
+    let mut unrecorded = GameState::new(15, 5);
+    five(&mut unrecorded, 2, Player::Min);
+    assert_eq!(unrecorded.validate_integrity(), Err(IntegrityError::UnrecordedWin(Player::Min)));
+    unrecorded.winner = Some(Player::Max);
+    assert_eq!(unrecorded.validate_integrity(), Err(IntegrityError::UnrecordedWin(Player::Min)));
+
+    let mut unsupported = GameState::new(15, 5);
+    unsupported.board.place_stone(7, 7, Player::Max);
+    unsupported.winner = Some(Player::Max);
+    assert_eq!(unsupported.validate_integrity(), Err(IntegrityError::UnsupportedWinner(Player::Max)));
+    // Enough captures back the same winner up
+    unsupported.max_captures = unsupported.capture_win_pairs;
+    assert_eq!(unsupported.validate_integrity(), Ok(()));
+}
+
+#[test]
+fn test_line_win_impossible_on_a_board_shorter_than_the_chain() {
+    let mut state = GameState::new(4, 5);
+    assert!(!state.line_win_possible());
+    assert!(GameState::new(15, 5).line_win_possible());
+    assert!(GameState::new_rect(4, 5, 5).line_win_possible());
+
+    // Four across the whole board is all Max gets, and it is not a win
+    for (row, col) in [(0, 0), (0, 1), (0, 2)] {
+        state.board.place_stone(row, col, Player::Max);
+    }
+    state.current_player = Player::Max;
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+    state.make_move((0, 3));
+    assert_eq!(state.check_winner(), None);
+    assert!(state.find_all_winning_moves(Player::Max).is_empty());
+    assert!(state.validate_integrity().is_ok());
+
+    // Captures still decide the game
+    let mut state = GameState::new(4, 5);
+    state.capture_win_pairs = 1;
+    state.board.place_stone(2, 0, Player::Max);
+    state.board.place_stone(2, 1, Player::Min);
+    state.board.place_stone(2, 2, Player::Min);
+    state.current_player = Player::Max;
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+    assert_eq!(state.find_all_winning_moves(Player::Max), vec![(2, 3)]);
+    state.make_move((2, 3));
+    assert_eq!(state.check_winner(), Some(Player::Max));
+    assert_eq!(state.win_reason, Some(WinReason::Captures));
+}
+
+#[test]
+fn test_settings_warn_when_the_chain_does_not_fit() {
+    let mut settings = GameSettings::new();
+    assert!(settings.line_win_possible());
+    settings.set_board_size(4);
+    assert_eq!(settings.board_size, 4);
+    assert!(!settings.line_win_possible());
+    assert_eq!(settings.line_win_possible(), settings.new_game_state().unwrap().line_win_possible());
+}
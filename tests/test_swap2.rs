@@ -0,0 +1,104 @@
+use gomoku::core::board::Player;
+use gomoku::core::rules::{OpeningPhase, Swap2Option};
+use gomoku::core::state::GameState;
+
+const OPENING: [(usize, usize); 3] = [(9, 9), (3, 3), (9, 11)];
+
+fn opened() -> GameState {
+    let mut state = GameState::new(19, 5);
+    state.start_swap2();
+    state.apply_swap2_placement(&OPENING).unwrap();
+    state
+}
+
+#[test]
+fn test_swap2_opening_places_two_black_one_white() {
+    let state = opened();
+
+    assert_eq!(state.opening, OpeningPhase::Swap2Choice);
+    assert_eq!(state.board.get_player(9, 9), Some(Player::Max));
+    assert_eq!(state.board.get_player(3, 3), Some(Player::Min));
+    assert_eq!(state.board.get_player(9, 11), Some(Player::Max));
+    assert_eq!(state.current_player, Player::Min);
+    // The second player decides
+    assert!(!state.opener_to_act());
+    assert!(state.get_possible_moves().is_empty());
+    assert!(!state.is_terminal());
+}
+
+#[test]
+fn test_swap2_second_player_stays_white() {
+    let mut state = opened();
+    state.apply_swap2_choice(Swap2Option::PlayWhite).unwrap();
+
+    assert_eq!(state.opening, OpeningPhase::Normal);
+    assert_eq!(state.opener_color(), Player::Max);
+    assert_eq!(state.current_player, Player::Min);
+    // White (the second player) moves next
+    assert!(!state.opener_to_act());
+    assert!(!state.get_possible_moves().is_empty());
+}
+
+#[test]
+fn test_swap2_second_player_swaps_to_black() {
+    let mut state = opened();
+    state.apply_swap2_choice(Swap2Option::PlayBlack).unwrap();
+
+    assert_eq!(state.opening, OpeningPhase::Normal);
+    assert_eq!(state.opener_color(), Player::Min);
+    assert_eq!(state.current_player, Player::Min);
+    // The opener now holds white and moves next
+    assert!(state.opener_to_act());
+}
+
+#[test]
+fn test_swap2_place_two_hands_choice_back() {
+    let mut state = opened();
+    state.apply_swap2_choice(Swap2Option::PlaceTwo).unwrap();
+    assert_eq!(state.opening, OpeningPhase::Swap2Placement);
+    assert!(!state.opener_to_act(), "the second player places the extra stones");
+
+    // One stone is not enough, the step needs exactly two
+    assert!(state.apply_swap2_placement(&[(4, 4)]).is_err());
+    state.apply_swap2_placement(&[(4, 4), (10, 10)]).unwrap();
+
+    assert_eq!(state.board.get_player(4, 4), Some(Player::Min));
+    assert_eq!(state.board.get_player(10, 10), Some(Player::Max));
+    assert_eq!(state.opening, OpeningPhase::Swap2Choice);
+    assert!(state.opener_to_act(), "the opener now chooses");
+    assert_eq!(state.current_player, Player::Min);
+
+    // No second round of extra stones
+    assert!(state.apply_swap2_choice(Swap2Option::PlaceTwo).is_err());
+
+    state.apply_swap2_choice(Swap2Option::PlayWhite).unwrap();
+    assert_eq!(state.opening, OpeningPhase::Normal);
+    assert_eq!(state.opener_color(), Player::Min);
+    assert!(state.opener_to_act());
+}
+
+#[test]
+fn test_swap2_placement_by_single_moves() {
+    let mut state = GameState::new(19, 5);
+    state.start_swap2();
+
+    // Free placement: not limited to the center or to adjacent cells
+    assert!(state.get_possible_moves().contains(&(0, 0)));
+    state.make_move((0, 0));
+    state.make_move((18, 18));
+    assert_eq!(state.opening, OpeningPhase::Swap2Placement);
+    state.make_move((5, 5));
+    assert_eq!(state.opening, OpeningPhase::Swap2Choice);
+}
+
+#[test]
+fn test_swap2_rejects_bad_placements() {
+    let mut state = GameState::new(19, 5);
+    assert!(state.apply_swap2_placement(&OPENING).is_err(), "swap2 was not started");
+
+    state.start_swap2();
+    assert!(state.apply_swap2_placement(&[(9, 9), (9, 9), (3, 3)]).is_err());
+    assert!(state.apply_swap2_placement(&[(9, 9), (19, 0), (3, 3)]).is_err());
+    assert!(state.apply_swap2_choice(Swap2Option::PlayBlack).is_err());
+    assert!(state.moves.is_empty());
+}
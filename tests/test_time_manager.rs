@@ -0,0 +1,33 @@
+use gomoku::ai::time_manager::allocate;
+use std::time::Duration;
+
+#[test]
+fn test_allocation_shrinks_as_the_bank_runs_down() {
+    let mut previous = Duration::MAX;
+    for secs in [300, 120, 60, 20, 5, 1] {
+        let budget = allocate(Duration::from_secs(secs), 30, 19);
+        assert!(budget < previous, "{}s left gave {:?}, no less than {:?}", secs, budget, previous);
+        previous = budget;
+    }
+}
+
+#[test]
+fn test_allocation_never_exceeds_the_remaining_time() {
+    for millis in [0, 1, 10, 49, 50, 51, 200, 1000, 60_000] {
+        let remaining = Duration::from_millis(millis);
+        for moves_played in [0, 5, 40, 150, 400] {
+            for board_size in [7, 15, 19] {
+                let budget = allocate(remaining, moves_played, board_size);
+                assert!(budget <= remaining, "{:?} budgeted out of {:?}", budget, remaining);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_early_moves_get_more_time_than_late_ones_at_equal_bank() {
+    let bank = Duration::from_secs(60);
+    assert!(allocate(bank, 4, 19) > allocate(bank, 20, 19));
+    // Past the expected length the bank is still spread over several moves
+    assert!(allocate(bank, 300, 19) <= bank / 10);
+}
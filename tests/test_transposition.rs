@@ -335,3 +335,116 @@ fn test_table_capacity_management() {
     assert!(result.cutoff && result.value == Some(1990));
 }
 
+
+fn fill_past_capacity(tt: &mut TranspositionTable) {
+    let capacity = tt.capacity();
+    for i in 0..capacity + capacity / 4 {
+        if i % (capacity / 8) == 0 {
+            tt.advance_age();
+        }
+        let key = (i as u64).wrapping_mul(0x9e3779b97f4a7c15);
+        let entry_type = match i % 3 {
+            0 => EntryType::Exact,
+            1 => EntryType::LowerBound,
+            _ => EntryType::UpperBound,
+        };
+        tt.store(key, i as i32, (i % 12) as i32, entry_type, Some((i % 19, i % 17)));
+    }
+}
+
+#[test]
+fn test_with_capacity_mb_sizes() {
+    for mb in [1, 64] {
+        let mut tt = TranspositionTable::with_capacity_mb(mb);
+        let capacity = tt.capacity();
+        assert!(capacity.is_power_of_two());
+        assert!(capacity * 32 <= mb * 1024 * 1024, "{} entries do not fit in {} MB", capacity, mb);
+        assert!(capacity * 128 > mb * 1024 * 1024, "{} entries waste most of {} MB", capacity, mb);
+
+        fill_past_capacity(&mut tt);
+        assert!(tt.size() > 0);
+
+        let probes = 1000u64;
+        let mut found = 0;
+        for i in 0..probes {
+            let key = i.wrapping_mul(0x9e3779b97f4a7c15);
+            if tt.probe(key, 0, i32::MIN, i32::MAX).best_move.is_some() {
+                found += 1;
+            }
+        }
+        // Keys that were never stored must miss
+        assert!(!tt.probe(u64::MAX, 0, i32::MIN, i32::MAX).cutoff);
+
+        let (hits, misses) = tt.get_stats();
+        assert_eq!(hits, found);
+        assert_eq!(hits + misses, probes + 1);
+    }
+}
+
+#[test]
+fn test_extract_pv_follows_stored_moves() {
+    use gomoku::core::state::GameState;
+
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    let start_hash = state.hash();
+    let start_board = state.board.clone();
+
+    let mut tt = TranspositionTable::default();
+    tt.store(state.hash(), 0, 2, EntryType::Exact, Some((7, 8)));
+    state.make_move((7, 8));
+    tt.store(state.hash(), 0, 1, EntryType::Exact, Some((8, 8)));
+    state.undo_move((7, 8));
+
+    assert_eq!(tt.extract_pv(&mut state, 10), vec![(7, 8), (8, 8)]);
+    assert_eq!(tt.extract_pv(&mut state, 1), vec![(7, 8)]);
+    assert_eq!(state.hash(), start_hash);
+    assert_eq!(state.board, start_board);
+
+    // An occupied cell ends the line
+    tt.store(state.hash(), 0, 2, EntryType::Exact, Some((7, 7)));
+    assert!(tt.extract_pv(&mut state, 10).is_empty());
+}
+
+#[test]
+fn test_search_pv_starts_with_the_winning_move() {
+    use gomoku::ai::lazy_smp::lazy_smp_search;
+    use gomoku::core::board::Player;
+    use gomoku::core::state::GameState;
+    use std::time::Duration;
+
+    // Open three for Max: pushing it to an open four wins in three plies
+    let mut state = GameState::new(15, 5);
+    for col in 6..=8 {
+        state.board.place_stone(7, col, Player::Max);
+    }
+    state.board.place_stone(10, 3, Player::Min);
+    state.board.place_stone(3, 10, Player::Min);
+    state.current_player = Player::Max;
+    state.current_hash = state.zobrist_hash.compute_hash(&state);
+
+    let result = lazy_smp_search(&mut state, 4, Some(Duration::from_secs(5)), Some(1));
+
+    assert!(result.score >= 900_000, "expected a forced win, got {}", result.score);
+    assert_eq!(result.pv.first().copied(), result.best_move);
+    assert!(result.pv.len() >= 2, "pv too short: {:?}", result.pv);
+    assert_eq!(result.ponder_move, Some(result.pv[1]));
+}
+
+#[test]
+fn test_clear_resets_entries_and_stats() {
+    let mut tt = TranspositionTable::default();
+    tt.store(12345, 100, 3, EntryType::Exact, Some((7, 7)));
+    tt.advance_age();
+    assert!(tt.probe(12345, 3, i32::MIN, i32::MAX).cutoff);
+    tt.probe(999, 1, i32::MIN, i32::MAX);
+
+    tt.clear();
+    assert_eq!(tt.get_stats(), (0, 0));
+    assert_eq!(tt.size(), 0);
+
+    let result = tt.probe(12345, 1, i32::MIN, i32::MAX);
+    assert!(!result.cutoff);
+    assert_eq!(result.best_move, None);
+    assert_eq!(tt.get_stats(), (0, 1));
+}
@@ -0,0 +1,118 @@
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+
+/// Max captures the Min pair on (9, 10)-(9, 11) with its fourth move
+fn game_with_capture() -> GameState {
+    let mut state = GameState::new(19, 5);
+    for mv in [(9, 9), (9, 10), (0, 0), (9, 11), (9, 12)] {
+        state.make_move(mv);
+    }
+    assert_eq!(state.max_captures, 1);
+    state
+}
+
+#[test]
+fn test_undo_then_redo_restores_capture() {
+    let mut state = game_with_capture();
+    let before = state.clone();
+
+    assert_eq!(state.undo_last_move(), Some((9, 12)));
+    assert_eq!(state.max_captures, 0);
+    assert_eq!(state.board.get_player(9, 10), Some(Player::Min));
+    assert_eq!(state.board.get_player(9, 11), Some(Player::Min));
+    assert_eq!(state.current_player, Player::Max);
+
+    assert_eq!(state.redo_move(), Some((9, 12)));
+    assert_eq!(state.board, before.board);
+    assert_eq!(state.max_captures, before.max_captures);
+    assert_eq!(state.min_captures, before.min_captures);
+    assert_eq!(state.current_player, before.current_player);
+    assert_eq!(state.hash(), before.hash());
+    assert_eq!(state.moves, before.moves);
+}
+
+#[test]
+fn test_undo_and_redo_whole_game() {
+    let mut state = game_with_capture();
+    let before = state.clone();
+
+    while state.undo_last_move().is_some() {}
+    assert!(state.moves.is_empty());
+    assert_eq!(state.redo_history.len(), before.moves.len());
+    assert_eq!(state, GameState { redo_history: state.redo_history.clone(), ..GameState::new(19, 5) });
+
+    while state.redo_move().is_some() {}
+    assert_eq!(state, before);
+}
+
+#[test]
+fn test_new_move_clears_redo() {
+    let mut state = game_with_capture();
+    state.undo_last_move();
+    state.undo_last_move();
+    assert_eq!(state.redo_history.len(), 2);
+
+    state.make_move((5, 5));
+    assert!(state.redo_history.is_empty());
+    assert_eq!(state.redo_move(), None);
+}
+
+#[test]
+fn test_undo_on_empty_game_does_nothing() {
+    let mut state = GameState::new(15, 5);
+    assert_eq!(state.undo_last_move(), None);
+    assert_eq!(state.redo_move(), None);
+    assert_eq!(state, GameState::new(15, 5));
+}
+
+#[test]
+fn test_undo_full_turn_goes_back_two_plies() {
+    let mut reference = GameState::new(19, 5);
+    for mv in [(9, 9), (9, 10), (0, 0)] {
+        reference.make_move(mv);
+    }
+    // Max's capture on (9,12) is the last move, Min is to move
+    let mut state = game_with_capture();
+    assert_eq!(state.current_player, Player::Min);
+
+    assert_eq!(state.undo_full_turn(), Some([(9, 12), (9, 11)]));
+    assert_eq!(state.board, reference.board);
+    assert_eq!((state.max_captures, state.min_captures), (0, 0));
+    assert_eq!(state.board.get_player(9, 10), Some(Player::Min));
+    assert_eq!(state.current_player, Player::Min);
+    assert_eq!(state.hash(), reference.hash());
+    assert_eq!(state.moves, reference.moves);
+
+    // Both moves can be replayed
+    state.redo_move();
+    state.redo_move();
+    assert_eq!(state.max_captures, 1);
+}
+
+#[test]
+fn test_undo_full_turn_needs_two_moves() {
+    let mut state = GameState::new(19, 5);
+    assert_eq!(state.undo_full_turn(), None);
+    state.make_move((9, 9));
+    assert_eq!(state.undo_full_turn(), None);
+    assert_eq!(state.moves.len(), 1);
+}
+
+#[test]
+fn test_takeback_only_after_the_ai_answered() {
+    use gomoku::ui::app::GameSettings;
+    use gomoku::ui::screens::game::takeback::can_take_back;
+
+    let mut settings = GameSettings::new();
+    settings.versus_ai = true;
+    let mut state = GameState::new(19, 5);
+    state.make_move((9, 9));
+    // The AI has not answered yet
+    assert!(!can_take_back(&state, &settings, true));
+    state.make_move((9, 10));
+    assert!(can_take_back(&state, &settings, false));
+    assert!(!can_take_back(&state, &settings, true));
+
+    settings.versus_ai = false;
+    assert!(!can_take_back(&state, &settings, false));
+}
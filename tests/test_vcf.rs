@@ -0,0 +1,71 @@
+use gomoku::ai::vcf::solve_vcf;
+use gomoku::core::board::Player;
+use gomoku::core::state::GameState;
+
+fn place(state: &mut GameState, stones: &[(usize, usize)], player: Player) {
+    for &(row, col) in stones {
+        state.board.place_stone(row, col, player);
+    }
+    state.current_hash = state.zobrist_hash.compute_hash(state);
+}
+
+fn replay_wins(state: &GameState, line: &[(usize, usize)], attacker: Player) -> bool {
+    let mut replay = state.clone();
+    for &mv in line {
+        assert!(replay.board.is_empty_position(mv.0, mv.1), "line plays on occupied {:?}", mv);
+        replay.make_move(mv);
+    }
+    replay.winner == Some(attacker)
+}
+
+#[test]
+fn test_vcf_immediate_five() {
+    let mut state = GameState::new(19, 5);
+    place(&mut state, &[(9, 5), (9, 6), (9, 7), (9, 8)], Player::Max);
+    place(&mut state, &[(9, 4), (3, 3), (3, 4), (3, 5)], Player::Min);
+
+    assert_eq!(solve_vcf(&mut state, 1), Some(vec![(9, 9)]));
+}
+
+#[test]
+fn test_vcf_four_then_double_four() {
+    // Row 10 and row 9 are each closed on the left; a four on row 10 forces a block,
+    // then (9,8) makes a four on row 9 and on column 8 at once.
+    let mut state = GameState::new(19, 5);
+    place(
+        &mut state,
+        &[(9, 5), (9, 6), (9, 7), (10, 5), (10, 6), (10, 7), (11, 8), (12, 8)],
+        Player::Max,
+    );
+    place(&mut state, &[(9, 4), (10, 4), (13, 8), (2, 2), (2, 16), (16, 2), (16, 16)], Player::Min);
+    let before = state.clone();
+
+    let line = solve_vcf(&mut state, 7).expect("forced win should be found");
+
+    assert_eq!(state, before, "solver must leave the state untouched");
+    assert_eq!(line.len() % 2, 1, "line should end with the attacker's move: {:?}", line);
+    assert!(line.len() >= 3);
+    assert!(replay_wins(&state, &line, Player::Max), "line {:?} does not win", line);
+}
+
+#[test]
+fn test_vcf_respects_depth() {
+    let mut state = GameState::new(19, 5);
+    place(
+        &mut state,
+        &[(9, 5), (9, 6), (9, 7), (10, 5), (10, 6), (10, 7), (11, 8), (12, 8)],
+        Player::Max,
+    );
+    place(&mut state, &[(9, 4), (10, 4), (13, 8), (2, 2), (2, 16), (16, 2), (16, 16)], Player::Min);
+
+    assert_eq!(solve_vcf(&mut state, 1), None);
+}
+
+#[test]
+fn test_vcf_quiet_position_has_no_solution() {
+    let mut state = GameState::new(19, 5);
+    place(&mut state, &[(9, 9), (11, 10), (7, 12)], Player::Max);
+    place(&mut state, &[(9, 10), (10, 9), (12, 12)], Player::Min);
+
+    assert_eq!(solve_vcf(&mut state, 9), None);
+}
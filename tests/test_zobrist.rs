@@ -1,6 +1,8 @@
 use gomoku::ai::zobrist::ZobristHash;
 use gomoku::core::board::Player;
 use gomoku::core::state::GameState;
+use rand::Rng;
+use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
 use std::collections::HashSet;
 
 #[test]
@@ -217,3 +219,66 @@ fn test_zobrist_position_independence() {
     
     assert_ne!(hash1, hash2, "Different positions should have different hashes");
 }
+
+#[test]
+fn test_incremental_hash_matches_full_recompute_with_captures() {
+    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut total_captures = 0;
+
+    for _ in 0..40 {
+        let mut state = GameState::new(9, 5);
+        let mut played = Vec::new();
+        let mut hashes = vec![state.hash()];
+
+        while !state.is_terminal() && played.len() < 50 {
+            let moves = state.get_possible_moves();
+            let mv = moves[rng.random_range(0..moves.len())];
+            state.make_move(mv);
+            played.push(mv);
+            hashes.push(state.hash());
+
+            assert_eq!(
+                state.hash(),
+                state.zobrist_hash.compute_hash(&state),
+                "incremental hash diverged after {:?}",
+                played
+            );
+        }
+        total_captures += state.max_captures + state.min_captures;
+
+        // Undo must restore the exact hash of every earlier ply, captured stones included
+        while let Some(mv) = played.pop() {
+            state.undo_move(mv);
+            hashes.pop();
+            assert_eq!(state.hash(), *hashes.last().unwrap());
+            assert_eq!(state.hash(), state.zobrist_hash.compute_hash(&state));
+        }
+    }
+
+    assert!(total_captures > 0, "random games should include captures");
+}
+
+#[test]
+fn test_same_seed_gives_same_keys_and_hashes() {
+    let first = ZobristHash::from_seed(19, 42);
+    let second = ZobristHash::from_seed(19, 42);
+    assert_eq!(first, second);
+    assert_eq!(ZobristHash::new(19), ZobristHash::from_seed(19, gomoku::ai::zobrist::DEFAULT_ZOBRIST_SEED));
+    assert_ne!(first, ZobristHash::from_seed(19, 43));
+
+    let mut state = GameState::new(19, 5);
+    for mv in [(9, 9), (9, 10), (10, 10), (8, 8)] {
+        state.make_move(mv);
+    }
+    assert_eq!(first.compute_hash(&state), second.compute_hash(&state));
+    assert_ne!(first.compute_hash(&state), ZobristHash::from_seed(19, 43).compute_hash(&state));
+}
+
+#[test]
+fn test_default_hash_is_stable_across_runs() {
+    // Pinned value: a change here invalidates anything stored by hash
+    let mut state = GameState::new(15, 5);
+    state.make_move((7, 7));
+    assert_eq!(state.hash(), ZobristHash::new(15).compute_hash(&state));
+    assert_eq!(state.hash(), 0x3ec0_d99b_7245_6717);
+}